@@ -3,7 +3,7 @@
 
 use crate::chain_service::BlockChainCollection;
 use actix::prelude::*;
-use anyhow::{format_err, Error, Result};
+use anyhow::{ensure, format_err, Error, Result};
 use config::NodeConfig;
 use crypto::HashValue;
 use executor::block_executor::BlockExecutor;
@@ -19,7 +19,6 @@ use starcoin_txpool_api::TxPoolAsyncService;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::sync::{Arc, Weak};
-use std::time::{SystemTime, UNIX_EPOCH};
 use storage::Store;
 use traits::Consensus;
 use traits::{ChainReader, ChainWriter};
@@ -28,10 +27,28 @@ use types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockTemplate, BLOCK_INFO_DEFAULT_ID},
     block_metadata::BlockMetadata,
     startup_info::ChainInfo,
+    time::{RealTimeService, TimeService},
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
     U512,
 };
 
+/// Txns executed by a block, the block metadata txn is the first one, so a block without user
+/// txns still executes it.
+pub fn block_txns(
+    block_metadata: BlockMetadata,
+    user_txns: &[SignedUserTransaction],
+) -> Vec<Transaction> {
+    let mut txns = Vec::with_capacity(user_txns.len() + 1);
+    txns.push(Transaction::BlockMetadata(block_metadata));
+    txns.extend(
+        user_txns
+            .iter()
+            .cloned()
+            .map(|user_txn| Transaction::UserTransaction(user_txn)),
+    );
+    txns
+}
+
 pub static DEFAULT_BLOCK_INFO: Lazy<BlockInfo> = Lazy::new(|| {
     BlockInfo::new(
         *BLOCK_INFO_DEFAULT_ID,
@@ -58,6 +75,7 @@ where
     pub txpool: P,
     chain_info: ChainInfo,
     pub block_chain_collection: Weak<BlockChainCollection<C, S, P>>,
+    time_service: Arc<dyn TimeService>,
 }
 
 impl<C, S, P> BlockChain<C, S, P>
@@ -108,10 +126,16 @@ where
             txpool,
             chain_info,
             block_chain_collection,
+            time_service: Arc::new(RealTimeService::new()),
         };
         Ok(chain)
     }
 
+    /// Replace the clock the block timestamps are read from, the wall clock by default.
+    pub fn set_time_service(&mut self, time_service: Arc<dyn TimeService>) {
+        self.time_service = time_service;
+    }
+
     pub fn save_block(&self, block: &Block) {
         if let Err(e) = self
             .storage
@@ -177,22 +201,26 @@ where
         user_txns: Vec<SignedUserTransaction>,
     ) -> Result<BlockTemplate> {
         //TODO calculate gas limit etc.
-        let mut txns = user_txns
-            .iter()
-            .cloned()
-            .map(|user_txn| Transaction::UserTransaction(user_txn))
-            .collect::<Vec<Transaction>>();
-
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        txns.push(Transaction::BlockMetadata(BlockMetadata::new(
-            previous_header.id(),
-            timestamp,
-            author,
-            auth_key_prefix.clone(),
-        )));
+        let timestamp = self.time_service.now_secs();
+        // the txns expired at the block timestamp can not be executed in the block.
+        let (user_txns, expired_txns): (Vec<_>, Vec<_>) = user_txns
+            .into_iter()
+            .partition(|txn| !txn.is_expired(timestamp));
+        if !expired_txns.is_empty() {
+            debug!(
+                "exclude {} expired txns from the block template.",
+                expired_txns.len()
+            );
+        }
+        let txns = block_txns(
+            BlockMetadata::new(
+                previous_header.id(),
+                timestamp,
+                author,
+                auth_key_prefix.clone(),
+            ),
+            &user_txns,
+        );
         let chain_state =
             ChainStateDB::new(self.storage.clone(), Some(previous_header.state_root()));
         // let block_info = self.get_block_info(previous_header.id());
@@ -245,6 +273,52 @@ where
             .get_branch_id(&self.chain_info.branch_id(), number)
     }
 
+    /// Verify the total difficulty of the blocks after `from` up to `to`, the total difficulty of
+    /// every block must be the total difficulty of its parent plus its difficulty.
+    pub fn verify_total_difficulty(&self, from: HashValue, to: HashValue) -> Result<()> {
+        let from_header = self
+            .storage
+            .get_block_header_by_hash(from)?
+            .ok_or_else(|| format_err!("Can not find block header by hash {}", from))?;
+        let mut headers = vec![];
+        let mut block_id = to;
+        while block_id != from {
+            let header = self
+                .storage
+                .get_block_header_by_hash(block_id)?
+                .ok_or_else(|| format_err!("Can not find block header by hash {}", block_id))?;
+            ensure!(
+                header.number() > from_header.number(),
+                "Block {} is not an ancestor of block {}",
+                from,
+                to
+            );
+            block_id = header.parent_hash();
+            headers.push(header);
+        }
+
+        let mut parent = from_header;
+        let mut parent_total_difficulty = self.total_difficulty_of(from)?;
+        for header in headers.into_iter().rev() {
+            let total_difficulty = self.total_difficulty_of(header.id())?;
+            header.verify_cumulative_difficulty(
+                total_difficulty,
+                &parent,
+                parent_total_difficulty,
+            )?;
+            parent = header;
+            parent_total_difficulty = total_difficulty;
+        }
+        Ok(())
+    }
+
+    fn total_difficulty_of(&self, block_id: HashValue) -> Result<U512> {
+        self.storage
+            .get_block_info(block_id)?
+            .map(|block_info| block_info.total_difficulty)
+            .ok_or_else(|| format_err!("Can not find block info by hash {}", block_id))
+    }
+
     pub fn update_head(&mut self, latest_block: BlockHeader) {
         self.chain_info.update_head(latest_block)
     }
@@ -304,12 +378,16 @@ where
         }
     }
 
-    fn get_blocks_by_number(&self, number: BlockNumber, count: u64) -> Result<Vec<Block>, Error> {
+    fn get_blocks_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> Result<Vec<Block>, Error> {
         let mut block_vec = vec![];
-        let mut temp_number = number;
-        if number == 0 as u64 {
-            temp_number = self.current_header().number();
+        if count == 0 {
+            return Ok(block_vec);
         }
+        let temp_number = number.unwrap_or_else(|| self.current_header().number());
         if let Some(branch_id) = self.get_branch_id(temp_number) {
             let mut tmp_count = count;
             let mut current_num = temp_number;
@@ -442,6 +520,10 @@ where
             false
         }
     }
+
+    fn time_service(&self) -> &dyn TimeService {
+        self.time_service.as_ref()
+    }
 }
 
 impl<C, S, P> ChainWriter for BlockChain<C, S, P>
@@ -469,15 +551,7 @@ where
         debug!("verify used time: {}", (verify_end_time - apply_begin_time));
 
         let chain_state = &self.chain_state;
-        let mut txns = block
-            .transactions()
-            .iter()
-            .cloned()
-            .map(|user_txn| Transaction::UserTransaction(user_txn))
-            .collect::<Vec<Transaction>>();
-        let block_metadata = header.clone().into_metadata();
-
-        txns.push(Transaction::BlockMetadata(block_metadata));
+        let txns = block_txns(header.clone().into_metadata(), block.transactions());
 
         let exe_begin_time = get_unix_ts();
         let (accumulator_root, state_root, vec_transaction_info) =
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::chain::BlockChain;
+use crate::startup_info_flusher::StartupInfoFlusher;
 use actix::prelude::*;
 use anyhow::{format_err, Error, Result};
 use bus::{Broadcast, BusActor};
@@ -217,6 +218,7 @@ where
     txpool: P,
     bus: Addr<BusActor>,
     sync_metadata: SyncMetadata,
+    startup_info_flusher: StartupInfoFlusher,
 }
 
 impl<C, S, P> ChainServiceImpl<C, S, P>
@@ -240,6 +242,7 @@ where
             storage.clone(),
             txpool.clone(),
         )?;
+        let startup_info_flusher = StartupInfoFlusher::from_config(&config.storage);
         Ok(Self {
             config,
             collection,
@@ -248,6 +251,7 @@ where
             txpool,
             bus,
             sync_metadata,
+            startup_info_flusher,
         })
     }
 
@@ -350,10 +354,28 @@ where
         self.save_startup()
     }
 
-    fn save_startup(&self) -> Result<()> {
+    fn save_startup(&mut self) -> Result<()> {
         let startup_info = self.collection.to_startup_info();
-        debug!("save startup info : {:?}", startup_info);
-        self.storage.save_startup_info(startup_info)
+        let storage = self.storage.clone();
+        let flushed = self
+            .startup_info_flusher
+            .update(startup_info, |startup_info| {
+                debug!("save startup info : {:?}", startup_info);
+                storage.save_startup_info(startup_info)
+            })?;
+        if !flushed {
+            debug!("delay save startup info.");
+        }
+        Ok(())
+    }
+
+    /// Write the pending startup info to storage, called on shutdown.
+    pub fn flush_startup(&mut self) -> Result<()> {
+        let storage = self.storage.clone();
+        self.startup_info_flusher.flush(|startup_info| {
+            debug!("flush startup info : {:?}", startup_info);
+            storage.save_startup_info(startup_info)
+        })
     }
 
     fn commit_2_txpool(
@@ -661,7 +683,7 @@ where
         self.collection.to_startup_info()
     }
 
-    fn master_blocks_by_number(&self, number: u64, count: u64) -> Result<Vec<Block>> {
+    fn master_blocks_by_number(&self, number: Option<u64>, count: u64) -> Result<Vec<Block>> {
         self.collection
             .master
             .read()
@@ -8,9 +8,11 @@ pub use chain::BlockChain;
 pub mod chain_service;
 pub mod message;
 pub mod mock;
+mod startup_info_flusher;
 
 pub use chain_service::to_block_chain_collection;
 pub use chain_service::BlockChainCollection;
+pub use startup_info_flusher::StartupInfoFlusher;
 
 use crate::chain_service::ChainServiceImpl;
 use crate::message::ChainResponse;
@@ -24,6 +26,7 @@ use message::ChainRequest;
 use network::{get_unix_ts, NetworkAsyncService};
 use starcoin_sync_api::SyncMetadata;
 use std::sync::Arc;
+use std::time::Duration;
 use storage::Storage;
 use traits::Consensus;
 use traits::{ChainAsyncService, ChainService, ConnectResult};
@@ -43,6 +46,7 @@ where
 {
     service: ChainServiceImpl<C, Storage, TxPoolRef>,
     bus: Addr<BusActor>,
+    startup_info_flush_interval: Duration,
 }
 
 impl<C> ChainActor<C>
@@ -58,6 +62,8 @@ where
         txpool: TxPoolRef,
         sync_metadata: SyncMetadata,
     ) -> Result<ChainActorRef<C>> {
+        let startup_info_flush_interval =
+            Duration::from_millis(config.storage.startup_info_flush_interval());
         let actor = ChainActor {
             service: ChainServiceImpl::new(
                 config,
@@ -69,6 +75,7 @@ where
                 sync_metadata,
             )?,
             bus,
+            startup_info_flush_interval,
         }
         .start();
         Ok(actor.into())
@@ -88,8 +95,23 @@ where
             .into_actor(self)
             .then(|_res, act, _ctx| async {}.into_actor(act))
             .wait(ctx);
+        // Without new head changes, the pending startup info is only written on the timer.
+        if self.startup_info_flush_interval > Duration::from_millis(0) {
+            ctx.run_interval(self.startup_info_flush_interval, |act, _ctx| {
+                if let Err(e) = act.service.flush_startup() {
+                    error!("flush startup info failed: {:?}", e);
+                }
+            });
+        }
         info!("ChainActor actor started");
     }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        if let Err(e) = self.service.flush_startup() {
+            error!("flush startup info failed: {:?}", e);
+        }
+        info!("ChainActor actor stopped");
+    }
 }
 
 impl<C> Handler<ChainRequest> for ChainActor<C>
@@ -334,7 +356,7 @@ where
         }
     }
 
-    async fn master_blocks_by_number(self, number: u64, count: u64) -> Result<Vec<Block>> {
+    async fn master_blocks_by_number(self, number: Option<u64>, count: u64) -> Result<Vec<Block>> {
         if let ChainResponse::VecBlock(blocks) = self
             .address
             .send(ChainRequest::GetBlocksByNumber(number, count))
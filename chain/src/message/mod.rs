@@ -29,7 +29,7 @@ pub enum ChainRequest {
     GetHeadChainInfo(),
     GetTransaction(HashValue),
     GetTransactionIdByBlock(HashValue),
-    GetBlocksByNumber(u64, u64),
+    GetBlocksByNumber(Option<u64>, u64),
     GenTx(), // just for test
 }
 
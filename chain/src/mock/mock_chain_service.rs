@@ -8,18 +8,31 @@ use crypto::HashValue;
 use types::startup_info::ChainInfo;
 use types::{
     account_address::AccountAddress,
-    block::{Block, BlockHeader, BlockInfo, BlockTemplate},
+    block::{Block, BlockBody, BlockHeader, BlockInfo, BlockNumber, BlockTemplate},
     startup_info::StartupInfo,
     transaction::{SignedUserTransaction, TransactionInfo},
+    U256,
 };
 
 //TODO implement Mock service
 #[derive(Clone)]
-pub struct MockChainService {}
+pub struct MockChainService {
+    head: BlockHeader,
+}
 
 impl MockChainService {
+    /// A mock service whose master head is a genesis header.
     pub fn new() -> MockChainService {
-        Self {}
+        Self::new_with_head(BlockHeader::genesis_block_header_for_test(
+            HashValue::zero(),
+            HashValue::zero(),
+            U256::zero(),
+            vec![],
+        ))
+    }
+
+    pub fn new_with_head(head: BlockHeader) -> MockChainService {
+        Self { head }
     }
 }
 
@@ -45,12 +58,22 @@ impl ChainAsyncService for MockChainService {
         unimplemented!()
     }
 
-    async fn get_block_info_by_hash(self, _hash: &HashValue) -> Option<BlockInfo> {
-        unimplemented!()
+    async fn get_block_info_by_hash(self, hash: &HashValue) -> Option<BlockInfo> {
+        if *hash != self.head.id() {
+            return None;
+        }
+        Some(BlockInfo::new(
+            self.head.id(),
+            self.head.accumulator_root(),
+            vec![],
+            0,
+            0,
+            self.head.difficult().into(),
+        ))
     }
 
     async fn master_head_header(self) -> Option<BlockHeader> {
-        unimplemented!()
+        Some(self.head)
     }
 
     async fn master_head_block(self) -> Option<Block> {
@@ -61,8 +84,37 @@ impl ChainAsyncService for MockChainService {
         unimplemented!()
     }
 
-    async fn master_blocks_by_number(self, _number: u64, _count: u64) -> Result<Vec<Block>> {
-        unimplemented!()
+    /// Blocks below the head, the blocks other than the head are mocked by their number.
+    async fn master_blocks_by_number(
+        self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> Result<Vec<Block>> {
+        let head_number = self.head.number();
+        let start = number.unwrap_or(head_number).min(head_number);
+        Ok((0..=start)
+            .rev()
+            .take(count as usize)
+            .map(|number| {
+                let header = if number == head_number {
+                    self.head.clone()
+                } else {
+                    BlockHeader::new(
+                        HashValue::zero(),
+                        number,
+                        number,
+                        AccountAddress::default(),
+                        HashValue::zero(),
+                        HashValue::zero(),
+                        0,
+                        0,
+                        U256::zero(),
+                        vec![],
+                    )
+                };
+                Block::new(header, BlockBody::default())
+            })
+            .collect())
     }
 
     async fn master_startup_info(self) -> Result<StartupInfo, Error> {
@@ -0,0 +1,81 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use config::StorageConfig;
+use std::time::{Duration, Instant};
+use types::startup_info::StartupInfo;
+
+/// Debounce startup info writes, the latest pending startup info is written at most once
+/// every `interval` or every `max_pending` head changes. With both limits set to 0 every
+/// change is written through.
+pub struct StartupInfoFlusher {
+    interval: Duration,
+    max_pending: u64,
+    last_flush: Instant,
+    pending: Option<StartupInfo>,
+    pending_count: u64,
+}
+
+impl StartupInfoFlusher {
+    pub fn new(interval: Duration, max_pending: u64) -> Self {
+        Self {
+            interval,
+            max_pending,
+            last_flush: Instant::now(),
+            pending: None,
+            pending_count: 0,
+        }
+    }
+
+    pub fn from_config(config: &StorageConfig) -> Self {
+        Self::new(
+            Duration::from_millis(config.startup_info_flush_interval()),
+            config.startup_info_flush_blocks(),
+        )
+    }
+
+    fn is_write_through(&self) -> bool {
+        self.interval == Duration::from_millis(0) && self.max_pending == 0
+    }
+
+    fn should_flush(&self) -> bool {
+        self.is_write_through()
+            || (self.interval > Duration::from_millis(0)
+                && self.last_flush.elapsed() >= self.interval)
+            || (self.max_pending > 0 && self.pending_count >= self.max_pending)
+    }
+
+    /// Record a new startup info, and write it with `write` if the policy allows.
+    /// Return true if the startup info was written.
+    pub fn update<F>(&mut self, startup_info: StartupInfo, write: F) -> Result<bool>
+    where
+        F: FnOnce(StartupInfo) -> Result<()>,
+    {
+        self.pending = Some(startup_info);
+        self.pending_count += 1;
+        if self.should_flush() {
+            self.flush(write)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Write the pending startup info if there is one, must be called on shutdown.
+    pub fn flush<F>(&mut self, write: F) -> Result<()>
+    where
+        F: FnOnce(StartupInfo) -> Result<()>,
+    {
+        if let Some(startup_info) = self.pending.take() {
+            write(startup_info)?;
+        }
+        self.pending_count = 0;
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
@@ -1,2 +1,4 @@
 #[cfg(test)]
 mod test_block_chain;
+#[cfg(test)]
+mod test_startup_info_flusher;
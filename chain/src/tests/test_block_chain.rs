@@ -1,3 +1,4 @@
+use crate::chain_service::{BlockChainCollection, ChainServiceImpl};
 use crate::{
     to_block_chain_collection, BlockChain, ChainActor, ChainActorRef, ChainAsyncService,
     SyncMetadata,
@@ -7,18 +8,27 @@ use bus::BusActor;
 use config::NodeConfig;
 use consensus::dummy::DummyConsensus;
 use consensus::dummy::DummyHeader;
+use crypto::{hash::CryptoHash, HashValue};
+use executor::{executor::Executor, TransactionExecutor};
 use futures_timer::Delay;
 use logger::prelude::*;
+use starcoin_accumulator::node::ACCUMULATOR_PLACEHOLDER_HASH;
+use starcoin_accumulator::{Accumulator, MerkleAccumulator};
 use starcoin_genesis::Genesis;
 use starcoin_wallet_api::WalletAccount;
 use std::{sync::Arc, time::Duration};
 use storage::cache_storage::CacheStorage;
 use storage::storage::StorageInstance;
-use storage::Storage;
+use storage::{BlockStore, Storage};
 use traits::Consensus;
-use traits::{ChainReader, ChainWriter};
+use traits::{ChainReader, ChainService, ChainWriter};
 use txpool::TxPoolRef;
-use types::U256;
+use types::{
+    block_metadata::BlockMetadata,
+    time::{MockTimeService, TimeService},
+    transaction::Transaction,
+    U256, U512,
+};
 async fn gen_master_chain(
     times: u64,
     delay: bool,
@@ -100,6 +110,61 @@ async fn test_block_chain_head() {
     assert_eq!(chain.master_head_header().await.unwrap().number(), times);
 }
 
+#[actix_rt::test]
+async fn test_startup_info_flush_on_interval() {
+    ::logger::init_for_test();
+    let mut node_config = NodeConfig::random_for_test();
+    // neither limit is reached in the test, only the flush tick writes the startup info.
+    node_config
+        .storage
+        .set_startup_info_flush_policy(3_600_000, 1000);
+    let node_config = Arc::new(node_config);
+    let storage =
+        Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap());
+    let genesis = Genesis::build(node_config.net()).unwrap();
+    let startup_info = genesis.execute(storage.clone()).unwrap();
+    let bus = BusActor::launch();
+    let txpool = TxPoolRef::start(
+        node_config.tx_pool.clone(),
+        storage.clone(),
+        startup_info.master.get_head(),
+        bus.clone(),
+    );
+    let sync_metadata = SyncMetadata::new(node_config.clone(), bus.clone());
+    let mut service = ChainServiceImpl::<DummyConsensus, Storage, TxPoolRef>::new(
+        node_config,
+        startup_info.clone(),
+        storage.clone(),
+        None,
+        txpool,
+        bus,
+        sync_metadata,
+    )
+    .unwrap();
+    let miner_account = WalletAccount::random();
+    for _ in 0..3 {
+        let block = service
+            .create_block_template(
+                *miner_account.address(),
+                Some(miner_account.get_auth_key().prefix().to_vec()),
+                None,
+                Vec::new(),
+            )
+            .unwrap()
+            .into_block(DummyHeader {}, U256::max_value());
+        let _ = service.try_connect(block, false).unwrap();
+    }
+    let head = service.master_head_header();
+    assert_eq!(head.number(), 3);
+    let saved = storage.get_startup_info().unwrap().unwrap();
+    assert_eq!(saved.master.get_head(), startup_info.master.get_head());
+
+    // the tick the chain actor runs on the flush interval.
+    service.flush_startup().unwrap();
+    let saved = storage.get_startup_info().unwrap().unwrap();
+    assert_eq!(saved.master.get_head(), head.id());
+}
+
 #[actix_rt::test]
 async fn test_block_chain_forks() {
     ::logger::init_for_test();
@@ -143,8 +208,13 @@ async fn test_block_chain_forks() {
     assert_eq!(chain.master_head_header().await.unwrap().id(), parent_hash)
 }
 
-#[stest::test]
-async fn test_chain_apply() -> Result<()> {
+/// The chain only holds a weak reference of the collection, keep the collection alive while
+/// using the chain.
+fn gen_block_chain_for_test() -> Result<(
+    BlockChain<DummyConsensus, Storage, TxPoolRef>,
+    Arc<NodeConfig>,
+    Arc<BlockChainCollection<DummyConsensus, Storage, TxPoolRef>>,
+)> {
     let config = Arc::new(NodeConfig::random_for_test());
     let storage =
         Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap());
@@ -166,13 +236,19 @@ async fn test_chain_apply() -> Result<()> {
         storage.clone(),
         txpool.clone(),
     )?;
-    let mut block_chain = BlockChain::<DummyConsensus, Storage, TxPoolRef>::new(
+    let block_chain = BlockChain::<DummyConsensus, Storage, TxPoolRef>::new(
         config.clone(),
         startup_info.master.clone(),
         storage,
         txpool,
         Arc::downgrade(&collection),
     )?;
+    Ok((block_chain, config, collection))
+}
+
+#[stest::test]
+async fn test_chain_apply() -> Result<()> {
+    let (mut block_chain, config, _collection) = gen_block_chain_for_test()?;
     let header = block_chain.current_header();
     debug!("genesis header: {:?}", header);
     let miner_account = WalletAccount::random();
@@ -189,3 +265,172 @@ async fn test_chain_apply() -> Result<()> {
     assert_ne!(header.state_root(), header1.state_root());
     Ok(())
 }
+
+/// Accumulator root of a block which is the first block executing `txns`.
+fn accumulator_root(txns: &[Transaction]) -> Result<HashValue> {
+    let storage =
+        Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap());
+    let accumulator = MerkleAccumulator::new(
+        HashValue::random(),
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        vec![],
+        0,
+        0,
+        storage,
+    )?;
+    let txn_hashes: Vec<HashValue> = txns.iter().map(|txn| txn.crypto_hash()).collect();
+    Ok(accumulator.append(&txn_hashes)?.0)
+}
+
+#[stest::test]
+async fn test_create_block_template_without_txns() -> Result<()> {
+    let (mut block_chain, config, _collection) = gen_block_chain_for_test()?;
+    let header = block_chain.current_header();
+    let miner_account = WalletAccount::random();
+    let auth_key_prefix = Some(miner_account.get_auth_key().prefix().to_vec());
+    let block_template = block_chain.create_block_template(
+        *miner_account.address(),
+        auth_key_prefix.clone(),
+        None,
+        vec![],
+    )?;
+    // only the block metadata txn is executed.
+    let block_metadata = BlockMetadata::new(
+        header.id(),
+        block_template.timestamp,
+        *miner_account.address(),
+        auth_key_prefix,
+    );
+    assert_eq!(
+        block_template.accumulator_root,
+        accumulator_root(&[Transaction::BlockMetadata(block_metadata)])?
+    );
+    assert_ne!(block_template.state_root, header.state_root());
+
+    let block = DummyConsensus::create_block(config, &block_chain, block_template)?;
+    assert!(block.transactions().is_empty());
+    assert!(block_chain.apply(block)?);
+    Ok(())
+}
+
+#[stest::test]
+async fn test_create_block_template_with_txns() -> Result<()> {
+    let (mut block_chain, config, _collection) = gen_block_chain_for_test()?;
+    let header = block_chain.current_header();
+    let miner_account = WalletAccount::random();
+    let auth_key_prefix = Some(miner_account.get_auth_key().prefix().to_vec());
+    let receiver = WalletAccount::random();
+    // association account sequence number is 1 after genesis.
+    let user_txn = Executor::build_mint_txn(
+        *receiver.address(),
+        receiver.get_auth_key().prefix().to_vec(),
+        1,
+        10000,
+    )
+    .as_signed_user_txn()?
+    .clone();
+    let block_template = block_chain.create_block_template(
+        *miner_account.address(),
+        auth_key_prefix.clone(),
+        None,
+        vec![user_txn.clone()],
+    )?;
+    // the block metadata txn is executed before the user txns.
+    let block_metadata = BlockMetadata::new(
+        header.id(),
+        block_template.timestamp,
+        *miner_account.address(),
+        auth_key_prefix,
+    );
+    assert_eq!(
+        block_template.accumulator_root,
+        accumulator_root(&[
+            Transaction::BlockMetadata(block_metadata),
+            Transaction::UserTransaction(user_txn.clone()),
+        ])?
+    );
+
+    let block = DummyConsensus::create_block(config, &block_chain, block_template)?;
+    assert_eq!(block.transactions(), &[user_txn]);
+    assert!(block_chain.apply(block)?);
+    Ok(())
+}
+
+#[stest::test]
+async fn test_block_template_with_mock_time_service() -> Result<()> {
+    let (mut block_chain, _config, _collection) = gen_block_chain_for_test()?;
+    let miner_account = WalletAccount::random();
+    let auth_key_prefix = Some(miner_account.get_auth_key().prefix().to_vec());
+    let receiver = WalletAccount::random();
+    let user_txn = Executor::build_mint_txn(
+        *receiver.address(),
+        receiver.get_auth_key().prefix().to_vec(),
+        1,
+        10000,
+    )
+    .as_signed_user_txn()?
+    .clone();
+    let expiration_secs = user_txn.expiration_time().as_secs();
+
+    let time_service = Arc::new(MockTimeService::new_with_secs(expiration_secs - 10));
+    block_chain.set_time_service(time_service.clone());
+    assert!(!user_txn.is_expired(time_service.now_secs()));
+    let block_template = block_chain.create_block_template(
+        *miner_account.address(),
+        auth_key_prefix.clone(),
+        None,
+        vec![user_txn.clone()],
+    )?;
+    assert_eq!(block_template.timestamp, expiration_secs - 10);
+    assert_eq!(block_template.body.transactions(), &[user_txn.clone()]);
+
+    // the txn expires before the next block.
+    time_service.increase(Duration::from_secs(20));
+    assert!(user_txn.is_expired(time_service.now_secs()));
+    let block_template = block_chain.create_block_template(
+        *miner_account.address(),
+        auth_key_prefix,
+        None,
+        vec![user_txn],
+    )?;
+    assert_eq!(block_template.timestamp, expiration_secs + 10);
+    assert!(block_template.body.transactions().is_empty());
+    Ok(())
+}
+
+#[stest::test]
+async fn test_verify_total_difficulty() -> Result<()> {
+    let (mut block_chain, config, _collection) = gen_block_chain_for_test()?;
+    let genesis_id = block_chain.current_header().id();
+    let miner_account = WalletAccount::random();
+    let mut block_ids = vec![];
+    for _ in 0..3 {
+        let block_template = block_chain.create_block_template(
+            *miner_account.address(),
+            Some(miner_account.get_auth_key().prefix().to_vec()),
+            None,
+            vec![],
+        )?;
+        let block = DummyConsensus::create_block(config.clone(), &block_chain, block_template)?;
+        block_ids.push(block.header().id());
+        assert!(block_chain.apply(block)?);
+    }
+    block_chain.verify_total_difficulty(genesis_id, block_ids[2])?;
+    block_chain.verify_total_difficulty(block_ids[0], block_ids[2])?;
+    // `from` must be an ancestor of `to`.
+    assert!(block_chain
+        .verify_total_difficulty(block_ids[2], block_ids[0])
+        .is_err());
+
+    // tamper the total difficulty of the second block.
+    let mut block_info = ChainReader::get_block_info(&block_chain, Some(block_ids[1]))?.unwrap();
+    block_info.total_difficulty = block_info.total_difficulty + U512::one();
+    block_chain.save_block_info(block_info);
+    block_chain.verify_total_difficulty(genesis_id, block_ids[0])?;
+    let err = block_chain
+        .verify_total_difficulty(genesis_id, block_ids[2])
+        .unwrap_err();
+    // the first inconsistency is reported.
+    assert!(err.to_string().contains(&block_ids[1].to_string()));
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::StartupInfoFlusher;
+use crypto::HashValue;
+use std::time::Duration;
+use types::{
+    account_address::AccountAddress,
+    block::BlockHeader,
+    startup_info::{ChainInfo, StartupInfo},
+    U256,
+};
+
+fn gen_startup_info(number: u64) -> StartupInfo {
+    let header = BlockHeader::new(
+        HashValue::random(),
+        number,
+        number,
+        AccountAddress::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        0,
+        U256::zero(),
+        vec![],
+    );
+    StartupInfo::new(ChainInfo::new(None, header.id(), &header), vec![])
+}
+
+#[test]
+fn test_startup_info_write_through() {
+    let mut flusher = StartupInfoFlusher::new(Duration::from_millis(0), 0);
+    let mut writes = 0;
+    for i in 0..10 {
+        let flushed = flusher
+            .update(gen_startup_info(i), |_| {
+                writes += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert!(flushed);
+    }
+    assert_eq!(writes, 10);
+    assert!(!flusher.has_pending());
+}
+
+#[test]
+fn test_startup_info_flush_coalesce() {
+    let mut flusher = StartupInfoFlusher::new(Duration::from_secs(3600), 4);
+    let mut written = Vec::new();
+    let mut latest = None;
+    for i in 0..10 {
+        let startup_info = gen_startup_info(i);
+        latest = Some(startup_info.clone());
+        flusher
+            .update(startup_info, |startup_info| {
+                written.push(startup_info);
+                Ok(())
+            })
+            .unwrap();
+    }
+    // 10 head changes coalesce into 2 writes, the last 2 changes are pending.
+    assert_eq!(written.len(), 2);
+    assert!(flusher.has_pending());
+
+    flusher
+        .flush(|startup_info| {
+            written.push(startup_info);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(written.len(), 3);
+    assert_eq!(written.last(), latest.as_ref());
+    assert!(!flusher.has_pending());
+}
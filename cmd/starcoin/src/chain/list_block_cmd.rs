@@ -11,8 +11,9 @@ use structopt::StructOpt;
 #[derive(Debug, StructOpt)]
 #[structopt(name = "list_block")]
 pub struct GetOpt {
-    #[structopt(name = "number", long, default_value = "0")]
-    number: usize,
+    /// The number of the first block, default to the head.
+    #[structopt(name = "number", long)]
+    number: Option<u64>,
     #[structopt(name = "count", long, default_value = "1")]
     count: usize,
 }
@@ -31,8 +32,9 @@ impl CommandAction for ListBlockCommand {
     ) -> Result<Self::ReturnItem> {
         let client = ctx.state().client();
         let opt = ctx.opt();
-        let blocks = client.chain_get_blocks_by_number(opt.number as u64, opt.count as u64)?;
-        let blockview = blocks
+        let page = client.chain_get_blocks_by_number(opt.number, opt.count as u64)?;
+        let blockview = page
+            .blocks
             .iter()
             .map(|block| BlockView::from(block.clone()))
             .collect();
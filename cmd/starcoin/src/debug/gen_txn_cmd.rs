@@ -8,10 +8,11 @@ use scmd::{CommandAction, ExecContext};
 use serde::{Deserialize, Serialize};
 use starcoin_executor::executor::Executor;
 use starcoin_executor::TransactionExecutor;
-use starcoin_rpc_client::RemoteStateReader;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
 use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::authenticator::AuthenticationKey;
+use starcoin_types::transaction::SignedUserTransaction;
 use std::time::Duration;
 use structopt::StructOpt;
 
@@ -40,6 +41,12 @@ pub struct GenTxnOpt {
     ///Transfer amount of every transaction, default is 1.
     #[structopt(short = "v", default_value = "1")]
     amount: u64,
+
+    ///Timeout seconds of every transaction submission, a timeout submission is counted as failed,
+    ///and the sequence number of the next transaction is fetched from the node again.
+    ///If absent, wait the submission without timeout.
+    #[structopt(long = "timeout")]
+    timeout: Option<u64>,
 }
 
 pub struct GenTxnCommand;
@@ -50,6 +57,7 @@ pub struct GenerateResult {
     total_amount: u64,
     submit_success: usize,
     submit_fail: usize,
+    submit_timeout: usize,
     //TODO add execute result and gas_used after watch api provider.
 }
 
@@ -108,30 +116,297 @@ impl CommandAction for GenTxnCommand {
                 "Can not find account on chain by address:{}",
                 sender.address()
             ))?;
-        let sequence_number = account_resource.sequence_number();
         let mut gen_result = GenerateResult::default();
         gen_result.count = opt.count;
-        for i in 0..opt.count {
-            let (to, to_auth_key_prefix) = account_provider.as_ref()();
-
-            let raw_txn = Executor::build_transfer_txn(
-                sender.address,
-                vec![],
-                to,
-                to_auth_key_prefix,
-                sequence_number + i as u64,
-                opt.amount,
-            );
-            gen_result.total_amount += opt.amount;
-            let txn = client.wallet_sign_txn(raw_txn)?;
-            let result = client.submit_transaction(txn.clone())?;
-            if result {
-                gen_result.submit_success += 1;
+        gen_result.total_amount = opt.amount * opt.count as u64;
+        submit_txns(
+            client,
+            sender.address,
+            account_resource.sequence_number(),
+            opt.count,
+            opt.timeout.map(Duration::from_secs),
+            &mut gen_result,
+            |sequence_number| {
+                let (to, to_auth_key_prefix) = account_provider.as_ref()();
+                let raw_txn = Executor::build_transfer_txn(
+                    sender.address,
+                    vec![],
+                    to,
+                    to_auth_key_prefix,
+                    sequence_number,
+                    opt.amount,
+                );
+                client.wallet_sign_txn(raw_txn)
+            },
+        )?;
+
+        Ok(gen_result)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Submitted {
+    Accepted,
+    Rejected,
+    /// The node may still accept the txn after the timeout.
+    Timeout,
+}
+
+/// Submit `count` txns of `sender` built by `build_txn` from the `sequence_number`, the sequence
+/// number only advances after a txn is accepted, so a failed txn does not fail all the txns after
+/// it. After a timeout, the sequence number is fetched from the node again.
+fn submit_txns<F>(
+    client: &RpcClient,
+    sender: AccountAddress,
+    mut sequence_number: u64,
+    count: usize,
+    timeout: Option<Duration>,
+    gen_result: &mut GenerateResult,
+    mut build_txn: F,
+) -> Result<()>
+where
+    F: FnMut(u64) -> Result<SignedUserTransaction>,
+{
+    for _ in 0..count {
+        let txn = build_txn(sequence_number)?;
+        match submit_txn(client, txn, timeout, gen_result)? {
+            Submitted::Accepted => sequence_number += 1,
+            Submitted::Rejected => {}
+            Submitted::Timeout => sequence_number = next_sequence_number(client, sender)?,
+        }
+    }
+    Ok(())
+}
+
+/// The sequence number of the next txn of `sender`, after its txns in the pool if any, or else
+/// the one on chain.
+fn next_sequence_number(client: &RpcClient, sender: AccountAddress) -> Result<u64> {
+    if let Some(sequence_number) = client.next_sequence_number_in_txpool(sender)? {
+        return Ok(sequence_number);
+    }
+    let chain_state_reader = RemoteStateReader::new(client);
+    let account_resource = AccountStateReader::new(&chain_state_reader)
+        .get_account_resource(&sender)?
+        .ok_or_else(|| format_err!("Can not find account on chain by address:{}", sender))?;
+    Ok(account_resource.sequence_number())
+}
+
+/// Submit the txn and record the result.
+fn submit_txn(
+    client: &RpcClient,
+    txn: SignedUserTransaction,
+    timeout: Option<Duration>,
+    gen_result: &mut GenerateResult,
+) -> Result<Submitted> {
+    let result = match timeout {
+        Some(timeout) => client.submit_transaction_with_timeout(txn, timeout)?,
+        None => Some(client.submit_transaction(txn)?),
+    };
+    let submitted = match result {
+        Some(result) if result.is_accepted() => {
+            gen_result.submit_success += 1;
+            Submitted::Accepted
+        }
+        Some(_) => {
+            gen_result.submit_fail += 1;
+            Submitted::Rejected
+        }
+        None => {
+            gen_result.submit_fail += 1;
+            gen_result.submit_timeout += 1;
+            Submitted::Timeout
+        }
+    };
+    Ok(submitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::futures::future;
+    use jsonrpc_core::{Error, IoHandler};
+    use starcoin_crypto::ed25519::Ed25519PublicKey;
+    use starcoin_rpc_api::txpool::TxPoolApi;
+    use starcoin_rpc_api::FutureResult;
+    use starcoin_types::transaction::{AdmissionResult, PreflightResult, RawUserTransaction};
+    use std::sync::{Arc, Mutex};
+
+    /// A txpool records the sequence numbers of the submitted txns, and accepts them except the
+    /// `reject_index`th submission, it never responds when `stalled`, or to the `stall_index`th
+    /// submission which it still accepts.
+    #[derive(Default)]
+    struct MockTxPool {
+        stalled: bool,
+        stall_index: Option<usize>,
+        reject_index: Option<usize>,
+        submitted: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl TxPoolApi for MockTxPool {
+        fn submit_transaction(&self, txn: SignedUserTransaction) -> FutureResult<AdmissionResult> {
+            let mut submitted = self.submitted.lock().unwrap();
+            let index = submitted.len();
+            submitted.push(txn.sequence_number());
+            if self.stalled || self.stall_index == Some(index) {
+                Box::new(future::empty::<_, Error>())
+            } else if self.reject_index == Some(index) {
+                Box::new(future::ok::<_, Error>(AdmissionResult::Discarded(
+                    "mock reject".to_string(),
+                )))
             } else {
-                gen_result.submit_fail += 1;
+                Box::new(future::ok::<_, Error>(AdmissionResult::Accepted))
             }
         }
 
-        Ok(gen_result)
+        fn submit_transactions(
+            &self,
+            txns: Vec<SignedUserTransaction>,
+        ) -> FutureResult<Vec<AdmissionResult>> {
+            Box::new(future::ok::<_, Error>(
+                txns.iter().map(|_| AdmissionResult::Accepted).collect(),
+            ))
+        }
+
+        fn preflight(
+            &self,
+            _raw_txn: RawUserTransaction,
+            _public_key: Ed25519PublicKey,
+        ) -> FutureResult<PreflightResult> {
+            unimplemented!()
+        }
+
+        fn next_sequence_number(&self, _address: AccountAddress) -> FutureResult<Option<u64>> {
+            // the stalled submission is accepted, the rejected one is not in the pool.
+            let submitted = self.submitted.lock().unwrap();
+            let next = submitted
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| self.reject_index != Some(*index))
+                .map(|(_, seq_number)| seq_number + 1)
+                .max();
+            Box::new(future::ok::<_, Error>(next))
+        }
+    }
+
+    fn mock_client(txpool: MockTxPool) -> RpcClient {
+        let mut io = IoHandler::new();
+        io.extend_with(txpool.to_delegate());
+        RpcClient::connect_local(io)
+    }
+
+    #[test]
+    fn test_submit_timeout() -> Result<()> {
+        let timeout = Some(Duration::from_millis(200));
+        let mut gen_result = GenerateResult::default();
+        let client = mock_client(MockTxPool {
+            stalled: true,
+            ..Default::default()
+        });
+        submit_txn(
+            &client,
+            SignedUserTransaction::mock(),
+            timeout,
+            &mut gen_result,
+        )?;
+        submit_txn(
+            &client,
+            SignedUserTransaction::mock(),
+            timeout,
+            &mut gen_result,
+        )?;
+        assert_eq!(gen_result.submit_success, 0);
+        assert_eq!(gen_result.submit_fail, 2);
+        assert_eq!(gen_result.submit_timeout, 2);
+
+        let client = mock_client(MockTxPool::default());
+        submit_txn(
+            &client,
+            SignedUserTransaction::mock(),
+            timeout,
+            &mut gen_result,
+        )?;
+        submit_txn(
+            &client,
+            SignedUserTransaction::mock(),
+            None,
+            &mut gen_result,
+        )?;
+        assert_eq!(gen_result.submit_success, 2);
+        assert_eq!(gen_result.submit_fail, 2);
+        assert_eq!(gen_result.submit_timeout, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequence_number_after_reject() -> Result<()> {
+        let submitted = Arc::new(Mutex::new(vec![]));
+        let client = mock_client(MockTxPool {
+            reject_index: Some(1),
+            submitted: submitted.clone(),
+            ..Default::default()
+        });
+        let mut gen_result = GenerateResult::default();
+        submit_mock_txns(&client, 5, 3, None, &mut gen_result)?;
+        // the third txn reuses the sequence number of the rejected one.
+        assert_eq!(*submitted.lock().unwrap(), vec![5, 6, 6]);
+        assert_eq!(gen_result.submit_success, 2);
+        assert_eq!(gen_result.submit_fail, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequence_number_after_timeout() -> Result<()> {
+        let submitted = Arc::new(Mutex::new(vec![]));
+        let client = mock_client(MockTxPool {
+            stall_index: Some(1),
+            submitted: submitted.clone(),
+            ..Default::default()
+        });
+        let mut gen_result = GenerateResult::default();
+        submit_mock_txns(
+            &client,
+            5,
+            3,
+            Some(Duration::from_millis(200)),
+            &mut gen_result,
+        )?;
+        // the timeout txn is in the pool, so the third txn follows it.
+        assert_eq!(*submitted.lock().unwrap(), vec![5, 6, 7]);
+        assert_eq!(gen_result.submit_success, 2);
+        assert_eq!(gen_result.submit_timeout, 1);
+        Ok(())
+    }
+
+    fn submit_mock_txns(
+        client: &RpcClient,
+        sequence_number: u64,
+        count: usize,
+        timeout: Option<Duration>,
+        gen_result: &mut GenerateResult,
+    ) -> Result<()> {
+        let template = SignedUserTransaction::mock();
+        submit_txns(
+            client,
+            template.sender(),
+            sequence_number,
+            count,
+            timeout,
+            gen_result,
+            |sequence_number| {
+                let raw_txn = Executor::build_transfer_txn(
+                    template.sender(),
+                    vec![],
+                    AccountAddress::random(),
+                    vec![],
+                    sequence_number,
+                    1,
+                );
+                Ok(SignedUserTransaction::new(
+                    raw_txn,
+                    template.public_key(),
+                    template.signature(),
+                ))
+            },
+        )
     }
 }
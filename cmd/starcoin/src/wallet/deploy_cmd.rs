@@ -1,13 +1,23 @@
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
-use anyhow::{bail, Result};
+use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_LENGTH};
 use starcoin_crypto::hash::{CryptoHash, HashValue};
-use starcoin_rpc_client::RemoteStateReader;
-use starcoin_state_api::AccountStateReader;
+use starcoin_executor::executor::Executor;
+use starcoin_executor::TransactionExecutor;
+use starcoin_rpc_client::{RemoteStateReader, RpcClient};
+use starcoin_state_api::{AccountStateReader, ChainStateReader, OverlayChainState};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config;
-use starcoin_types::transaction::{Module, RawUserTransaction};
+use starcoin_types::time::{RealTimeService, TimeService};
+use starcoin_types::transaction::{
+    Module, RawUserTransaction, SignedUserTransaction, TransactionStatus,
+};
+use starcoin_types::vm_error::StatusCode;
+use starcoin_wallet_api::WalletAccount;
+use std::convert::TryFrom;
 use std::fs::OpenOptions;
 use std::io::Read;
 use std::time::Duration;
@@ -21,15 +31,60 @@ pub struct DeployOpt {
     #[structopt(
         short = "f",
         name = "bytecode_file",
-        help = "module bytecode file path"
+        help = "module bytecode file path, repeat it to deploy multi modules in order",
+        required = true
     )]
-    bytecode_file: String,
+    bytecode_file: Vec<String>,
     #[structopt(
         short = "g",
         name = "max-gas-amount",
-        help = "max gas used to deploy the module"
+        help = "max gas used to deploy every module"
     )]
     max_gas_amount: u64,
+    #[structopt(
+        long = "dry-run",
+        help = "execute the deploy txns in order against current chain state without signing and submitting them"
+    )]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DeployStatus {
+    /// The deploy txn is accepted by the txpool.
+    Submitted,
+    /// The deploy txn is executed by the dry run.
+    Executed,
+    /// The module failed to load, dry run or submit.
+    Rejected,
+    /// Not deployed as a module before it is rejected.
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeployResult {
+    pub bytecode_file: String,
+    pub status: DeployStatus,
+    /// Hash of the submitted txn, the dry run does not sign the txn so it has no hash.
+    pub txn_hash: Option<HashValue>,
+    /// Execution status of the dry run.
+    pub dry_run_status: Option<String>,
+    /// Gas used by the dry run.
+    pub gas_used: Option<u64>,
+    /// Why the module is rejected.
+    pub error: Option<String>,
+}
+
+impl DeployResult {
+    fn new(bytecode_file: String) -> Self {
+        Self {
+            bytecode_file,
+            status: DeployStatus::Skipped,
+            txn_hash: None,
+            dry_run_status: None,
+            gas_used: None,
+            error: None,
+        }
+    }
 }
 
 pub struct DeployCommand;
@@ -38,58 +93,436 @@ impl CommandAction for DeployCommand {
     type State = CliState;
     type GlobalOpt = StarcoinOpt;
     type Opt = DeployOpt;
-    type ReturnItem = HashValue;
+    type ReturnItem = Vec<DeployResult>;
 
     fn run(
         &self,
         ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
     ) -> Result<Self::ReturnItem> {
         let opt = ctx.opt();
-        let bytecode_path = ctx.opt().bytecode_file.clone();
-        let mut file = OpenOptions::new()
-            .read(true)
-            .write(false)
-            .open(bytecode_path)?;
-        let mut bytecode = vec![];
-        file.read_to_end(&mut bytecode)?;
-        let compiled_module = match move_vm::CompiledModule::deserialize(bytecode.as_slice()) {
-            Err(e) => {
-                bail!("invalid bytecode file, cannot deserialize as module, {}", e);
-            }
-            Ok(compiled_module) => compiled_module,
-        };
-        let module_address = compiled_module.address().clone();
-        // from libra address to our address
-        let module_address = AccountAddress::new(module_address.into());
         let client = ctx.state().client();
+        let signer = client
+            .wallet_default()?
+            .ok_or_else(|| format_err!("default account not exists in wallet"))?;
         let chain_state_reader = RemoteStateReader::new(client);
         let account_state_reader = AccountStateReader::new(&chain_state_reader);
-        let account_resource = account_state_reader.get_account_resource(&module_address)?;
+        let account_resource = account_state_reader.get_account_resource(&signer.address)?;
 
         if account_resource.is_none() {
             bail!(
                 "account of module address {} not exists on chain",
-                &module_address
+                &signer.address
             );
         }
         let account_resource = account_resource.unwrap();
-        let deploy_txn = RawUserTransaction::new_module(
-            module_address,
+        Ok(deploy_modules(
+            client,
+            &chain_state_reader,
+            &signer,
             account_resource.sequence_number(),
-            Module::new(bytecode),
+            opt.bytecode_file.as_slice(),
             opt.max_gas_amount,
-            1,
-            account_config::starcoin_type_tag(),
-            Duration::from_secs(60 * 5),
+            opt.dry_run,
+        ))
+    }
+}
+
+/// Deploy the modules in order from the `sequence_number` of the `signer`, stop at the first
+/// rejected module and skip the modules after it.
+fn deploy_modules(
+    client: &RpcClient,
+    chain_state_reader: &dyn ChainStateReader,
+    signer: &WalletAccount,
+    mut sequence_number: u64,
+    bytecode_files: &[String],
+    max_gas_amount: u64,
+    dry_run: bool,
+) -> Vec<DeployResult> {
+    // the dry run of a module sees the modules and the sequence number of the ones before it.
+    let chain_state = OverlayChainState::new(chain_state_reader);
+    let mut rejected = false;
+    let mut results = vec![];
+    for bytecode_file in bytecode_files {
+        let mut result = DeployResult::new(bytecode_file.clone());
+        if rejected {
+            results.push(result);
+            continue;
+        }
+        let deployed = load_module(bytecode_file, signer.address).and_then(|bytecode| {
+            let deploy_txn = RawUserTransaction::new_module(
+                signer.address,
+                sequence_number,
+                Module::new(bytecode),
+                max_gas_amount,
+                1,
+                account_config::starcoin_type_tag(),
+                // expire in 5 minutes.
+                Duration::from_secs(RealTimeService::new().now_secs() + 60 * 5),
+            );
+            if dry_run {
+                dry_run_module(
+                    &chain_state,
+                    deploy_txn,
+                    signer.public_key.clone(),
+                    &mut result,
+                )
+            } else {
+                submit(client, deploy_txn, &mut result)
+            }
+        });
+        match deployed {
+            Ok(()) => {
+                result.status = if dry_run {
+                    DeployStatus::Executed
+                } else {
+                    DeployStatus::Submitted
+                };
+                sequence_number += 1;
+            }
+            Err(e) => {
+                result.status = DeployStatus::Rejected;
+                result.error = Some(e.to_string());
+                rejected = true;
+            }
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Simulate the deploy txn and keep its writes in `chain_state` for the next modules.
+fn dry_run_module(
+    chain_state: &OverlayChainState,
+    deploy_txn: RawUserTransaction,
+    public_key: Ed25519PublicKey,
+    result: &mut DeployResult,
+) -> Result<()> {
+    // the simulation does not check the signature, so the wallet is not required to be unlocked.
+    let signature = Ed25519Signature::try_from(&[0u8; ED25519_SIGNATURE_LENGTH][..])?;
+    let txn = SignedUserTransaction::new(deploy_txn, public_key, signature);
+    let output = Executor::simulate_transaction(chain_state, txn)?;
+    let status = output.status();
+    result.dry_run_status = Some(format!("{:?}", status));
+    result.gas_used = Some(output.gas_used());
+    match status {
+        TransactionStatus::Keep(vm_status) if vm_status.major_status == StatusCode::EXECUTED => {
+            chain_state.apply_write_set(output.write_set());
+            Ok(())
+        }
+        _ => bail!("deploy-txn dry run failed: {:?}", status),
+    }
+}
+
+fn submit(
+    client: &RpcClient,
+    deploy_txn: RawUserTransaction,
+    result: &mut DeployResult,
+) -> Result<()> {
+    let signed_txn = client.wallet_sign_txn(deploy_txn)?;
+    result.txn_hash = Some(CryptoHash::crypto_hash(&signed_txn));
+    let admission = client.submit_transaction(signed_txn)?;
+    if admission.is_accepted() {
+        Ok(())
+    } else {
+        bail!("deploy-txn is reject by node: {:?}", admission)
+    }
+}
+
+/// Read the module file, the module must be deserializable and of the `signer` address.
+fn load_module(bytecode_file: &str, signer: AccountAddress) -> Result<Vec<u8>> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(false)
+        .open(bytecode_file)?;
+    let mut bytecode = vec![];
+    file.read_to_end(&mut bytecode)?;
+    check_module_signer(module_address(bytecode.as_slice())?, signer)?;
+    Ok(bytecode)
+}
+
+fn module_address(bytecode: &[u8]) -> Result<AccountAddress> {
+    let compiled_module = match move_vm::CompiledModule::deserialize(bytecode) {
+        Err(e) => {
+            bail!("invalid bytecode file, cannot deserialize as module, {}", e);
+        }
+        Ok(compiled_module) => compiled_module,
+    };
+    let module_address = compiled_module.address().clone();
+    // from libra address to our address
+    Ok(AccountAddress::new(module_address.into()))
+}
+
+/// The deploy txn is sent by the module address, so it must be signed by the account of the
+/// module address, check it before the node rejects the txn.
+fn check_module_signer(module_address: AccountAddress, signer: AccountAddress) -> Result<()> {
+    if module_address != signer {
+        bail!(
+            "module address {} does not match the signer {}, a module can only be deployed by the account of its address",
+            module_address,
+            signer
         );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::mock_wallet::MockWallet;
+    use bytecode_verifier::VerifiedModule;
+    use compiler::Compiler;
+    use jsonrpc_core::futures::future;
+    use jsonrpc_core::IoHandler;
+    use starcoin_config::{temp_path, ChainNetwork};
+    use starcoin_crypto::ed25519::Ed25519PublicKey;
+    use starcoin_rpc_api::txpool::TxPoolApi;
+    use starcoin_rpc_api::wallet::WalletApi;
+    use starcoin_rpc_api::FutureResult;
+    use starcoin_state_api::ChainStateWriter;
+    use starcoin_state_tree::mock::MockStateNodeStore;
+    use starcoin_statedb::ChainStateDB;
+    use starcoin_types::transaction::{AdmissionResult, PreflightResult, Transaction};
+    use starcoin_vm_runtime::account::Account;
+    use starcoin_vm_runtime::common_transactions::create_account_txn_sent_as_association;
+    use starcoin_vm_runtime::mock_vm::KEEP_STATUS;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use vm::file_format::empty_module;
+
+    /// A txpool which accepts every txn and counts them.
+    #[derive(Clone, Default)]
+    struct MockTxPool {
+        submitted: Arc<AtomicUsize>,
+    }
+
+    impl TxPoolApi for MockTxPool {
+        fn submit_transaction(&self, _tx: SignedUserTransaction) -> FutureResult<AdmissionResult> {
+            self.submitted.fetch_add(1, Ordering::SeqCst);
+            Box::new(future::ok(AdmissionResult::Accepted))
+        }
+
+        fn submit_transactions(
+            &self,
+            _txns: Vec<SignedUserTransaction>,
+        ) -> FutureResult<Vec<AdmissionResult>> {
+            unimplemented!()
+        }
+
+        fn preflight(
+            &self,
+            _raw_txn: RawUserTransaction,
+            _public_key: Ed25519PublicKey,
+        ) -> FutureResult<PreflightResult> {
+            unimplemented!()
+        }
 
-        let signed_txn = client.wallet_sign_txn(deploy_txn)?;
-        let txn_hash = CryptoHash::crypto_hash(&signed_txn);
-        let succ = client.submit_transaction(signed_txn)?;
-        if succ {
-            Ok(txn_hash)
-        } else {
-            bail!("deploy-txn is reject by node")
+        fn next_sequence_number(&self, _address: AccountAddress) -> FutureResult<Option<u64>> {
+            unimplemented!()
         }
     }
+
+    fn mock_client(txpool: MockTxPool, wallet: MockWallet) -> RpcClient {
+        let mut io = IoHandler::new();
+        io.extend_with(TxPoolApi::to_delegate(txpool));
+        io.extend_with(WalletApi::to_delegate(wallet));
+        RpcClient::connect_local(io)
+    }
+
+    /// The genesis state with the `account` created.
+    fn gen_chain_state(account: &Account) -> Result<ChainStateDB> {
+        let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config())?;
+        let chain_state = ChainStateDB::new(Arc::new(MockStateNodeStore::new()), None);
+        chain_state.apply(state_set)?;
+        let txn = Transaction::UserTransaction(create_account_txn_sent_as_association(
+            account, 1, 50_000_000,
+        ));
+        let output = Executor::execute_transaction(&chain_state, txn)?;
+        assert_eq!(KEEP_STATUS.clone(), *output.status());
+        Ok(chain_state)
+    }
+
+    fn empty_module_bytecode() -> Vec<u8> {
+        let mut bytecode = vec![];
+        empty_module()
+            .freeze()
+            .expect("empty module must be valid")
+            .serialize(&mut bytecode)
+            .expect("serialize module must success");
+        bytecode
+    }
+
+    fn module_bytecode(address: AccountAddress) -> Vec<u8> {
+        let compiler = Compiler {
+            address: address.into(),
+            ..Compiler::default()
+        };
+        compiler
+            .into_module_blob("file_name", "module M {}")
+            .unwrap()
+    }
+
+    /// Write the modules to files in `dir` and return the file paths.
+    fn write_modules(dir: &Path, modules: Vec<(&str, Vec<u8>)>) -> Result<Vec<String>> {
+        modules
+            .into_iter()
+            .map(|(name, bytecode)| {
+                let file = dir.join(name);
+                std::fs::write(&file, bytecode)?;
+                Ok(file.to_str().unwrap().to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_address_differs_from_signer() -> Result<()> {
+        let bytecode = empty_module_bytecode();
+        let module_address = module_address(bytecode.as_slice())?;
+        let default_account = AccountAddress::random();
+        assert_ne!(module_address, default_account);
+        assert!(check_module_signer(module_address, default_account).is_err());
+        check_module_signer(module_address, module_address)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_module() -> Result<()> {
+        let dir = temp_path();
+        let bytecode = empty_module_bytecode();
+        let signer = module_address(bytecode.as_slice())?;
+        let files = write_modules(
+            dir.path(),
+            vec![("a.mv", bytecode.clone()), ("bad.mv", vec![1, 2, 3])],
+        )?;
+        assert_eq!(load_module(files[0].as_str(), signer)?, bytecode);
+        assert!(load_module(files[1].as_str(), signer).is_err());
+        // the module must be of the signer.
+        assert!(load_module(files[0].as_str(), AccountAddress::random()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deploy_stop_at_rejected_module() -> Result<()> {
+        let dir = temp_path();
+        let wallet = MockWallet::new(true);
+        let signer = WalletAccount::new(wallet.address, wallet.public_key.clone(), true);
+        let bytecode = module_bytecode(wallet.address);
+        let files = write_modules(
+            dir.path(),
+            vec![
+                ("a.mv", bytecode.clone()),
+                ("bad.mv", vec![1, 2, 3]),
+                ("b.mv", bytecode),
+            ],
+        )?;
+        let txpool = MockTxPool::default();
+        let client = mock_client(txpool.clone(), wallet);
+        let chain_state = ChainStateDB::new(Arc::new(MockStateNodeStore::new()), None);
+        let results = deploy_modules(
+            &client,
+            &chain_state,
+            &signer,
+            0,
+            files.as_slice(),
+            100_000,
+            false,
+        );
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].status, DeployStatus::Submitted);
+        assert!(results[0].txn_hash.is_some());
+        assert!(results[0].error.is_none());
+        assert_eq!(results[1].status, DeployStatus::Rejected);
+        assert!(results[1].txn_hash.is_none());
+        assert!(results[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("cannot deserialize as module"));
+        // the module after the rejected one is not submitted.
+        assert_eq!(results[2].status, DeployStatus::Skipped);
+        assert!(results[2].txn_hash.is_none());
+        assert!(results[2].error.is_none());
+        assert_eq!(txpool.submitted.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run() -> Result<()> {
+        let dir = temp_path();
+        let account = Account::new();
+        let chain_state = gen_chain_state(&account)?;
+        let signer = WalletAccount::new(*account.address(), account.pubkey.clone(), true);
+        let txpool = MockTxPool::default();
+        let client = mock_client(txpool.clone(), MockWallet::new(false));
+        let files = write_modules(
+            dir.path(),
+            vec![("m.mv", module_bytecode(*account.address()))],
+        )?;
+        let results = deploy_modules(
+            &client,
+            &chain_state,
+            &signer,
+            0,
+            files.as_slice(),
+            100_000,
+            true,
+        );
+        assert_eq!(results.len(), 1);
+        assert!(results[0].error.is_none(), "{:?}", results[0]);
+        assert!(results[0].gas_used.unwrap() > 0);
+        assert_eq!(results[0].status, DeployStatus::Executed);
+        assert!(results[0].txn_hash.is_none());
+        // nothing reaches the pool or the chain state.
+        assert_eq!(txpool.submitted.load(Ordering::SeqCst), 0);
+        let account_resource = AccountStateReader::new(&chain_state)
+            .get_account_resource(account.address())?
+            .unwrap();
+        assert_eq!(account_resource.sequence_number(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run_missing_dependency() -> Result<()> {
+        let dir = temp_path();
+        let account = Account::new();
+        let chain_state = gen_chain_state(&account)?;
+        let signer = WalletAccount::new(*account.address(), account.pubkey.clone(), true);
+        let txpool = MockTxPool::default();
+        let client = mock_client(txpool.clone(), MockWallet::new(false));
+        // module N is not published, so M which depends on it can not be published.
+        let compiler = Compiler {
+            address: (*account.address()).into(),
+            ..Compiler::default()
+        };
+        let dep_module = VerifiedModule::new(
+            compiler
+                .into_compiled_module("dep_file_name", "module N {}")
+                .unwrap(),
+        )
+        .unwrap();
+        let compiler = Compiler {
+            address: (*account.address()).into(),
+            extra_deps: vec![dep_module],
+            ..Compiler::default()
+        };
+        let program = format!("module M {{ import {}.N; }}", account.address());
+        let bytecode = compiler
+            .into_module_blob("file_name", program.as_str())
+            .unwrap();
+        let files = write_modules(dir.path(), vec![("m.mv", bytecode)])?;
+        let results = deploy_modules(
+            &client,
+            &chain_state,
+            &signer,
+            0,
+            files.as_slice(),
+            100_000,
+            true,
+        );
+        assert_eq!(results.len(), 1);
+        assert!(results[0].error.is_some());
+        assert_eq!(results[0].status, DeployStatus::Rejected);
+        assert_eq!(txpool.submitted.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
 }
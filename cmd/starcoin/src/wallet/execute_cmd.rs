@@ -8,6 +8,7 @@ use starcoin_state_api::AccountStateReader;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_config;
 use starcoin_types::language_storage::TypeTag;
+use starcoin_types::time::{RealTimeService, TimeService};
 use starcoin_types::transaction::{
     parse_as_transaction_argument, RawUserTransaction, Script, TransactionArgument,
 };
@@ -103,16 +104,17 @@ impl CommandAction for ExecuteCommand {
             opt.max_gas_amount,
             1,
             account_config::starcoin_type_tag(),
-            Duration::from_secs(60 * 5),
+            // expire in 5 minutes.
+            Duration::from_secs(RealTimeService::new().now_secs() + 60 * 5),
         );
 
         let signed_txn = client.wallet_sign_txn(script_txn)?;
         let txn_hash = CryptoHash::crypto_hash(&signed_txn);
-        let succ = client.submit_transaction(signed_txn)?;
-        if succ {
+        let result = client.submit_transaction(signed_txn)?;
+        if result.is_accepted() {
             Ok(txn_hash)
         } else {
-            bail!("execute-txn is reject by node")
+            bail!("execute-txn is reject by node: {:?}", result)
         }
     }
 }
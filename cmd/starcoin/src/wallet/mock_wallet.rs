@@ -0,0 +1,94 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpc_core::futures::future;
+use jsonrpc_core::Error;
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use starcoin_rpc_api::wallet::WalletApi;
+use starcoin_rpc_api::FutureResult;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use starcoin_wallet_api::error::AccountServiceError;
+use starcoin_wallet_api::WalletAccount;
+use std::convert::TryFrom;
+
+/// A wallet of a single account, it signs the txns only when `unlocked`.
+pub struct MockWallet {
+    private_key: Ed25519PrivateKey,
+    pub public_key: Ed25519PublicKey,
+    pub address: AccountAddress,
+    unlocked: bool,
+}
+
+impl MockWallet {
+    pub fn new(unlocked: bool) -> Self {
+        let private_key = Ed25519PrivateKey::try_from(&[1u8; 32][..]).unwrap();
+        let public_key = Ed25519PublicKey::from(&private_key);
+        let address = AccountAddress::from_public_key(&public_key);
+        Self {
+            private_key,
+            public_key,
+            address,
+            unlocked,
+        }
+    }
+}
+
+impl WalletApi for MockWallet {
+    fn default(&self) -> FutureResult<Option<WalletAccount>> {
+        unimplemented!()
+    }
+
+    fn create(&self, _password: String) -> FutureResult<WalletAccount> {
+        unimplemented!()
+    }
+
+    fn list(&self) -> FutureResult<Vec<WalletAccount>> {
+        unimplemented!()
+    }
+
+    fn get(&self, address: AccountAddress) -> FutureResult<Option<WalletAccount>> {
+        let account = if address == self.address {
+            Some(WalletAccount::new(address, self.public_key.clone(), true))
+        } else {
+            None
+        };
+        Box::new(future::ok::<_, Error>(account))
+    }
+
+    fn sign_txn(&self, raw_txn: RawUserTransaction) -> FutureResult<SignedUserTransaction> {
+        if self.unlocked {
+            let signed_txn = raw_txn
+                .sign(&self.private_key, self.public_key.clone())
+                .unwrap()
+                .into_inner();
+            Box::new(future::ok::<_, Error>(signed_txn))
+        } else {
+            Box::new(future::err::<SignedUserTransaction, _>(
+                Error::invalid_params(AccountServiceError::AccountLocked(self.address).to_string()),
+            ))
+        }
+    }
+
+    fn unlock(
+        &self,
+        _address: AccountAddress,
+        _password: String,
+        _duration: std::time::Duration,
+    ) -> FutureResult<()> {
+        unimplemented!()
+    }
+
+    fn import(
+        &self,
+        _address: AccountAddress,
+        _private_key: Vec<u8>,
+        _password: String,
+    ) -> FutureResult<WalletAccount> {
+        unimplemented!()
+    }
+
+    fn export(&self, _address: AccountAddress, _password: String) -> FutureResult<Vec<u8>> {
+        unimplemented!()
+    }
+}
@@ -8,6 +8,8 @@ mod execute_cmd;
 mod export_cmd;
 mod import_cmd;
 mod list_cmd;
+#[cfg(test)]
+mod mock_wallet;
 mod show_cmd;
 mod sign_txn_cmd;
 mod unlock_cmd;
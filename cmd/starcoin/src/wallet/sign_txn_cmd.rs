@@ -3,13 +3,55 @@
 
 use crate::cli_state::CliState;
 use crate::StarcoinOpt;
-use anyhow::Result;
+use anyhow::{bail, format_err, Result};
 use scmd::{CommandAction, ExecContext};
+use serde::Serialize;
+use starcoin_rpc_client::RpcClient;
+use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use starcoin_wallet_api::error::AccountServiceError;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use structopt::StructOpt;
 
+/// Sign the raw txn by the wallet account of its sender, the account must be unlocked.
 #[derive(Debug, StructOpt)]
 #[structopt(name = "sign_txn")]
-pub struct SignTxnOpt {}
+pub struct SignTxnOpt {
+    ///The file of the scs serialized raw txn.
+    ///If absent, read the hex of the scs serialized raw txn from stdin.
+    #[structopt(short = "i", parse(from_os_str))]
+    input: Option<PathBuf>,
+
+    ///Output format of the signed txn, support [hex|json], hex is the hex of the scs serialized txn.
+    #[structopt(long = "format", default_value = "hex")]
+    format: SignedTxnFormat,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SignedTxnFormat {
+    Hex,
+    Json,
+}
+
+impl FromStr for SignedTxnFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hex" => Ok(SignedTxnFormat::Hex),
+            "json" => Ok(SignedTxnFormat::Json),
+            _ => bail!("unknown signed txn format: {}, support [hex|json]", s),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SignTxnResult {
+    Hex(String),
+    Json(SignedUserTransaction),
+}
 
 pub struct SignTxnCommand;
 
@@ -17,13 +59,113 @@ impl CommandAction for SignTxnCommand {
     type State = CliState;
     type GlobalOpt = StarcoinOpt;
     type Opt = SignTxnOpt;
-    type ReturnItem = ();
+    type ReturnItem = SignTxnResult;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        let raw_txn = read_raw_txn(opt.input.as_ref().map(PathBuf::as_path))?;
+        sign_txn(client, raw_txn, opt.format)
+    }
+}
 
-    fn run(&self, ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>) -> Result<()> {
-        //let client = ctx.state().client();
-        let _opt = ctx.opt();
-        //let account = client.account_create(ctx.opt().password.clone())?;
-        println!("TODO sign txn command");
+fn read_raw_txn(input: Option<&Path>) -> Result<RawUserTransaction> {
+    let bytes = match input {
+        Some(input) => std::fs::read(input)?,
+        None => {
+            let mut encoded = String::new();
+            std::io::stdin().read_to_string(&mut encoded)?;
+            hex::decode(encoded.trim())
+                .map_err(|e| format_err!("invalid hex of the raw txn, {}", e))?
+        }
+    };
+    scs::from_bytes(bytes.as_slice())
+        .map_err(|e| format_err!("invalid raw txn, cannot deserialize as raw txn, {}", e))
+}
+
+fn sign_txn(
+    client: &RpcClient,
+    raw_txn: RawUserTransaction,
+    format: SignedTxnFormat,
+) -> Result<SignTxnResult> {
+    let sender = raw_txn.sender();
+    if client.wallet_get(sender)?.is_none() {
+        bail!("the txn sender {} not exists in wallet", sender);
+    }
+    let signed_txn = client.wallet_sign_txn(raw_txn).map_err(|e| {
+        // the wallet rpc returns the account locked error as the message of the rpc error.
+        let locked = AccountServiceError::AccountLocked(sender);
+        if e.to_string().contains(locked.to_string().as_str()) {
+            anyhow::Error::new(locked).context(format!(
+                "sign txn by the locked account {}, please unlock it by `wallet unlock`",
+                sender
+            ))
+        } else {
+            format_err!("sign txn by account {} failed, {}", sender, e)
+        }
+    })?;
+    Ok(match format {
+        SignedTxnFormat::Hex => SignTxnResult::Hex(hex::encode(scs::to_bytes(&signed_txn)?)),
+        SignedTxnFormat::Json => SignTxnResult::Json(signed_txn),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::mock_wallet::MockWallet;
+    use jsonrpc_core::IoHandler;
+    use starcoin_config::temp_path;
+    use starcoin_rpc_api::wallet::WalletApi;
+
+    fn mock_client(wallet: MockWallet) -> RpcClient {
+        let mut io = IoHandler::new();
+        io.extend_with(wallet.to_delegate());
+        RpcClient::connect_local(io)
+    }
+
+    #[test]
+    fn test_sign_txn() -> Result<()> {
+        let wallet = MockWallet::new(true);
+        let raw_txn = RawUserTransaction::mock_by_sender(wallet.address);
+        let dir = temp_path();
+        let input = dir.path().join("raw_txn");
+        std::fs::write(&input, scs::to_bytes(&raw_txn)?)?;
+        assert_eq!(read_raw_txn(Some(input.as_path()))?, raw_txn);
+
+        let client = mock_client(wallet);
+        let signed_txn = match sign_txn(&client, raw_txn.clone(), SignedTxnFormat::Hex)? {
+            SignTxnResult::Hex(encoded) => {
+                scs::from_bytes::<SignedUserTransaction>(hex::decode(encoded)?.as_slice())?
+            }
+            other => panic!("expect hex result, got {:?}", other),
+        };
+        assert_eq!(signed_txn.raw_txn(), &raw_txn);
+        assert!(signed_txn.check_signature().is_ok());
+        match sign_txn(&client, raw_txn, SignedTxnFormat::Json)? {
+            SignTxnResult::Json(txn) => assert_eq!(txn, signed_txn),
+            other => panic!("expect json result, got {:?}", other),
+        }
+
+        // the sender is not in the wallet.
+        let raw_txn = RawUserTransaction::mock();
+        assert!(sign_txn(&client, raw_txn, SignedTxnFormat::Hex).is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_sign_txn_locked() {
+        let wallet = MockWallet::new(false);
+        let address = wallet.address;
+        let raw_txn = RawUserTransaction::mock_by_sender(address);
+        let client = mock_client(wallet);
+        let err = sign_txn(&client, raw_txn, SignedTxnFormat::Hex).unwrap_err();
+        match err.downcast_ref::<AccountServiceError>() {
+            Some(AccountServiceError::AccountLocked(locked)) => assert_eq!(*locked, address),
+            _ => panic!("expect account locked error, got {:?}", err),
+        }
+    }
 }
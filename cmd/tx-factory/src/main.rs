@@ -197,6 +197,8 @@ impl TxnMocker {
             user_txn.sender(),
             user_txn.sequence_number(),
         );
-        self.client.submit_transaction(user_txn)
+        self.client
+            .submit_transaction(user_txn)
+            .map(|result| result.is_accepted())
     }
 }
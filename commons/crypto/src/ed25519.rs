@@ -1,4 +1,113 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::hash::HashValue;
+use crate::traits::VerifyingKey;
+use anyhow::{format_err, Result};
 pub use libra_crypto::ed25519::*;
+use std::cmp::Ordering;
+
+/// The order of the ed25519 base point, in little endian.
+const L: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+/// Verify the signatures of many messages in one batch, it is faster than verifying them one by
+/// one. If the batch is invalid, the error tells the index of the first invalid signature.
+/// As `verify_signature`, a signature with a non canonical S is rejected, the batch equation
+/// alone does not reject it.
+pub fn verify_batch(msgs: &[(HashValue, Ed25519PublicKey, Ed25519Signature)]) -> Result<()> {
+    if msgs.is_empty() {
+        return Ok(());
+    }
+    for (i, (_, _, signature)) in msgs.iter().enumerate() {
+        if !is_canonical_s(&signature.to_bytes()[32..]) {
+            return Err(format_err!(
+                "Invalid signature at index {}: non canonical S",
+                i
+            ));
+        }
+    }
+    if dalek_verify_batch(msgs).is_ok() {
+        return Ok(());
+    }
+    for (i, (message, public_key, signature)) in msgs.iter().enumerate() {
+        if let Err(e) = public_key.verify_signature(message, signature) {
+            return Err(format_err!("Invalid signature at index {}: {:?}", i, e));
+        }
+    }
+    // a batch only fails with an invalid signature, keep the batch result anyway.
+    Err(format_err!("Invalid signature batch"))
+}
+
+fn dalek_verify_batch(
+    msgs: &[(HashValue, Ed25519PublicKey, Ed25519Signature)],
+) -> std::result::Result<(), ed25519_dalek::SignatureError> {
+    let messages: Vec<Vec<u8>> = msgs
+        .iter()
+        .map(|(message, _, _)| message.to_vec())
+        .collect();
+    let messages: Vec<&[u8]> = messages.iter().map(|message| message.as_slice()).collect();
+    let public_keys = msgs
+        .iter()
+        .map(|(_, public_key, _)| ed25519_dalek::PublicKey::from_bytes(&public_key.to_bytes()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let signatures = msgs
+        .iter()
+        .map(|(_, _, signature)| ed25519_dalek::Signature::from_bytes(&signature.to_bytes()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    ed25519_dalek::verify_batch(&messages, &signatures, &public_keys)
+}
+
+/// Whether the little endian scalar `s` is less than `L`, a signature with S not less than `L`
+/// is malleable.
+fn is_canonical_s(s: &[u8]) -> bool {
+    for (s_byte, l_byte) in s.iter().zip(L.iter()).rev() {
+        match s_byte.cmp(l_byte) {
+            Ordering::Less => return true,
+            Ordering::Greater => return false,
+            Ordering::Equal => {}
+        }
+    }
+    // S == L
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keygen::KeyGen;
+    use crate::SigningKey;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_non_canonical_s() {
+        let zero = [0u8; 32];
+        assert!(is_canonical_s(&zero));
+        assert!(!is_canonical_s(&L));
+        let mut l_minus_one = L;
+        l_minus_one[0] -= 1;
+        assert!(is_canonical_s(&l_minus_one));
+
+        // S + L passes the batch equation as S does, it must be rejected.
+        let (private_key, public_key) = KeyGen::from_seed([0u8; 32]).generate_keypair();
+        let message = HashValue::random();
+        let signature = private_key.sign_message(&message);
+        assert!(verify_batch(&[(message, public_key.clone(), signature.clone())]).is_ok());
+        let mut signature = signature.to_bytes();
+        assert!(is_canonical_s(&signature[32..]));
+        let mut carry = 0u16;
+        for (s_byte, l_byte) in signature[32..].iter_mut().zip(L.iter()) {
+            let sum = u16::from(*s_byte) + u16::from(*l_byte) + carry;
+            *s_byte = sum as u8;
+            carry = sum >> 8;
+        }
+        assert!(!is_canonical_s(&signature[32..]));
+        // the decoding of libra-crypto may reject it already.
+        if let Ok(signature) = Ed25519Signature::try_from(&signature[..]) {
+            let err = verify_batch(&[(message, public_key, signature)]).unwrap_err();
+            assert!(err.to_string().contains("index 0"), "{}", err);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{ensure, Result};
 pub use libra_crypto::hash::HashValue;
 
 pub use crypto_macro::CryptoHash;
@@ -70,3 +71,26 @@ pub fn create_literal_hash(word: &str) -> HashValue {
     s.resize(HashValue::LENGTH, 0);
     HashValue::from_slice(&s).expect("Cannot fail")
 }
+
+/// Parse a value from a hex literal with an optional `0x` prefix.
+pub trait FromHexLiteral: Sized {
+    fn from_hex_literal(literal: &str) -> Result<Self>;
+}
+
+impl FromHexLiteral for HashValue {
+    fn from_hex_literal(literal: &str) -> Result<Self> {
+        let hex_str = if literal.starts_with("0x") {
+            &literal[2..]
+        } else {
+            literal
+        };
+        let bytes = hex::decode(hex_str)?;
+        ensure!(
+            bytes.len() == HashValue::LENGTH,
+            "invalid hash length {}, expect {} bytes.",
+            bytes.len(),
+            HashValue::LENGTH
+        );
+        Ok(HashValue::from_slice(&bytes)?)
+    }
+}
@@ -9,6 +9,7 @@ use rand::{
     rngs::{OsRng, StdRng},
     Rng, SeedableRng,
 };
+use rand_chacha::ChaChaRng;
 
 /// Ed25519 key generator.
 pub struct KeyGen(StdRng);
@@ -27,6 +28,14 @@ impl KeyGen {
         Self::from_seed(seed)
     }
 
+    /// Derive an Ed25519 key pair from the seed with a ChaCha rng, the same seed always derives
+    /// the same key pair. It is for tests and tools, never use it for production keys.
+    pub fn keypair_from_seed(seed: [u8; 32]) -> (Ed25519PrivateKey, Ed25519PublicKey) {
+        let private_key = Ed25519PrivateKey::generate(&mut ChaChaRng::from_seed(seed));
+        let public_key = private_key.public_key();
+        (private_key, public_key)
+    }
+
     /// Generate an Ed25519 key pair.
     pub fn generate_keypair(&mut self) -> (Ed25519PrivateKey, Ed25519PublicKey) {
         let private_key = Ed25519PrivateKey::generate(&mut self.0);
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
-use starcoin_crypto::{hash::CryptoHash, HashValue};
+use starcoin_crypto::{
+    hash::{CryptoHash, FromHexLiteral},
+    HashValue,
+};
 
 #[derive(Debug, Hash, Serialize, Deserialize, CryptoHash)]
 struct TestStruct {
@@ -31,3 +34,20 @@ fn test_crypto_hash_for_basic_type() {
         b"test".to_vec().crypto_hash()
     );
 }
+
+#[test]
+fn test_hash_from_hex_literal() {
+    let hash = HashValue::random();
+    let hex_str = hash.to_hex();
+    assert_eq!(HashValue::from_hex_literal(&hex_str).unwrap(), hash);
+    assert_eq!(
+        HashValue::from_hex_literal(&format!("0x{}", hex_str)).unwrap(),
+        hash
+    );
+    // wrong length
+    assert!(HashValue::from_hex_literal("0x").is_err());
+    assert!(HashValue::from_hex_literal(&hex_str[2..]).is_err());
+    assert!(HashValue::from_hex_literal(&format!("{}00", hex_str)).is_err());
+    // not hex
+    assert!(HashValue::from_hex_literal(&format!("0x{}zz", &hex_str[2..])).is_err());
+}
@@ -0,0 +1,43 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use starcoin_crypto::ed25519::{verify_batch, Ed25519PublicKey, Ed25519Signature};
+use starcoin_crypto::keygen::KeyGen;
+use starcoin_crypto::{HashValue, SigningKey};
+
+fn signed_messages(count: usize) -> Vec<(HashValue, Ed25519PublicKey, Ed25519Signature)> {
+    let mut keygen = KeyGen::from_seed([0u8; 32]);
+    (0..count)
+        .map(|_| {
+            let (private_key, public_key) = keygen.generate_keypair();
+            let message = HashValue::random();
+            let signature = private_key.sign_message(&message);
+            (message, public_key, signature)
+        })
+        .collect()
+}
+
+#[test]
+fn test_verify_batch() {
+    assert!(verify_batch(&signed_messages(32)).is_ok());
+}
+
+#[test]
+fn test_verify_batch_invalid_signature() {
+    let mut msgs = signed_messages(8);
+    // the signature of message 5 is for another message.
+    msgs[5].0 = HashValue::random();
+    let err = verify_batch(&msgs).unwrap_err();
+    assert!(err.to_string().contains("index 5"), "{}", err);
+
+    let mut msgs = signed_messages(8);
+    let other_key = msgs[2].1.clone();
+    msgs[6].1 = other_key;
+    let err = verify_batch(&msgs).unwrap_err();
+    assert!(err.to_string().contains("index 6"), "{}", err);
+}
+
+#[test]
+fn test_verify_empty_batch() {
+    assert!(verify_batch(&[]).is_ok());
+}
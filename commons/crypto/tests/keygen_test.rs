@@ -0,0 +1,16 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use starcoin_crypto::keygen::KeyGen;
+
+#[test]
+fn test_keypair_from_seed() {
+    let (private_key1, public_key1) = KeyGen::keypair_from_seed([1u8; 32]);
+    let (private_key2, public_key2) = KeyGen::keypair_from_seed([1u8; 32]);
+    assert_eq!(private_key1.to_bytes(), private_key2.to_bytes());
+    assert_eq!(public_key1, public_key2);
+
+    let (private_key3, public_key3) = KeyGen::keypair_from_seed([2u8; 32]);
+    assert_ne!(private_key1.to_bytes(), private_key3.to_bytes());
+    assert_ne!(public_key1, public_key3);
+}
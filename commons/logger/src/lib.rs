@@ -76,10 +76,13 @@ impl LoggerHandle {
         self.update_logger(arg);
     }
 
-    pub fn update_level(&self, level: LevelFilter) {
+    /// Update the log level, and return the previous level.
+    pub fn update_level(&self, level: LevelFilter) -> LevelFilter {
         let mut arg = self.arg.lock().unwrap().clone();
+        let previous_level = arg.level;
         arg.level = level;
         self.update_logger(arg);
+        previous_level
     }
 
     fn update_logger(&self, arg: LoggerConfigArg) {
@@ -206,7 +209,7 @@ mod tests {
         assert_eq!(handle.stderr(), handle2.stderr());
         let origin_level = handle.level();
 
-        handle.update_level(LevelFilter::Off);
+        assert_eq!(handle.update_level(LevelFilter::Off), origin_level);
 
         assert_eq!(handle.level(), LevelFilter::Off);
         assert_eq!(handle.level(), handle2.level());
@@ -3,7 +3,7 @@
 
 use crate::error::CmdError;
 use crate::{print_action_result, Command, CommandAction, CommandExec, OutputFormat};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use clap::{crate_authors, crate_version, App, Arg, ArgMatches, SubCommand};
 use git_version::git_version;
 use lazy_static::lazy_static;
@@ -75,7 +75,7 @@ where
             .arg(
                 Arg::with_name(OUTPUT_FORMAT_ARG)
                     .short("o")
-                    .help("set output-format, support [json|table]")
+                    .help("set output-format, support [json|table|csv]")
                     .takes_value(true)
                     .default_value("table"),
             );
@@ -148,9 +148,13 @@ where
             .get_matches_from_safe_borrow(&mut std::env::args_os())?;
         let output_format = matches
             .value_of(OUTPUT_FORMAT_ARG)
-            .expect("output-format arg must exist")
-            .parse()
-            .expect("parse output-format must success.");
+            .expect("output-format arg must exist");
+        let output_format = output_format.parse::<OutputFormat>().map_err(|_| {
+            format_err!(
+                "unknown output format: {}, support [json|table|csv]",
+                output_format
+            )
+        })?;
 
         let (global_opt, state) = self.init_global_opt(&matches)?;
         let (cmd_name, arg_matches) = matches.subcommand();
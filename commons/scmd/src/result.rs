@@ -1,16 +1,17 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use cli_table::format::CellFormat;
 use cli_table::{Cell, Row, Table};
 use flatten_json::flatten;
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 use std::str::FromStr;
 
 pub enum OutputFormat {
     JSON,
     TABLE,
+    CSV,
 }
 
 impl FromStr for OutputFormat {
@@ -19,7 +20,9 @@ impl FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "json" => OutputFormat::JSON,
-            _ => OutputFormat::TABLE,
+            "table" => OutputFormat::TABLE,
+            "csv" => OutputFormat::CSV,
+            _ => return Err(()),
         })
     }
 }
@@ -28,6 +31,7 @@ pub fn print_action_result(value: Value, format: OutputFormat) -> Result<()> {
     match format {
         OutputFormat::JSON => print_json(value),
         OutputFormat::TABLE => print_table(value),
+        OutputFormat::CSV => print_csv(value),
     }
 }
 
@@ -38,30 +42,63 @@ pub fn print_json(value: Value) -> Result<()> {
     Ok(())
 }
 
+/// Render the arrays in the value as comma-joined strings, a table cell can not hold an array.
+fn join_arrays(value: &Value) -> Value {
+    match value {
+        Value::Array(values) => Value::String(
+            values
+                .iter()
+                .map(|v| value_to_string(&join_arrays(v)))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(k, v)| (k.clone(), join_arrays(v)))
+                .collect(),
+        ),
+        v => v.clone(),
+    }
+}
+
+fn is_simple_value(value: &Value) -> bool {
+    value.is_number() || value.is_boolean() || value.is_string()
+}
+
+fn flatten_object(value: &Value) -> Result<Map<String, Value>> {
+    let mut flat = json!({});
+    flatten(value, &mut flat, None, true)
+        .map_err(|e| anyhow::Error::msg(e.description().to_string()))?;
+    match flat {
+        Value::Object(obj) => Ok(obj),
+        _ => unreachable!("must be a object"),
+    }
+}
+
+/// The field names of the flattened first value, or `None` if the value is simple.
+fn head_field_names(first_value: &Value) -> Result<Option<Vec<String>>> {
+    if is_simple_value(first_value) {
+        Ok(None)
+    } else {
+        Ok(Some(flatten_object(first_value)?.keys().cloned().collect()))
+    }
+}
+
 fn head_row(first_value: &Value) -> Result<(Row, Box<dyn RowBuilder>)> {
     let bold = CellFormat::builder().bold(true).build();
-    let simple_value = first_value.is_number()
-        || first_value.is_boolean()
-        || first_value.is_boolean()
-        || first_value.is_string();
-    if simple_value {
-        let row = Row::new(vec![Cell::new("Result", bold)]);
-        Ok((row, Box::new(SimpleRowBuilder)))
-    } else {
-        let mut flat = json!({});
-        flatten(first_value, &mut flat, None, true)
-            .map_err(|e| anyhow::Error::msg(e.description().to_string()))?;
-        let obj = flat.as_object().expect("must be a object");
-        let mut cells = vec![];
-        let mut field_names = vec![];
-        for (k, _v) in obj {
-            field_names.push(k.to_string());
+    match head_field_names(first_value)? {
+        None => {
+            let row = Row::new(vec![Cell::new("Result", bold)]);
+            Ok((row, Box::new(SimpleRowBuilder)))
         }
-        for field_name in &field_names {
-            cells.push(Cell::new(field_name, bold));
+        Some(field_names) => {
+            let mut cells = vec![];
+            for field_name in &field_names {
+                cells.push(Cell::new(field_name, bold));
+            }
+            let row = Row::new(cells);
+            Ok((row, Box::new(ObjectRowBuilder { field_names })))
         }
-        let row = Row::new(cells);
-        Ok((row, Box::new(ObjectRowBuilder { field_names })))
     }
 }
 
@@ -79,18 +116,15 @@ fn print_vec_table(values: Vec<Value>) -> Result<()> {
     if values.is_empty() {
         return Ok(());
     }
-    let first = &values[0];
-    let first_value = serde_json::to_value(first)?;
+    let values: Vec<Value> = values.iter().map(join_arrays).collect();
+    let first_value = &values[0];
     if first_value.is_null() {
         return Ok(());
     }
-    if first_value.is_array() {
-        bail!("Not support embed array in Action Result.")
-    }
-    let (head_row, row_builder) = head_row(&first_value)?;
+    let (head_row, row_builder) = head_row(first_value)?;
     let mut rows = vec![];
     rows.push(head_row);
-    rows.push(row_builder.build_row(&first_value)?);
+    rows.push(row_builder.build_row(first_value)?);
     for value in values[1..].iter() {
         rows.push(row_builder.build_row(&value)?);
     }
@@ -100,17 +134,12 @@ fn print_vec_table(values: Vec<Value>) -> Result<()> {
 }
 
 fn print_value_table(value: Value) -> Result<()> {
-    let simple_value =
-        value.is_number() || value.is_boolean() || value.is_boolean() || value.is_string();
-    if simple_value {
+    if is_simple_value(&value) {
         println!("{}", value_to_string(&value));
     } else {
         // value must be a object at here.
         let bold = CellFormat::builder().bold(true).build();
-        let mut flat = json!({});
-        flatten(&value, &mut flat, None, true)
-            .map_err(|e| anyhow::Error::msg(e.description().to_string()))?;
-        let obj = flat.as_object().expect("must be a object");
+        let obj = flatten_object(&join_arrays(&value))?;
         let mut rows = vec![];
         for (k, v) in obj {
             let row = Row::new(vec![
@@ -156,15 +185,145 @@ struct ObjectRowBuilder {
 
 impl RowBuilder for ObjectRowBuilder {
     fn build_row(&self, value: &Value) -> Result<Row> {
-        let mut flat = json!({});
-        flatten(value, &mut flat, None, true)
-            .map_err(|e| anyhow::Error::msg(e.description().to_string()))?;
-        let obj = flat.as_object().expect("must be a object");
-        let mut cells = vec![];
-        for field in &self.field_names {
-            let v = obj.get(field).unwrap_or(&Value::Null);
-            cells.push(Cell::new(value_to_string(v).as_str(), Default::default()));
-        }
+        let cells = object_row(&self.field_names, value)?
+            .iter()
+            .map(|v| Cell::new(v.as_str(), Default::default()))
+            .collect();
         Ok(Row::new(cells))
     }
 }
+
+/// The fields of the flattened value in the order of `field_names`, a missing field is empty.
+fn object_row(field_names: &[String], value: &Value) -> Result<Vec<String>> {
+    let obj = flatten_object(value)?;
+    Ok(field_names
+        .iter()
+        .map(|field| value_to_string(obj.get(field).unwrap_or(&Value::Null)))
+        .collect())
+}
+
+pub fn print_csv(value: Value) -> Result<()> {
+    print!("{}", format_csv(value)?);
+    Ok(())
+}
+
+/// Format the value as RFC 4180 csv, with a header row and a row for every item of the value.
+fn format_csv(value: Value) -> Result<String> {
+    let values = match value {
+        Value::Null => return Ok(String::new()),
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+    let values: Vec<Value> = values.iter().map(join_arrays).collect();
+    let first_value = match values.first() {
+        Some(first_value) => first_value,
+        None => return Ok(String::new()),
+    };
+    let mut records = vec![];
+    match head_field_names(first_value)? {
+        None => {
+            records.push(vec!["Result".to_string()]);
+            for value in &values {
+                records.push(vec![value_to_string(value)]);
+            }
+        }
+        Some(field_names) => {
+            for value in &values {
+                records.push(object_row(&field_names, value)?);
+            }
+            records.insert(0, field_names);
+        }
+    }
+    Ok(records
+        .iter()
+        .map(|record| {
+            let fields: Vec<String> = record.iter().map(|field| csv_field(field)).collect();
+            format!("{}\r\n", fields.join(","))
+        })
+        .collect())
+}
+
+/// Quote the field if it contains a comma, a quote or a line break, the quotes are doubled.
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct BlockIds {
+        number: u64,
+        ids: Vec<String>,
+    }
+
+    #[test]
+    fn test_table_with_array_field() {
+        let values = vec![
+            BlockIds {
+                number: 1,
+                ids: vec!["a".to_string(), "b".to_string()],
+            },
+            BlockIds {
+                number: 2,
+                ids: vec![],
+            },
+        ];
+        let value = serde_json::to_value(&values).unwrap();
+        assert_eq!(join_arrays(&value[0]), json!({"number": 1, "ids": "a,b"}));
+        assert_eq!(join_arrays(&value[1]), json!({"number": 2, "ids": ""}));
+        print_table(value.clone()).unwrap();
+        print_table(value[0].clone()).unwrap();
+        // the embed arrays of an array.
+        print_table(json!([["a", "b"], ["c"]])).unwrap();
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert!(matches!(
+            "json".parse::<OutputFormat>(),
+            Ok(OutputFormat::JSON)
+        ));
+        assert!(matches!(
+            "table".parse::<OutputFormat>(),
+            Ok(OutputFormat::TABLE)
+        ));
+        assert!(matches!(
+            "csv".parse::<OutputFormat>(),
+            Ok(OutputFormat::CSV)
+        ));
+        assert!("josn".parse::<OutputFormat>().is_err());
+        assert!("".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_csv_simple_value() {
+        assert_eq!(format_csv(json!(1)).unwrap(), "Result\r\n1\r\n");
+        assert_eq!(
+            format_csv(json!(["a", "b,c", "say \"hi\"", "x\ny"])).unwrap(),
+            "Result\r\na\r\n\"b,c\"\r\n\"say \"\"hi\"\"\"\r\n\"x\ny\"\r\n"
+        );
+        assert_eq!(format_csv(Value::Null).unwrap(), "");
+        assert_eq!(format_csv(json!([])).unwrap(), "");
+    }
+
+    #[test]
+    fn test_csv_nested_object() {
+        let value = json!([
+            {"number": 1, "header": {"id": "0x01", "author": "a,b"}, "ids": ["x", "y"]},
+            {"number": 2, "header": {"id": "0x02"}, "ids": []},
+        ]);
+        assert_eq!(
+            format_csv(value).unwrap(),
+            "header.author,header.id,ids,number\r\n\
+             \"a,b\",0x01,\"x,y\",1\r\n\
+             ,0x02,,2\r\n"
+        );
+    }
+}
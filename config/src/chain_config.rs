@@ -151,6 +151,8 @@ pub struct ChainConfig {
     pub reward_halving_interval: u64,
     /// How many block to delay before rewarding miners.
     pub reward_delay: u64,
+    /// Genesis block timestamp, pins the genesis block id of the ChainNetwork.
+    pub genesis_timestamp: u64,
     /// Genesis difficult, should match consensus in different ChainNetwork.
     pub difficult: U256,
     /// Genesis consensus header.
@@ -172,6 +174,7 @@ pub static DEV_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| {
         base_block_reward: 5000 * 1000_000,
         reward_halving_interval: 100,
         reward_delay: 1,
+        genesis_timestamp: 0,
         difficult: U256::zero(),
         consensus_header: vec![],
         pre_mine_config: Some(PreMineConfig {
@@ -187,6 +190,7 @@ static HALLEY_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
     base_block_reward: 5000 * 1000_000,
     reward_halving_interval: 1000,
     reward_delay: 3,
+    genesis_timestamp: 0,
     difficult: U256::max_value(),
     consensus_header: vec![],
     pre_mine_config: Some(PreMineConfig {
@@ -204,6 +208,7 @@ static PROXIMA_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
     base_block_reward: 5000 * 1000_000,
     reward_halving_interval: 10000,
     reward_delay: 7,
+    genesis_timestamp: 0,
     difficult: U256::max_value(),
     consensus_header: vec![],
     pre_mine_config: None,
@@ -214,6 +219,7 @@ static MAIN_CHAIN_CONFIG: Lazy<ChainConfig> = Lazy::new(|| ChainConfig {
     base_block_reward: 5000 * 1000_000,
     reward_halving_interval: 52500,
     reward_delay: 7,
+    genesis_timestamp: 0,
     difficult: U256::max_value(),
     consensus_header: vec![],
     pre_mine_config: None,
@@ -18,6 +18,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 pub static DEFAULT_NETWORK_PORT: u16 = 9840;
+pub const DEFAULT_HANDSHAKE_TIMEOUT_SECS: u64 = 40;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -26,6 +27,8 @@ pub struct NetworkConfig {
     pub listen: Multiaddr,
     pub seeds: Vec<Multiaddr>,
     network_key_file: PathBuf,
+    // Seconds to wait for the status handshake of a connected peer before dropping it.
+    pub handshake_timeout: u64,
     #[serde(skip)]
     pub network_keypair: Option<Arc<KeyPair<Ed25519PrivateKey, Ed25519PublicKey>>>,
     #[serde(skip)]
@@ -82,6 +85,7 @@ impl ConfigModule for NetworkConfig {
                 .expect("Parse multi address fail."),
             seeds: vec![],
             network_key_file: PathBuf::from("network_key"),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT_SECS,
             network_keypair: None,
             self_peer_id: None,
             self_address: None,
@@ -8,6 +8,8 @@ use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 const DEFAULT_MAX_REQUEST_BODY_SIZE: usize = 10 * 1024 * 1024; //10M
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 5;
+pub const DEFAULT_MAX_BLOCKS_PER_REQUEST: u64 = 1000;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -22,6 +24,10 @@ pub struct RpcConfig {
     pub ws_address: Option<SocketAddr>,
     pub max_request_body_size: usize,
     pub threads: Option<usize>,
+    /// Seconds to wait for the in-flight requests to complete when the rpc server stops.
+    pub drain_timeout: u64,
+    /// The max count of blocks returned by a block list request, a larger count is clamped.
+    pub max_blocks_per_request: u64,
     #[serde(skip)]
     ipc_file_path: Option<PathBuf>,
 }
@@ -49,6 +55,8 @@ impl ConfigModule for RpcConfig {
             tcp_address: None,
             max_request_body_size: DEFAULT_MAX_REQUEST_BODY_SIZE,
             threads: None,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT_SECS,
+            max_blocks_per_request: DEFAULT_MAX_BLOCKS_PER_REQUEST,
             ipc_file_path: None,
         }
     }
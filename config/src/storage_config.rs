@@ -4,14 +4,32 @@
 use crate::{BaseConfig, ChainNetwork, ConfigModule, StarcoinOpt};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
     dir: PathBuf,
+    /// Minimum interval in milliseconds between two startup info writes, 0 disables the time limit.
+    startup_info_flush_interval: u64,
+    /// Number of head changes after which startup info is written, 0 disables the block limit.
+    startup_info_flush_blocks: u64,
+    /// Max number of entries of the in memory LRU cache in front of RocksDB.
+    cache_size: usize,
+    /// Size in bytes of the RocksDB block cache, unset keeps the RocksDB default.
+    block_cache_size: Option<usize>,
+    /// Size in bytes of the RocksDB memtable of every column family.
+    write_buffer_size: u64,
+    /// Max number of files RocksDB keeps open, -1 keeps all files open.
+    max_open_files: i32,
+    /// Paths of column families placed out of `dir` by column family name, such as state_node
+    /// on a separate disk. Relative paths are relative to the data dir.
+    cf_paths: HashMap<String, PathBuf>,
     #[serde(skip)]
     absolute_dir: Option<PathBuf>,
+    #[serde(skip)]
+    absolute_cf_paths: HashMap<String, PathBuf>,
 }
 
 impl Default for StorageConfig {
@@ -27,18 +45,65 @@ impl StorageConfig {
             .cloned()
             .expect("config should init first.")
     }
+
+    pub fn startup_info_flush_interval(&self) -> u64 {
+        self.startup_info_flush_interval
+    }
+
+    pub fn startup_info_flush_blocks(&self) -> u64 {
+        self.startup_info_flush_blocks
+    }
+
+    pub fn cache_size(&self) -> usize {
+        self.cache_size
+    }
+
+    pub fn block_cache_size(&self) -> Option<usize> {
+        self.block_cache_size
+    }
+
+    pub fn write_buffer_size(&self) -> u64 {
+        self.write_buffer_size
+    }
+
+    pub fn max_open_files(&self) -> i32 {
+        self.max_open_files
+    }
+
+    pub fn cf_paths(&self) -> HashMap<String, PathBuf> {
+        self.absolute_cf_paths.clone()
+    }
+
+    //just for test
+    pub fn set_startup_info_flush_policy(&mut self, interval: u64, blocks: u64) {
+        self.startup_info_flush_interval = interval;
+        self.startup_info_flush_blocks = blocks;
+    }
 }
 
 impl ConfigModule for StorageConfig {
     fn default_with_net(_net: ChainNetwork) -> Self {
         Self {
             dir: PathBuf::from("starcoindb/db"),
+            startup_info_flush_interval: 0,
+            startup_info_flush_blocks: 0,
+            cache_size: 65535,
+            block_cache_size: None,
+            write_buffer_size: 64 << 20,
+            max_open_files: -1,
+            cf_paths: HashMap::new(),
             absolute_dir: None,
+            absolute_cf_paths: HashMap::new(),
         }
     }
 
     fn random(&mut self, base: &BaseConfig) {
         self.absolute_dir = Some(base.data_dir().join(self.dir.as_path()));
+        self.absolute_cf_paths = self
+            .cf_paths
+            .iter()
+            .map(|(cf_name, cf_path)| (cf_name.clone(), base.data_dir().join(cf_path)))
+            .collect();
     }
 
     fn load(&mut self, base: &BaseConfig, _opt: &StarcoinOpt) -> Result<()> {
@@ -47,6 +112,11 @@ impl ConfigModule for StorageConfig {
         } else {
             self.dir.clone()
         });
+        self.absolute_cf_paths = self
+            .cf_paths
+            .iter()
+            .map(|(cf_name, cf_path)| (cf_name.clone(), base.data_dir().join(cf_path)))
+            .collect();
         Ok(())
     }
 }
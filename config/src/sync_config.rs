@@ -5,10 +5,20 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+pub const DEFAULT_STATE_SYNC_WORKERS: usize = 4;
+pub const DEFAULT_STATE_SYNC_MAX_PEER_FAILURES: usize = 3;
+pub const DEFAULT_STATE_SYNC_QUEUE_SIZE: usize = 4096;
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct SyncConfig {
     sync_mode: SyncMode,
+    /// Number of threads applying received state nodes in fast sync.
+    state_sync_workers: usize,
+    /// Times a state or accumulator node may fail to sync from peers before state sync gives up.
+    state_sync_max_peer_failures: usize,
+    /// Max state or accumulator nodes waiting to sync, children of synced nodes wait for room.
+    state_sync_queue_size: usize,
 }
 
 impl SyncConfig {
@@ -20,6 +30,18 @@ impl SyncConfig {
         self.sync_mode == SyncMode::LIGHT
     }
 
+    pub fn state_sync_workers(&self) -> usize {
+        self.state_sync_workers
+    }
+
+    pub fn state_sync_max_peer_failures(&self) -> usize {
+        self.state_sync_max_peer_failures
+    }
+
+    pub fn state_sync_queue_size(&self) -> usize {
+        self.state_sync_queue_size
+    }
+
     //just for test
     pub fn fast_sync_mode(&mut self) {
         self.sync_mode = SyncMode::FAST_SYNC;
@@ -34,6 +56,9 @@ impl ConfigModule for SyncConfig {
             } else {
                 SyncMode::FAST_SYNC
             },
+            state_sync_workers: DEFAULT_STATE_SYNC_WORKERS,
+            state_sync_max_peer_failures: DEFAULT_STATE_SYNC_MAX_PEER_FAILURES,
+            state_sync_queue_size: DEFAULT_STATE_SYNC_QUEUE_SIZE,
         }
     }
 
@@ -12,6 +12,10 @@ pub struct TxPoolConfig {
     pub max_mem_usage: u64,
     /// Minimal allowed gas price.
     pub minimal_gas_price: u64,
+    /// Local relay policy, new txns with a lower gas price are not admitted to the pool.
+    /// It is independent of the consensus floor checked by the VM, so it does not change
+    /// whether a txn in a block is valid.
+    pub local_min_gas_price: u64,
     /// Maximal gas limit for a single transaction.
     #[serde(skip)]
     pub tx_gas_limit: u64,
@@ -30,6 +34,7 @@ impl ConfigModule for TxPoolConfig {
             max_per_sender: 16,
             max_mem_usage: 64 * 1024 * 1024, // 64M
             minimal_gas_price: 0,
+            local_min_gas_price: 0,
             tx_gas_limit: u64::max_value(),
         }
     }
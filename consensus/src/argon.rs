@@ -15,6 +15,7 @@ use std::sync::Arc;
 use traits::ChainReader;
 use traits::{Consensus, ConsensusHeader};
 use types::block::BlockHeader;
+use types::time::TimeService;
 use types::{H256, U256};
 
 #[derive(Clone, Debug)]
@@ -52,7 +53,11 @@ impl Consensus for ArgonConsensus {
         let target = difficult::get_next_work_required(reader);
         target_to_difficult(target)
     }
-    fn solve_consensus_header(header_hash: &[u8], difficulty: U256) -> Self::ConsensusHeader {
+    fn solve_consensus_header(
+        header_hash: &[u8],
+        difficulty: U256,
+        _time_service: &dyn TimeService,
+    ) -> Self::ConsensusHeader {
         let mut nonce = generate_nonce();
         loop {
             let pow_hash: U256 = calculate_hash(&set_header_nonce(&header_hash, nonce)).into();
@@ -71,8 +76,7 @@ impl Consensus for ArgonConsensus {
         header: &BlockHeader,
     ) -> Result<()> {
         let difficulty = header.difficult();
-        let consensus_header: ArgonConsensusHeader =
-            ArgonConsensusHeader::try_from(header.consensus_header().to_vec())?;
+        let consensus_header: ArgonConsensusHeader = header.decode_consensus_header()?;
         let nonce = consensus_header.nonce;
         let header = header.parent_hash().to_hex();
         info!(
@@ -129,3 +133,37 @@ pub fn set_header_nonce(header: &[u8], nonce: u64) -> Vec<u8> {
 pub fn vec_to_u64(v: Vec<u8>) -> u64 {
     LittleEndian::read_u64(&v)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::HashValue;
+    use types::account_address::AccountAddress;
+
+    fn header_with_consensus_header<H: Into<Vec<u8>>>(consensus_header: H) -> BlockHeader {
+        BlockHeader::new(
+            HashValue::random(),
+            0,
+            1,
+            AccountAddress::random(),
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            U256::one(),
+            consensus_header,
+        )
+    }
+
+    #[test]
+    fn test_decode_consensus_header() {
+        let header = header_with_consensus_header(ArgonConsensusHeader { nonce: 1024 });
+        let consensus_header: ArgonConsensusHeader = header.decode_consensus_header().unwrap();
+        assert_eq!(consensus_header.nonce, 1024);
+
+        let header = header_with_consensus_header(vec![1u8, 2, 3]);
+        assert!(header
+            .decode_consensus_header::<ArgonConsensusHeader>()
+            .is_err());
+    }
+}
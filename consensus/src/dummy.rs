@@ -7,11 +7,11 @@ use logger::prelude::*;
 use rand::prelude::*;
 use std::convert::TryFrom;
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 use traits::ChainReader;
 use traits::{Consensus, ConsensusHeader};
 use types::block::BlockHeader;
+use types::time::TimeService;
 use types::U256;
 
 //TODO add some field to DummyHeader.
@@ -52,10 +52,14 @@ impl Consensus for DummyConsensus {
         time.into()
     }
 
-    fn solve_consensus_header(_header_hash: &[u8], difficulty: U256) -> Self::ConsensusHeader {
+    fn solve_consensus_header(
+        _header_hash: &[u8],
+        difficulty: U256,
+        time_service: &dyn TimeService,
+    ) -> Self::ConsensusHeader {
         let time: u64 = difficulty.as_u64();
         debug!("DummyConsensus rand sleep time : {}", time);
-        thread::sleep(Duration::from_millis(time));
+        time_service.sleep(Duration::from_millis(time));
         DummyHeader {}
     }
 
@@ -83,6 +83,7 @@ impl Genesis {
         let (accumulator_root, _) = accumulator.append(vec![txn_info_hash].as_slice())?;
 
         let block = Block::genesis_block(
+            chain_config.genesis_timestamp,
             accumulator_root,
             transaction_info.state_root_hash(),
             chain_config.difficult,
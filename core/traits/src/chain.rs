@@ -8,6 +8,7 @@ use starcoin_types::{
     account_address::AccountAddress,
     block::{Block, BlockHeader, BlockInfo, BlockNumber, BlockTemplate},
     startup_info::ChainInfo,
+    time::TimeService,
     transaction::{SignedUserTransaction, Transaction, TransactionInfo},
     U512,
 };
@@ -18,7 +19,8 @@ pub trait ChainReader {
     fn get_header(&self, hash: HashValue) -> Result<Option<BlockHeader>>;
     fn get_header_by_number(&self, number: BlockNumber) -> Result<Option<BlockHeader>>;
     fn get_block_by_number(&self, number: BlockNumber) -> Result<Option<Block>>;
-    fn get_blocks_by_number(&self, number: BlockNumber, count: u64) -> Result<Vec<Block>>;
+    /// Get at most `count` blocks from `number` towards genesis, `None` starts from the head.
+    fn get_blocks_by_number(&self, number: Option<BlockNumber>, count: u64) -> Result<Vec<Block>>;
     fn get_block(&self, hash: HashValue) -> Result<Option<Block>>;
     fn get_block_transactions(&self, block_id: HashValue) -> Result<Vec<TransactionInfo>>;
     fn get_transaction(&self, hash: HashValue) -> Result<Option<Transaction>>;
@@ -37,6 +39,8 @@ pub trait ChainReader {
     fn get_block_info(&self, block_id: Option<HashValue>) -> Result<Option<BlockInfo>>;
     fn get_total_difficulty(&self) -> Result<U512>;
     fn exist_block(&self, block_id: HashValue) -> bool;
+    /// The clock of the chain, used for the block timestamp and the consensus.
+    fn time_service(&self) -> &dyn TimeService;
 }
 
 pub trait ChainWriter {
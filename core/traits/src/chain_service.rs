@@ -31,7 +31,11 @@ pub trait ChainService {
     fn master_head_block(&self) -> Block;
     fn master_block_by_number(&self, number: BlockNumber) -> Result<Option<Block>>;
     fn master_startup_info(&self) -> StartupInfo;
-    fn master_blocks_by_number(&self, number: BlockNumber, count: u64) -> Result<Vec<Block>>;
+    fn master_blocks_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> Result<Vec<Block>>;
     fn get_transaction(&self, hash: HashValue) -> Result<Option<TransactionInfo>>;
     fn get_block_txn_ids(&self, block_id: HashValue) -> Result<Vec<TransactionInfo>>;
     /////////////////////////////////////////////// just for test
@@ -66,7 +70,11 @@ pub trait ChainAsyncService:
     async fn master_head_header(self) -> Option<BlockHeader>;
     async fn master_head_block(self) -> Option<Block>;
     async fn master_block_by_number(self, number: BlockNumber) -> Result<Block>;
-    async fn master_blocks_by_number(self, number: BlockNumber, count: u64) -> Result<Vec<Block>>;
+    async fn master_blocks_by_number(
+        self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> Result<Vec<Block>>;
     async fn master_startup_info(self) -> Result<StartupInfo>;
     async fn master_head(self) -> Result<ChainInfo>;
     async fn get_transaction(self, txn_id: HashValue) -> Result<TransactionInfo>;
@@ -6,6 +6,7 @@ use anyhow::Result;
 use starcoin_config::NodeConfig;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockTemplate},
+    time::TimeService,
     U256,
 };
 use std::convert::TryFrom;
@@ -22,9 +23,13 @@ pub trait Consensus: std::marker::Unpin + Clone + Sync + Send {
 
     fn calculate_next_difficulty(config: Arc<NodeConfig>, reader: &dyn ChainReader) -> U256;
 
-    /// Calculate new block consensus header
+    /// Calculate new block consensus header, waiting on `time_service` if the consensus needs to.
     // TODO use &HashValue to replace &[u8] for parent_hash
-    fn solve_consensus_header(parent_hash: &[u8], difficult: U256) -> Self::ConsensusHeader;
+    fn solve_consensus_header(
+        parent_hash: &[u8],
+        difficult: U256,
+        time_service: &dyn TimeService,
+    ) -> Self::ConsensusHeader;
 
     fn verify_header(
         config: Arc<NodeConfig>,
@@ -42,6 +47,7 @@ pub trait Consensus: std::marker::Unpin + Clone + Sync + Send {
         let consensus_header = Self::solve_consensus_header(
             block_template.parent_hash.to_vec().as_slice(),
             difficulty,
+            reader.time_service(),
         );
         Ok(block_template.into_block(consensus_header, difficulty))
     }
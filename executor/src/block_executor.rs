@@ -1,8 +1,6 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::executor::Executor;
-use crate::TransactionExecutor;
 use crypto::{hash::CryptoHash, HashValue};
 // use logger::prelude::*;
 use starcoin_accumulator::{Accumulator, MerkleAccumulator};
@@ -11,6 +9,7 @@ use types::error::BlockExecutorError;
 use types::error::ExecutorResult;
 use types::transaction::TransactionStatus;
 use types::transaction::{Transaction, TransactionInfo};
+use vm_runtime::starcoin_vm::StarcoinVM;
 
 #[derive(Clone)]
 pub struct BlockExecutor {}
@@ -22,14 +21,30 @@ impl BlockExecutor {
         accumulator: &MerkleAccumulator,
         txns: Vec<Transaction>,
         is_preview: bool,
+    ) -> ExecutorResult<(HashValue, HashValue, Vec<TransactionInfo>)> {
+        Self::block_execute_with_vm(
+            &mut StarcoinVM::new(),
+            chain_state,
+            accumulator,
+            txns,
+            is_preview,
+        )
+    }
+
+    /// Execute the block transactions by `vm`, so the gas schedule is loaded once for the block.
+    pub fn block_execute_with_vm(
+        vm: &mut StarcoinVM,
+        chain_state: &dyn ChainState,
+        accumulator: &MerkleAccumulator,
+        txns: Vec<Transaction>,
+        is_preview: bool,
     ) -> ExecutorResult<(HashValue, HashValue, Vec<TransactionInfo>)> {
         let mut state_root = HashValue::zero();
         let mut transaction_hash = vec![];
         let mut vec_transaction_info = vec![];
         for txn in txns {
             let txn_hash = txn.crypto_hash();
-            let output = Executor::execute_transaction(chain_state, txn.clone())
-                .map_err(|_err| BlockExecutorError::BlockTransactionExecuteErr(txn_hash))?;
+            let output = vm.execute_transaction(chain_state, txn.clone());
 
             match output.status() {
                 TransactionStatus::Discard(status) => {
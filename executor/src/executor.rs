@@ -59,6 +59,15 @@ impl TransactionExecutor for Executor {
         Ok(output)
     }
 
+    fn simulate_transaction(
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> Result<TransactionOutput> {
+        let mut vm = StarcoinVM::new();
+        let output = vm.simulate_transaction(chain_state, txn);
+        Ok(output)
+    }
+
     fn validate_transaction(
         chain_state: &dyn ChainState,
         txn: SignedUserTransaction,
@@ -2,16 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    block_executor::BlockExecutor,
     executor::Executor,
     mock_executor::{get_signed_txn, MockExecutor},
     TransactionExecutor,
 };
 use anyhow::Result;
+use bytecode_verifier::VerifiedModule;
 use compiler::Compiler;
-use crypto::keygen::KeyGen;
+use crypto::{keygen::KeyGen, HashValue};
 use logger::prelude::*;
+use starcoin_accumulator::{
+    node::ACCUMULATOR_PLACEHOLDER_HASH, MerkleAccumulator, MockAccumulatorStore,
+};
 use starcoin_config::ChainNetwork;
-use starcoin_state_api::{ChainState, ChainStateWriter};
+use starcoin_state_api::{ChainState, ChainStateReader, ChainStateWriter};
 use state_tree::mock::MockStateNodeStore;
 use statedb::ChainStateDB;
 use std::sync::Arc;
@@ -24,8 +29,9 @@ use types::{
     account_config::BalanceResource,
     block_metadata::BlockMetadata,
     transaction::Transaction,
-    transaction::{Module, TransactionPayload},
+    transaction::{Module, TransactionPayload, TransactionStatus},
     vm_error::{StatusCode, VMStatus},
+    write_set::WriteOpKind,
 };
 use vm_runtime::mock_vm::{
     encode_mint_transaction, encode_transfer_program, encode_transfer_transaction, KEEP_STATUS,
@@ -33,6 +39,7 @@ use vm_runtime::mock_vm::{
 use vm_runtime::{
     account::Account,
     common_transactions::{create_account_txn_sent_as_association, peer_to_peer_txn},
+    starcoin_vm::{StarcoinVM, VMConfig, VerifyResult},
 };
 
 #[stest::test]
@@ -145,6 +152,41 @@ fn test_validate_txn() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_simulate_txn() -> Result<()> {
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    let sender_account_address = AccountAddress::random();
+    let receiver_account_address = AccountAddress::random();
+    let (private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let program = encode_transfer_program(receiver_account_address, 100);
+    let txn = get_signed_txn(
+        sender_account_address,
+        0,
+        &private_key,
+        public_key.clone(),
+        program.clone(),
+    );
+    let output = MockExecutor::simulate_transaction(&chain_state, txn)?;
+    assert_eq!(
+        *output.status(),
+        TransactionStatus::Discard(VMStatus::new(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST))
+    );
+
+    chain_state.create_account(sender_account_address)?;
+    let state_root = chain_state.commit()?;
+    let txn = get_signed_txn(sender_account_address, 0, &private_key, public_key, program);
+    let output = MockExecutor::simulate_transaction(&chain_state, txn)?;
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+    // executing the transfer creates the receiver, the simulation does not.
+    assert!(chain_state
+        .get(&AccessPath::new_for_account(receiver_account_address))?
+        .is_none());
+    assert_eq!(chain_state.commit()?, state_root);
+    Ok(())
+}
+
 #[stest::test]
 fn test_validate_txn_with_starcoin_vm() -> Result<()> {
     let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
@@ -282,6 +324,61 @@ fn test_execute_transfer_txn_with_starcoin_vm() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_trace_transfer_txn() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sender = Account::new();
+    let receiver = Account::new();
+    for (seq_num, account) in vec![&sender, &receiver].into_iter().enumerate() {
+        let txn = Transaction::UserTransaction(create_account_txn_sent_as_association(
+            account,
+            seq_num as u64 + 1,
+            50_000_000,
+        ));
+        let output = Executor::execute_transaction(&chain_state, txn).unwrap();
+        assert_eq!(KEEP_STATUS.clone(), *output.status());
+    }
+
+    let raw_txn = Executor::build_transfer_txn(
+        *sender.address(),
+        sender.auth_key_prefix(),
+        *receiver.address(),
+        receiver.auth_key_prefix(),
+        0,
+        1000,
+    );
+    let txn = sender.create_user_txn_from_raw_txn(raw_txn);
+    let (output, descriptions) = StarcoinVM::new().trace_transaction(&chain_state, txn);
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+    for description in &descriptions {
+        info!("{}", description);
+    }
+    for address in vec![sender.address(), receiver.address()] {
+        for tag in vec!["LibraAccount::T", "LibraAccount::Balance"] {
+            assert!(
+                descriptions
+                    .iter()
+                    .any(|description| description.address == *address
+                        && description.tag == tag
+                        && description.kind == WriteOpKind::Modify
+                        && description.value_len > 0),
+                "{} of {:?} is not modified",
+                tag,
+                address
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[stest::test]
 fn test_sequence_number() -> Result<()> {
     let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
@@ -316,6 +413,284 @@ fn test_sequence_number() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_max_gas_units_config() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let account = Account::new();
+    let txn = create_account_txn_sent_as_association(&account, sequence_number, 1_000);
+    let max_gas_amount = txn.max_gas_amount();
+
+    let mut vm = StarcoinVM::new_with_config(VMConfig {
+        max_gas_units: max_gas_amount,
+    });
+    assert_eq!(vm.verify_transaction(&chain_state, txn.clone()), None);
+
+    let mut vm = StarcoinVM::new_with_config(VMConfig {
+        max_gas_units: max_gas_amount - 1,
+    });
+    let status = vm.verify_transaction(&chain_state, txn).unwrap();
+    assert_eq!(
+        status.major_status,
+        StatusCode::MAX_GAS_UNITS_EXCEEDS_MAX_GAS_UNITS_BOUND
+    );
+    Ok(())
+}
+
+#[stest::test]
+fn test_verify_result() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let mut vm = StarcoinVM::new();
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let account1 = Account::new();
+    let txn = create_account_txn_sent_as_association(&account1, sequence_number, 1_000);
+    assert_eq!(
+        vm.verify_transaction_result(&chain_state, txn.clone()),
+        VerifyResult::Ok
+    );
+    assert_eq!(vm.verify_transaction(&chain_state, txn), None);
+
+    let future_txn = create_account_txn_sent_as_association(&account1, sequence_number + 5, 1_000);
+    assert_eq!(
+        vm.verify_transaction_result(&chain_state, future_txn.clone()),
+        VerifyResult::Future
+    );
+    assert_eq!(vm.verify_transaction(&chain_state, future_txn), None);
+
+    // account1 is not created yet.
+    let account2 = Account::new();
+    let invalid_txn = peer_to_peer_txn(&account1, &account2, 0, 100);
+    let result = vm.verify_transaction_result(&chain_state, invalid_txn.clone());
+    match &result {
+        VerifyResult::Invalid(status) => assert_eq!(
+            vm.verify_transaction(&chain_state, invalid_txn),
+            Some(status.clone())
+        ),
+        other => panic!("txn of a missing account should be invalid: {:?}", other),
+    }
+    assert!(!result.is_ok());
+    Ok(())
+}
+
+#[stest::test]
+fn test_gas_schedule_cached() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set.clone())
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let mut vm = StarcoinVM::new();
+    let mint = |vm: &mut StarcoinVM| {
+        let sequence_number =
+            get_sequence_number(account_config::association_address(), &chain_state);
+        let account = Account::new();
+        let txn = Executor::build_mint_txn(
+            account.address().clone(),
+            account.auth_key_prefix(),
+            sequence_number,
+            1000,
+        );
+        let output = vm.execute_transaction(&chain_state, txn);
+        assert_eq!(KEEP_STATUS.clone(), *output.status());
+    };
+    mint(&mut vm);
+    mint(&mut vm);
+    assert_eq!(vm.gas_schedule_loads(), 1);
+
+    // the genesis state set writes the gas schedule again.
+    let output = vm.execute_transaction(&chain_state, Transaction::StateSet(state_set));
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+    mint(&mut vm);
+    assert_eq!(vm.gas_schedule_loads(), 2);
+    Ok(())
+}
+
+#[stest::test]
+fn test_gas_schedule_loaded_once_per_block() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let txns: Vec<Transaction> = (0..3)
+        .map(|i| {
+            let account = Account::new();
+            Executor::build_mint_txn(
+                account.address().clone(),
+                account.auth_key_prefix(),
+                sequence_number + i,
+                1000,
+            )
+        })
+        .collect();
+    let accumulator = MerkleAccumulator::new(
+        HashValue::zero(),
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        vec![],
+        0,
+        0,
+        Arc::new(MockAccumulatorStore::new()),
+    )?;
+    let mut vm = StarcoinVM::new();
+    let (_accumulator_root, _state_root, txn_infos) =
+        BlockExecutor::block_execute_with_vm(&mut vm, &chain_state, &accumulator, txns, true)
+            .map_err(|e| anyhow::format_err!("execute block failed: {:?}", e))?;
+    assert_eq!(txn_infos.len(), 3);
+    assert_eq!(vm.gas_schedule_loads(), 1);
+    Ok(())
+}
+
+#[stest::test]
+fn test_execute_block() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let account1 = Account::new();
+    let account2 = Account::new();
+    // the transfer depends on account1 created by the first txn.
+    let txns = vec![
+        Transaction::UserTransaction(create_account_txn_sent_as_association(
+            &account1,
+            sequence_number,
+            50_000_000,
+        )),
+        Transaction::UserTransaction(peer_to_peer_txn(&account1, &account2, 0, 100)),
+        // the sequence number is used by the transfer above.
+        Transaction::UserTransaction(peer_to_peer_txn(&account1, &account2, 0, 100)),
+    ];
+    let outputs = StarcoinVM::new().execute_block(&chain_state, txns);
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(KEEP_STATUS.clone(), *outputs[0].status());
+    assert_eq!(KEEP_STATUS.clone(), *outputs[1].status());
+    match outputs[2].status() {
+        TransactionStatus::Discard(_) => {}
+        status => panic!(
+            "txn with a used sequence number should be discarded: {:?}",
+            status
+        ),
+    }
+
+    assert_eq!(get_balance(account2.address().clone(), &chain_state), 100);
+    assert_eq!(
+        get_sequence_number(account1.address().clone(), &chain_state),
+        1
+    );
+    Ok(())
+}
+
+#[stest::test]
+fn test_estimate_gas() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let account1 = Account::new();
+    let account2 = Account::new();
+    let txn = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1,
+        sequence_number,
+        50_000_000,
+    ));
+    let output = Executor::execute_transaction(&chain_state, txn).unwrap();
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+
+    let mut vm = StarcoinVM::new();
+    let transfer = peer_to_peer_txn(&account1, &account2, 0, 100);
+    let gas = vm.estimate_gas(&chain_state, transfer).unwrap();
+    assert!(gas > 0);
+    // nothing is committed.
+    assert_eq!(get_balance(account2.address().clone(), &chain_state), 0);
+    assert_eq!(
+        get_sequence_number(account1.address().clone(), &chain_state),
+        0
+    );
+
+    // the transfer amount is more than the balance.
+    let failing_transfer = peer_to_peer_txn(&account1, &account2, 0, 100_000_000);
+    let status = vm.estimate_gas(&chain_state, failing_transfer).unwrap_err();
+    assert_eq!(status.major_status, StatusCode::ABORTED);
+    Ok(())
+}
+
+#[stest::test]
+fn test_changed_resources() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let sequence_number = get_sequence_number(account_config::association_address(), &chain_state);
+    let account1 = Account::new();
+    let account2 = Account::new();
+    let txn = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1,
+        sequence_number,
+        50_000_000,
+    ));
+    let output = Executor::execute_transaction(&chain_state, txn).unwrap();
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+
+    let txn = Transaction::UserTransaction(peer_to_peer_txn(&account1, &account2, 0, 100));
+    let output = Executor::execute_transaction(&chain_state, txn).unwrap();
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+    assert!(output.emitted_events().is_empty());
+
+    let changed_resources = output.changed_resources();
+    assert_eq!(changed_resources.len(), output.write_set().len());
+    let changed_paths: Vec<AccessPath> = changed_resources
+        .into_iter()
+        .map(|(access_path, _write_op)| access_path)
+        .collect();
+    for access_path in vec![
+        AccessPath::new_for_account(account1.address().clone()),
+        AccessPath::new_for_balance(account1.address().clone()),
+        AccessPath::new_for_account(account2.address().clone()),
+        AccessPath::new_for_balance(account2.address().clone()),
+    ] {
+        assert!(
+            changed_paths.contains(&access_path),
+            "{:?} should be changed",
+            access_path
+        );
+    }
+    Ok(())
+}
+
 fn get_sequence_number(addr: AccountAddress, chain_state: &dyn ChainState) -> u64 {
     let access_path = AccessPath::new_for_account(addr);
     let state = chain_state
@@ -416,3 +791,89 @@ fn test_publish_module() -> Result<()> {
 
     Ok(())
 }
+
+#[stest::test]
+fn test_simulate_publish_module() -> Result<()> {
+    let (_hash, state_set) = Executor::init_genesis(ChainNetwork::Dev.get_config()).unwrap();
+    let storage = MockStateNodeStore::new();
+    let chain_state = ChainStateDB::new(Arc::new(storage), None);
+
+    chain_state
+        .apply(state_set)
+        .unwrap_or_else(|e| panic!("Failure to apply state set: {}", e));
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1, 1, // fix me
+        50_000_000,
+    ));
+    let output1 = Executor::execute_transaction(&chain_state, txn1).unwrap();
+    assert_eq!(KEEP_STATUS.clone(), *output1.status());
+
+    let program = String::from(
+        "
+        module M {
+
+        }
+        ",
+    );
+    let compiled_module = compile_module_with_address(account1.address(), "file_name", &program);
+    let txn = account1.create_signed_txn_impl(
+        *account1.address(),
+        compiled_module,
+        0,
+        100_000,
+        1,
+        account_config::starcoin_type_tag().into(),
+    );
+    let output = Executor::simulate_transaction(&chain_state, txn).unwrap();
+    assert_eq!(KEEP_STATUS.clone(), *output.status());
+    assert!(output.gas_used() > 0);
+
+    // the simulated txn is not applied to the chain state.
+    assert_eq!(
+        get_sequence_number(account1.address().clone(), &chain_state),
+        0
+    );
+
+    // module N is not published, so M which depends on it can not be published.
+    let compiler = Compiler {
+        address: account1.address().clone().into(),
+        ..Compiler::default()
+    };
+    let dep_module = VerifiedModule::new(
+        compiler
+            .into_compiled_module("dep_file_name", "module N {}")
+            .unwrap(),
+    )
+    .unwrap();
+    let program = format!(
+        "
+        module M {{
+            import {}.N;
+        }}
+        ",
+        account1.address()
+    );
+    let compiler = Compiler {
+        address: account1.address().clone().into(),
+        extra_deps: vec![dep_module],
+        ..Compiler::default()
+    };
+    let compiled_module = Module::new(
+        compiler
+            .into_module_blob("file_name", program.as_str())
+            .unwrap(),
+    );
+    let txn = account1.create_signed_txn_impl(
+        *account1.address(),
+        TransactionPayload::Module(compiled_module),
+        0,
+        100_000,
+        1,
+        account_config::starcoin_type_tag().into(),
+    );
+    let output = Executor::simulate_transaction(&chain_state, txn).unwrap();
+    assert_ne!(KEEP_STATUS.clone(), *output.status());
+    Ok(())
+}
@@ -28,6 +28,13 @@ pub trait TransactionExecutor: std::marker::Unpin + Clone {
         txn: Transaction,
     ) -> Result<TransactionOutput>;
 
+    /// Execute the user transaction without updating state, to check whether it would succeed and
+    /// the gas it would use.
+    fn simulate_transaction(
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> Result<TransactionOutput>;
+
     /// Executes the prologue and verifies that the transaction is valid.
     fn validate_transaction(
         chain_state: &dyn ChainState,
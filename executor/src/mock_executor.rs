@@ -83,6 +83,14 @@ impl TransactionExecutor for MockExecutor {
         output
     }
 
+    fn simulate_transaction(
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> Result<TransactionOutput> {
+        let mut vm = MockVM::new();
+        Ok(vm.simulate_transaction(chain_state, txn))
+    }
+
     fn validate_transaction(
         chain_state: &dyn ChainState,
         txn: SignedUserTransaction,
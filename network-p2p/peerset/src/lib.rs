@@ -544,6 +544,9 @@ impl Peerset {
 
                 (peer_id.to_base58(), state)
             }).collect::<HashMap<_, _>>(),
+            "priority_groups": self.data.priority_groups().map(|(group_id, peers)| {
+                (group_id.clone(), peers.iter().map(PeerId::to_base58).collect::<Vec<_>>())
+            }).collect::<HashMap<_, _>>(),
             "reserved_only": self.reserved_only,
             "message_queue": self.message_queue.len(),
         })
@@ -640,6 +643,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_debug_info_priority_groups() {
+        let reserved_peer = PeerId::random();
+        let config = PeersetConfig {
+            in_peers: 0,
+            out_peers: 1,
+            bootnodes: vec![],
+            reserved_only: true,
+            reserved_nodes: vec![reserved_peer.clone()],
+        };
+        let (mut peerset, _handle) = Peerset::from_config(config);
+        let info = peerset.debug_info();
+        assert_eq!(
+            info["priority_groups"]["reserved"],
+            serde_json::json!([reserved_peer.to_base58()])
+        );
+        assert_eq!(info["reserved_only"], true);
+    }
+
     #[test]
     fn test_peerset_incoming() {
         let bootnode = PeerId::random();
@@ -371,6 +371,11 @@ impl PeersState {
         self.priority_nodes.get(group_id).cloned()
     }
 
+    /// Get all the priority groups, the reserved nodes are a priority group too.
+    pub fn priority_groups(&self) -> impl Iterator<Item = (&String, &HashSet<PeerId>)> {
+        self.priority_nodes.iter()
+    }
+
     /// Set whether to only allow connections to/from peers in a priority group.
     /// Calling this method does not affect any existing connection, e.g.
     /// enabling priority only will not disconnect from any non-priority peers
@@ -11,6 +11,7 @@ use libp2p::{
     wasm_ext,
 };
 use std::fmt;
+use std::time::Duration;
 use std::{
     error::Error,
     fs,
@@ -36,6 +37,12 @@ impl ProtocolId {
     pub fn as_bytes(&self) -> &[u8] {
         self.0.as_ref()
     }
+
+    /// The protocol id of a chain. The custom substream name is derived from it, so nodes of
+    /// different chains fail the libp2p protocol negotiation and never open the substream.
+    pub fn from_chain_id(chain_id: u64) -> ProtocolId {
+        ProtocolId::from(format!("starcoin{}", chain_id).as_bytes())
+    }
 }
 
 /// Network initialization parameters.
@@ -85,6 +92,8 @@ pub struct NetworkConfiguration {
     pub node_name: String,
 
     pub transport: TransportConfig,
+    /// Time to wait for the Status handshake of a connected peer before dropping it.
+    pub handshake_timeout: Duration,
 
     pub genesis_hash: HashValue,
 
@@ -142,6 +151,7 @@ impl Default for NetworkConfiguration {
                 wasm_external_transport: None,
                 use_yamux_flow_control: false,
             },
+            handshake_timeout: crate::protocol::DEFAULT_HANDSHAKE_TIMEOUT,
             genesis_hash: HashValue::default(),
             self_info: PeerInfo::default(),
         }
@@ -9,8 +9,6 @@ use crate::protocol::generic_proto::{GenericProto, GenericProtoOut};
 use crate::utils::interval;
 use crate::{DiscoveryNetBehaviour, Multiaddr};
 
-use crate::network_state::Peer;
-
 use bytes::{Bytes, BytesMut};
 use futures::prelude::*;
 use libp2p::core::{nodes::listeners::ListenerId, ConnectedPoint};
@@ -22,6 +20,7 @@ use log::Level;
 use crate::protocol::message::generic::{ConsensusMessage, Message, Status};
 use crypto::HashValue;
 use scs::SCSCodec;
+use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
@@ -32,13 +31,16 @@ use std::time;
 use types::peer_info::PeerInfo;
 use wasm_timer::Instant;
 
-const REQUEST_TIMEOUT_SEC: u64 = 40;
+/// Default time to wait for the Status handshake of a connected peer.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: time::Duration = time::Duration::from_secs(40);
 /// Interval at which we perform time based maintenance
 const TICK_TIMEOUT: time::Duration = time::Duration::from_millis(1100);
 /// Current protocol version.
 pub(crate) const CURRENT_VERSION: u32 = 1;
 /// Lowest version we support
 pub(crate) const MIN_VERSION: u32 = 1;
+/// Number of consecutive send failures after which a peer gets a reputation penalty.
+const MAX_SEND_FAILURES: u32 = 3;
 
 mod rep {
     use peerset::ReputationChange as Rep;
@@ -73,6 +75,8 @@ mod rep {
     pub const BAD_ROLE: Rep = Rep::new_fatal("Unsupported role");
     /// Peer response data does not have requested bits.
     pub const BAD_RESPONSE: Rep = Rep::new(-(1 << 12), "Incomplete response");
+    /// We repeatedly failed to send messages to the peer.
+    pub const SEND_FAILED: Rep = Rep::new(-(1 << 10), "Repeated send failure");
 }
 
 #[derive(Debug)]
@@ -107,6 +111,13 @@ struct PacketStats {
     count_out: u64,
 }
 
+/// A peer that completed the Status handshake.
+struct Peer {
+    info: PeerInfo,
+    /// The protocol version negotiated in the handshake.
+    protocol_version: u32,
+}
+
 struct ContextData {
     // All connected peers
     peers: HashMap<PeerId, Peer>,
@@ -123,6 +134,8 @@ pub struct Protocol {
     important_peers: HashSet<PeerId>,
     /// Connected peers pending Status message.
     handshaking_peers: HashMap<PeerId, HandshakingPeer>,
+    /// Time after which a peer pending Status message is dropped.
+    handshake_timeout: time::Duration,
     /// Used to report reputation changes.
     peerset_handle: peerset::PeersetHandle,
     /// Handles opening the unique substream and sending and receiving raw messages.
@@ -130,6 +143,8 @@ pub struct Protocol {
     context_data: ContextData,
     /// The `PeerId`'s of all boot nodes.
     boot_node_ids: Arc<HashSet<PeerId>>,
+    /// Consecutive send failures of each peer, reset on a successful send.
+    send_failures: HashMap<PeerId, u32>,
 
     chain_info: ChainInfo,
 }
@@ -199,13 +214,17 @@ impl NetworkBehaviour for Protocol {
                 CustomMessageOutcome::None
             }
             GenericProtoOut::CustomProtocolClosed { peer_id, .. } => {
-                self.on_peer_disconnected(peer_id.clone());
-                // Notify all the notification protocols as closed.
-                CustomMessageOutcome::NotificationStreamClosed { remote: peer_id }
+                self.on_peer_disconnected(peer_id)
             }
             GenericProtoOut::CustomMessage { peer_id, message } => {
                 self.on_custom_message(peer_id, message)
             }
+            GenericProtoOut::Notification {
+                peer_id, message, ..
+            } => CustomMessageOutcome::NotificationsReceived {
+                remote: peer_id,
+                messages: vec![message.freeze()],
+            },
             GenericProtoOut::Clogged {
                 peer_id: _,
                 messages,
@@ -291,6 +310,7 @@ impl Protocol {
         protocol_id: ProtocolId,
         chain_info: ChainInfo,
         boot_node_ids: Arc<HashSet<PeerId>>,
+        handshake_timeout: time::Duration,
     ) -> crate::net_error::Result<(Protocol, peerset::PeersetHandle)> {
         let important_peers = {
             let mut imp_p = HashSet::new();
@@ -308,6 +328,7 @@ impl Protocol {
         let protocol = Protocol {
             tick_timeout: Box::pin(interval(TICK_TIMEOUT)),
             handshaking_peers: HashMap::new(),
+            handshake_timeout,
             important_peers,
             peerset_handle: peerset_handle.clone(),
             behaviour,
@@ -316,6 +337,7 @@ impl Protocol {
             },
             chain_info,
             boot_node_ids,
+            send_failures: HashMap::new(),
         };
 
         Ok((protocol, peerset_handle))
@@ -341,6 +363,19 @@ impl Protocol {
         self.behaviour.is_enabled(peer_id)
     }
 
+    /// Returns the protocol version negotiated with the peer, if the handshake is done.
+    pub fn protocol_version(&self, peer_id: &PeerId) -> Option<u32> {
+        self.context_data
+            .peers
+            .get(peer_id)
+            .map(|peer| peer.protocol_version)
+    }
+
+    /// Returns the info the peer advertised in its Status, if the handshake is done.
+    pub fn peer_info(&self, peer_id: &PeerId) -> Option<&PeerInfo> {
+        self.context_data.peers.get(peer_id).map(|peer| &peer.info)
+    }
+
     /// Returns the state of the peerset manager, for debugging purposes.
     pub fn peerset_debug_info(&mut self) -> serde_json::Value {
         self.behaviour.peerset_debug_info()
@@ -369,7 +404,7 @@ impl Protocol {
     /// Called by peer to report status
     fn on_status_message(&mut self, who: PeerId, status: Status) -> CustomMessageOutcome {
         trace!(target: "sync", "New peer {} {:?}", who, status);
-        let _protocol_version = {
+        let protocol_version = {
             if self.context_data.peers.contains_key(&who) {
                 log!(
                     target: "sync",
@@ -400,17 +435,21 @@ impl Protocol {
 
                 return CustomMessageOutcome::None;
             }
-            if status.version < MIN_VERSION && CURRENT_VERSION < status.min_supported_version {
-                log!(
-                    target: "sync",
-                    if self.important_peers.contains(&who) { Level::Warn } else { Level::Trace },
-                    "Peer {:?} using unsupported protocol version {}", who, status.version
-                );
-                self.peerset_handle
-                    .report_peer(who.clone(), rep::BAD_PROTOCOL);
-                self.behaviour.disconnect_peer(&who);
-                return CustomMessageOutcome::None;
-            }
+            let protocol_version = match negotiate_version(&status) {
+                Some(version) => version,
+                None => {
+                    log!(
+                        target: "sync",
+                        if self.important_peers.contains(&who) { Level::Warn } else { Level::Trace },
+                        "Peer {:?} using unsupported protocol versions {}..={}",
+                        who, status.min_supported_version, status.version
+                    );
+                    self.peerset_handle
+                        .report_peer(who.clone(), rep::BAD_PROTOCOL);
+                    self.behaviour.disconnect_peer(&who);
+                    return CustomMessageOutcome::None;
+                }
+            };
 
             match self.handshaking_peers.remove(&who) {
                 Some(_handshaking) => {}
@@ -420,9 +459,16 @@ impl Protocol {
                 }
             };
 
-            debug!(target: "sync", "Connected {}", who);
-            status.version
+            debug!(target: "sync", "Connected {} with protocol version {}", who, protocol_version);
+            protocol_version
         };
+        self.context_data.peers.insert(
+            who.clone(),
+            Peer {
+                info: status.info.clone(),
+                protocol_version,
+            },
+        );
         // Notify all the notification protocols as open.
         CustomMessageOutcome::NotificationStreamOpened {
             remote: who,
@@ -430,8 +476,52 @@ impl Protocol {
         }
     }
 
-    fn send_message(&mut self, who: &PeerId, message: Message) {
-        send_message(&mut self.behaviour, who, message);
+    fn send_message<M: Serialize>(&mut self, who: &PeerId, message: M) -> anyhow::Result<()> {
+        let result = send_message(&mut self.behaviour, who, &message);
+        self.on_send_result(who, result)
+    }
+
+    /// Sends the message to every peer we have an open channel to.
+    pub fn broadcast_message(&mut self, message: Message) {
+        let peers: Vec<PeerId> = self.open_peers().cloned().collect();
+        self.send_message_to(&peers, message);
+    }
+
+    /// Sends the message to each of the given peers, skipping the peers we have no open channel
+    /// to.
+    pub fn send_message_to(&mut self, peers: &[PeerId], message: Message) {
+        for peer in peers {
+            if !self.is_open(peer) {
+                trace!(target: "sync", "Skip sending message to {}, no open channel", peer);
+                continue;
+            }
+            // the failure is logged and accounted by `send_message`.
+            let _ = self.send_message(peer, message.clone());
+        }
+    }
+
+    fn on_send_result(&mut self, who: &PeerId, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        match result {
+            Ok(()) => {
+                self.send_failures.remove(who);
+                Ok(())
+            }
+            Err(e) => {
+                warn!(target: "sync", "Failed to send message to {}: {:?}", who, e);
+                self.on_send_failure(who.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Records a failed send to the peer, and reports it once the failures pile up.
+    fn on_send_failure(&mut self, who: PeerId) {
+        let failures = self.send_failures.entry(who.clone()).or_insert(0);
+        *failures += 1;
+        if *failures >= MAX_SEND_FAILURES {
+            self.send_failures.remove(&who);
+            self.peerset_handle.report_peer(who, rep::SEND_FAILED);
+        }
     }
 
     /// Called when a new peer is connected
@@ -443,11 +533,13 @@ impl Protocol {
                 timestamp: Instant::now(),
             },
         );
-        self.send_status(who);
+        if let Err(e) = self.send_status(who.clone()) {
+            debug!(target: "sync", "Send status to {} failed: {:?}", who, e);
+        }
     }
 
     /// Send Status message
-    fn send_status(&mut self, who: PeerId) {
+    fn send_status(&mut self, who: PeerId) -> anyhow::Result<()> {
         let status = message::generic::Status {
             version: CURRENT_VERSION,
             min_supported_version: MIN_VERSION,
@@ -459,7 +551,7 @@ impl Protocol {
     }
 
     /// Called by peer when it is disconnecting
-    pub fn on_peer_disconnected(&mut self, peer: PeerId) {
+    pub fn on_peer_disconnected(&mut self, peer: PeerId) -> CustomMessageOutcome {
         if self.important_peers.contains(&peer) {
             warn!(target: "sync", "Reserved peer {} disconnected", peer);
         } else {
@@ -467,9 +559,19 @@ impl Protocol {
         }
 
         // lock all the the peer lists so that add/remove peer events are in order
-        {
+        let opened = {
             self.handshaking_peers.remove(&peer);
+            // a reconnected peer starts over with no send failures.
+            self.send_failures.remove(&peer);
+            self.context_data.peers.remove(&peer).is_some()
         };
+        if opened {
+            // Notify all the notification protocols as closed.
+            CustomMessageOutcome::NotificationStreamClosed { remote: peer }
+        } else {
+            // The notification protocols were never reported open to the peer.
+            CustomMessageOutcome::None
+        }
     }
 
     /// Called as a back-pressure mechanism if the networking detects that the peer cannot process
@@ -494,9 +596,11 @@ impl Protocol {
         let tick = Instant::now();
         let mut aborting = Vec::new();
         {
-            for (who, _) in self.handshaking_peers.iter().filter(|(_, handshaking)| {
-                (tick - handshaking.timestamp).as_secs() > REQUEST_TIMEOUT_SEC
-            }) {
+            for (who, _) in self
+                .handshaking_peers
+                .iter()
+                .filter(|(_, handshaking)| tick - handshaking.timestamp > self.handshake_timeout)
+            {
                 info!(
                     target: "sync",
                     "Handshake timeout {}", who
@@ -513,22 +617,27 @@ impl Protocol {
 
     /// Send a notification to the given peer we're connected to.
     ///
-    /// Doesn't do anything if we don't have a notifications substream for that protocol with that
-    /// peer.
+    /// The notification goes to the substream of `protocol_name` if the protocol is registered,
+    /// otherwise it is sent as a `ConsensusMessage` on the legacy substream.
+    ///
+    /// Returns an error if we don't have an open channel with that peer or the message can't be
+    /// encoded.
     pub fn write_notification(
         &mut self,
         target: PeerId,
-        _protocol_name: Cow<'static, [u8]>,
+        protocol_name: Cow<'static, [u8]>,
         message: impl Into<Vec<u8>>,
-    ) {
-        self.send_message(
-            &target,
-            Message::Consensus(ConsensusMessage {
-                data: message.into(),
-            }),
-        );
-        // self.behaviour
-        //     .write_notification(&target, protocol_name, message);
+    ) -> anyhow::Result<()> {
+        let message = message.into();
+        if self.behaviour.is_notif_protocol(&protocol_name) {
+            let result = send_notification(&mut self.behaviour, &target, protocol_name, message);
+            self.on_send_result(&target, result)
+        } else {
+            self.send_message(
+                &target,
+                Message::Consensus(ConsensusMessage { data: message }),
+            )
+        }
     }
 
     pub fn register_notifications_protocol(
@@ -543,9 +652,9 @@ impl Protocol {
         self.context_data
             .peers
             .iter()
-            .map(|(peer_id, _peer)| event::Event::NotificationStreamOpened {
+            .map(|(peer_id, peer)| event::Event::NotificationStreamOpened {
                 remote: peer_id.clone(),
-                info: self.chain_info.self_info.clone(),
+                info: peer.info.clone(),
             })
             .collect()
     }
@@ -555,12 +664,515 @@ impl Protocol {
     }
 }
 
-fn send_message(
+/// Picks the highest version supported by both sides, or `None` if the version ranges of the
+/// peer and ours do not overlap.
+fn negotiate_version(status: &Status) -> Option<u32> {
+    let version = std::cmp::min(CURRENT_VERSION, status.version);
+    if version >= MIN_VERSION && version >= status.min_supported_version {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+fn send_message<M: Serialize>(
     behaviour: &mut GenericProto,
     who: &PeerId,
-    message: Message,
+    message: &M,
 ) -> anyhow::Result<()> {
-    let encoded = message.encode()?;
+    let encoded = scs::to_bytes(message)?;
+    anyhow::ensure!(behaviour.is_open(who), "No open channel with peer {}", who);
     behaviour.send_packet(who, encoded);
     Ok(())
 }
+
+fn send_notification(
+    behaviour: &mut GenericProto,
+    who: &PeerId,
+    protocol_name: Cow<'static, [u8]>,
+    message: Vec<u8>,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(behaviour.is_open(who), "No open channel with peer {}", who);
+    // the remote may not have opened the protocol substream, it then reads the legacy one.
+    let encoded_fallback_message = Message::Consensus(ConsensusMessage {
+        data: message.clone(),
+    })
+    .encode()?;
+    behaviour.write_notification(who, protocol_name, message, encoded_fallback_message);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::swarm::Swarm;
+    use libp2p::Transport;
+    use std::task::Context;
+    use std::{io, iter};
+
+    struct DummyPollParams(PeerId);
+
+    impl PollParameters for DummyPollParams {
+        type SupportedProtocolsIter = iter::Empty<Vec<u8>>;
+        type ListenedAddressesIter = iter::Empty<Multiaddr>;
+        type ExternalAddressesIter = iter::Empty<Multiaddr>;
+
+        fn supported_protocols(&self) -> Self::SupportedProtocolsIter {
+            iter::empty()
+        }
+
+        fn listened_addresses(&self) -> Self::ListenedAddressesIter {
+            iter::empty()
+        }
+
+        fn external_addresses(&self) -> Self::ExternalAddressesIter {
+            iter::empty()
+        }
+
+        fn local_peer_id(&self) -> &PeerId {
+            &self.0
+        }
+    }
+
+    fn new_protocol() -> Protocol {
+        new_protocol_with_timeout(DEFAULT_HANDSHAKE_TIMEOUT)
+    }
+
+    fn new_protocol_with_timeout(handshake_timeout: time::Duration) -> Protocol {
+        let peerset_config = peerset::PeersetConfig {
+            in_peers: 25,
+            out_peers: 25,
+            bootnodes: vec![],
+            reserved_only: false,
+            reserved_nodes: vec![],
+        };
+        let chain_info = ChainInfo {
+            genesis_hash: HashValue::random(),
+            self_info: PeerInfo::default(),
+        };
+        let (protocol, _) = Protocol::new(
+            peerset_config,
+            ProtocolId::from(&b"test"[..]),
+            chain_info,
+            Arc::new(HashSet::new()),
+            handshake_timeout,
+        )
+        .unwrap();
+        protocol
+    }
+
+    /// Lets the peerset process the pending reputation reports.
+    fn drain_peerset(protocol: &mut Protocol) {
+        let mut params = DummyPollParams(PeerId::random());
+        futures::executor::block_on(future::poll_fn(|cx| {
+            let _ = protocol.behaviour.poll(cx, &mut params);
+            Poll::Ready(())
+        }));
+    }
+
+    fn reputation_of(protocol: &mut Protocol, peer: &PeerId) -> Option<i64> {
+        protocol.peerset_debug_info()["nodes"][peer.to_base58()]["reputation"].as_i64()
+    }
+
+    fn new_status(protocol: &Protocol, min_supported_version: u32, version: u32) -> Status {
+        Status {
+            version,
+            min_supported_version,
+            genesis_hash: protocol.chain_info.genesis_hash,
+            info: PeerInfo::default(),
+        }
+    }
+
+    #[test]
+    fn test_negotiate_version() {
+        let mut protocol = new_protocol();
+        // the peer supports versions 1..=3, the highest one we both support is ours.
+        let peer = PeerId::random();
+        protocol.on_peer_connected(peer.clone());
+        let status = new_status(&protocol, 1, CURRENT_VERSION + 2);
+        assert_eq!(negotiate_version(&status), Some(CURRENT_VERSION));
+        match protocol.on_status_message(peer.clone(), status) {
+            CustomMessageOutcome::NotificationStreamOpened { remote, .. } => {
+                assert_eq!(remote, peer)
+            }
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+        assert_eq!(protocol.protocol_version(&peer), Some(CURRENT_VERSION));
+
+        // the peer only supports versions newer than ours.
+        let peer = PeerId::random();
+        protocol.on_peer_connected(peer.clone());
+        let status = new_status(&protocol, CURRENT_VERSION + 1, CURRENT_VERSION + 2);
+        assert_eq!(negotiate_version(&status), None);
+        match protocol.on_status_message(peer.clone(), status) {
+            CustomMessageOutcome::None => {}
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+        assert_eq!(protocol.protocol_version(&peer), None);
+        drain_peerset(&mut protocol);
+        assert_eq!(
+            reputation_of(&mut protocol, &peer),
+            Some(rep::BAD_PROTOCOL.value as i64)
+        );
+    }
+
+    #[test]
+    fn test_status_peer_info() {
+        let mut protocol = new_protocol();
+        let mut peers = vec![];
+        for (block_number, total_difficult) in vec![(10, 100u64), (12, 300), (11, 200)] {
+            let peer = PeerId::random();
+            let info = PeerInfo::new(
+                peer.clone().into(),
+                block_number,
+                types::U512::from(total_difficult),
+                HashValue::random(),
+            );
+            protocol.on_peer_connected(peer.clone());
+            let mut status = new_status(&protocol, MIN_VERSION, CURRENT_VERSION);
+            status.info = info.clone();
+            protocol.on_status_message(peer.clone(), status);
+            assert_eq!(protocol.peer_info(&peer), Some(&info));
+            peers.push(peer);
+        }
+
+        protocol.on_peer_disconnected(peers[1].clone());
+        assert!(protocol.peer_info(&peers[1]).is_none());
+    }
+
+    #[test]
+    fn test_close_only_opened_stream() {
+        let mut protocol = new_protocol();
+        // the peer disconnects before sending its Status.
+        let peer = PeerId::random();
+        protocol.on_peer_connected(peer.clone());
+        match protocol.on_peer_disconnected(peer.clone()) {
+            CustomMessageOutcome::None => {}
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+
+        let peer = PeerId::random();
+        protocol.on_peer_connected(peer.clone());
+        let status = new_status(&protocol, MIN_VERSION, CURRENT_VERSION);
+        protocol.on_status_message(peer.clone(), status);
+        match protocol.on_peer_disconnected(peer.clone()) {
+            CustomMessageOutcome::NotificationStreamClosed { remote } => assert_eq!(remote, peer),
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+        // the peer is gone, a second close is not reported.
+        match protocol.on_peer_disconnected(peer) {
+            CustomMessageOutcome::None => {}
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn test_handshake_timeout() {
+        let mut protocol = new_protocol_with_timeout(time::Duration::from_secs(1));
+        let peer = PeerId::random();
+        // the peer never sends its Status.
+        protocol.on_peer_connected(peer.clone());
+        protocol.tick();
+        drain_peerset(&mut protocol);
+        assert_eq!(reputation_of(&mut protocol, &peer), None);
+
+        std::thread::sleep(time::Duration::from_millis(1100));
+        protocol.tick();
+        drain_peerset(&mut protocol);
+        assert_eq!(
+            reputation_of(&mut protocol, &peer),
+            Some(rep::TIMEOUT.value as i64)
+        );
+    }
+
+    /// Wraps around the `Protocol` and adds hardcoded node addresses to it.
+    struct ProtocolWithAddr {
+        inner: Protocol,
+        addrs: Vec<(PeerId, Multiaddr)>,
+    }
+
+    impl std::ops::Deref for ProtocolWithAddr {
+        type Target = Protocol;
+
+        fn deref(&self) -> &Self::Target {
+            &self.inner
+        }
+    }
+
+    impl std::ops::DerefMut for ProtocolWithAddr {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.inner
+        }
+    }
+
+    impl NetworkBehaviour for ProtocolWithAddr {
+        type ProtocolsHandler = <Protocol as NetworkBehaviour>::ProtocolsHandler;
+        type OutEvent = <Protocol as NetworkBehaviour>::OutEvent;
+
+        fn new_handler(&mut self) -> Self::ProtocolsHandler {
+            self.inner.new_handler()
+        }
+
+        fn addresses_of_peer(&mut self, peer_id: &PeerId) -> Vec<Multiaddr> {
+            let mut list = self.inner.addresses_of_peer(peer_id);
+            for (p, a) in self.addrs.iter() {
+                if p == peer_id {
+                    list.push(a.clone());
+                }
+            }
+            list
+        }
+
+        fn inject_connected(&mut self, peer_id: PeerId, endpoint: ConnectedPoint) {
+            self.inner.inject_connected(peer_id, endpoint)
+        }
+
+        fn inject_disconnected(&mut self, peer_id: &PeerId, endpoint: ConnectedPoint) {
+            self.inner.inject_disconnected(peer_id, endpoint)
+        }
+
+        fn inject_node_event(
+            &mut self,
+            peer_id: PeerId,
+            event: <<Self::ProtocolsHandler as IntoProtocolsHandler>::Handler as ProtocolsHandler>::OutEvent,
+        ) {
+            self.inner.inject_node_event(peer_id, event)
+        }
+
+        fn poll(
+            &mut self,
+            cx: &mut Context,
+            params: &mut impl PollParameters,
+        ) -> Poll<
+            NetworkBehaviourAction<
+                <<Self::ProtocolsHandler as IntoProtocolsHandler>::Handler as ProtocolsHandler>::InEvent,
+                Self::OutEvent
+            >
+>{
+            self.inner.poll(cx, params)
+        }
+
+        fn inject_replaced(
+            &mut self,
+            peer_id: PeerId,
+            closed_endpoint: ConnectedPoint,
+            new_endpoint: ConnectedPoint,
+        ) {
+            self.inner
+                .inject_replaced(peer_id, closed_endpoint, new_endpoint)
+        }
+
+        fn inject_dial_failure(&mut self, peer_id: &PeerId) {
+            self.inner.inject_dial_failure(peer_id)
+        }
+    }
+
+    /// Builds `count` nodes of the same chain, the first one has all the others as bootstrap
+    /// nodes.
+    fn build_nodes(count: usize) -> Vec<Swarm<ProtocolWithAddr>> {
+        let genesis_hash = HashValue::random();
+        let keypairs: Vec<_> = (0..count)
+            .map(|_| libp2p::identity::Keypair::generate_ed25519())
+            .collect();
+        let addrs: Vec<Multiaddr> = (0..count)
+            .map(|_| {
+                format!("/memory/{}", rand::random::<u64>())
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+
+        (0..count)
+            .map(|index| {
+                let keypair = keypairs[index].clone();
+                let transport = libp2p::core::transport::MemoryTransport
+                    .and_then(move |out, endpoint| {
+                        let secio = libp2p::secio::SecioConfig::new(keypair);
+                        libp2p::core::upgrade::apply(
+                            out,
+                            secio,
+                            endpoint,
+                            libp2p::core::upgrade::Version::V1,
+                        )
+                    })
+                    .and_then(move |(peer_id, stream), endpoint| {
+                        libp2p::core::upgrade::apply(
+                            stream,
+                            libp2p::yamux::Config::default(),
+                            endpoint,
+                            libp2p::core::upgrade::Version::V1,
+                        )
+                        .map_ok(|muxer| (peer_id, libp2p::core::muxing::StreamMuxerBox::new(muxer)))
+                    })
+                    .timeout(time::Duration::from_secs(20))
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+                    .boxed();
+
+                let peerset_config = peerset::PeersetConfig {
+                    in_peers: 25,
+                    out_peers: 25,
+                    bootnodes: if index == 0 {
+                        keypairs
+                            .iter()
+                            .skip(1)
+                            .map(|keypair| keypair.public().into_peer_id())
+                            .collect()
+                    } else {
+                        vec![]
+                    },
+                    reserved_only: false,
+                    reserved_nodes: vec![],
+                };
+                let chain_info = ChainInfo {
+                    genesis_hash,
+                    self_info: PeerInfo::default(),
+                };
+                let (protocol, _) = Protocol::new(
+                    peerset_config,
+                    ProtocolId::from(&b"test"[..]),
+                    chain_info,
+                    Arc::new(HashSet::new()),
+                    DEFAULT_HANDSHAKE_TIMEOUT,
+                )
+                .unwrap();
+                let behaviour = ProtocolWithAddr {
+                    inner: protocol,
+                    addrs: addrs
+                        .iter()
+                        .enumerate()
+                        .filter(|(n, _)| *n != index)
+                        .map(|(n, a)| (keypairs[n].public().into_peer_id(), a.clone()))
+                        .collect(),
+                };
+
+                let mut swarm = Swarm::new(
+                    transport,
+                    behaviour,
+                    keypairs[index].public().into_peer_id(),
+                );
+                Swarm::listen_on(&mut swarm, addrs[index].clone()).unwrap();
+                swarm
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_broadcast_message() {
+        let mut nodes = build_nodes(4);
+        let data = vec![1u8, 2, 3];
+        let message = Message::Consensus(ConsensusMessage { data: data.clone() });
+        let mut opened = HashSet::new();
+        let mut broadcasted = false;
+        let mut received = HashSet::new();
+
+        futures::executor::block_on(future::poll_fn(move |cx| {
+            for (index, node) in nodes.iter_mut().enumerate() {
+                while let Poll::Ready(Some(event)) = node.poll_next_unpin(cx) {
+                    match event {
+                        CustomMessageOutcome::NotificationStreamOpened { remote, .. }
+                            if index == 0 =>
+                        {
+                            opened.insert(remote);
+                        }
+                        CustomMessageOutcome::NotificationsReceived { messages, .. } => {
+                            assert_ne!(index, 0);
+                            assert_eq!(messages, vec![Bytes::from(data.clone())]);
+                            assert!(received.insert(index));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // all the other nodes are connected to the first one.
+            if !broadcasted && opened.len() == 3 {
+                assert_eq!(nodes[0].open_peers().count(), 3);
+                nodes[0].broadcast_message(message.clone());
+                broadcasted = true;
+                cx.waker().wake_by_ref();
+            }
+            if received.len() == 3 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }));
+    }
+
+    #[test]
+    fn test_send_message_to_skips_closed_peers() {
+        let mut protocol = new_protocol();
+        let peers = vec![PeerId::random(), PeerId::random()];
+        protocol.send_message_to(
+            &peers,
+            Message::Consensus(ConsensusMessage { data: vec![1u8] }),
+        );
+        // no channel is open with these peers, nothing is sent to them.
+        for peer in &peers {
+            assert_eq!(protocol.send_failures.get(peer), None);
+        }
+    }
+
+    #[test]
+    fn test_send_status_error_surfaces() {
+        let mut protocol = new_protocol();
+        let peer = PeerId::random();
+        let err = protocol.send_status(peer.clone()).unwrap_err();
+        assert!(err.to_string().contains("No open channel"));
+        assert_eq!(protocol.send_failures.get(&peer), Some(&1));
+    }
+
+    #[test]
+    fn test_send_failures_cleared_on_disconnect() {
+        let mut protocol = new_protocol();
+        let peer = PeerId::random();
+        assert!(protocol.send_status(peer.clone()).is_err());
+        assert_eq!(protocol.send_failures.get(&peer), Some(&1));
+        protocol.on_peer_disconnected(peer.clone());
+        assert_eq!(protocol.send_failures.get(&peer), None);
+    }
+
+    /// A message which always fails to encode.
+    struct UnencodableMessage;
+
+    impl Serialize for UnencodableMessage {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("unencodable message"))
+        }
+    }
+
+    #[test]
+    fn test_send_encode_error_surfaces() {
+        let mut protocol = new_protocol();
+        let peer = PeerId::random();
+        let err = protocol
+            .send_message(&peer, UnencodableMessage)
+            .unwrap_err();
+        assert!(err.to_string().contains("unencodable message"));
+        assert_eq!(protocol.send_failures.get(&peer), Some(&1));
+    }
+
+    #[test]
+    fn test_repeated_send_failure_penalty() {
+        let mut protocol = new_protocol();
+        // no channel is open with this peer, so every send fails.
+        let peer = PeerId::random();
+        for i in 1..MAX_SEND_FAILURES {
+            assert!(protocol
+                .write_notification(peer.clone(), Cow::Borrowed(&b"test"[..]), vec![1u8])
+                .is_err());
+            assert_eq!(protocol.send_failures.get(&peer), Some(&i));
+        }
+        drain_peerset(&mut protocol);
+        assert_eq!(reputation_of(&mut protocol, &peer), None);
+
+        assert!(protocol
+            .write_notification(peer.clone(), Cow::Borrowed(&b"test"[..]), vec![1u8])
+            .is_err());
+        assert_eq!(protocol.send_failures.get(&peer), None);
+        drain_peerset(&mut protocol);
+        assert_eq!(
+            reputation_of(&mut protocol, &peer),
+            Some(rep::SEND_FAILED.value as i64)
+        );
+    }
+}
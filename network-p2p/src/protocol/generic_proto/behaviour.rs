@@ -22,14 +22,12 @@ use crate::{config::ProtocolId, DiscoveryNetBehaviour};
 
 use bytes::BytesMut;
 
-use crate::protocol::message::generic::{ConsensusMessage, Message};
 use fnv::FnvHashMap;
 use futures::prelude::*;
 use libp2p::core::{ConnectedPoint, Multiaddr, PeerId};
 use libp2p::swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters};
 use log::{debug, error, trace, warn};
 use rand::distributions::{Distribution as _, Uniform};
-use scs::SCSCodec;
 use smallvec::SmallVec;
 use std::task::{Context, Poll};
 use std::{borrow::Cow, cmp, collections::hash_map::Entry};
@@ -236,6 +234,16 @@ pub enum GenericProtoOut {
         message: BytesMut,
     },
 
+    /// Receives a message on a registered notifications protocol substream.
+    Notification {
+        /// Id of the peer the message came from.
+        peer_id: PeerId,
+        /// Name of the notifications protocol the message was received on.
+        protocol_name: Cow<'static, [u8]>,
+        /// Message that has been received.
+        message: BytesMut,
+    },
+
     /// The substream used by the protocol is pretty large. We should print avoid sending more
     /// messages on it if possible.
     Clogged {
@@ -279,6 +287,13 @@ impl GenericProto {
             .push((protocol_name.into(), handshake_msg.into()));
     }
 
+    /// Returns true if the notifications protocol has been registered.
+    pub fn is_notif_protocol(&self, protocol_name: &[u8]) -> bool {
+        self.notif_protocols
+            .iter()
+            .any(|(name, _)| &name[..] == protocol_name)
+    }
+
     /// Returns the list of all the peers we have an open channel to.
     pub fn open_peers<'a>(&'a self) -> impl Iterator<Item = &'a PeerId> + 'a {
         self.peers
@@ -416,13 +431,14 @@ impl GenericProto {
     /// Also note that even if we have a valid open substream, it may in fact be already closed
     /// without us knowing, in which case the packet will not be received.
     ///
-    /// > **Note**: Ideally the `engine_id` parameter wouldn't be necessary. See the documentation
-    /// >			of [`NotifsHandlerIn`] for more information.
+    /// The `encoded_fallback_message` is sent on the legacy substream instead if the remote
+    /// has not opened a substream for the protocol.
     pub fn write_notification(
         &mut self,
         target: &PeerId,
         protocol_name: Cow<'static, [u8]>,
         message: impl Into<Vec<u8>>,
+        encoded_fallback_message: Vec<u8>,
     ) {
         if !self.is_open(target) {
             return;
@@ -440,6 +456,7 @@ impl GenericProto {
             peer_id: target.clone(),
             event: NotifsHandlerIn::SendNotification {
                 message: message.into(),
+                encoded_fallback_message,
                 protocol_name,
             },
         });
@@ -1146,13 +1163,9 @@ impl NetworkBehaviour for GenericProto {
                     str::from_utf8(&protocol_name)
                 );
                 trace!(target: "sub-libp2p", "External API <= Message({:?})", source);
-                let msg = Message::Consensus(ConsensusMessage {
-                    data: message.to_vec(),
-                });
-
-                let message = From::from(&msg.encode().unwrap()[..]);
-                let event = GenericProtoOut::CustomMessage {
+                let event = GenericProtoOut::Notification {
                     peer_id: source,
+                    protocol_name,
                     message,
                 };
 
@@ -192,12 +192,13 @@ pub enum NotifsHandlerIn {
         /// `ConsensusMessage` message.
         protocol_name: Cow<'static, [u8]>,
 
-        /// The engine ID to use, in case we need to send this message over the legacy substream.
+        /// The message to send on the legacy substream, if the notifications substream of the
+        /// protocol is not open.
         ///
-        /// > **Note**: Ideally this field wouldn't be necessary, and we would deduce the engine
-        /// >			ID from the existing handlers. However, it is possible (especially in test
-        /// >			situations) that we open connections before all the notification protocols
-        /// >			have been registered, in which case we always rely on the legacy substream.
+        /// > **Note**: It is possible (especially in test situations) that we open connections
+        /// >			before all the notification protocols have been registered, in which case
+        /// >			we always rely on the legacy substream.
+        encoded_fallback_message: Vec<u8>,
 
         /// The message to send.
         message: Vec<u8>,
@@ -364,23 +365,25 @@ impl ProtocolsHandler for NotifsHandler {
                 .inject_event(LegacyProtoHandlerIn::SendCustomMessage { message }),
             NotifsHandlerIn::SendNotification {
                 message,
+                encoded_fallback_message,
                 protocol_name,
             } => {
                 for handler in &mut self.out_handlers {
                     if handler.protocol_name() != &protocol_name[..] {
-                        break;
+                        continue;
                     }
 
                     if handler.is_open() {
                         handler.inject_event(NotifsOutHandlerIn::Send(message));
                         return;
-                    } else {
-                        //debug_assert_eq!(engine_id, *ngn_id);
                     }
+                    break;
                 }
 
                 self.legacy
-                    .inject_event(LegacyProtoHandlerIn::SendCustomMessage { message });
+                    .inject_event(LegacyProtoHandlerIn::SendCustomMessage {
+                        message: encoded_fallback_message,
+                    });
             }
         }
     }
@@ -335,6 +335,86 @@ fn basic_two_nodes_requests_in_parallel() {
     });
 }
 
+#[test]
+fn notifications_routed_by_protocol() {
+    // Both nodes register two notifications protocols, the first one keeps sending a
+    // notification on each of them until the second one has received both on the right
+    // substream.
+    const PROTOCOL_A: &[u8] = b"/test/notif/a";
+    const PROTOCOL_B: &[u8] = b"/test/notif/b";
+    const FALLBACK: &[u8] = b"fallback";
+
+    let (mut service1, mut service2) = build_nodes();
+    service1.register_notif_protocol(PROTOCOL_A, Vec::new());
+    service1.register_notif_protocol(PROTOCOL_B, Vec::new());
+    service2.register_notif_protocol(PROTOCOL_A, Vec::new());
+    service2.register_notif_protocol(PROTOCOL_B, Vec::new());
+    assert!(service1.is_notif_protocol(PROTOCOL_A));
+    assert!(!service1.is_notif_protocol(b"/test/notif/c"));
+
+    let mut remote = None;
+    let mut delay = futures_timer::Delay::new(Duration::from_millis(100));
+    let fut1 = future::poll_fn(move |cx| -> Poll<()> {
+        // the notifications substreams are opened after the legacy one, so resend until the
+        // remote receives them.
+        while let Poll::Ready(()) = delay.poll_unpin(cx) {
+            if let Some(peer_id) = &remote {
+                service1.write_notification(
+                    peer_id,
+                    PROTOCOL_A.into(),
+                    b"a".to_vec(),
+                    FALLBACK.to_vec(),
+                );
+                service1.write_notification(
+                    peer_id,
+                    PROTOCOL_B.into(),
+                    b"b".to_vec(),
+                    FALLBACK.to_vec(),
+                );
+            }
+            delay.reset(Duration::from_millis(100));
+        }
+        loop {
+            match ready!(service1.poll_next_unpin(cx)) {
+                Some(GenericProtoOut::CustomProtocolOpen { peer_id, .. }) => remote = Some(peer_id),
+                _ => panic!(),
+            }
+        }
+    });
+
+    let mut received = HashSet::new();
+    let fut2 = future::poll_fn(move |cx| loop {
+        match ready!(service2.poll_next_unpin(cx)) {
+            Some(GenericProtoOut::CustomProtocolOpen { .. }) => {}
+            // sent before the notifications substream is open.
+            Some(GenericProtoOut::CustomMessage { message, .. }) => {
+                assert_eq!(&message[..], FALLBACK)
+            }
+            Some(GenericProtoOut::Notification {
+                protocol_name,
+                message,
+                ..
+            }) => {
+                if &protocol_name[..] == PROTOCOL_A {
+                    assert_eq!(&message[..], b"a");
+                } else {
+                    assert_eq!(&protocol_name[..], PROTOCOL_B);
+                    assert_eq!(&message[..], b"b");
+                }
+                received.insert(protocol_name);
+                if received.len() == 2 {
+                    return Poll::Ready(());
+                }
+            }
+            _ => panic!(),
+        }
+    });
+
+    futures::executor::block_on(async move {
+        future::select(fut1, fut2).await;
+    });
+}
+
 #[ignore]
 #[test]
 fn reconnect_after_disconnect() {
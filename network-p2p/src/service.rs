@@ -195,6 +195,7 @@ impl NetworkWorker {
             params.protocol_id.clone(),
             chain_info,
             boot_node_ids,
+            params.network_config.handshake_timeout,
         )?;
 
         // Build the swarm.
@@ -477,6 +478,22 @@ impl NetworkService {
             .unbounded_send(ServiceToWorkerMsg::SelfInfo(info));
     }
 
+    /// Returns the state of the peerset manager, with the priority groups and the reputations of
+    /// the known peers.
+    pub async fn peerset_debug_info(&self) -> serde_json::Value {
+        let (tx, rx) = oneshot::channel();
+        let _ = self
+            .to_worker
+            .unbounded_send(ServiceToWorkerMsg::PeersetDebugInfo(tx));
+        match rx.await {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("sth wrong {}", e);
+                serde_json::Value::Null
+            }
+        }
+    }
+
     /// Returns a stream containing the events that happen on the network.
     ///
     /// If this method is called multiple times, the events are duplicated.
@@ -652,6 +669,7 @@ enum ServiceToWorkerMsg {
     ConnectedPeers(oneshot::Sender<HashSet<PeerId>>),
     SelfInfo(PeerInfo),
     AddressByPeerID(PeerId, oneshot::Sender<Vec<Multiaddr>>),
+    PeersetDebugInfo(oneshot::Sender<serde_json::Value>),
 }
 
 /// Main network worker. Must be polled in order for the network to advance.
@@ -703,11 +721,15 @@ impl Future for NetworkWorker {
                     message,
                     protocol_name,
                     target,
-                } => this.network_service.user_protocol_mut().write_notification(
-                    target,
-                    protocol_name,
-                    message,
-                ),
+                } => {
+                    if let Err(e) = this.network_service.user_protocol_mut().write_notification(
+                        target.clone(),
+                        protocol_name,
+                        message,
+                    ) {
+                        debug!("write notification to {} failed: {:?}", target, e);
+                    }
+                }
                 ServiceToWorkerMsg::RegisterNotifProtocol { protocol_name } => {
                     let events = this
                         .network_service
@@ -741,6 +763,13 @@ impl Future for NetworkWorker {
                 ServiceToWorkerMsg::AddressByPeerID(peer_id, tx) => {
                     tx.send(this.network_service.get_address(&peer_id));
                 }
+                ServiceToWorkerMsg::PeersetDebugInfo(tx) => {
+                    let _ = tx.send(
+                        this.network_service
+                            .user_protocol_mut()
+                            .peerset_debug_info(),
+                    );
+                }
             }
         }
 
@@ -7,8 +7,10 @@ mod tests {
     use crate::{Event, Multiaddr, NodeKeyConfig, PeerId, ProtocolId, Secret};
     use crate::{NetworkConfiguration, NetworkWorker, Params};
     use crypto::HashValue;
-    use futures::stream::StreamExt;
+    use futures::{future::FutureExt, stream::StreamExt};
+    use futures_timer::Delay;
     use libp2p::identity;
+    use sg_config::ChainNetwork;
     use std::str::FromStr;
     use std::thread;
     use std::time::Duration;
@@ -151,6 +153,57 @@ mod tests {
         rt.block_on(fut);
     }
 
+    #[test]
+    fn test_different_chain_protocol_id() {
+        ::logger::init_for_test();
+
+        let mut rt = Runtime::new().unwrap();
+        let handle = rt.handle().clone();
+
+        let protocol1 = ProtocolId::from_chain_id(ChainNetwork::Dev.chain_id());
+        let protocol2 = ProtocolId::from_chain_id(ChainNetwork::Halley.chain_id());
+        assert_ne!(protocol1, protocol2);
+
+        let config1 = generate_config(vec![]);
+        let worker1 = NetworkWorker::new(Params::new(config1.clone(), protocol1)).unwrap();
+        let service1 = worker1.service().clone();
+        let mut stream = service1.event_stream();
+        service1.register_notifications_protocol(PROTOCOL_NAME);
+
+        handle.spawn(worker1);
+
+        let seed: Multiaddr = format!(
+            "{}/p2p/{}",
+            &config1.listen_addresses.get(0).expect("should have"),
+            service1.peer_id().to_base58()
+        )
+        .parse()
+        .unwrap();
+        let config2 = generate_config(vec![seed]);
+        let worker2 = NetworkWorker::new(Params::new(config2, protocol2)).unwrap();
+        let service2 = worker2.service().clone();
+        service2.register_notifications_protocol(PROTOCOL_NAME);
+
+        handle.spawn(worker2);
+
+        let fut = async move {
+            let mut delay = Delay::new(Duration::from_secs(3)).fuse();
+            loop {
+                futures::select! {
+                    event = stream.next().fuse() => match event {
+                        Some(Event::NotificationStreamOpened { remote, .. }) => {
+                            panic!("substream with {} should not be opened", remote);
+                        }
+                        Some(event) => info!("event is {:?}", event),
+                        None => break,
+                    },
+                    _ = delay => break,
+                }
+            }
+        };
+        rt.block_on(fut);
+    }
+
     fn generate_config(boot_nodes: Vec<Multiaddr>) -> NetworkConfiguration {
         let mut config = NetworkConfiguration::default();
         let listen = format!("/ip4/127.0.0.1/tcp/{}", sg_config::get_available_port());
@@ -33,7 +33,17 @@ pub trait NetworkService: Send + Sync + Clone + Sized + std::marker::Unpin {
 
     async fn best_peer(&self) -> Result<Option<PeerInfo>>;
 
+    /// A random peer other than self and the `excluded` peers.
+    async fn random_peer_excluding(
+        &self,
+        excluded: &[starcoin_types::peer_info::PeerId],
+    ) -> Result<Option<PeerInfo>>;
+
     async fn get_peer_set_size(&self) -> Result<usize>;
+
+    /// The peerset state of the network for debugging, with the priority groups and the
+    /// reputations of the known peers.
+    async fn peerset_debug_info(&self) -> Result<serde_json::Value>;
 }
 
 #[derive(Clone)]
@@ -91,7 +101,38 @@ impl NetworkService for DummyNetworkService {
         Ok(Some(self.peers.get(0).expect("should have").clone()))
     }
 
+    async fn random_peer_excluding(
+        &self,
+        excluded: &[starcoin_types::peer_info::PeerId],
+    ) -> Result<Option<PeerInfo>> {
+        Ok(self
+            .peers
+            .iter()
+            .find(|peer| !excluded.contains(&peer.get_peer_id()))
+            .cloned())
+    }
+
     async fn get_peer_set_size(&self) -> Result<usize> {
         Ok(0)
     }
+
+    /// The peers are connected with the initial reputation.
+    async fn peerset_debug_info(&self) -> Result<serde_json::Value> {
+        let nodes: serde_json::Map<String, serde_json::Value> = self
+            .peers
+            .iter()
+            .map(|peer| {
+                (
+                    peer.get_peer_id().to_base58(),
+                    serde_json::json!({"connected": true, "reputation": 0}),
+                )
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "nodes": nodes,
+            "priority_groups": {},
+            "reserved_only": false,
+            "message_queue": 0,
+        }))
+    }
 }
@@ -18,12 +18,11 @@ use network_p2p::{
 };
 use parity_codec::alloc::collections::HashSet;
 use parking_lot::Mutex;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 use types::peer_info::PeerInfo;
 
 const PROTOCOL_NAME: &[u8] = b"/starcoin/consensus/1";
-const PROTOCOL_ID: &[u8] = b"stargate";
 
 #[derive(Clone)]
 pub struct SNetworkService {
@@ -40,8 +39,8 @@ pub struct NetworkInner {
 }
 
 impl SNetworkService {
-    pub fn new(cfg: NetworkConfiguration, handle: Handle) -> Self {
-        let protocol = network_p2p::ProtocolId::from(PROTOCOL_ID);
+    pub fn new(cfg: NetworkConfiguration, chain_id: u64, handle: Handle) -> Self {
+        let protocol = network_p2p::ProtocolId::from_chain_id(chain_id);
 
         let worker = NetworkWorker::new(Params::new(cfg, protocol)).unwrap();
         let service = worker.service().clone();
@@ -161,6 +160,10 @@ impl SNetworkService {
     pub async fn get_address(&self, peer_id: PeerId) -> Vec<Multiaddr> {
         self.service.get_address(peer_id).await
     }
+
+    pub async fn peerset_debug_info(&self) -> serde_json::Value {
+        self.service.peerset_debug_info().await
+    }
 }
 
 impl NetworkInner {
@@ -254,6 +257,7 @@ impl NetworkInner {
 pub fn build_network_service(
     cfg: &NetworkConfig,
     handle: Handle,
+    chain_id: u64,
     genesis_hash: HashValue,
     self_info: PeerInfo,
 ) -> (
@@ -273,11 +277,12 @@ pub fn build_network_service(
             .unwrap();
             NodeKeyConfig::Ed25519(Secret::Input(secret))
         },
+        handshake_timeout: Duration::from_secs(cfg.handshake_timeout),
         genesis_hash,
         self_info,
         ..NetworkConfiguration::default()
     };
-    let mut service = SNetworkService::new(config, handle);
+    let mut service = SNetworkService::new(config, chain_id, handle);
     let (net_tx, net_rx, event_rx, control_tx) = service.run();
     (service, net_tx, net_rx, event_rx, control_tx)
 }
@@ -6,7 +6,7 @@ mod tests {
     use crate::net::{build_network_service, SNetworkService};
     use crate::NetworkMessage;
     use crate::PeerEvent;
-    use config::{get_available_port, NodeConfig};
+    use config::{get_available_port, ChainNetwork, NodeConfig};
     use crypto::hash::HashValue;
     use futures::{
         channel::mpsc::{UnboundedReceiver, UnboundedSender},
@@ -81,6 +81,7 @@ mod tests {
             let server = build_network_service(
                 &config,
                 handle.clone(),
+                ChainNetwork::Dev.chain_id(),
                 HashValue::default(),
                 PeerInfo::default(),
             );
@@ -255,6 +256,7 @@ mod tests {
         let (service1, _net_tx1, _net_rx1, _event_rx1, _command_tx1) = build_network_service(
             &node_config1,
             rt.handle().clone(),
+            ChainNetwork::Dev.chain_id(),
             HashValue::default(),
             PeerInfo::default(),
         );
@@ -273,6 +275,7 @@ mod tests {
         let (service2, _net_tx2, _net_rx2, _event_rx2, _command_tx2) = build_network_service(
             &node_config2,
             rt.handle().clone(),
+            ChainNetwork::Dev.chain_id(),
             HashValue::default(),
             PeerInfo::default(),
         );
@@ -287,6 +290,7 @@ mod tests {
         let (service3, _net_tx3, _net_rx3, _event_rx3, _command_tx3) = build_network_service(
             &node_config3,
             rt.handle().clone(),
+            ChainNetwork::Dev.chain_id(),
             HashValue::default(),
             PeerInfo::default(),
         );
@@ -322,6 +326,7 @@ mod tests {
         let (service2, _net_tx2, _net_rx2, _event_tx2, _command_tx2) = build_network_service(
             &node_config2,
             rt.handle().clone(),
+            ChainNetwork::Dev.chain_id(),
             HashValue::default(),
             PeerInfo::default(),
         );
@@ -331,6 +336,7 @@ mod tests {
         let (service3, _net_tx3, _net_rx3, _event_rx3, _command_tx3) = build_network_service(
             &node_config3,
             rt.handle().clone(),
+            ChainNetwork::Dev.chain_id(),
             HashValue::default(),
             PeerInfo::default(),
         );
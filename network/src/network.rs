@@ -19,10 +19,12 @@ use libp2p::PeerId;
 use lru::LruCache;
 use network_api::{messages::RawRpcRequestMessage, NetworkService};
 use network_p2p::Multiaddr;
+use rand::seq::SliceRandom;
 
 use async_trait::async_trait;
 use scs::SCSCodec;
 use starcoin_sync_api::sync_messages::PeerNewBlock;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::prelude::*;
@@ -81,6 +83,31 @@ impl PeerInfoNet {
     }
 }
 
+/// Record the info the peer advertised in its Status handshake, a reconnected peer replaces
+/// the info of its last connection.
+fn on_peer_handshake(
+    peers: &mut HashMap<PeerId, PeerInfoNet>,
+    peer_id: PeerId,
+    peer_info: PeerInfo,
+) {
+    match peers.entry(peer_id) {
+        Entry::Occupied(mut entry) => entry.get_mut().peer_info = peer_info,
+        Entry::Vacant(entry) => {
+            entry.insert(PeerInfoNet::new(peer_info));
+        }
+    }
+}
+
+/// The peer with the highest total difficulty, excluding self.
+fn best_peer(peers: &HashMap<PeerId, PeerInfoNet>, self_peer_id: &PeerId) -> Option<PeerInfo> {
+    peers
+        .iter()
+        .filter(|(peer_id, _)| *peer_id != self_peer_id)
+        .map(|(_, peer)| &peer.peer_info)
+        .max_by_key(|peer_info| peer_info.total_difficult)
+        .cloned()
+}
+
 #[async_trait]
 impl NetworkService for NetworkAsyncService {
     async fn send_peer_message(&self, peer_id: PeerId, msg: PeerMessage) -> Result<()> {
@@ -171,22 +198,34 @@ impl NetworkService for NetworkAsyncService {
     }
 
     async fn best_peer(&self) -> Result<Option<PeerInfo>> {
+        Ok(best_peer(&*self.inner.peers.lock().await, &self.peer_id))
+    }
+
+    async fn random_peer_excluding(
+        &self,
+        excluded: &[types::peer_info::PeerId],
+    ) -> Result<Option<PeerInfo>> {
         let self_peer_id = types::peer_info::PeerId::new(self.peer_id.clone());
-        let best_peer_set = self.best_peer_set().await?;
-        let best_peer = best_peer_set
-            .iter()
-            .filter(|peer| self_peer_id != peer.get_peer_id())
-            .next();
-        match best_peer {
-            Some(peer) => Ok(Some(peer.clone())),
-            None => Ok(None),
-        }
+        let peers: Vec<PeerInfo> = self
+            .peer_set()
+            .await?
+            .into_iter()
+            .filter(|peer| {
+                let peer_id = peer.get_peer_id();
+                peer_id != self_peer_id && !excluded.contains(&peer_id)
+            })
+            .collect();
+        Ok(peers.choose(&mut rand::thread_rng()).cloned())
     }
 
     async fn get_peer_set_size(&self) -> Result<usize> {
         let size = self.inner.peers.lock().await.len();
         Ok(size)
     }
+
+    async fn peerset_debug_info(&self) -> Result<serde_json::Value> {
+        Ok(self.inner.network_service.peerset_debug_info().await)
+    }
 }
 
 impl NetworkAsyncService {
@@ -246,6 +285,7 @@ impl NetworkActor {
         let (service, tx, rx, event_rx, tx_command) = build_network_service(
             &network_config,
             handle.clone(),
+            node_config.net().chain_id(),
             genesis_hash,
             self_info.clone(),
         );
@@ -472,11 +512,7 @@ impl Inner {
     }
 
     async fn on_peer_connected(&self, peer_id: PeerId, peer_info: PeerInfo) -> Result<()> {
-        self.peers
-            .lock()
-            .await
-            .entry(peer_id.clone())
-            .or_insert_with(|| PeerInfoNet::new(peer_info));
+        on_peer_handshake(&mut *self.peers.lock().await, peer_id, peer_info);
 
         let path = self.node_config.base.data_dir();
         let file = Path::new(PEERS_FILE_NAME);
@@ -675,6 +711,7 @@ mod tests {
     use tokio::task;
     use types::account_address::AccountAddress;
     use types::transaction::SignedUserTransaction;
+    use types::U512;
 
     #[rtype(result = "Result<()>")]
     #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Message, Clone)]
@@ -691,6 +728,48 @@ mod tests {
         assert_eq!(peer_info, peer_info_decode);
     }
 
+    #[test]
+    fn test_best_peer_by_handshake_total_difficulty() {
+        let new_peer_info = |peer_id: &PeerId, block_number: u64, total_difficult: u64| {
+            PeerInfo::new(
+                peer_id.clone().into(),
+                block_number,
+                U512::from(total_difficult),
+                HashValue::random(),
+            )
+        };
+        let self_peer_id = PeerId::random();
+        let mut peers = HashMap::new();
+        assert!(best_peer(&peers, &self_peer_id).is_none());
+
+        on_peer_handshake(
+            &mut peers,
+            self_peer_id.clone(),
+            new_peer_info(&self_peer_id, 20, 1000),
+        );
+        assert!(best_peer(&peers, &self_peer_id).is_none());
+
+        let peer_ids: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+        // the highest block number does not decide, the total difficulty does.
+        for (peer_id, (block_number, total_difficult)) in
+            peer_ids.iter().zip(vec![(12, 100), (10, 300), (11, 200)])
+        {
+            on_peer_handshake(
+                &mut peers,
+                peer_id.clone(),
+                new_peer_info(peer_id, block_number, total_difficult),
+            );
+        }
+        let best = best_peer(&peers, &self_peer_id).unwrap();
+        assert_eq!(best.get_peer_id(), peer_ids[1].clone().into());
+        assert_eq!(best.total_difficult, U512::from(300));
+
+        // the peer reconnects with a new handshake.
+        let peer_info = new_peer_info(&peer_ids[0], 13, 400);
+        on_peer_handshake(&mut peers, peer_ids[0].clone(), peer_info.clone());
+        assert_eq!(best_peer(&peers, &self_peer_id), Some(peer_info));
+    }
+
     #[ignore]
     #[stest::test]
     fn test_network_with_mock() {
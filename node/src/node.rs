@@ -16,7 +16,7 @@ use starcoin_rpc_server::RpcActor;
 use starcoin_state_service::ChainStateActor;
 use starcoin_storage::block_info::BlockInfoStore;
 use starcoin_storage::cache_storage::CacheStorage;
-use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::db_storage::{DBStorage, RocksdbConfig};
 use starcoin_storage::{storage::StorageInstance, BlockStore, Storage};
 use starcoin_sync::SyncActor;
 use starcoin_sync_api::SyncMetadata;
@@ -56,8 +56,16 @@ where
 
     let sync_event_receiver_future = bus.clone().channel::<SystemEvents>();
 
-    let cache_storage = Arc::new(CacheStorage::new());
-    let db_storage = Arc::new(DBStorage::new(config.storage.clone().dir()));
+    let cache_storage = Arc::new(CacheStorage::new_with_capacity(config.storage.cache_size()));
+    let db_storage = Arc::new(DBStorage::new_with_config(
+        config.storage.dir(),
+        RocksdbConfig {
+            block_cache_size: config.storage.block_cache_size(),
+            write_buffer_size: config.storage.write_buffer_size(),
+            max_open_files: config.storage.max_open_files(),
+            cf_paths: config.storage.cf_paths(),
+        },
+    ));
     let storage = Arc::new(
         Storage::new(StorageInstance::new_cache_and_db_instance(
             cache_storage.clone(),
@@ -184,6 +192,7 @@ where
         chain_state_service,
         Some(network.clone()),
         Some(logger_handle),
+        bus.clone(),
     )?;
     let receiver = if config.miner.pacemaker_strategy == PacemakerStrategy::Ondemand {
         Some(txpool.clone().subscribe_txns().await?)
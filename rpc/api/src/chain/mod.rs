@@ -4,11 +4,21 @@
 pub use self::gen_client::Client as ChainClient;
 use crate::FutureResult;
 use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
-use starcoin_types::block::{Block, BlockNumber};
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
 use starcoin_types::startup_info::ChainInfo;
 use starcoin_types::transaction::TransactionInfo;
 
+/// A page of blocks in descending number order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockPage {
+    pub blocks: Vec<Block>,
+    /// The number to continue the list from, `None` if the page reaches genesis.
+    pub next_number: Option<BlockNumber>,
+}
+
 #[rpc]
 pub trait ChainApi {
     // Get chain head info
@@ -20,9 +30,14 @@ pub trait ChainApi {
     // Get chain blocks by number
     #[rpc(name = "chain.get_block_by_number")]
     fn get_block_by_number(&self, number: BlockNumber) -> FutureResult<Block>;
-    // Get chain blocks by number
+    // Get chain blocks from number towards genesis, start from the head if number is null.
+    // The count is clamped by the max blocks per request of the node.
     #[rpc(name = "chain.get_blocks_by_number")]
-    fn get_blocks_by_number(&self, number: BlockNumber, count: u64) -> FutureResult<Vec<Block>>;
+    fn get_blocks_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> FutureResult<BlockPage>;
     // Get chain transactions
     #[rpc(name = "chain.get_transaction")]
     fn get_transaction(&self, transaction_id: HashValue) -> FutureResult<TransactionInfo>;
@@ -30,3 +45,27 @@ pub trait ChainApi {
     #[rpc(name = "chain.get_txn_by_block")]
     fn get_txn_by_block(&self, block_id: HashValue) -> FutureResult<Vec<TransactionInfo>>;
 }
+
+#[rpc(server)]
+pub trait ChainPubSubApi {
+    type Metadata;
+
+    /// Subscribe the headers of the new head blocks of the master chain.
+    #[pubsub(
+        subscription = "chain.new_headers",
+        subscribe,
+        name = "chain.subscribe_new_headers"
+    )]
+    fn subscribe_new_headers(&self, meta: Self::Metadata, subscriber: Subscriber<BlockHeader>);
+
+    #[pubsub(
+        subscription = "chain.new_headers",
+        unsubscribe,
+        name = "chain.unsubscribe_new_headers"
+    )]
+    fn unsubscribe_new_headers(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> jsonrpc_core::Result<bool>;
+}
@@ -1,17 +1,24 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
-use jsonrpc_core::Result;
+use crate::FutureResult;
+use jsonrpc_core::{Result, Value};
 use jsonrpc_derive::rpc;
 
 pub use self::gen_client::Client as DebugClient;
 
 #[rpc]
 pub trait DebugApi {
+    /// Set the log level to one of error, warn, info, debug and trace, return the previous level.
     #[rpc(name = "debug.set_log_level")]
-    fn set_log_level(&self, level: String) -> Result<()>;
+    fn set_log_level(&self, level: String) -> Result<String>;
 
     ///Trigger the node panic, only work for dev network.
     #[rpc(name = "debug.panic")]
     fn panic(&self) -> Result<()>;
+
+    /// Get the peerset state of the network, with the priority groups such as the reserved peers
+    /// and the reputations of the known peers. It is an empty object if the node has no network.
+    #[rpc(name = "debug.peerset")]
+    fn peerset(&self) -> FutureResult<Value>;
 }
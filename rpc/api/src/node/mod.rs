@@ -26,6 +26,15 @@ impl NodeInfo {
     }
 }
 
+/// The health of a node, for orchestration to check whether the node is synced and serving.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeHealth {
+    /// Whether the master chain has caught up with the best peer.
+    pub synced: bool,
+    pub peer_count: usize,
+    pub latest_block_number: u64,
+}
+
 #[rpc]
 pub trait NodeApi {
     /// Get node run status, just for api available check.
@@ -41,4 +50,8 @@ pub trait NodeApi {
     fn peers(&self) -> FutureResult<Vec<PeerInfo>>;
     #[rpc(name = "node.metrics")]
     fn metrics(&self) -> Result<HashMap<String, String>>;
+
+    /// Get node health, a node without network is never synced.
+    #[rpc(name = "node.health")]
+    fn health(&self) -> FutureResult<NodeHealth>;
 }
@@ -3,12 +3,68 @@
 
 use crate::FutureResult;
 use jsonrpc_derive::rpc;
-use starcoin_types::transaction::SignedUserTransaction;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_txpool_api::TxnStatusEvent;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::{
+    AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction,
+};
 
 pub use self::gen_client::Client as TxPoolClient;
 
 #[rpc]
 pub trait TxPoolApi {
     #[rpc(name = "txpool.submit_transaction")]
-    fn submit_transaction(&self, tx: SignedUserTransaction) -> FutureResult<bool>;
+    fn submit_transaction(&self, tx: SignedUserTransaction) -> FutureResult<AdmissionResult>;
+
+    /// Submit the txns in order, and return the admission result of every txn.
+    #[rpc(name = "txpool.submit_transactions")]
+    fn submit_transactions(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> FutureResult<Vec<AdmissionResult>>;
+
+    /// Check whether the unsigned txn would be admitted, and simulate it to estimate its gas,
+    /// the txn is not submitted.
+    #[rpc(name = "txpool.preflight")]
+    fn preflight(
+        &self,
+        raw_txn: RawUserTransaction,
+        public_key: Ed25519PublicKey,
+    ) -> FutureResult<PreflightResult>;
+
+    /// The sequence number after the largest one of the `address` txns in the pool, null if the
+    /// pool has no txn of the `address`.
+    #[rpc(name = "txpool.next_sequence_number")]
+    fn next_sequence_number(&self, address: AccountAddress) -> FutureResult<Option<u64>>;
+}
+
+#[rpc(server)]
+pub trait TxPoolPubSubApi {
+    type Metadata;
+
+    /// Subscribe the status events of the txns sent by `sender`.
+    #[pubsub(
+        subscription = "txpool.txn_status",
+        subscribe,
+        name = "txpool.subscribe_txn_status"
+    )]
+    fn subscribe_txn_status(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<TxnStatusEvent>,
+        sender: AccountAddress,
+    );
+
+    #[pubsub(
+        subscription = "txpool.txn_status",
+        unsubscribe,
+        name = "txpool.unsubscribe_txn_status"
+    )]
+    fn unsubscribe_txn_status(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> jsonrpc_core::Result<bool>;
 }
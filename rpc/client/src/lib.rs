@@ -16,7 +16,9 @@ use starcoin_state_api::StateWithProof;
 use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_state::AccountState;
-use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction, TransactionInfo};
+use starcoin_types::transaction::{
+    AdmissionResult, RawUserTransaction, SignedUserTransaction, TransactionInfo,
+};
 use starcoin_wallet_api::WalletAccount;
 use std::cell::RefCell;
 use std::ops::Deref;
@@ -30,7 +32,8 @@ use tokio_compat::runtime::Runtime;
 mod remote_state_reader;
 
 pub use crate::remote_state_reader::RemoteStateReader;
-use starcoin_rpc_api::node::NodeInfo;
+use starcoin_rpc_api::chain::BlockPage;
+use starcoin_rpc_api::node::{NodeHealth, NodeInfo};
 use starcoin_types::block::{Block, BlockNumber};
 use starcoin_types::peer_info::PeerInfo;
 use starcoin_types::startup_info::ChainInfo;
@@ -150,12 +153,52 @@ impl RpcClient {
             .map_err(map_err)
     }
 
-    pub fn submit_transaction(&self, txn: SignedUserTransaction) -> anyhow::Result<bool> {
+    pub fn node_health(&self) -> anyhow::Result<NodeHealth> {
+        self.call_rpc_blocking(|inner| async move { inner.node_client.health().compat().await })
+            .map_err(map_err)
+    }
+
+    pub fn submit_transaction(
+        &self,
+        txn: SignedUserTransaction,
+    ) -> anyhow::Result<AdmissionResult> {
         self.call_rpc_blocking(|inner| async move {
             inner.txpool_client.submit_transaction(txn).compat().await
         })
         .map_err(map_err)
     }
+
+    /// Submit the txn, and give up waiting the result after `timeout`, return None if timeout.
+    pub fn submit_transaction_with_timeout(
+        &self,
+        txn: SignedUserTransaction,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<AdmissionResult>> {
+        self.call_rpc_blocking(|inner| async move {
+            let fut = inner.txpool_client.submit_transaction(txn).compat();
+            match tokio::time::timeout(timeout, fut).await {
+                Ok(result) => result.map(Some),
+                Err(_) => Ok(None),
+            }
+        })
+        .map_err(map_err)
+    }
+    /// The sequence number after the largest one of the `address` txns in the pool, None if the
+    /// pool has no txn of the `address`.
+    pub fn next_sequence_number_in_txpool(
+        &self,
+        address: AccountAddress,
+    ) -> anyhow::Result<Option<u64>> {
+        self.call_rpc_blocking(|inner| async move {
+            inner
+                .txpool_client
+                .next_sequence_number(address)
+                .compat()
+                .await
+        })
+        .map_err(map_err)
+    }
+
     //TODO should split client for different api ?
     // such as  RpcClient().account().default()
     pub fn wallet_default(&self) -> anyhow::Result<Option<WalletAccount>> {
@@ -268,15 +311,23 @@ impl RpcClient {
         .map_err(map_err)
     }
 
-    pub fn debug_set_log_level(&self, level: Level) -> anyhow::Result<()> {
-        self.call_rpc_blocking(|inner| async move {
-            inner
-                .debug_client
-                .set_log_level(level.to_string())
-                .compat()
-                .await
-        })
-        .map_err(map_err)
+    /// Set the log level of the node, return the previous level.
+    pub fn debug_set_log_level(&self, level: Level) -> anyhow::Result<LevelFilter> {
+        let previous_level = self
+            .call_rpc_blocking(|inner| async move {
+                inner
+                    .debug_client
+                    .set_log_level(level.to_string())
+                    .compat()
+                    .await
+            })
+            .map_err(map_err)?;
+        Ok(previous_level.parse()?)
+    }
+
+    pub fn debug_peerset(&self) -> anyhow::Result<serde_json::Value> {
+        self.call_rpc_blocking(|inner| async move { inner.debug_client.peerset().compat().await })
+            .map_err(map_err)
     }
 
     pub fn debug_panic(&self) -> anyhow::Result<()> {
@@ -308,9 +359,9 @@ impl RpcClient {
     }
     pub fn chain_get_blocks_by_number(
         &self,
-        number: BlockNumber,
+        number: Option<BlockNumber>,
         count: u64,
-    ) -> anyhow::Result<Vec<Block>> {
+    ) -> anyhow::Result<BlockPage> {
         self.call_rpc_blocking(|inner| async move {
             inner
                 .chain_client
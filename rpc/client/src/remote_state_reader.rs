@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 use crate::RpcClient;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use starcoin_crypto::HashValue;
 use starcoin_state_api::{ChainStateReader, StateWithProof};
 use starcoin_types::access_path::AccessPath;
@@ -48,4 +48,12 @@ impl<'a> ChainStateReader for RemoteStateReader<'a> {
     fn dump(&self) -> Result<ChainStateSet> {
         unimplemented!()
     }
+
+    fn list_accounts_from(
+        &self,
+        _start: HashValue,
+        _limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)> {
+        bail!("list accounts is not supported by the remote state reader.")
+    }
 }
@@ -5,8 +5,10 @@ use actix::prelude::*;
 use anyhow::Result;
 use futures::channel::oneshot;
 use jsonrpc_core::IoHandler;
+use starcoin_chain::mock::mock_chain_service::MockChainService;
 use starcoin_config::NodeConfig;
 use starcoin_logger::prelude::*;
+use starcoin_network::NetworkAsyncService;
 use starcoin_rpc_api::node::NodeApi;
 use starcoin_rpc_client::RpcClient;
 use starcoin_rpc_server::module::NodeRpcImpl;
@@ -29,9 +31,15 @@ fn test_multi_client() -> Result<()> {
 
     system.block_on(async {
         let (stop_sender, stop_receiver) = oneshot::channel::<bool>();
-        let mut io_handler = IoHandler::new();
+        let mut io_handler = IoHandler::default();
         //io_handler.add_method("status", |_params: Params| Ok(Value::Bool(true)));
-        io_handler.extend_with(NodeApi::to_delegate(NodeRpcImpl::new(config.clone(), None)));
+        io_handler.extend_with(NodeApi::to_delegate(
+            NodeRpcImpl::<_, NetworkAsyncService>::new(
+                config.clone(),
+                None,
+                MockChainService::new(),
+            ),
+        ));
         let (_rpc_actor, iohandler) = RpcActor::launch_with_handler(config, io_handler).unwrap();
 
         let client_task = move || {
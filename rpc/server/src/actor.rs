@@ -1,31 +1,44 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
+use crate::metadata::Metadata;
 use crate::module::{
-    ChainRpcImpl, DebugRpcImpl, NodeRpcImpl, StateRpcImpl, TxPoolRpcImpl, WalletRpcImpl,
+    ChainPubSubImpl, ChainRpcImpl, DebugRpcImpl, NodeRpcImpl, StateRpcImpl, TxPoolPubSubImpl,
+    TxPoolRpcImpl, WalletRpcImpl,
 };
 use crate::service::RpcService;
 use actix::prelude::*;
 use anyhow::Result;
 use jsonrpc_core::IoHandler;
+use starcoin_bus::BusActor;
 use starcoin_config::NodeConfig;
 use starcoin_logger::prelude::*;
 use starcoin_logger::LoggerHandle;
 use starcoin_network::NetworkAsyncService;
-use starcoin_rpc_api::chain::ChainApi;
+use starcoin_rpc_api::chain::{ChainApi, ChainPubSubApi};
 use starcoin_rpc_api::debug::DebugApi;
 use starcoin_rpc_api::wallet::WalletApi;
-use starcoin_rpc_api::{node::NodeApi, state::StateApi, txpool::TxPoolApi};
+use starcoin_rpc_api::{
+    node::NodeApi,
+    state::StateApi,
+    txpool::{TxPoolApi, TxPoolPubSubApi},
+};
 use starcoin_state_api::ChainStateAsyncService;
 use starcoin_traits::ChainAsyncService;
 use starcoin_txpool_api::TxPoolAsyncService;
 use starcoin_wallet_api::WalletAsyncService;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The interval to check whether the in-flight requests complete when draining.
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 pub struct RpcActor {
     config: Arc<NodeConfig>,
-    io_handler: IoHandler,
+    io_handler: IoHandler<Metadata>,
     server: Option<RpcService>,
+    drain_deadline: Option<Instant>,
 }
 
 impl RpcActor {
@@ -38,7 +51,8 @@ impl RpcActor {
         //TODO after network async service provide trait, remove Option.
         network_service: Option<NetworkAsyncService>,
         logger_handle: Option<Arc<LoggerHandle>>,
-    ) -> Result<(Addr<RpcActor>, IoHandler)>
+        bus: Addr<BusActor>,
+    ) -> Result<(Addr<RpcActor>, IoHandler<Metadata>)>
     where
         CS: ChainAsyncService + 'static,
         TS: TxPoolAsyncService + 'static,
@@ -47,40 +61,57 @@ impl RpcActor {
     {
         Self::launch_with_apis(
             config.clone(),
-            NodeRpcImpl::new(config.clone(), network_service),
-            Some(ChainRpcImpl::new(chain_service)),
+            NodeRpcImpl::new(
+                config.clone(),
+                network_service.clone(),
+                chain_service.clone(),
+            ),
+            Some(ChainRpcImpl::new(config.clone(), chain_service)),
+            Some(ChainPubSubImpl::new(bus.clone())),
             Some(TxPoolRpcImpl::new(txpool_service)),
+            Some(TxPoolPubSubImpl::new(bus)),
             Some(WalletRpcImpl::new(account_service)),
             Some(StateRpcImpl::new(state_service)),
-            logger_handle.map(|logger_handle| DebugRpcImpl::new(config, logger_handle)),
+            logger_handle
+                .map(|logger_handle| DebugRpcImpl::new(config, logger_handle, network_service)),
         )
     }
 
-    pub fn launch_with_apis<C, N, T, A, S, D>(
+    pub fn launch_with_apis<C, CP, N, T, P, A, S, D>(
         config: Arc<NodeConfig>,
         node_api: N,
         chain_api: Option<C>,
+        chain_pubsub_api: Option<CP>,
         txpool_api: Option<T>,
+        txpool_pubsub_api: Option<P>,
         account_api: Option<A>,
         state_api: Option<S>,
         debug_api: Option<D>,
-    ) -> Result<(Addr<Self>, IoHandler)>
+    ) -> Result<(Addr<Self>, IoHandler<Metadata>)>
     where
         N: NodeApi,
         C: ChainApi,
+        CP: ChainPubSubApi<Metadata = Metadata>,
         T: TxPoolApi,
+        P: TxPoolPubSubApi<Metadata = Metadata>,
         A: WalletApi,
         S: StateApi,
         D: DebugApi,
     {
-        let mut io_handler = IoHandler::new();
+        let mut io_handler = IoHandler::default();
         io_handler.extend_with(NodeApi::to_delegate(node_api));
         if let Some(chain_api) = chain_api {
             io_handler.extend_with(ChainApi::to_delegate(chain_api));
         }
+        if let Some(chain_pubsub_api) = chain_pubsub_api {
+            io_handler.extend_with(ChainPubSubApi::to_delegate(chain_pubsub_api));
+        }
         if let Some(txpool_api) = txpool_api {
             io_handler.extend_with(TxPoolApi::to_delegate(txpool_api));
         }
+        if let Some(txpool_pubsub_api) = txpool_pubsub_api {
+            io_handler.extend_with(TxPoolPubSubApi::to_delegate(txpool_pubsub_api));
+        }
         if let Some(account_api) = account_api {
             io_handler.extend_with(WalletApi::to_delegate(account_api));
         }
@@ -95,12 +126,13 @@ impl RpcActor {
 
     pub fn launch_with_handler(
         config: Arc<NodeConfig>,
-        io_handler: IoHandler,
-    ) -> Result<(Addr<Self>, IoHandler)> {
+        io_handler: IoHandler<Metadata>,
+    ) -> Result<(Addr<Self>, IoHandler<Metadata>)> {
         let actor = RpcActor {
             config,
             server: None,
             io_handler: io_handler.clone(),
+            drain_deadline: None,
         };
         Ok((actor.start(), io_handler))
     }
@@ -110,7 +142,43 @@ impl RpcActor {
         self.server = Some(server);
     }
 
+    /// Start draining the server, return false if there is no server to drain or the drain has
+    /// already started.
+    fn start_drain(&mut self) -> bool {
+        match &self.server {
+            Some(server) if self.drain_deadline.is_none() => {
+                info!(
+                    "Rpc server start draining, in-flight requests: {}",
+                    server.in_flight()
+                );
+                server.start_drain();
+                self.drain_deadline =
+                    Some(Instant::now() + Duration::from_secs(self.config.rpc.drain_timeout));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn is_drained(&self) -> bool {
+        match (&self.server, self.drain_deadline) {
+            (Some(server), Some(deadline)) => {
+                let in_flight = server.in_flight();
+                if in_flight > 0 && Instant::now() >= deadline {
+                    warn!(
+                        "Rpc server drain timeout, drop {} in-flight requests.",
+                        in_flight
+                    );
+                    return true;
+                }
+                in_flight == 0
+            }
+            _ => true,
+        }
+    }
+
     fn do_stop(&mut self) {
+        self.drain_deadline = None;
         let server = std::mem::replace(&mut self.server, None);
         match server {
             Some(server) => server.close(),
@@ -126,12 +194,35 @@ impl Actor for RpcActor {
         self.do_start();
     }
 
-    fn stopping(&mut self, _ctx: &mut Self::Context) -> Running {
+    fn stopping(&mut self, ctx: &mut Self::Context) -> Running {
+        if self.start_drain() {
+            ctx.run_interval(DRAIN_CHECK_INTERVAL, |act, ctx| {
+                if act.is_drained() {
+                    ctx.stop();
+                }
+            });
+            return Running::Continue;
+        }
         self.do_stop();
         Running::Stop
     }
 }
 
+/// Query the bound address of the http rpc server.
+pub struct GetLocalAddr;
+
+impl Message for GetLocalAddr {
+    type Result = Option<SocketAddr>;
+}
+
+impl Handler<GetLocalAddr> for RpcActor {
+    type Result = Option<SocketAddr>;
+
+    fn handle(&mut self, _msg: GetLocalAddr, _ctx: &mut Self::Context) -> Self::Result {
+        self.server.as_ref().and_then(|server| server.local_addr())
+    }
+}
+
 impl Supervised for RpcActor {
     fn restarting(&mut self, _ctx: &mut Self::Context) {
         info!("Restart JSON rpc service.");
@@ -143,10 +234,35 @@ impl Supervised for RpcActor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::drain::SERVER_DRAINING_ERROR_CODE;
+    use jsonrpc_core::futures::Future as _;
     use starcoin_chain::mock::mock_chain_service::MockChainService;
     use starcoin_state_api::mock::MockChainStateService;
     use starcoin_txpool_mock_service::MockTxPoolService;
     use starcoin_wallet_api::mock::MockWalletService;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+
+    fn http_call(addr: SocketAddr, method: &str) -> String {
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#,
+            method
+        );
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
 
     #[stest::test]
     async fn test_start() {
@@ -164,7 +280,81 @@ mod tests {
             state_service,
             None,
             Some(logger_handle),
+            BusActor::launch(),
+        )
+        .unwrap();
+    }
+
+    #[stest::test]
+    async fn test_local_addr_with_ephemeral_port() {
+        let logger_handle = starcoin_logger::init_for_test();
+        let mut config = NodeConfig::random_for_test();
+        config.rpc.http_address = Some("127.0.0.1:0".parse().unwrap());
+        let (rpc_actor, _io_handler) = RpcActor::launch(
+            Arc::new(config),
+            MockTxPoolService::new(),
+            MockChainService::new(),
+            MockWalletService::new().unwrap(),
+            MockChainStateService::new(),
+            None,
+            Some(logger_handle),
+            BusActor::launch(),
         )
         .unwrap();
+        let local_addr = rpc_actor.send(GetLocalAddr).await.unwrap().unwrap();
+        assert!(local_addr.ip().is_loopback());
+        assert_ne!(local_addr.port(), 0);
+    }
+
+    #[stest::test]
+    async fn test_drain_slow_request() {
+        let _logger_handle = starcoin_logger::init_for_test();
+        let mut config = NodeConfig::random_for_test();
+        config.rpc.http_address = Some("127.0.0.1:0".parse().unwrap());
+        config.rpc.drain_timeout = 5;
+
+        let started = Arc::new(AtomicBool::new(false));
+        let slow_started = started.clone();
+        let mut io_handler = IoHandler::default();
+        io_handler.add_method("slow", move |_params| {
+            slow_started.store(true, Ordering::SeqCst);
+            let (tx, rx) = jsonrpc_core::futures::sync::oneshot::channel();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_secs(1));
+                let _ = tx.send(jsonrpc_core::Value::from("done"));
+            });
+            rx.map_err(|_| jsonrpc_core::Error::internal_error())
+        });
+        let (rpc_actor, _io_handler) =
+            RpcActor::launch_with_handler(Arc::new(config), io_handler).unwrap();
+        let addr = rpc_actor.send(GetLocalAddr).await.unwrap().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            tx.send(http_call(addr, "slow")).unwrap();
+        });
+        while !started.load(Ordering::SeqCst) {
+            actix::clock::delay_for(Duration::from_millis(10)).await;
+        }
+
+        // drop the only address to stop the actor while the slow request is in flight.
+        let stop_at = Instant::now();
+        drop(rpc_actor);
+        actix::clock::delay_for(Duration::from_millis(200)).await;
+        let rejected = http_call(addr, "slow");
+        assert!(rejected.contains(SERVER_DRAINING_ERROR_CODE.to_string().as_str()));
+
+        let response = loop {
+            if let Ok(response) = rx.try_recv() {
+                break response;
+            }
+            actix::clock::delay_for(Duration::from_millis(10)).await;
+        };
+        assert!(response.contains(r#""result":"done""#), "{}", response);
+        assert!(stop_at.elapsed() < Duration::from_secs(5));
+
+        // the server is closed after the drain.
+        actix::clock::delay_for(DRAIN_CHECK_INTERVAL * 5).await;
+        assert!(TcpStream::connect(addr).is_err());
     }
 }
@@ -0,0 +1,80 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metadata::Metadata;
+use jsonrpc_core::futures::future::{self, Either, Future};
+use jsonrpc_core::middleware::Middleware;
+use jsonrpc_core::{Error, ErrorCode, FutureOutput, FutureResponse, Request, Response, Version};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The error code of the requests rejected by a draining server.
+pub const SERVER_DRAINING_ERROR_CODE: i64 = -32050;
+
+/// Count the in-flight requests of all transports, and reject the new requests once the drain
+/// starts, so the server can be closed after the in-flight requests complete.
+#[derive(Clone, Debug, Default)]
+pub struct DrainMiddleware {
+    state: Arc<DrainState>,
+}
+
+#[derive(Debug, Default)]
+struct DrainState {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+}
+
+impl DrainMiddleware {
+    pub fn start_drain(&self) {
+        self.state.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.state.in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Decrease the in-flight count when the request completes or is dropped.
+struct InFlightGuard(Arc<DrainState>);
+
+impl InFlightGuard {
+    fn new(state: Arc<DrainState>) -> Self {
+        state.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(state)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Middleware<Metadata> for DrainMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_request<F, X>(&self, request: Request, meta: Metadata, next: F) -> Either<Self::Future, X>
+    where
+        F: FnOnce(Request, Metadata) -> X + Send,
+        X: Future<Item = Option<Response>, Error = ()> + Send + 'static,
+    {
+        // count the request before the check, the drain never misses a request it let in.
+        let guard = InFlightGuard::new(self.state.clone());
+        if self.state.draining.load(Ordering::SeqCst) {
+            let error = Error {
+                code: ErrorCode::ServerError(SERVER_DRAINING_ERROR_CODE),
+                message: "Rpc server is stopping.".to_string(),
+                data: None,
+            };
+            return Either::A(Box::new(future::ok(Some(Response::from(
+                error,
+                Some(Version::V2),
+            )))));
+        }
+        Either::A(Box::new(next(request, meta).then(move |response| {
+            drop(guard);
+            response
+        })))
+    }
+}
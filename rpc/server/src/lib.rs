@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2
 
 mod actor;
+mod drain;
+mod metadata;
 pub mod module;
 mod service;
 
-pub use actor::RpcActor;
+pub use actor::{GetLocalAddr, RpcActor};
+pub use metadata::Metadata;
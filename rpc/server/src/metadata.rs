@@ -0,0 +1,27 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+use std::sync::Arc;
+
+/// Metadata of a rpc request, only requests from transports with a session can subscribe.
+#[derive(Clone, Default)]
+pub struct Metadata {
+    session: Option<Arc<Session>>,
+}
+
+impl jsonrpc_core::Metadata for Metadata {}
+
+impl PubSubMetadata for Metadata {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
+
+impl From<Arc<Session>> for Metadata {
+    fn from(session: Arc<Session>) -> Self {
+        Self {
+            session: Some(session),
+        }
+    }
+}
@@ -1,20 +1,34 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metadata::Metadata;
 use crate::module::map_err;
+use actix::prelude::*;
+use futures::compat::Future01CompatExt;
 use futures::future::TryFutureExt;
+use futures::StreamExt;
+use jsonrpc_pubsub::typed::{Sink, Subscriber};
+use jsonrpc_pubsub::SubscriptionId;
+use starcoin_bus::{Bus, BusActor};
+use starcoin_config::NodeConfig;
 use starcoin_crypto::HashValue;
-use starcoin_rpc_api::chain::ChainApi;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::chain::{BlockPage, ChainApi, ChainPubSubApi};
 use starcoin_rpc_api::FutureResult;
 use starcoin_traits::ChainAsyncService;
-use starcoin_types::block::Block;
+use starcoin_types::block::{Block, BlockHeader, BlockNumber};
 use starcoin_types::startup_info::ChainInfo;
+use starcoin_types::system_events::SystemEvents;
 use starcoin_types::transaction::TransactionInfo;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
 pub struct ChainRpcImpl<S>
 where
     S: ChainAsyncService + 'static,
 {
+    config: Arc<NodeConfig>,
     service: S,
 }
 
@@ -22,8 +36,8 @@ impl<S> ChainRpcImpl<S>
 where
     S: ChainAsyncService,
 {
-    pub fn new(service: S) -> Self {
-        Self { service }
+    pub fn new(config: Arc<NodeConfig>, service: S) -> Self {
+        Self { config, service }
     }
 }
 
@@ -54,11 +68,25 @@ where
         Box::new(fut.compat())
     }
 
-    fn get_blocks_by_number(&self, number: u64, count: u64) -> FutureResult<Vec<Block>> {
+    fn get_blocks_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+    ) -> FutureResult<BlockPage> {
+        let count = count.min(self.config.rpc.max_blocks_per_request);
         let fut = self
             .service
             .clone()
             .master_blocks_by_number(number, count)
+            .map_ok(|blocks| {
+                let next_number = blocks
+                    .last()
+                    .and_then(|block| block.header().number().checked_sub(1));
+                BlockPage {
+                    blocks,
+                    next_number,
+                }
+            })
             .map_err(map_err);
         Box::new(fut.compat())
     }
@@ -81,3 +109,194 @@ where
         Box::new(fut.compat())
     }
 }
+
+type NewHeaderSubscribers = Arc<RwLock<HashMap<SubscriptionId, Sink<BlockHeader>>>>;
+
+/// Forward the headers of the new head blocks on the bus to the subscribers.
+pub struct ChainPubSubImpl {
+    subscribers: NewHeaderSubscribers,
+    next_id: AtomicU64,
+}
+
+impl ChainPubSubImpl {
+    pub fn new(bus: Addr<BusActor>) -> Self {
+        let subscribers = NewHeaderSubscribers::default();
+        Arbiter::spawn(Self::forward_events(bus, subscribers.clone()));
+        Self {
+            subscribers,
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    async fn forward_events(bus: Addr<BusActor>, subscribers: NewHeaderSubscribers) {
+        let mut events = match bus.channel::<SystemEvents>().await {
+            Ok(events) => events,
+            Err(e) => {
+                error!("fail to subscribe system events, err: {:?}", e);
+                return;
+            }
+        };
+        while let Some(event) = events.next().await {
+            let header = match event {
+                SystemEvents::NewHeadBlock(block) => block.header().clone(),
+                _ => continue,
+            };
+            let sinks: Vec<_> = subscribers
+                .read()
+                .expect("read new header subscribers failed.")
+                .iter()
+                .map(|(id, sink)| (id.clone(), sink.clone()))
+                .collect();
+            for (id, sink) in sinks {
+                if let Err(e) = sink.notify(Ok(header.clone())).compat().await {
+                    debug!("new header subscription {:?} is closed: {:?}", id, e);
+                    subscribers
+                        .write()
+                        .expect("write new header subscribers failed.")
+                        .remove(&id);
+                }
+            }
+        }
+    }
+}
+
+impl ChainPubSubApi for ChainPubSubImpl {
+    type Metadata = Metadata;
+
+    fn subscribe_new_headers(&self, _meta: Metadata, subscriber: Subscriber<BlockHeader>) {
+        let id = SubscriptionId::Number(self.next_id.fetch_add(1, Ordering::SeqCst));
+        if let Ok(sink) = subscriber.assign_id(id.clone()) {
+            self.subscribers
+                .write()
+                .expect("write new header subscribers failed.")
+                .insert(id, sink);
+        }
+    }
+
+    fn unsubscribe_new_headers(
+        &self,
+        _meta: Option<Metadata>,
+        id: SubscriptionId,
+    ) -> jsonrpc_core::Result<bool> {
+        Ok(self
+            .subscribers
+            .write()
+            .expect("write new header subscribers failed.")
+            .remove(&id)
+            .is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix::clock::delay_for;
+    use futures::compat::Stream01CompatExt;
+    use jsonrpc_core::{IoHandler, MetaIoHandler};
+    use jsonrpc_pubsub::Session;
+    use starcoin_chain::mock::mock_chain_service::MockChainService;
+    use starcoin_rpc_client::RpcClient;
+    use starcoin_types::account_address::AccountAddress;
+    use starcoin_types::block::{BlockBody, BlockDetail};
+    use starcoin_types::{U256, U512};
+    use std::time::Duration;
+
+    fn header_of(number: BlockNumber) -> BlockHeader {
+        BlockHeader::new(
+            HashValue::zero(),
+            number,
+            number,
+            AccountAddress::default(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            U256::zero(),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_get_blocks_by_number_clamped() {
+        let mut config = NodeConfig::random_for_test();
+        config.rpc.max_blocks_per_request = 10;
+        let mut io = IoHandler::new();
+        io.extend_with(
+            ChainRpcImpl::new(
+                Arc::new(config),
+                MockChainService::new_with_head(header_of(15)),
+            )
+            .to_delegate(),
+        );
+        let client = RpcClient::connect_local(io);
+
+        let page = client.chain_get_blocks_by_number(None, 100).unwrap();
+        let numbers: Vec<BlockNumber> = page
+            .blocks
+            .iter()
+            .map(|block| block.header().number())
+            .collect();
+        assert_eq!(numbers, (6..=15).rev().collect::<Vec<_>>());
+        assert_eq!(page.next_number, Some(5));
+
+        // continue from the cursor until genesis.
+        let page = client
+            .chain_get_blocks_by_number(page.next_number, 100)
+            .unwrap();
+        assert_eq!(page.blocks.len(), 6);
+        assert_eq!(page.blocks.last().unwrap().header().number(), 0);
+        assert_eq!(page.next_number, None);
+    }
+
+    #[stest::test]
+    async fn test_subscribe_new_headers() {
+        let bus = BusActor::launch();
+        let mut io = MetaIoHandler::<Metadata>::default();
+        io.extend_with(ChainPubSubImpl::new(bus.clone()).to_delegate());
+        let (sender, receiver) = jsonrpc_core::futures::sync::mpsc::channel(8);
+        let meta = Metadata::from(Arc::new(Session::new(sender)));
+
+        let request =
+            r#"{"jsonrpc":"2.0","method":"chain.subscribe_new_headers","params":[],"id":1}"#;
+        let response = io.handle_request(request, meta.clone()).compat().await;
+        assert_eq!(
+            response.unwrap(),
+            Some(r#"{"jsonrpc":"2.0","result":0,"id":1}"#.to_string())
+        );
+        // wait the forward task subscribing the bus.
+        delay_for(Duration::from_millis(100)).await;
+
+        let headers = vec![header_of(1), header_of(2)];
+        for header in &headers {
+            let block = Block::new(header.clone(), BlockBody::default());
+            bus.clone()
+                .broadcast(SystemEvents::NewHeadBlock(BlockDetail::new(
+                    block,
+                    U512::zero(),
+                )))
+                .await
+                .unwrap();
+        }
+        let notifications: Vec<String> = receiver
+            .compat()
+            .take(2)
+            .map(|notification| notification.unwrap())
+            .collect()
+            .await;
+        for (notification, header) in notifications.iter().zip(headers.iter()) {
+            let value: serde_json::Value = serde_json::from_str(notification).unwrap();
+            assert_eq!(value["method"], "chain.new_headers");
+            let received: BlockHeader =
+                serde_json::from_value(value["params"]["result"].clone()).unwrap();
+            assert_eq!(received.id(), header.id());
+        }
+
+        let request =
+            r#"{"jsonrpc":"2.0","method":"chain.unsubscribe_new_headers","params":[0],"id":2}"#;
+        let response = io.handle_request(request, meta).compat().await;
+        assert_eq!(
+            response.unwrap(),
+            Some(r#"{"jsonrpc":"2.0","result":true,"id":2}"#.to_string())
+        );
+    }
+}
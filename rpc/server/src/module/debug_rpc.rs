@@ -1,31 +1,58 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
-use jsonrpc_core::Result;
+use crate::module::map_err;
+use futures::future::TryFutureExt;
+use futures::FutureExt;
+use jsonrpc_core::{Result, Value};
+use network_api::NetworkService;
 use starcoin_config::NodeConfig;
-use starcoin_logger::prelude::LevelFilter;
+use starcoin_logger::prelude::Level;
 use starcoin_logger::LoggerHandle;
 use starcoin_rpc_api::debug::DebugApi;
+use starcoin_rpc_api::FutureResult;
 use std::str::FromStr;
 use std::sync::Arc;
 
-pub struct DebugRpcImpl {
+pub struct DebugRpcImpl<N>
+where
+    N: NetworkService + 'static,
+{
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
+    network_service: Option<N>,
 }
 
-impl DebugRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, log_handle: Arc<LoggerHandle>) -> Self {
-        Self { config, log_handle }
+impl<N> DebugRpcImpl<N>
+where
+    N: NetworkService,
+{
+    pub fn new(
+        config: Arc<NodeConfig>,
+        log_handle: Arc<LoggerHandle>,
+        network_service: Option<N>,
+    ) -> Self {
+        Self {
+            config,
+            log_handle,
+            network_service,
+        }
     }
 }
 
-impl DebugApi for DebugRpcImpl {
-    fn set_log_level(&self, level: String) -> Result<()> {
-        self.log_handle
-            .update_level(LevelFilter::from_str(level.as_str()).map_err(to_invalid_param_err)?);
-        Ok(())
+impl<N> DebugApi for DebugRpcImpl<N>
+where
+    N: NetworkService,
+{
+    fn set_log_level(&self, level: String) -> Result<String> {
+        let level = Level::from_str(level.as_str()).map_err(|_| {
+            jsonrpc_core::Error::invalid_params(format!(
+                "Invalid log level: {}, expect one of error, warn, info, debug and trace.",
+                level
+            ))
+        })?;
+        let previous_level = self.log_handle.update_level(level.to_level_filter());
+        Ok(previous_level.to_string().to_lowercase())
     }
 
     fn panic(&self) -> Result<()> {
@@ -34,4 +61,82 @@ impl DebugApi for DebugRpcImpl {
         }
         panic!("DebugApi.panic")
     }
+
+    fn peerset(&self) -> FutureResult<Value> {
+        let network_service = self.network_service.clone();
+        let fut = async move {
+            match network_service {
+                Some(network_service) => network_service.peerset_debug_info().await,
+                None => Ok(Value::Object(Default::default())),
+            }
+        };
+        Box::new(
+            fut.map_err(|e: anyhow::Error| map_err(e.into()))
+                .boxed()
+                .compat(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::IoHandler;
+    use network_api::DummyNetworkService;
+    use starcoin_logger::prelude::LevelFilter;
+    use starcoin_network::NetworkAsyncService;
+    use starcoin_rpc_client::RpcClient;
+    use starcoin_types::peer_info::{PeerId, PeerInfo};
+
+    #[test]
+    fn test_set_log_level() {
+        let logger_handle = starcoin_logger::init_for_test();
+        let config = Arc::new(NodeConfig::random_for_test());
+        let mut io = IoHandler::new();
+        io.extend_with(
+            DebugRpcImpl::<NetworkAsyncService>::new(config, logger_handle.clone(), None)
+                .to_delegate(),
+        );
+        let client = RpcClient::connect_local(io.clone());
+        client.debug_set_log_level(Level::Debug).unwrap();
+        assert_eq!(logger_handle.level(), LevelFilter::Debug);
+
+        let request =
+            r#"{"jsonrpc":"2.0","method":"debug.set_log_level","params":["verbose"],"id":1}"#;
+        let response = io.handle_request_sync(request).unwrap();
+        assert!(
+            response.contains("Invalid log level: verbose"),
+            "{}",
+            response
+        );
+        assert_eq!(logger_handle.level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_peerset() {
+        let logger_handle = starcoin_logger::init_for_test();
+        let config = Arc::new(NodeConfig::random_for_test());
+        let peer_id = PeerId::random();
+        let network = DummyNetworkService::new(
+            PeerId::random().into(),
+            vec![PeerInfo::new_for_test(peer_id.clone())],
+        );
+        let mut io = IoHandler::new();
+        io.extend_with(
+            DebugRpcImpl::new(config.clone(), logger_handle.clone(), Some(network)).to_delegate(),
+        );
+        let client = RpcClient::connect_local(io);
+        let peerset = client.debug_peerset().unwrap();
+        assert_eq!(peerset["nodes"][peer_id.to_base58()]["reputation"], 0);
+        assert!(peerset["priority_groups"].is_object());
+
+        // no network.
+        let mut io = IoHandler::new();
+        io.extend_with(
+            DebugRpcImpl::<NetworkAsyncService>::new(config, logger_handle, None).to_delegate(),
+        );
+        let client = RpcClient::connect_local(io);
+        let peerset = client.debug_peerset().unwrap();
+        assert_eq!(peerset, Value::Object(Default::default()));
+    }
 }
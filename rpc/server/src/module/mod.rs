@@ -10,11 +10,11 @@ mod state_rpc;
 mod txpool_rpc;
 mod wallet_rpc;
 
-pub use self::chain_rpc::ChainRpcImpl;
+pub use self::chain_rpc::{ChainPubSubImpl, ChainRpcImpl};
 pub use self::debug_rpc::DebugRpcImpl;
 pub use self::node_rpc::NodeRpcImpl;
 pub use self::state_rpc::StateRpcImpl;
-pub use self::txpool_rpc::TxPoolRpcImpl;
+pub use self::txpool_rpc::{TxPoolPubSubImpl, TxPoolRpcImpl};
 pub use self::wallet_rpc::WalletRpcImpl;
 
 use starcoin_wallet_api::error::AccountServiceError;
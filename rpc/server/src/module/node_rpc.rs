@@ -2,31 +2,49 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::module::map_err;
+use anyhow::format_err;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
 use jsonrpc_core::Result;
 use network_api::NetworkService;
 use starcoin_config::NodeConfig;
 use starcoin_metrics;
-use starcoin_network::NetworkAsyncService;
-use starcoin_rpc_api::node::{NodeApi, NodeInfo};
+use starcoin_rpc_api::node::{NodeApi, NodeHealth, NodeInfo};
 use starcoin_rpc_api::FutureResult;
+use starcoin_traits::ChainAsyncService;
 use starcoin_types::peer_info::PeerInfo;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-pub struct NodeRpcImpl {
+pub struct NodeRpcImpl<CS, N>
+where
+    CS: ChainAsyncService + 'static,
+    N: NetworkService + 'static,
+{
     config: Arc<NodeConfig>,
-    service: Option<NetworkAsyncService>,
+    service: Option<N>,
+    chain_service: CS,
 }
 
-impl NodeRpcImpl {
-    pub fn new(config: Arc<NodeConfig>, service: Option<NetworkAsyncService>) -> Self {
-        Self { config, service }
+impl<CS, N> NodeRpcImpl<CS, N>
+where
+    CS: ChainAsyncService,
+    N: NetworkService,
+{
+    pub fn new(config: Arc<NodeConfig>, service: Option<N>, chain_service: CS) -> Self {
+        Self {
+            config,
+            service,
+            chain_service,
+        }
     }
 }
 
-impl NodeApi for NodeRpcImpl {
+impl<CS, N> NodeApi for NodeRpcImpl<CS, N>
+where
+    CS: ChainAsyncService,
+    N: NetworkService,
+{
     fn status(&self) -> Result<bool> {
         //TODO check service status.
         Ok(true)
@@ -66,4 +84,101 @@ impl NodeApi for NodeRpcImpl {
     fn metrics(&self) -> Result<HashMap<String, String>> {
         Ok(starcoin_metrics::get_all_metrics())
     }
+
+    fn health(&self) -> FutureResult<NodeHealth> {
+        let service = self.service.clone();
+        let chain_service = self.chain_service.clone();
+        let fut = async move {
+            let head = chain_service
+                .clone()
+                .master_head_header()
+                .await
+                .ok_or_else(|| format_err!("Can not find master head header."))?;
+            let (synced, peer_count) = match service {
+                Some(service) => {
+                    let peers = service.best_peer_set().await?;
+                    let synced = match peers.first() {
+                        Some(best_peer) => {
+                            let block_info = chain_service
+                                .get_block_info_by_hash(&head.id())
+                                .await
+                                .ok_or_else(|| {
+                                    format_err!("Can not find block info by hash {:?}.", head.id())
+                                })?;
+                            block_info.get_total_difficult() >= best_peer.total_difficult
+                        }
+                        None => false,
+                    };
+                    (synced, peers.len())
+                }
+                None => (false, 0),
+            };
+            Ok(NodeHealth {
+                synced,
+                peer_count,
+                latest_block_number: head.number(),
+            })
+        };
+        Box::new(
+            fut.map_err(|e: anyhow::Error| map_err(e.into()))
+                .boxed()
+                .compat(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::IoHandler;
+    use network_api::DummyNetworkService;
+    use starcoin_chain::mock::mock_chain_service::MockChainService;
+    use starcoin_network::NetworkAsyncService;
+    use starcoin_rpc_client::RpcClient;
+    use starcoin_types::peer_info::PeerId;
+    use starcoin_types::U512;
+
+    fn health_with_peer(total_difficult: U512) -> NodeHealth {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let peer = PeerInfo::new(PeerId::random(), 0, total_difficult, Default::default());
+        let network = DummyNetworkService::new(PeerId::random().into(), vec![peer]);
+        let mut io = IoHandler::new();
+        io.extend_with(
+            NodeRpcImpl::new(config, Some(network), MockChainService::new()).to_delegate(),
+        );
+        let client = RpcClient::connect_local(io);
+        client.node_health().unwrap()
+    }
+
+    #[test]
+    fn test_health_without_network() {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let mut io = IoHandler::new();
+        io.extend_with(
+            NodeRpcImpl::<_, NetworkAsyncService>::new(config, None, MockChainService::new())
+                .to_delegate(),
+        );
+        let client = RpcClient::connect_local(io);
+        let health = client.node_health().unwrap();
+        assert!(!health.synced);
+        assert_eq!(health.peer_count, 0);
+        assert_eq!(health.latest_block_number, 0);
+    }
+
+    #[test]
+    fn test_health_synced() {
+        // the genesis head of the mock chain has the same total difficulty as the peer.
+        let health = health_with_peer(U512::zero());
+        assert!(health.synced);
+        assert_eq!(health.peer_count, 1);
+        assert_eq!(health.latest_block_number, 0);
+    }
+
+    #[test]
+    fn test_health_not_synced() {
+        let health = health_with_peer(U512::from(1));
+        assert!(!health.synced);
+        assert_eq!(health.peer_count, 1);
+        assert_eq!(health.latest_block_number, 0);
+    }
 }
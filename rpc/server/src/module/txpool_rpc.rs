@@ -1,11 +1,29 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use actix::prelude::*;
+use futures::compat::Future01CompatExt;
 use futures::future::TryFutureExt;
-use starcoin_rpc_api::{txpool::TxPoolApi, FutureResult};
-use starcoin_txpool_api::TxPoolAsyncService;
-use starcoin_types::transaction::SignedUserTransaction;
+use futures::StreamExt;
+use jsonrpc_pubsub::typed::{Sink, Subscriber};
+use jsonrpc_pubsub::SubscriptionId;
+use starcoin_bus::{Bus, BusActor};
+use starcoin_crypto::ed25519::Ed25519PublicKey;
+use starcoin_logger::prelude::*;
+use starcoin_rpc_api::{
+    txpool::{TxPoolApi, TxPoolPubSubApi},
+    FutureResult,
+};
+use starcoin_txpool_api::{TxPoolAsyncService, TxnStatusEvent};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::{
+    AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
+use crate::metadata::Metadata;
 use crate::module::map_err;
 /// Re-export the API
 pub use starcoin_rpc_api::txpool::*;
@@ -30,10 +48,120 @@ impl<S> TxPoolApi for TxPoolRpcImpl<S>
 where
     S: TxPoolAsyncService,
 {
-    fn submit_transaction(&self, txn: SignedUserTransaction) -> FutureResult<bool> {
-        let fut = self.service.clone().add(txn).map_err(map_err);
+    fn submit_transaction(&self, txn: SignedUserTransaction) -> FutureResult<AdmissionResult> {
+        let fut = self.service.clone().submit_txn(txn).map_err(map_err);
         Box::new(fut.compat())
     }
+
+    fn submit_transactions(
+        &self,
+        txns: Vec<SignedUserTransaction>,
+    ) -> FutureResult<Vec<AdmissionResult>> {
+        let fut = self.service.clone().submit_txns(txns).map_err(map_err);
+        Box::new(fut.compat())
+    }
+
+    fn preflight(
+        &self,
+        raw_txn: RawUserTransaction,
+        public_key: Ed25519PublicKey,
+    ) -> FutureResult<PreflightResult> {
+        let fut = self
+            .service
+            .clone()
+            .preflight(raw_txn, public_key)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+
+    fn next_sequence_number(&self, address: AccountAddress) -> FutureResult<Option<u64>> {
+        let fut = self
+            .service
+            .clone()
+            .next_sequence_number(address)
+            .map_err(map_err);
+        Box::new(fut.compat())
+    }
+}
+
+type TxnStatusSubscribers =
+    Arc<RwLock<HashMap<SubscriptionId, (AccountAddress, Sink<TxnStatusEvent>)>>>;
+
+/// Forward the txn status events on the bus to the subscribers of the txn sender.
+pub struct TxPoolPubSubImpl {
+    subscribers: TxnStatusSubscribers,
+    next_id: AtomicU64,
+}
+
+impl TxPoolPubSubImpl {
+    pub fn new(bus: Addr<BusActor>) -> Self {
+        let subscribers = TxnStatusSubscribers::default();
+        Arbiter::spawn(Self::forward_events(bus, subscribers.clone()));
+        Self {
+            subscribers,
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    async fn forward_events(bus: Addr<BusActor>, subscribers: TxnStatusSubscribers) {
+        let mut events = match bus.channel::<TxnStatusEvent>().await {
+            Ok(events) => events,
+            Err(e) => {
+                error!("fail to subscribe txn status events, err: {:?}", e);
+                return;
+            }
+        };
+        while let Some(event) = events.next().await {
+            let sinks: Vec<_> = subscribers
+                .read()
+                .expect("read txn status subscribers failed.")
+                .iter()
+                .filter(|(_, (sender, _))| *sender == event.sender())
+                .map(|(id, (_, sink))| (id.clone(), sink.clone()))
+                .collect();
+            for (id, sink) in sinks {
+                if let Err(e) = sink.notify(Ok(event.clone())).compat().await {
+                    debug!("txn status subscription {:?} is closed: {:?}", id, e);
+                    subscribers
+                        .write()
+                        .expect("write txn status subscribers failed.")
+                        .remove(&id);
+                }
+            }
+        }
+    }
+}
+
+impl TxPoolPubSubApi for TxPoolPubSubImpl {
+    type Metadata = Metadata;
+
+    fn subscribe_txn_status(
+        &self,
+        _meta: Metadata,
+        subscriber: Subscriber<TxnStatusEvent>,
+        sender: AccountAddress,
+    ) {
+        let id = SubscriptionId::Number(self.next_id.fetch_add(1, Ordering::SeqCst));
+        if let Ok(sink) = subscriber.assign_id(id.clone()) {
+            self.subscribers
+                .write()
+                .expect("write txn status subscribers failed.")
+                .insert(id, (sender, sink));
+        }
+    }
+
+    fn unsubscribe_txn_status(
+        &self,
+        _meta: Option<Metadata>,
+        id: SubscriptionId,
+    ) -> jsonrpc_core::Result<bool> {
+        Ok(self
+            .subscribers
+            .write()
+            .expect("write txn status subscribers failed.")
+            .remove(&id)
+            .is_some())
+    }
 }
 
 #[cfg(test)]
@@ -55,7 +183,7 @@ mod tests {
         let txpool_service = MockTxPoolService::new();
         io.extend_with(TxPoolRpcImpl::new(txpool_service).to_delegate());
         let request = r#"{"jsonrpc":"2.0","method":"txpool.submit_transaction","params":[{"public_key":"731fe437a8d3fbb25fa389307ac615e3a503e49be40e1b8cf9e5136fb44b9e5f","raw_txn":{"expiration_time":0,"gas_specifier":{"Struct":{"address":"00000000000000000000000000000000","module":"Starcoin","name":"T","type_params":[]}},"gas_unit_price":0,"max_gas_amount":0,"payload":{"Script":{"args":[],"ty_args":[],"code":[]}},"sender":"00000000000000000000000000000000","sequence_number":0},"signature":"6d2bcccb51de9046890e88e1a1c351b4b6342a1c59159074483ce511a17755ee778907ed6664ea637d7fabad1685de78cd277ca82ed8b75094e42901b152ef07"}],"id":0}"#;
-        let response = r#"{"jsonrpc":"2.0","result":true,"id":0}"#;
+        let response = r#"{"jsonrpc":"2.0","result":"accepted","id":0}"#;
         assert_eq!(
             io.handle_request(request).wait().unwrap(),
             Some(response.to_string())
@@ -1,14 +1,19 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use jsonrpc_core::IoHandler;
+use crate::drain::DrainMiddleware;
+use crate::metadata::Metadata;
+use jsonrpc_core::{IoHandler, MetaIoHandler};
 use jsonrpc_http_server;
+use jsonrpc_ipc_server::RequestContext;
+use jsonrpc_pubsub::Session;
 use jsonrpc_server_utils::cors::AccessControlAllowOrigin;
 use jsonrpc_server_utils::hosts::DomainsValidation;
 use jsonrpc_tcp_server;
 use jsonrpc_ws_server;
 use starcoin_config::NodeConfig;
 use starcoin_logger::prelude::*;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 pub struct RpcService {
@@ -16,18 +21,27 @@ pub struct RpcService {
     http: Option<jsonrpc_http_server::Server>,
     tcp: Option<jsonrpc_tcp_server::Server>,
     ws: Option<jsonrpc_ws_server::Server>,
+    drain: DrainMiddleware,
 }
 
 impl RpcService {
-    pub fn new(config: Arc<NodeConfig>, io_handler: IoHandler) -> RpcService {
+    pub fn new(config: Arc<NodeConfig>, io_handler: IoHandler<Metadata>) -> RpcService {
+        let drain = DrainMiddleware::default();
+        let mut handler = MetaIoHandler::with_middleware(drain.clone());
+        handler.extend_with(MetaIoHandler::from(io_handler));
         let ipc_file = config.rpc.get_ipc_file();
-        let ipc = jsonrpc_ipc_server::ServerBuilder::new(io_handler.clone())
-            .start(ipc_file.to_str().expect("Path to string should success."))
-            .expect(format!("Unable to start IPC server with ipc file: {:?}", ipc_file).as_str());
+        let ipc = jsonrpc_ipc_server::ServerBuilder::with_meta_extractor(
+            handler.clone(),
+            |context: &RequestContext| {
+                Metadata::from(Arc::new(Session::new(context.sender.clone())))
+            },
+        )
+        .start(ipc_file.to_str().expect("Path to string should success."))
+        .expect(format!("Unable to start IPC server with ipc file: {:?}", ipc_file).as_str());
         info!("Ipc rpc server start at :{:?}", ipc_file);
         let http = match &config.rpc.http_address {
             Some(address) => {
-                let http = jsonrpc_http_server::ServerBuilder::new(io_handler)
+                let http = jsonrpc_http_server::ServerBuilder::new(handler)
                     .cors(DomainsValidation::AllowOnly(vec![
                         AccessControlAllowOrigin::Null,
                         AccessControlAllowOrigin::Any,
@@ -37,7 +51,7 @@ impl RpcService {
                     .health_api(("/status", "status"))
                     .start_http(address)
                     .expect("Unable to start RPC server.");
-                info!("Http rpc server start at :{}", address);
+                info!("Http rpc server start at :{}", http.address());
                 Some(http)
             }
             None => None,
@@ -48,9 +62,25 @@ impl RpcService {
             http,
             tcp: None,
             ws: None,
+            drain,
         }
     }
 
+    /// Reject the new requests, the server keeps serving the in-flight requests until closed.
+    pub fn start_drain(&self) {
+        self.drain.start_drain();
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.drain.in_flight()
+    }
+
+    /// The address the http server is bound to, the actual port is reported when the configured
+    /// port is 0.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.as_ref().map(|http| *http.address())
+    }
+
     pub fn close(self) {
         self.ipc.close();
         if let Some(http) = self.http {
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use merkle_tree::{blob::Blob, proof::SparseMerkleProof};
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::{hash::CryptoHash, HashValue};
@@ -11,9 +11,15 @@ use starcoin_types::{
     account_config::{AccountResource, BalanceResource},
     account_state::AccountState,
     state_set::ChainStateSet,
+    write_set::{WriteOp, WriteSet},
 };
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
+/// Page size of `ChainStateReader::list_accounts`.
+pub const LIST_ACCOUNTS_PAGE_SIZE: usize = 1000;
+
 #[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateProof {
     account_state: Option<Blob>,
@@ -75,6 +81,17 @@ impl StateProof {
     }
 }
 
+/// Verify the `value` of `access_path` got from a untrusted node against a trusted state `root`,
+/// `None` value verifies the access path does not exist.
+pub fn verify_state_proof(
+    root: HashValue,
+    access_path: &AccessPath,
+    value: Option<&[u8]>,
+    proof: &StateProof,
+) -> Result<()> {
+    proof.verify(root, access_path.clone(), value)
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateWithProof {
     pub state: Option<Vec<u8>>,
@@ -111,6 +128,26 @@ pub trait ChainStateReader {
     fn state_root(&self) -> HashValue;
 
     fn dump(&self) -> Result<ChainStateSet>;
+
+    /// List at most `limit` accounts whose address hash is greater or equal to `start`,
+    /// ordered by address hash. Also return the address hash to continue from, if any.
+    fn list_accounts_from(
+        &self,
+        start: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)>;
+
+    /// List all accounts in the state, page by page to bound the memory of the tree walk.
+    fn list_accounts(&self) -> Result<Vec<AccountAddress>> {
+        let mut accounts = vec![];
+        let mut start = Some(HashValue::zero());
+        while let Some(start_key) = start {
+            let (page, next) = self.list_accounts_from(start_key, LIST_ACCOUNTS_PAGE_SIZE)?;
+            accounts.extend(page);
+            start = next;
+        }
+        Ok(accounts)
+    }
 }
 
 pub trait ChainStateWriter {
@@ -133,6 +170,189 @@ pub trait ChainStateWriter {
 /// `ChainState` is a trait that defines chain's global state.
 pub trait ChainState: ChainStateReader + ChainStateWriter {}
 
+/// A `ChainState` over a `ChainStateReader` which rejects every write, such as for simulating
+/// transactions against the state of a remote node.
+pub struct ReadOnlyChainState<'a> {
+    reader: &'a dyn ChainStateReader,
+}
+
+impl<'a> ReadOnlyChainState<'a> {
+    pub fn new(reader: &'a dyn ChainStateReader) -> Self {
+        Self { reader }
+    }
+}
+
+impl<'a> ChainStateReader for ReadOnlyChainState<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        self.reader.get(access_path)
+    }
+
+    fn get_with_proof(&self, access_path: &AccessPath) -> Result<StateWithProof> {
+        self.reader.get_with_proof(access_path)
+    }
+
+    fn get_account_state(&self, address: &AccountAddress) -> Result<Option<AccountState>> {
+        self.reader.get_account_state(address)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.reader.is_genesis()
+    }
+
+    fn state_root(&self) -> HashValue {
+        self.reader.state_root()
+    }
+
+    fn dump(&self) -> Result<ChainStateSet> {
+        self.reader.dump()
+    }
+
+    fn list_accounts_from(
+        &self,
+        start: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)> {
+        self.reader.list_accounts_from(start, limit)
+    }
+}
+
+impl<'a> ChainStateWriter for ReadOnlyChainState<'a> {
+    fn set(&self, access_path: &AccessPath, _value: Vec<u8>) -> Result<()> {
+        bail!("Chain state is read only, set {:?} rejected.", access_path)
+    }
+
+    fn remove(&self, access_path: &AccessPath) -> Result<()> {
+        bail!(
+            "Chain state is read only, remove {:?} rejected.",
+            access_path
+        )
+    }
+
+    fn create_account(&self, account_address: AccountAddress) -> Result<()> {
+        bail!(
+            "Chain state is read only, create account {} rejected.",
+            account_address
+        )
+    }
+
+    fn apply(&self, _state_set: ChainStateSet) -> Result<()> {
+        bail!("Chain state is read only, apply state set rejected.")
+    }
+
+    fn commit(&self) -> Result<HashValue> {
+        bail!("Chain state is read only, commit rejected.")
+    }
+
+    fn flush(&self) -> Result<()> {
+        bail!("Chain state is read only, flush rejected.")
+    }
+}
+
+impl<'a> ChainState for ReadOnlyChainState<'a> {}
+
+/// A `ChainState` over a `ChainStateReader` which keeps the writes in memory, such as for
+/// simulating a sequence of transactions against the state of a remote node, every transaction
+/// reads the writes of the ones before it.
+/// Only `get` sees the writes, the account state, proof and root are of the reader.
+pub struct OverlayChainState<'a> {
+    reader: &'a dyn ChainStateReader,
+    /// `None` is a removed access path.
+    writes: RefCell<HashMap<AccessPath, Option<Vec<u8>>>>,
+}
+
+impl<'a> OverlayChainState<'a> {
+    pub fn new(reader: &'a dyn ChainStateReader) -> Self {
+        Self {
+            reader,
+            writes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Apply the write set of a transaction output.
+    pub fn apply_write_set(&self, write_set: &WriteSet) {
+        let mut writes = self.writes.borrow_mut();
+        for (access_path, write_op) in write_set {
+            let value = match write_op {
+                WriteOp::Value(value) => Some(value.clone()),
+                WriteOp::Deletion => None,
+            };
+            writes.insert(access_path.clone(), value);
+        }
+    }
+}
+
+impl<'a> ChainStateReader for OverlayChainState<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        match self.writes.borrow().get(access_path) {
+            Some(value) => Ok(value.clone()),
+            None => self.reader.get(access_path),
+        }
+    }
+
+    fn get_with_proof(&self, access_path: &AccessPath) -> Result<StateWithProof> {
+        self.reader.get_with_proof(access_path)
+    }
+
+    fn get_account_state(&self, address: &AccountAddress) -> Result<Option<AccountState>> {
+        self.reader.get_account_state(address)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.reader.is_genesis()
+    }
+
+    fn state_root(&self) -> HashValue {
+        self.reader.state_root()
+    }
+
+    fn dump(&self) -> Result<ChainStateSet> {
+        self.reader.dump()
+    }
+
+    fn list_accounts_from(
+        &self,
+        start: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)> {
+        self.reader.list_accounts_from(start, limit)
+    }
+}
+
+impl<'a> ChainStateWriter for OverlayChainState<'a> {
+    fn set(&self, access_path: &AccessPath, value: Vec<u8>) -> Result<()> {
+        self.writes
+            .borrow_mut()
+            .insert(access_path.clone(), Some(value));
+        Ok(())
+    }
+
+    fn remove(&self, access_path: &AccessPath) -> Result<()> {
+        self.writes.borrow_mut().insert(access_path.clone(), None);
+        Ok(())
+    }
+
+    fn create_account(&self, account_address: AccountAddress) -> Result<()> {
+        bail!(
+            "Chain state overlay does not support create account {}.",
+            account_address
+        )
+    }
+
+    fn apply(&self, _state_set: ChainStateSet) -> Result<()> {
+        bail!("Chain state overlay does not support apply state set.")
+    }
+
+    fn commit(&self) -> Result<HashValue> {
+        bail!("Chain state overlay can not be committed.")
+    }
+
+    fn flush(&self) -> Result<()> {
+        bail!("Chain state overlay can not be flushed.")
+    }
+}
+
+impl<'a> ChainState for OverlayChainState<'a> {}
+
 /// `AccountStateReader` is a helper struct for read account state.
 pub struct AccountStateReader<'a> {
     //TODO add a cache.
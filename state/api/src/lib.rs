@@ -11,8 +11,10 @@ use starcoin_types::{
 
 mod chain_state;
 pub mod mock;
+pub use chain_state::verify_state_proof;
 pub use chain_state::{
-    AccountStateReader, ChainState, ChainStateReader, ChainStateWriter, StateProof, StateWithProof,
+    AccountStateReader, ChainState, ChainStateReader, ChainStateWriter, OverlayChainState,
+    ReadOnlyChainState, StateProof, StateWithProof, LIST_ACCOUNTS_PAGE_SIZE,
 };
 
 pub trait ChainStateService: ChainStateReader {
@@ -55,4 +55,12 @@ impl ChainStateReader for ChainStateServiceImpl {
     fn dump(&self) -> Result<ChainStateSet> {
         unimplemented!()
     }
+
+    fn list_accounts_from(
+        &self,
+        start: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)> {
+        self.reader.list_accounts_from(start, limit)
+    }
 }
@@ -45,4 +45,8 @@ impl StateNodeStore for MockStateNodeStore {
         }
         Ok(())
     }
+
+    fn contains(&self, hash: &HashValue) -> Result<bool> {
+        Ok(self.nodes.lock().unwrap().contains_key(hash))
+    }
 }
@@ -55,6 +55,11 @@ pub trait StateNodeStore: std::marker::Send + std::marker::Sync {
     fn get(&self, hash: &HashValue) -> Result<Option<StateNode>>;
     fn put(&self, key: HashValue, node: StateNode) -> Result<()>;
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<()>;
+
+    /// Whether the node is saved, stores should override it to skip decoding the node.
+    fn contains(&self, hash: &HashValue) -> Result<bool> {
+        self.get(hash).map(|node| node.is_some())
+    }
 }
 
 pub struct StateCache {
@@ -267,6 +272,24 @@ impl StateTree {
         Ok(StateSet::new(states))
     }
 
+    /// Return at most `limit` states with key greater or equal to `start_key`, in key order.
+    pub fn scan(&self, start_key: HashValue, limit: usize) -> Result<Vec<(HashValue, Vec<u8>)>> {
+        let cur_root_hash = self.root_hash();
+        let mut cache_guard = self.cache.lock().unwrap();
+        let cache = cache_guard.deref_mut();
+        let reader = CachedTreeReader {
+            store: self.storage.as_ref(),
+            cache,
+        };
+        let iterator = JellyfishMerkleIterator::new(Arc::new(reader), cur_root_hash, start_key)?;
+        let mut states = vec![];
+        for item in iterator.take(limit) {
+            let item = item?;
+            states.push((item.0, item.1.into()));
+        }
+        Ok(states)
+    }
+
     /// passing None value with a key means delete the key
     fn updates(&self, updates: Vec<(HashValue, Option<Blob>)>) -> Result<HashValue> {
         let cur_root_hash = self.root_hash();
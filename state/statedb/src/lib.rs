@@ -15,14 +15,15 @@ use starcoin_state_api::{
 use starcoin_state_tree::{StateNodeStore, StateTree};
 use starcoin_types::{
     access_path::{AccessPath, DataType},
-    account_address::AccountAddress,
+    account_address::{AccountAddress, ADDRESS_LENGTH},
     account_config::{
         account_balance_struct_tag, account_struct_tag, AccountResource, BalanceResource,
+        ACCOUNT_RESOURCE_PATH,
     },
     account_state::AccountState,
     state_set::{AccountStateSet, ChainStateSet},
 };
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -298,6 +299,42 @@ impl ChainStateDB {
         Ok(object)
     }
 
+    /// The state tree only keeps the address hash, so the address of an account is taken from
+    /// the authentication key of its account resource, and checked against the hash.
+    /// Return an error if the address can not be determined, such as an account whose
+    /// authentication key is rotated, rather than leaving the account out of a listing.
+    fn get_account_address(
+        &self,
+        address_hash: &HashValue,
+        account_state: &AccountState,
+    ) -> Result<AccountAddress> {
+        let resource_root = match account_state.storage_roots()[DataType::RESOURCE.storage_index()]
+        {
+            Some(resource_root) => resource_root,
+            None => bail!("account {:?} has no resource", address_hash),
+        };
+        let account_resource = match self
+            .new_state_tree(resource_root)
+            .get(&ACCOUNT_RESOURCE_PATH)?
+        {
+            Some(bytes) => AccountResource::make_from(bytes.as_slice())?,
+            None => bail!("account {:?} has no account resource", address_hash),
+        };
+        let auth_key = account_resource.authentication_key();
+        ensure!(
+            auth_key.len() >= ADDRESS_LENGTH,
+            "authentication key of account {:?} is too short to derive the address",
+            address_hash
+        );
+        let address = AccountAddress::try_from(&auth_key[auth_key.len() - ADDRESS_LENGTH..])?;
+        ensure!(
+            address.crypto_hash() == *address_hash,
+            "can not derive the address of account {:?}, its authentication key may be rotated",
+            address_hash
+        );
+        Ok(address)
+    }
+
     fn get_account_state_by_hash(&self, address_hash: &HashValue) -> Result<Option<AccountState>> {
         self.state_tree
             .get(address_hash)
@@ -396,6 +433,22 @@ impl ChainStateReader for ChainStateDB {
         }
         Ok(ChainStateSet::new(account_states))
     }
+
+    fn list_accounts_from(
+        &self,
+        start: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<AccountAddress>, Option<HashValue>)> {
+        ensure!(limit > 0, "list accounts limit should be greater than 0");
+        let states = self.state_tree.scan(start, limit + 1)?;
+        let next = states.get(limit).map(|(address_hash, _)| *address_hash);
+        let mut accounts = vec![];
+        for (address_hash, account_state_bytes) in states.into_iter().take(limit) {
+            let account_state = AccountState::decode(account_state_bytes.as_slice())?;
+            accounts.push(self.get_account_address(&address_hash, &account_state)?);
+        }
+        Ok((accounts, next))
+    }
 }
 
 impl ChainStateWriter for ChainStateDB {
@@ -510,8 +563,9 @@ impl ChainStateWriter for ChainStateDB {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use starcoin_state_api::AccountStateReader;
+    use starcoin_state_api::{verify_state_proof, AccountStateReader, OverlayChainState};
     use starcoin_state_tree::mock::MockStateNodeStore;
+    use starcoin_types::write_set::{WriteOp, WriteSetMut};
 
     #[test]
     fn test_state_proof() -> Result<()> {
@@ -535,6 +589,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_verify_state_proof() -> Result<()> {
+        let storage = MockStateNodeStore::new();
+        let chain_state_db = ChainStateDB::new(Arc::new(storage), None);
+        let account_address = AccountAddress::random();
+        chain_state_db.create_account(account_address)?;
+        chain_state_db.create_account(AccountAddress::random())?;
+        let state_root = chain_state_db.commit()?;
+        let access_path = AccessPath::new_for_account(account_address);
+        let state_with_proof = chain_state_db.get_with_proof(&access_path)?;
+        let value = state_with_proof.state.as_ref().map(|s| s.as_slice());
+        verify_state_proof(state_root, &access_path, value, &state_with_proof.proof)?;
+
+        // a not existed account is verified with a non-inclusion proof.
+        let absent_path = AccessPath::new_for_account(AccountAddress::random());
+        let absent = chain_state_db.get_with_proof(&absent_path)?;
+        assert!(absent.state.is_none());
+        verify_state_proof(state_root, &absent_path, None, &absent.proof)?;
+
+        // tampered value, root or proof.
+        assert!(
+            verify_state_proof(state_root, &access_path, None, &state_with_proof.proof).is_err()
+        );
+        assert!(verify_state_proof(
+            state_root,
+            &access_path,
+            Some(&[1u8, 2, 3]),
+            &state_with_proof.proof
+        )
+        .is_err());
+        assert!(verify_state_proof(
+            HashValue::random(),
+            &access_path,
+            value,
+            &state_with_proof.proof
+        )
+        .is_err());
+        let account_proof = &state_with_proof.proof.account_proof;
+        let mut siblings = account_proof.siblings().to_vec();
+        siblings[0] = HashValue::random();
+        let tampered_proof = StateProof::new(
+            chain_state_db
+                .get_account_state(&account_address)?
+                .map(|s| s.encode())
+                .transpose()?,
+            SparseMerkleProof::new(account_proof.leaf(), siblings),
+            state_with_proof.proof.account_state_proof.clone(),
+        );
+        assert!(verify_state_proof(state_root, &access_path, value, &tampered_proof).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_state_db() -> Result<()> {
         let storage = MockStateNodeStore::new();
@@ -580,6 +686,83 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_overlay_chain_state() -> Result<()> {
+        let storage = MockStateNodeStore::new();
+        let chain_state_db = ChainStateDB::new(Arc::new(storage), None);
+        let account_address = AccountAddress::random();
+        chain_state_db.create_account(account_address)?;
+        let state_root = chain_state_db.commit()?;
+        let access_path_balance = AccessPath::new_for_balance(account_address);
+        let access_path = AccessPath::new_for_account(account_address);
+
+        let overlay = OverlayChainState::new(&chain_state_db);
+        let write_set = WriteSetMut::new(vec![
+            (
+                access_path_balance.clone(),
+                WriteOp::Value(BalanceResource::new(10).try_into()?),
+            ),
+            (access_path.clone(), WriteOp::Deletion),
+        ])
+        .freeze()?;
+        overlay.apply_write_set(&write_set);
+        assert_eq!(
+            AccountStateReader::new(&overlay).get_balance(&account_address)?,
+            Some(10)
+        );
+        assert!(overlay.get(&access_path)?.is_none());
+
+        // the writes are only kept in the overlay.
+        let account_state_reader = AccountStateReader::new(&chain_state_db);
+        assert_eq!(account_state_reader.get_balance(&account_address)?, Some(0));
+        assert!(chain_state_db.get(&access_path)?.is_some());
+        assert_eq!(chain_state_db.state_root(), state_root);
+        assert!(overlay.commit().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_accounts() -> Result<()> {
+        let storage = MockStateNodeStore::new();
+        let chain_state_db = ChainStateDB::new(Arc::new(storage), None);
+        let mut addresses = vec![
+            AccountAddress::random(),
+            AccountAddress::random(),
+            AccountAddress::random(),
+        ];
+        for address in &addresses {
+            chain_state_db.create_account(*address)?;
+        }
+        chain_state_db.commit()?;
+        chain_state_db.flush()?;
+
+        let mut accounts = chain_state_db.list_accounts()?;
+        addresses.sort();
+        accounts.sort();
+        assert_eq!(accounts, addresses);
+
+        let (page, next) = chain_state_db.list_accounts_from(HashValue::zero(), 2)?;
+        assert_eq!(page.len(), 2);
+        let (rest, next) = chain_state_db.list_accounts_from(next.unwrap(), 2)?;
+        assert_eq!(rest.len(), 1);
+        assert!(next.is_none());
+
+        // the address of an account with a rotated authentication key can not be derived.
+        let account_resource = AccountResource::new(0, AccountAddress::random().to_vec());
+        chain_state_db.set(
+            &AccessPath::new(
+                addresses[0],
+                DataType::RESOURCE,
+                account_struct_tag().crypto_hash(),
+            ),
+            account_resource.try_into()?,
+        )?;
+        chain_state_db.commit()?;
+        chain_state_db.flush()?;
+        assert!(chain_state_db.list_accounts().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_write_no_exist_account() -> Result<()> {
         let storage = MockStateNodeStore::new();
@@ -9,6 +9,7 @@ use anyhow::Error;
 use anyhow::Result;
 use byteorder::{BigEndian, ReadBytesExt};
 use crypto::hash::HashValue;
+use logger::prelude::*;
 use scs::SCSCodec;
 use starcoin_accumulator::node_index::NodeIndex;
 use starcoin_accumulator::{
@@ -63,8 +64,19 @@ impl AccumulatorReader for AccumulatorStorage {
         self.node_store.get(hash)
     }
 
-    fn multiple_get(&self, _hash_vec: Vec<HashValue>) -> Result<Vec<AccumulatorNode>, Error> {
-        unimplemented!()
+    /// Get the nodes of `hash_vec` in order, missing nodes are skipped.
+    fn multiple_get(&self, hash_vec: Vec<HashValue>) -> Result<Vec<AccumulatorNode>, Error> {
+        let nodes = self.node_store.multi_get(hash_vec.clone())?;
+        Ok(hash_vec
+            .into_iter()
+            .zip(nodes)
+            .filter_map(|(hash, node)| {
+                if node.is_none() {
+                    debug!("accumulator node {:?} not found.", hash);
+                }
+                node
+            })
+            .collect())
     }
 }
 
@@ -0,0 +1,53 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use byteorder::{BigEndian, ByteOrder};
+use crypto::HashValue;
+
+/// Default bit size of the known block filter, 1M bits take 128KB memory.
+pub const DEFAULT_BLOOM_BITS: usize = 1 << 20;
+/// Number of probes for every block id.
+const BLOOM_HASHES: u64 = 4;
+
+/// A bloom filter of block ids. Block id is already a uniformly distributed hash,
+/// so the probe positions are derived from its bytes directly.
+pub struct BlockBloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+}
+
+impl BlockBloomFilter {
+    pub fn new(num_bits: usize) -> Self {
+        let num_words = (num_bits + 63) / 64;
+        Self {
+            bits: vec![0; num_words.max(1)],
+            num_bits: (num_words.max(1) * 64) as u64,
+        }
+    }
+
+    fn positions(&self, id: &HashValue) -> impl Iterator<Item = u64> {
+        let bytes = id.to_vec();
+        let h1 = BigEndian::read_u64(&bytes[0..8]);
+        let h2 = BigEndian::read_u64(&bytes[8..16]) | 1;
+        let num_bits = self.num_bits;
+        (0..BLOOM_HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    pub fn insert(&mut self, id: &HashValue) {
+        for pos in self.positions(id).collect::<Vec<_>>() {
+            self.bits[(pos / 64) as usize] |= 1u64 << (pos % 64);
+        }
+    }
+
+    /// Return false if the id is definitely not in the filter.
+    pub fn may_contain(&self, id: &HashValue) -> bool {
+        self.positions(id)
+            .all(|pos| self.bits[(pos / 64) as usize] & (1u64 << (pos % 64)) != 0)
+    }
+}
+
+impl Default for BlockBloomFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOOM_BITS)
+    }
+}
@@ -1,21 +1,27 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 use crate::batch::WriteBatch;
+use crate::block::bloom::BlockBloomFilter;
 use crate::define_storage;
 use crate::storage::{CodecStorage, KeyCodec, StorageInstance, ValueCodec};
 use crate::{
-    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_NUM_PREFIX_NAME, BLOCK_PREFIX_NAME,
-    BLOCK_SONS_PREFIX_NAME, BLOCK_TRANSATIONS_PREFIX_NAME,
+    ensure_slice_len_multiple, BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME,
+    BLOCK_NUM_PREFIX_NAME, BLOCK_PREFIX_NAME, BLOCK_SONS_PREFIX_NAME,
+    BLOCK_TRANSATIONS_PREFIX_NAME,
 };
-use anyhow::{bail, ensure, Error, Result};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use crypto::HashValue;
 use logger::prelude::*;
 use scs::SCSCodec;
 use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockNumber, BranchNumber};
+use std::collections::HashSet;
 use std::io::Write;
 use std::mem::size_of;
 use std::sync::{Arc, RwLock};
+
+mod bloom;
+
 define_storage!(BlockInnerStorage, HashValue, Block, BLOCK_PREFIX_NAME);
 define_storage!(
     BlockHeaderStorage,
@@ -63,6 +69,8 @@ pub struct BlockStorage {
     number_store: BlockNumberStorage,
     branch_number_store: BranchNumberStorage,
     block_txns_store: BlockTransactionsStorage,
+    //in memory filter of known block ids, None if the saved block ids can not be listed.
+    known_blocks: Option<RwLock<BlockBloomFilter>>,
 }
 
 impl ValueCodec for Block {
@@ -97,30 +105,19 @@ impl ValueCodec for BlockBody {
 
 impl ValueCodec for Vec<HashValue> {
     fn encode_value(&self) -> Result<Vec<u8>> {
-        let mut encoded = vec![];
+        let mut encoded = Vec::with_capacity(self.len() * size_of::<HashValue>());
         for hash in self {
-            encoded.write_all(&hash.to_vec()).unwrap();
+            encoded.write_all(&hash.to_vec())?;
         }
         Ok(encoded)
     }
 
     fn decode_value(data: &[u8]) -> Result<Self> {
         let hash_size = size_of::<HashValue>();
-        let mut decoded = vec![];
-        let mut ends = hash_size;
-        let len = data.len();
-        let mut begin: usize = 0;
-        loop {
-            if ends <= len {
-                let hash = HashValue::from_slice(&data[begin..ends])?;
-                decoded.push(hash);
-            } else {
-                break;
-            }
-            begin = ends;
-            ends = ends + hash_size;
-        }
-        Ok(decoded)
+        ensure_slice_len_multiple(data, hash_size)?;
+        data.chunks(hash_size)
+            .map(|hash| Ok(HashValue::from_slice(hash)?))
+            .collect()
     }
 }
 
@@ -153,14 +150,68 @@ impl KeyCodec for BranchNumber {
 
 impl BlockStorage {
     pub fn new(instance: StorageInstance) -> Self {
+        let header_store = BlockHeaderStorage::new(instance.clone());
+        let known_blocks = Self::load_known_blocks(&header_store);
+        Self::new_with_known_blocks(instance, header_store, known_blocks)
+    }
+
+    /// Create a block storage without the known block filter, `is_block_known` always reads
+    /// the header store. Used for read only snapshots, which can not list the saved headers.
+    pub fn new_without_known_blocks(instance: StorageInstance) -> Self {
+        let header_store = BlockHeaderStorage::new(instance.clone());
+        Self::new_with_known_blocks(instance, header_store, None)
+    }
+
+    fn new_with_known_blocks(
+        instance: StorageInstance,
+        header_store: BlockHeaderStorage,
+        known_blocks: Option<BlockBloomFilter>,
+    ) -> Self {
+        let known_blocks = known_blocks.map(RwLock::new);
         BlockStorage {
             block_store: BlockInnerStorage::new(instance.clone()),
-            header_store: BlockHeaderStorage::new(instance.clone()),
+            header_store,
             sons_store: RwLock::new(BlockSonsStorage::new(instance.clone())),
             body_store: BlockBodyStorage::new(instance.clone()),
             number_store: BlockNumberStorage::new(instance.clone()),
             branch_number_store: BranchNumberStorage::new(instance.clone()),
             block_txns_store: BlockTransactionsStorage::new(instance.clone()),
+            known_blocks,
+        }
+    }
+
+    /// Build the known block filter from the ids of all saved headers, return None if the
+    /// store can not list them, such as a cache only store.
+    fn load_known_blocks(header_store: &BlockHeaderStorage) -> Option<BlockBloomFilter> {
+        match header_store.cf_keys() {
+            Ok(block_ids) => {
+                let mut known_blocks = BlockBloomFilter::default();
+                for block_id in &block_ids {
+                    known_blocks.insert(block_id);
+                }
+                Some(known_blocks)
+            }
+            Err(e) => {
+                debug!("known block filter disabled, list headers failed: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Return whether the block header is saved. The in memory filter answers "definitely not"
+    /// without a storage read, a possible hit or a storage without filter reads the header store.
+    pub fn is_block_known(&self, block_id: HashValue) -> Result<bool> {
+        if let Some(known_blocks) = &self.known_blocks {
+            if !known_blocks.read().unwrap().may_contain(&block_id) {
+                return Ok(false);
+            }
+        }
+        self.header_store.contains_key(block_id)
+    }
+
+    fn add_known_block(&self, block_id: &HashValue) {
+        if let Some(known_blocks) = &self.known_blocks {
+            known_blocks.write().unwrap().insert(block_id);
         }
     }
     pub fn save(&self, block: Block) -> Result<()> {
@@ -174,6 +225,7 @@ impl BlockStorage {
 
     pub fn save_header(&self, header: BlockHeader) -> Result<()> {
         self.header_store.put(header.id(), header.clone()).unwrap();
+        self.add_known_block(&header.id());
         //save sons relationship
         self.put_sons(header.parent_hash(), header.id())
     }
@@ -182,7 +234,7 @@ impl BlockStorage {
         let mut key_hashes = vec![];
         for hash in self.header_store.keys().unwrap() {
             let hashval = HashValue::from_slice(hash.as_slice()).unwrap();
-            debug!("header key:{}", hashval.to_hex());
+            trace!("header key:{}", hashval.to_hex());
             key_hashes.push(hashval)
         }
         Ok(key_hashes)
@@ -226,31 +278,53 @@ impl BlockStorage {
     }
 
     pub fn commit_block(&self, block: Block) -> Result<()> {
-        let (header, body) = block.clone().into_inner();
-        //save header
-        let block_id = header.id();
-        self.save_header(header.clone()).unwrap();
-        //save number
-        self.save_number(header.number(), block_id).unwrap();
-        //save body
-        self.save_body(block_id, body).unwrap();
-        //save block cache
-        self.save(block)
+        self.commit_block_with(block, None, |sons_store, batch| {
+            sons_store.write_batch(batch)
+        })
     }
 
     pub fn commit_branch_block(&self, branch_id: HashValue, block: Block) -> Result<()> {
         debug!("commit block: {:?}, block: {:?}", branch_id, block);
+        self.commit_block_with(block, Some(branch_id), |sons_store, batch| {
+            sons_store.write_batch(batch)
+        })
+    }
+
+    /// Build one batch with the header, number, body, sons and block entries of the block, and
+    /// hand it to `write`. The sons of the parent are read and written under the sons lock, so
+    /// concurrent commits of siblings don't lose each other.
+    pub(crate) fn commit_block_with<F>(
+        &self,
+        block: Block,
+        branch_id: Option<HashValue>,
+        write: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&BlockSonsStorage, WriteBatch) -> Result<()>,
+    {
         let (header, body) = block.clone().into_inner();
-        //save header
         let block_id = header.id();
-        self.save_header(header.clone()).unwrap();
-        //save number
-        self.save_branch_number(branch_id, header.number(), block_id)
-            .unwrap();
-        //save body
-        self.save_body(block_id, body).unwrap();
-        //save block cache
-        self.save(block)
+        let mut batch = WriteBatch::new();
+        batch.put(BLOCK_HEADER_PREFIX_NAME, block_id, header.clone())?;
+        match branch_id {
+            Some(branch_id) => batch.put(
+                BLOCK_NUM_PREFIX_NAME,
+                (branch_id, header.number()),
+                block_id,
+            )?,
+            None => batch.put(BLOCK_NUM_PREFIX_NAME, header.number(), block_id)?,
+        }
+        batch.put(BLOCK_BODY_PREFIX_NAME, block_id, body)?;
+        let sons_store = self.sons_store.write().unwrap();
+        let mut sons = sons_store.get(header.parent_hash())?.unwrap_or_default();
+        if !sons.contains(&block_id) {
+            sons.push(block_id);
+        }
+        batch.put(BLOCK_SONS_PREFIX_NAME, header.parent_hash(), sons)?;
+        batch.put(BLOCK_PREFIX_NAME, block_id, block)?;
+        write(&sons_store, batch)?;
+        self.add_known_block(&block_id);
+        Ok(())
     }
 
     ///返回某个块到分叉块的路径上所有块的hash
@@ -258,7 +332,7 @@ impl BlockStorage {
         let mut vev_hash = Vec::new();
         let mut temp_block_id = block_id;
         loop {
-            debug!("block_id: {}", temp_block_id.to_hex());
+            trace!("block_id: {}", temp_block_id.to_hex());
             //get header by block_id
             match self.get_block_header_by_hash(temp_block_id)? {
                 Some(header) => {
@@ -348,6 +422,152 @@ impl BlockStorage {
         }
     }
 
+    /// Get common ancestor by walking back from the higher block, give up with `None` after
+    /// `max_depth` steps.
+    pub fn get_common_ancestor_bounded(
+        &self,
+        block_id1: HashValue,
+        block_id2: HashValue,
+        max_depth: u64,
+    ) -> Result<Option<HashValue>> {
+        if let Ok(Some(hash)) = self.get_relationship(block_id1, block_id2) {
+            return Ok(Some(hash));
+        }
+        if let Ok(Some(hash)) = self.get_relationship(block_id2, block_id1) {
+            return Ok(Some(hash));
+        }
+
+        let get_header = |block_id: HashValue| -> Result<BlockHeader> {
+            self.get_block_header_by_hash(block_id)?
+                .ok_or_else(|| format_err!("Error: can not find block {:?}", block_id))
+        };
+        let mut header1 = get_header(block_id1)?;
+        let mut header2 = get_header(block_id2)?;
+        let mut depth = 0;
+        while header1.id() != header2.id() {
+            if depth >= max_depth {
+                debug!(
+                    "common ancestor of {:?} and {:?} not found in {} blocks.",
+                    block_id1, block_id2, max_depth
+                );
+                return Ok(None);
+            }
+            let (number1, number2) = (header1.number(), header2.number());
+            if number1 >= number2 {
+                ensure!(
+                    header1.parent_hash() != HashValue::zero(),
+                    "invaild block id is zero."
+                );
+                header1 = get_header(header1.parent_hash())?;
+            }
+            if number2 >= number1 {
+                ensure!(
+                    header2.parent_hash() != HashValue::zero(),
+                    "invaild block id is zero."
+                );
+                header2 = get_header(header2.parent_hash())?;
+            }
+            depth += 1;
+        }
+        Ok(Some(header1.id()))
+    }
+
+    /// Delete the block, header, body, number and sons entries of the blocks from `branch_head`
+    /// back to `stop_at`(excluded), the `branch_id` number entries of them, and remove the branch
+    /// from the sons of `stop_at`.
+    /// Refuse to prune a branch which `latest_block_id` is built on.
+    pub fn prune_branch(
+        &self,
+        branch_id: HashValue,
+        branch_head: HashValue,
+        stop_at: HashValue,
+        latest_block_id: Option<HashValue>,
+    ) -> Result<u64> {
+        if let Some(latest_block_id) = latest_block_id {
+            ensure!(
+                !self.is_ancestor(branch_head, latest_block_id)?,
+                "block {:?} is on the chain of latest block {:?}, can not prune it.",
+                branch_head,
+                latest_block_id
+            );
+        }
+
+        let mut headers = vec![];
+        let mut block_id = branch_head;
+        while block_id != stop_at {
+            ensure!(
+                block_id != HashValue::zero(),
+                "block {:?} is not an ancestor of {:?}.",
+                stop_at,
+                branch_head
+            );
+            match self.get_block_header_by_hash(block_id)? {
+                Some(header) => {
+                    block_id = header.parent_hash();
+                    headers.push(header);
+                }
+                None => bail!("Error: can not find block {:?}", block_id),
+            }
+        }
+        let branch_root = match headers.last() {
+            Some(header) => header.id(),
+            None => return Ok(0),
+        };
+
+        let mut batch = WriteBatch::new();
+        for header in &headers {
+            let block_id = header.id();
+            batch.delete(BLOCK_PREFIX_NAME, block_id)?;
+            batch.delete(BLOCK_HEADER_PREFIX_NAME, block_id)?;
+            batch.delete(BLOCK_BODY_PREFIX_NAME, block_id)?;
+            batch.delete(BLOCK_SONS_PREFIX_NAME, block_id)?;
+            // the number index may point to a master block of the same number.
+            if self.number_store.get(header.number())? == Some(block_id) {
+                batch.delete(BLOCK_NUM_PREFIX_NAME, header.number())?;
+            }
+            let branch_number = (branch_id, header.number());
+            if self.branch_number_store.get(branch_number)? == Some(block_id) {
+                batch.delete(BLOCK_NUM_PREFIX_NAME, branch_number)?;
+            }
+        }
+        let sons_store = self.sons_store.write().unwrap();
+        let mut sons = sons_store.get(stop_at)?.unwrap_or_default();
+        sons.retain(|son| *son != branch_root);
+        if sons.is_empty() {
+            batch.delete(BLOCK_SONS_PREFIX_NAME, stop_at)?;
+        } else {
+            batch.put(BLOCK_SONS_PREFIX_NAME, stop_at, sons)?;
+        }
+        sons_store.write_batch(batch)?;
+        debug!(
+            "prune branch {:?} to {:?}, {} blocks.",
+            branch_head,
+            stop_at,
+            headers.len()
+        );
+        Ok(headers.len() as u64)
+    }
+
+    /// Return whether `ancestor` is `block_id` or an ancestor of it.
+    fn is_ancestor(&self, ancestor: HashValue, block_id: HashValue) -> Result<bool> {
+        let ancestor_number = match self.get_block_header_by_hash(ancestor)? {
+            Some(header) => header.number(),
+            None => return Ok(false),
+        };
+        let mut block_id = block_id;
+        loop {
+            if block_id == ancestor {
+                return Ok(true);
+            }
+            match self.get_block_header_by_hash(block_id)? {
+                Some(header) if header.number() > ancestor_number => {
+                    block_id = header.parent_hash();
+                }
+                _ => return Ok(false),
+            }
+        }
+    }
+
     pub fn get_latest_block_header(&self) -> Result<Option<BlockHeader>> {
         let max_number = self.number_store.get_len()?;
         if max_number == 0 {
@@ -356,16 +576,44 @@ impl BlockStorage {
         self.get_block_header_by_number(max_number - 1)
     }
 
-    pub fn get_latest_block(&self) -> Result<Block> {
+    pub fn get_latest_block(&self) -> Result<Option<Block>> {
         //get storage current len
         let max_number = self.number_store.get_len()?;
-        Ok(self.get_block_by_number(max_number - 1)?.unwrap())
+        if max_number == 0 {
+            return Ok(None);
+        }
+        self.get_block_by_number(max_number - 1)
     }
 
     pub fn get_block_header_by_hash(&self, block_id: HashValue) -> Result<Option<BlockHeader>> {
         self.header_store.get(block_id)
     }
 
+    /// Iterate the master headers from number 0 upward, it stops at the first missing number or
+    /// after the first error.
+    pub fn iter_headers_by_number(&self) -> Result<impl Iterator<Item = Result<BlockHeader>> + '_> {
+        let mut number = 0;
+        let mut stopped = false;
+        Ok(std::iter::from_fn(move || {
+            if stopped {
+                return None;
+            }
+            let header = match self.number_store.get(number) {
+                Ok(Some(block_id)) => self.header_store.get(block_id).and_then(|header| {
+                    header.ok_or_else(|| format_err!("can't find block header {:?}", block_id))
+                }),
+                Ok(None) => {
+                    stopped = true;
+                    return None;
+                }
+                Err(e) => Err(e),
+            };
+            stopped = header.is_err();
+            number += 1;
+            Some(header)
+        }))
+    }
+
     pub fn get_block_by_hash(&self, block_id: HashValue) -> Result<Option<Block>> {
         self.get(block_id)
     }
@@ -422,11 +670,23 @@ impl BlockStorage {
             _ => bail!("can't find block's transaction: {:?}", block_id),
         }
     }
+    /// Save the txn hashes of the block, a duplicated hash is only kept at its first position.
+    /// A block always executes the block metadata txn, so the list can not be empty.
     pub fn put_transactions(
         &self,
         block_id: HashValue,
         transactions: Vec<HashValue>,
     ) -> Result<()> {
+        ensure!(
+            !transactions.is_empty(),
+            "block {:?} transactions should not be empty.",
+            block_id
+        );
+        let mut seen = HashSet::with_capacity(transactions.len());
+        let transactions: Vec<HashValue> = transactions
+            .into_iter()
+            .filter(|txn_hash| seen.insert(*txn_hash))
+            .collect();
         self.block_txns_store.put(block_id, transactions)
     }
 
@@ -446,7 +706,7 @@ impl BlockStorage {
         Ok(None)
     }
 
-    fn get_sons(&self, parent_hash: HashValue) -> Result<Vec<HashValue>> {
+    pub(crate) fn get_sons(&self, parent_hash: HashValue) -> Result<Vec<HashValue>> {
         match self.sons_store.read().unwrap().get(parent_hash)? {
             Some(sons) => Ok(sons),
             None => bail!("cant't find sons: {}", parent_hash),
@@ -6,30 +6,70 @@ use crate::storage::{InnerStore, WriteOp};
 use anyhow::{Error, Result};
 use lru::LruCache;
 use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
-const LRU_CACHE_DEFAULT_SIZE: usize = 65535;
+pub const LRU_CACHE_DEFAULT_SIZE: usize = 65535;
 
+/// An LRU cache of at most `capacity` entries of all column families, the least recently used
+/// entry is evicted when it is full.
 pub struct CacheStorage {
     cache: Mutex<LruCache<Vec<u8>, Vec<u8>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl CacheStorage {
     pub fn new() -> Self {
-        CacheStorage {
-            cache: Mutex::new(LruCache::new(LRU_CACHE_DEFAULT_SIZE)),
-        }
+        Self::new_with_capacity(LRU_CACHE_DEFAULT_SIZE)
     }
     pub fn new_with_capacity(size: usize) -> Self {
         CacheStorage {
             cache: Mutex::new(LruCache::new(size)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
+
+    pub fn capacity(&self) -> usize {
+        self.cache.lock().cap()
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.cache.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of reads found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of reads not found in the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn record_read<T>(&self, value: &Option<T>) {
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
 }
 
 impl InnerStore for CacheStorage {
     fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
         let compose = compose_key(prefix_name.to_string(), key)?;
-        Ok(self.cache.lock().get(&compose).map(|v| v.to_vec()))
+        let value = self.cache.lock().get(&compose).map(|v| v.to_vec());
+        self.record_read(&value);
+        Ok(value)
     }
 
     fn put(&self, prefix_name: &str, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
@@ -46,7 +86,7 @@ impl InnerStore for CacheStorage {
     }
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()> {
         let compose = compose_key(prefix_name.to_string(), key)?;
-        self.cache.lock().pop(&compose).unwrap();
+        self.cache.lock().pop(&compose);
         Ok(())
     }
 
@@ -75,6 +115,36 @@ impl InnerStore for CacheStorage {
         }
         Ok(all_keys)
     }
+
+    /// Clone the whole cache, entries keep their recently used order.
+    fn snapshot(&self) -> Result<Arc<dyn InnerStore>, Error> {
+        let cache = self.cache.lock();
+        let mut snapshot = LruCache::new(cache.cap());
+        for (key, value) in cache.iter().rev() {
+            snapshot.put(key.to_vec(), value.to_vec());
+        }
+        Ok(Arc::new(CacheStorage {
+            cache: Mutex::new(snapshot),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }))
+    }
+
+    fn multi_get(
+        &self,
+        prefix_name: &str,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        let mut cache = self.cache.lock();
+        keys.into_iter()
+            .map(|key| {
+                let compose = compose_key(prefix_name.to_string(), key)?;
+                let value = cache.get(&compose).map(|v| v.to_vec());
+                self.record_read(&value);
+                Ok(value)
+            })
+            .collect()
+    }
 }
 
 fn compose_key(prefix_name: String, source_key: Vec<u8>) -> Result<Vec<u8>> {
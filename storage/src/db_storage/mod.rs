@@ -4,38 +4,128 @@
 use crate::batch::WriteBatch;
 use crate::storage::{ColumnFamilyName, InnerStore, WriteOp};
 use crate::VEC_PREFIX_NAME;
-use anyhow::{bail, format_err, Error, Result};
+use anyhow::{bail, ensure, format_err, Error, Result};
 use logger::prelude::*;
 use rocksdb::{
-    CFHandle, ColumnFamilyOptions, DBOptions, Writable, WriteBatch as DBWriteBatch, WriteOptions,
-    DB,
+    BlockBasedOptions, CFHandle, Cache, ColumnFamilyOptions, DBOptions, LRUCacheOptions, SeekKey,
+    Writable, WriteBatch as DBWriteBatch, WriteOptions, DB,
 };
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+mod snapshot;
+
+pub use snapshot::DBSnapshot;
 
 pub const DEFAULT_CF_NAME: ColumnFamilyName = "default";
 
 /// Type alias to improve readability.
 pub type ColumnFamilyOptionsMap = HashMap<ColumnFamilyName, ColumnFamilyOptions>;
+/// Column families whose sst files are placed out of the root path, by column family name.
+pub type ColumnFamilyPathMap = HashMap<String, PathBuf>;
+
+/// RocksDB tuning options, the defaults are the RocksDB defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RocksdbConfig {
+    /// Size in bytes of the LRU block cache shared by the column families of a db. `None` keeps
+    /// the RocksDB default, an 8MB cache for every column family.
+    pub block_cache_size: Option<usize>,
+    /// Size in bytes of the memtable of every column family.
+    pub write_buffer_size: u64,
+    /// Max number of open files, -1 keeps all files open.
+    pub max_open_files: i32,
+    /// Paths of the sst files of column families placed out of the root path, such as
+    /// state_node on a separate disk. The path of a column family must not change once the
+    /// column family holds data.
+    pub cf_paths: ColumnFamilyPathMap,
+}
+
+impl Default for RocksdbConfig {
+    fn default() -> Self {
+        Self {
+            block_cache_size: None,
+            write_buffer_size: 64 << 20,
+            max_open_files: -1,
+            cf_paths: ColumnFamilyPathMap::new(),
+        }
+    }
+}
+
+impl RocksdbConfig {
+    fn check_cf_paths(&self) -> Result<()> {
+        for cf_name in self.cf_paths.keys() {
+            ensure!(
+                VEC_PREFIX_NAME.contains(&cf_name.as_str()),
+                "Unknown column family name: {}",
+                cf_name
+            );
+        }
+        Ok(())
+    }
+
+    fn db_options(&self) -> DBOptions {
+        let mut db_opts = DBOptions::new();
+        db_opts.set_max_open_files(self.max_open_files);
+        db_opts
+    }
+
+    /// Options of the column families and the default one, sharing one block cache.
+    fn cf_options_map(&self, cf_names: &[ColumnFamilyName]) -> ColumnFamilyOptionsMap {
+        let block_opts = self.block_cache_size.map(|block_cache_size| {
+            let mut cache_opts = LRUCacheOptions::new();
+            cache_opts.set_capacity(block_cache_size);
+            let mut block_opts = BlockBasedOptions::new();
+            block_opts.set_block_cache(&Cache::new_lru_cache(cache_opts));
+            block_opts
+        });
+        let mut cf_opts_map = ColumnFamilyOptionsMap::new();
+        for cf_name in cf_names.iter().chain(std::iter::once(&DEFAULT_CF_NAME)) {
+            let mut cf_opts = ColumnFamilyOptions::new();
+            cf_opts.set_write_buffer_size(self.write_buffer_size);
+            if let Some(block_opts) = &block_opts {
+                cf_opts.set_block_based_table_factory(block_opts);
+            }
+            if let Some(cf_path) = self.cf_paths.get(*cf_name) {
+                cf_opts.set_cf_paths(&[(cf_path, u64::max_value())]);
+            }
+            cf_opts_map.insert(*cf_name, cf_opts);
+        }
+        cf_opts_map
+    }
+}
 
 pub struct DBStorage {
-    db: DB,
+    db: Arc<DB>,
+    config: RocksdbConfig,
 }
 
 impl DBStorage {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        Self::open(db_root_path, false, None).expect("Unable to open StarcoinDB")
+        Self::new_with_config(db_root_path, RocksdbConfig::default())
     }
+
+    pub fn new_with_config<P: AsRef<Path> + Clone>(db_root_path: P, config: RocksdbConfig) -> Self {
+        Self::open_with_config(db_root_path, false, None, config)
+            .expect("Unable to open StarcoinDB")
+    }
+
     pub fn open<P: AsRef<Path> + Clone>(
         db_root_path: P,
         readonly: bool,
         log_dir: Option<P>,
     ) -> Result<Self> {
-        let mut cf_opts_map = ColumnFamilyOptionsMap::new();
-        for prefix_name in &VEC_PREFIX_NAME.to_vec() {
-            cf_opts_map.insert(prefix_name, ColumnFamilyOptions::default());
-        }
-        cf_opts_map.insert(DEFAULT_CF_NAME, ColumnFamilyOptions::default());
+        Self::open_with_config(db_root_path, readonly, log_dir, RocksdbConfig::default())
+    }
+
+    pub fn open_with_config<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        readonly: bool,
+        log_dir: Option<P>,
+        config: RocksdbConfig,
+    ) -> Result<Self> {
+        config.check_cf_paths()?;
+        let cf_opts_map = config.cf_options_map(VEC_PREFIX_NAME.as_slice());
 
         let path = db_root_path.as_ref().join("starcoindb");
 
@@ -46,34 +136,64 @@ impl DBStorage {
                 bail!("Invalid log directory: {:?}", db_log_dir.as_ref());
             }
             info!("log stored at {:?}", db_log_dir.as_ref());
-            Self::open_readonly(path.clone(), cf_opts_map, db_log_dir.as_ref().to_path_buf())?
+            Self::open_readonly(
+                path.clone(),
+                cf_opts_map,
+                db_log_dir.as_ref().to_path_buf(),
+                &config,
+            )?
         } else {
-            Self::open_inner(path.clone(), cf_opts_map)?
+            Self::open_inner(path.clone(), cf_opts_map, &config)?
         };
 
-        info!("Opened StarcoinDB at {:?}", path);
+        info!("Opened StarcoinDB at {:?} with {:?}", path, config);
 
-        Ok(DBStorage { db })
+        Ok(DBStorage {
+            db: Arc::new(db),
+            config,
+        })
     }
 
-    fn open_inner<P: AsRef<Path>>(path: P, mut cf_opts_map: ColumnFamilyOptionsMap) -> Result<DB> {
-        let mut db_opts = DBOptions::new();
+    /// The RocksDB options the db is opened with.
+    pub fn config(&self) -> &RocksdbConfig {
+        &self.config
+    }
+
+    fn open_inner<P: AsRef<Path>>(
+        path: P,
+        mut cf_opts_map: ColumnFamilyOptionsMap,
+        config: &RocksdbConfig,
+    ) -> Result<DB> {
+        let mut db_opts = config.db_options();
         // For now we set the max total WAL size to be 1G. This config can be useful when column
         // families are updated at non-uniform frequencies.
         db_opts.set_max_total_wal_size(1 << 30);
 
-        // If db exists, just open it with all cfs.
+        // If db exists, open it with the existing cfs, then create the missing ones.
         if Self::db_exists(path.as_ref()) {
-            match DB::open_cf(
-                db_opts,
-                path.as_ref().to_str().ok_or_else(|| {
-                    format_err!("Path {:?} can not be converted to string.", path.as_ref())
-                })?,
-                cf_opts_map.into_iter().collect(),
-            ) {
-                Ok(db) => return Ok(db),
+            let path_str = path.as_ref().to_str().ok_or_else(|| {
+                format_err!("Path {:?} can not be converted to string.", path.as_ref())
+            })?;
+            let missing_cfs = Self::check_cfs(&db_opts, path_str, &cf_opts_map)?;
+            let missing_cf_opts: Vec<_> = missing_cfs
+                .iter()
+                .filter_map(|cf_name| cf_opts_map.remove_entry(cf_name))
+                .collect();
+            let mut db = match DB::open_cf(db_opts, path_str, cf_opts_map.into_iter().collect()) {
+                Ok(db) => db,
                 Err(e) => bail!("open cf err: {:?}", e),
+            };
+            for (cf_name, cf_opts) in missing_cf_opts {
+                info!(
+                    "Create missing column family {} at {:?}",
+                    cf_name,
+                    path.as_ref()
+                );
+                let _cf_handle = db
+                    .create_cf((cf_name, cf_opts))
+                    .map_err(Self::convert_rocksdb_err)?;
             }
+            return Ok(db);
         }
 
         // If db doesn't exist, create a db first with all column families.
@@ -108,12 +228,13 @@ impl DBStorage {
         path: P,
         cf_opts_map: ColumnFamilyOptionsMap,
         db_log_dir: P,
+        config: &RocksdbConfig,
     ) -> Result<DB> {
         if !Self::db_exists(path.as_ref()) {
             bail!("DB doesn't exists.");
         }
 
-        let mut db_opts = DBOptions::new();
+        let mut db_opts = config.db_options();
 
         db_opts.create_if_missing(false);
         db_opts.set_db_log_dir(db_log_dir.as_ref().to_str().ok_or_else(|| {
@@ -123,12 +244,21 @@ impl DBStorage {
             )
         })?);
 
+        let path_str = path.as_ref().to_str().ok_or_else(|| {
+            format_err!("Path {:?} can not be converted to string.", path.as_ref())
+        })?;
+        let missing_cfs = Self::check_cfs(&db_opts, path_str, &cf_opts_map)?;
+        ensure!(
+            missing_cfs.is_empty(),
+            "Column families {:?} not found in readonly db {:?}, open it in read-write mode first.",
+            missing_cfs,
+            path.as_ref()
+        );
+
         Ok(
             match DB::open_cf_for_read_only(
                 db_opts,
-                path.as_ref().to_str().ok_or_else(|| {
-                    format_err!("Path {:?} can not be converted to string.", path.as_ref())
-                })?,
+                path_str,
                 cf_opts_map.into_iter().collect(),
                 true,
             ) {
@@ -139,15 +269,41 @@ impl DBStorage {
     }
 
     pub fn drop_cf(&mut self) -> Result<(), Error> {
+        let db = Arc::get_mut(&mut self.db)
+            .ok_or_else(|| format_err!("Can not drop column families while db snapshots alive."))?;
         for cf in &VEC_PREFIX_NAME.to_vec() {
-            self.db
-                .drop_cf(cf)
-                .map_err(Self::convert_rocksdb_err)
-                .unwrap();
+            db.drop_cf(cf).map_err(Self::convert_rocksdb_err).unwrap();
         }
         Ok(())
     }
 
+    /// Compare the column families of the existing db at `path` with the expected ones, and
+    /// return the missing ones. Unexpected column families are not dropped silently, the db
+    /// should be migrated first.
+    fn check_cfs(
+        db_opts: &DBOptions,
+        path: &str,
+        cf_opts_map: &ColumnFamilyOptionsMap,
+    ) -> Result<Vec<ColumnFamilyName>> {
+        let existing_cfs =
+            DB::list_column_families(db_opts, path).map_err(Self::convert_rocksdb_err)?;
+        let unexpected_cfs: Vec<_> = existing_cfs
+            .iter()
+            .filter(|cf_name| !cf_opts_map.contains_key(cf_name.as_str()))
+            .collect();
+        ensure!(
+            unexpected_cfs.is_empty(),
+            "Unexpected column families {:?} in db {}, the db needs to be migrated.",
+            unexpected_cfs,
+            path
+        );
+        Ok(cf_opts_map
+            .keys()
+            .filter(|cf_name| !existing_cfs.iter().any(|existing| existing == *cf_name))
+            .cloned()
+            .collect())
+    }
+
     fn db_exists(path: &Path) -> bool {
         let rocksdb_current_file = path.join("CURRENT");
         rocksdb_current_file.is_file()
@@ -157,6 +313,18 @@ impl DBStorage {
         format_err!("RocksDB internal error: {}.", msg)
     }
 
+    /// Flush the memtable of the column family to sst files.
+    pub fn flush_cf(&self, cf_name: &str) -> Result<()> {
+        self.db
+            .flush_cf(self.get_cf_handle(cf_name)?, true)
+            .map_err(Self::convert_rocksdb_err)
+    }
+
+    /// Take a read only snapshot of the db.
+    pub fn snapshot(&self) -> DBSnapshot {
+        DBSnapshot::new(self.db.clone())
+    }
+
     fn get_cf_handle(&self, cf_name: &str) -> Result<&CFHandle> {
         self.db.cf_handle(cf_name).ok_or_else(|| {
             format_err!(
@@ -194,10 +362,13 @@ impl InnerStore for DBStorage {
     }
 
     fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool> {
-        match self.get(prefix_name, key) {
-            Ok(Some(_)) => Ok(true),
-            _ => Ok(false),
-        }
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        // the value is not copied out of the db vector.
+        Ok(self
+            .db
+            .get_cf(cf_handle, key.as_slice())
+            .map_err(Self::convert_rocksdb_err)?
+            .is_some())
     }
     fn remove(&self, prefix_name: &str, key: Vec<u8>) -> Result<()> {
         let cf_handle = self.get_cf_handle(prefix_name)?;
@@ -219,11 +390,9 @@ impl InnerStore for DBStorage {
                 .map_err(Self::convert_rocksdb_err)?;
             }
         }
-
         self.db
             .write_opt(&db_batch, &Self::default_write_options())
-            .map_err(Self::convert_rocksdb_err)?;
-        Ok(())
+            .map_err(Self::convert_rocksdb_err)
     }
 
     fn get_len(&self) -> Result<u64, Error> {
@@ -231,6 +400,18 @@ impl InnerStore for DBStorage {
     }
 
     fn keys(&self) -> Result<Vec<Vec<u8>>, Error> {
-        unimplemented!()
+        bail!("DB storage not support keys method, use cf_keys instead!")
+    }
+
+    fn cf_keys(&self, prefix_name: &str) -> Result<Vec<Vec<u8>>> {
+        let cf_handle = self.get_cf_handle(prefix_name)?;
+        let mut iter = self.db.iter_cf(cf_handle);
+        iter.seek(SeekKey::Start)
+            .map_err(Self::convert_rocksdb_err)?;
+        Ok(iter.map(|(key, _value)| key).collect())
+    }
+
+    fn snapshot(&self) -> Result<Arc<dyn InnerStore>, Error> {
+        Ok(Arc::new(DBStorage::snapshot(self)))
     }
 }
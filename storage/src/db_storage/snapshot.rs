@@ -0,0 +1,71 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::batch::WriteBatch;
+use crate::db_storage::DBStorage;
+use crate::storage::InnerStore;
+use anyhow::{bail, format_err, Result};
+use rocksdb::{Snapshot, DB};
+use std::sync::Arc;
+
+/// A read only view of `DBStorage` at the time the snapshot is taken.
+pub struct DBSnapshot {
+    db: Arc<DB>,
+    snapshot: Snapshot<Arc<DB>>,
+}
+
+impl DBSnapshot {
+    pub(crate) fn new(db: Arc<DB>) -> Self {
+        Self {
+            db: db.clone(),
+            snapshot: Snapshot::new(db),
+        }
+    }
+}
+
+impl InnerStore for DBSnapshot {
+    fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
+        let cf_handle = self.db.cf_handle(prefix_name).ok_or_else(|| {
+            format_err!(
+                "DB::cf_handle not found for column family name: {}",
+                prefix_name
+            )
+        })?;
+        Ok(self
+            .snapshot
+            .get_cf(cf_handle, key.as_slice())
+            .map_err(DBStorage::convert_rocksdb_err)?
+            .map(|value| value.to_vec()))
+    }
+
+    fn put(&self, prefix_name: &str, _key: Vec<u8>, _value: Vec<u8>) -> Result<()> {
+        bail!("DB snapshot is read only, put to {} rejected.", prefix_name)
+    }
+
+    fn contains_key(&self, prefix_name: &str, key: Vec<u8>) -> Result<bool> {
+        Ok(self.get(prefix_name, key)?.is_some())
+    }
+
+    fn remove(&self, prefix_name: &str, _key: Vec<u8>) -> Result<()> {
+        bail!(
+            "DB snapshot is read only, remove from {} rejected.",
+            prefix_name
+        )
+    }
+
+    fn write_batch(&self, _batch: WriteBatch) -> Result<()> {
+        bail!("DB snapshot is read only, write batch rejected.")
+    }
+
+    fn get_len(&self) -> Result<u64> {
+        bail!("DB snapshot not support get length method!")
+    }
+
+    fn keys(&self) -> Result<Vec<Vec<u8>>> {
+        bail!("DB snapshot not support keys method!")
+    }
+
+    fn snapshot(&self) -> Result<Arc<dyn InnerStore>> {
+        bail!("DB snapshot is already a snapshot!")
+    }
+}
@@ -2,23 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::accumulator::AccumulatorStorage;
+use crate::batch::WriteBatch;
 use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
+use crate::snapshot::StorageSnapshot;
 use crate::state_node::StateStorage;
 use crate::storage::{ColumnFamilyName, InnerStorage, KVStore, StorageInstance};
 use crate::transaction::TransactionStorage;
 use crate::transaction_info::TransactionInfoStorage;
 use anyhow::{ensure, Error, Result};
-use crypto::HashValue;
+use crypto::{hash::CryptoHash, HashValue};
 use once_cell::sync::Lazy;
+use starcoin_accumulator::node::ACCUMULATOR_PLACEHOLDER_HASH;
 use starcoin_accumulator::{
-    AccumulatorNode, AccumulatorReader, AccumulatorTreeStore, AccumulatorWriter,
+    Accumulator, AccumulatorNode, AccumulatorReader, AccumulatorTreeStore, AccumulatorWriter,
+    MerkleAccumulator,
 };
 use starcoin_types::transaction::Transaction;
 use starcoin_types::{
-    block::{Block, BlockBody, BlockHeader, BlockInfo},
+    block::{Block, BlockBody, BlockHeader, BlockInfo, BlockNumber},
     startup_info::StartupInfo,
     transaction::TransactionInfo,
+    U512,
 };
 use state_tree::{StateNode, StateNodeStore};
 use std::collections::BTreeMap;
@@ -31,6 +36,7 @@ pub mod block;
 pub mod block_info;
 pub mod cache_storage;
 pub mod db_storage;
+pub mod snapshot;
 pub mod state_node;
 pub mod storage;
 #[cfg(test)]
@@ -76,6 +82,10 @@ pub trait BlockStore {
     fn get_startup_info(&self) -> Result<Option<StartupInfo>>;
     fn save_startup_info(&self, startup_info: StartupInfo) -> Result<()>;
 
+    /// Startup info of a branch head, kept besides the canonical one of `get_startup_info`.
+    fn get_branch_startup_info(&self, branch_id: HashValue) -> Result<Option<StartupInfo>>;
+    fn save_branch_startup_info(&self, branch_id: HashValue, info: StartupInfo) -> Result<()>;
+
     fn get_headers(&self) -> Result<Vec<HashValue>>;
 
     fn save_branch_number(
@@ -99,7 +109,9 @@ pub trait BlockStore {
 
     fn get_latest_block_header(&self) -> Result<Option<BlockHeader>>;
 
-    fn get_latest_block(&self) -> Result<Block>;
+    fn get_latest_block(&self) -> Result<Option<Block>>;
+
+    fn is_block_known(&self, block_id: HashValue) -> Result<bool>;
 
     fn get_block_header_by_hash(&self, block_id: HashValue) -> Result<Option<BlockHeader>>;
 
@@ -126,12 +138,33 @@ pub trait BlockStore {
         block_id1: HashValue,
         block_id2: HashValue,
     ) -> Result<Option<HashValue>>;
+    /// Like `get_common_ancestor`, but return `None` after walking back `max_depth` blocks.
+    fn get_common_ancestor_bounded(
+        &self,
+        block_id1: HashValue,
+        block_id2: HashValue,
+        max_depth: u64,
+    ) -> Result<Option<HashValue>>;
     fn get_block_transactions(&self, block_id: HashValue) -> Result<Vec<HashValue>>;
     fn save_block_transactions(
         &self,
         block_id: HashValue,
         transactions: Vec<HashValue>,
     ) -> Result<()>;
+
+    /// Delete the blocks of the orphaned branch `branch_id`, from `branch_head` back to
+    /// `stop_at`(excluded). The master head is read from the saved startup info, so flush the
+    /// pending startup info before pruning. Return the count of deleted blocks.
+    fn prune_branch(
+        &self,
+        branch_id: HashValue,
+        branch_head: HashValue,
+        stop_at: HashValue,
+    ) -> Result<u64>;
+
+    /// The total difficulty of the chain ending at `block_id`, without loading the block.
+    /// A header only has the difficulty of its own block, so the total is read from the block info.
+    fn get_total_difficulty(&self, block_id: HashValue) -> Result<Option<U512>>;
 }
 
 pub trait TransactionInfoStore {
@@ -142,6 +175,8 @@ pub trait TransactionInfoStore {
 
 pub trait TransactionStore {
     fn get_transaction(&self, txn_hash: HashValue) -> Result<Option<Transaction>>;
+    /// Get transactions in the order of `txn_hashes`, `None` for a transaction not found.
+    fn get_transactions(&self, txn_hashes: Vec<HashValue>) -> Result<Vec<Option<Transaction>>>;
     fn save_transaction(&self, txn_info: Transaction) -> Result<()>;
     fn save_transaction_batch(&self, txn_vec: Vec<Transaction>) -> Result<()>;
 }
@@ -151,26 +186,131 @@ pub struct Storage {
     transaction_storage: TransactionStorage,
     block_storage: BlockStorage,
     state_node_storage: StateStorage,
-    accumulator_storage: AccumulatorStorage,
+    accumulator_storage: Arc<AccumulatorStorage>,
     block_info_storage: BlockInfoStorage,
     startup_info_storage: Arc<dyn KVStore>,
+    instance: StorageInstance,
 }
 
 impl Storage {
     pub fn new(instance: StorageInstance) -> Result<Self> {
+        let block_storage = BlockStorage::new(instance.clone());
+        Self::new_with_block_storage(instance, block_storage)
+    }
+
+    fn new_with_block_storage(
+        instance: StorageInstance,
+        block_storage: BlockStorage,
+    ) -> Result<Self> {
         Ok(Self {
             transaction_info_storage: TransactionInfoStorage::new(instance.clone()),
             transaction_storage: TransactionStorage::new(instance.clone()),
-            block_storage: BlockStorage::new(instance.clone()),
+            block_storage,
             state_node_storage: StateStorage::new(instance.clone()),
-            accumulator_storage: AccumulatorStorage::new(instance.clone()),
+            accumulator_storage: Arc::new(AccumulatorStorage::new(instance.clone())),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
             startup_info_storage: Arc::new(InnerStorage::new(
                 instance.clone(),
                 STARTUP_INFO_PREFIX_NAME,
             )),
+            instance,
         })
     }
+
+    /// Take a snapshot for reads that need a consistent view across column families,
+    /// writes committed after the snapshot are not visible to it.
+    /// The snapshot of a cache and db instance reads the db only, and its block storage has
+    /// no known block filter, the headers are not listed for every snapshot.
+    pub fn snapshot(&self) -> Result<StorageSnapshot> {
+        let instance = self.instance.snapshot()?;
+        let block_storage = BlockStorage::new_without_known_blocks(instance.clone());
+        Ok(StorageSnapshot::new(Storage::new_with_block_storage(
+            instance,
+            block_storage,
+        )?))
+    }
+
+    /// Remove transactions, transaction infos and block transaction index of master blocks
+    /// below `height`, headers and block infos are kept for chain integrity.
+    /// Return the number of pruned transactions.
+    pub fn prune_transactions_below(&self, height: BlockNumber) -> Result<u64> {
+        ensure!(
+            self.block_storage.get_number(height)?.is_some(),
+            "can not prune transactions above current head, height: {}",
+            height
+        );
+        let mut batch = WriteBatch::new();
+        let mut pruned = 0;
+        for number in 0..height {
+            let block_id = match self.block_storage.get_number(number)? {
+                Some(block_id) => block_id,
+                None => continue,
+            };
+            let txn_hashes = match self.block_storage.get_transactions(block_id) {
+                Ok(txn_hashes) => txn_hashes,
+                Err(_) => continue,
+            };
+            for txn_hash in txn_hashes {
+                if self.transaction_storage.get(txn_hash)?.is_some() {
+                    batch.delete(TRANSACTION_PREFIX_NAME, txn_hash)?;
+                    pruned += 1;
+                }
+                if self.transaction_info_storage.get(txn_hash)?.is_some() {
+                    batch.delete(TRANSACTION_INFO_PREFIX_NAME, txn_hash)?;
+                }
+            }
+            batch.delete(BLOCK_TRANSATIONS_PREFIX_NAME, block_id)?;
+        }
+        self.transaction_storage.write_batch(batch)?;
+        Ok(pruned)
+    }
+
+    /// Commit the block and its transaction infos, append the transaction info hashes to the
+    /// transaction accumulator of the parent block and save the new block info.
+    /// A block without parent block info starts from an empty accumulator.
+    pub fn commit_block_with_txn_infos(
+        &self,
+        block: Block,
+        txn_infos: Vec<TransactionInfo>,
+    ) -> Result<BlockInfo> {
+        let header = block.header().clone();
+        let (accumulator_root, frozen_subtree_roots, num_leaves, num_nodes, total_difficulty) =
+            match self.block_info_storage.get(header.parent_hash())? {
+                Some(parent_info) => (
+                    parent_info.accumulator_root,
+                    parent_info.frozen_subtree_roots,
+                    parent_info.num_leaves,
+                    parent_info.num_nodes,
+                    parent_info.total_difficulty,
+                ),
+                None => (*ACCUMULATOR_PLACEHOLDER_HASH, vec![], 0, 0, U512::zero()),
+            };
+        let accumulator = MerkleAccumulator::new(
+            header.id(),
+            accumulator_root,
+            frozen_subtree_roots,
+            num_leaves,
+            num_nodes,
+            self.accumulator_storage.clone(),
+        )?;
+        let leaves: Vec<HashValue> = txn_infos.iter().map(|info| info.crypto_hash()).collect();
+        let (accumulator_root, _) = accumulator.append(&leaves)?;
+        let block_info = BlockInfo::new(
+            header.id(),
+            accumulator_root,
+            accumulator.get_frozen_subtree_roots()?,
+            accumulator.num_leaves(),
+            accumulator.num_nodes(),
+            total_difficulty + header.difficult().into(),
+        );
+
+        self.transaction_info_storage
+            .save_transaction_infos(txn_infos)?;
+        self.block_storage.commit_block(block)?;
+        self.block_info_storage
+            .put(block_info.block_id, block_info.clone())?;
+        Ok(block_info)
+    }
 }
 
 impl StateNodeStore for Storage {
@@ -185,6 +325,10 @@ impl StateNodeStore for Storage {
     fn write_nodes(&self, nodes: BTreeMap<HashValue, StateNode>) -> Result<(), Error> {
         self.state_node_storage.write_nodes(nodes)
     }
+
+    fn contains(&self, hash: &HashValue) -> Result<bool> {
+        self.state_node_storage.contains(hash)
+    }
 }
 
 impl BlockStore for Storage {
@@ -204,6 +348,20 @@ impl BlockStore for Storage {
         )
     }
 
+    fn get_branch_startup_info(&self, branch_id: HashValue) -> Result<Option<StartupInfo>> {
+        self.startup_info_storage
+            .get(&branch_id.to_vec())
+            .and_then(|bytes| match bytes {
+                Some(bytes) => Ok(Some(bytes.try_into()?)),
+                None => Ok(None),
+            })
+    }
+
+    fn save_branch_startup_info(&self, branch_id: HashValue, info: StartupInfo) -> Result<()> {
+        self.startup_info_storage
+            .put(branch_id.to_vec(), info.try_into()?)
+    }
+
     fn get_headers(&self) -> Result<Vec<HashValue>> {
         self.block_storage.get_headers()
     }
@@ -250,10 +408,14 @@ impl BlockStore for Storage {
         self.block_storage.get_latest_block_header()
     }
 
-    fn get_latest_block(&self) -> Result<Block> {
+    fn get_latest_block(&self) -> Result<Option<Block>> {
         self.block_storage.get_latest_block()
     }
 
+    fn is_block_known(&self, block_id: HashValue) -> Result<bool> {
+        self.block_storage.is_block_known(block_id)
+    }
+
     fn get_block_header_by_hash(&self, block_id: HashValue) -> Result<Option<BlockHeader>> {
         self.block_storage.get_block_header_by_hash(block_id)
     }
@@ -296,6 +458,16 @@ impl BlockStore for Storage {
         self.block_storage.get_common_ancestor(block_id1, block_id2)
     }
 
+    fn get_common_ancestor_bounded(
+        &self,
+        block_id1: HashValue,
+        block_id2: HashValue,
+        max_depth: u64,
+    ) -> Result<Option<HashValue>> {
+        self.block_storage
+            .get_common_ancestor_bounded(block_id1, block_id2, max_depth)
+    }
+
     fn get_block_transactions(&self, block_id: HashValue) -> Result<Vec<HashValue>, Error> {
         self.block_storage.get_transactions(block_id)
     }
@@ -306,6 +478,27 @@ impl BlockStore for Storage {
     ) -> Result<()> {
         self.block_storage.put_transactions(block_id, transactions)
     }
+
+    fn prune_branch(
+        &self,
+        branch_id: HashValue,
+        branch_head: HashValue,
+        stop_at: HashValue,
+    ) -> Result<u64> {
+        let latest_block_id = match self.get_startup_info()? {
+            Some(startup_info) => Some(startup_info.master.get_head()),
+            None => self.get_latest_block_header()?.map(|header| header.id()),
+        };
+        self.block_storage
+            .prune_branch(branch_id, branch_head, stop_at, latest_block_id)
+    }
+
+    fn get_total_difficulty(&self, block_id: HashValue) -> Result<Option<U512>> {
+        Ok(self
+            .block_info_storage
+            .get(block_id)?
+            .map(|block_info| block_info.get_total_difficult()))
+    }
 }
 
 impl AccumulatorTreeStore for Storage {}
@@ -315,8 +508,8 @@ impl AccumulatorReader for Storage {
         self.accumulator_storage.get_node(hash)
     }
 
-    fn multiple_get(&self, _hash_vec: Vec<HashValue>) -> Result<Vec<AccumulatorNode>, Error> {
-        unimplemented!()
+    fn multiple_get(&self, hash_vec: Vec<HashValue>) -> Result<Vec<AccumulatorNode>, Error> {
+        self.accumulator_storage.multiple_get(hash_vec)
     }
 }
 
@@ -362,6 +555,10 @@ impl TransactionStore for Storage {
         self.transaction_storage.get(txn_hash)
     }
 
+    fn get_transactions(&self, txn_hashes: Vec<HashValue>) -> Result<Vec<Option<Transaction>>> {
+        self.transaction_storage.get_transactions(txn_hashes)
+    }
+
     fn save_transaction(&self, txn: Transaction) -> Result<(), Error> {
         self.transaction_storage.put(txn.id(), txn)
     }
@@ -433,3 +630,14 @@ fn ensure_slice_len_eq(data: &[u8], len: usize) -> Result<()> {
     );
     Ok(())
 }
+
+///ensure slice length is a multiple of the item length
+fn ensure_slice_len_multiple(data: &[u8], item_len: usize) -> Result<()> {
+    ensure!(
+        data.len() % item_len == 0,
+        "Unexpected data len {}, expected a multiple of {}.",
+        data.len(),
+        item_len,
+    );
+    Ok(())
+}
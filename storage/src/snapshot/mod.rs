@@ -0,0 +1,68 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block_info::BlockInfoStore;
+use crate::{BlockStore, Storage, TransactionInfoStore, TransactionStore};
+use anyhow::Result;
+use crypto::HashValue;
+use starcoin_types::{
+    block::{Block, BlockBody, BlockHeader, BlockInfo},
+    startup_info::StartupInfo,
+    transaction::{Transaction, TransactionInfo},
+};
+
+/// Read only view of `Storage` at a fixed point, taken by `Storage::snapshot`.
+/// It only supports point reads, listing keys or counting entries is not supported.
+pub struct StorageSnapshot {
+    storage: Storage,
+}
+
+impl StorageSnapshot {
+    pub(crate) fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    pub fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        self.storage.get_startup_info()
+    }
+
+    pub fn is_block_known(&self, block_id: HashValue) -> Result<bool> {
+        self.storage.block_storage.is_block_known(block_id)
+    }
+
+    pub fn get_block_header_by_hash(&self, block_id: HashValue) -> Result<Option<BlockHeader>> {
+        self.storage.get_block_header_by_hash(block_id)
+    }
+
+    pub fn get_block_by_hash(&self, block_id: HashValue) -> Result<Option<Block>> {
+        self.storage.get_block_by_hash(block_id)
+    }
+
+    pub fn get_body(&self, block_id: HashValue) -> Result<Option<BlockBody>> {
+        self.storage.get_body(block_id)
+    }
+
+    pub fn get_number(&self, number: u64) -> Result<Option<HashValue>> {
+        self.storage.get_number(number)
+    }
+
+    pub fn get_block_header_by_number(&self, number: u64) -> Result<Option<BlockHeader>> {
+        self.storage.get_block_header_by_number(number)
+    }
+
+    pub fn get_block_transactions(&self, block_id: HashValue) -> Result<Vec<HashValue>> {
+        self.storage.get_block_transactions(block_id)
+    }
+
+    pub fn get_block_info(&self, block_id: HashValue) -> Result<Option<BlockInfo>> {
+        self.storage.get_block_info(block_id)
+    }
+
+    pub fn get_transaction(&self, txn_hash: HashValue) -> Result<Option<Transaction>> {
+        self.storage.get_transaction(txn_hash)
+    }
+
+    pub fn get_transaction_info(&self, txn_hash: HashValue) -> Result<Option<TransactionInfo>> {
+        self.storage.get_transaction_info(txn_hash)
+    }
+}
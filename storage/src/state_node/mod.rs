@@ -43,4 +43,8 @@ impl StateNodeStore for StateStorage {
         }
         self.store.write_batch(batch)
     }
+
+    fn contains(&self, hash: &HashValue) -> Result<bool> {
+        self.store.contains_key(*hash)
+    }
 }
@@ -27,6 +27,9 @@ pub trait KVStore: Send + Sync {
     fn write_batch(&self, batch: WriteBatch) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    /// Keys of the column family of the store.
+    fn cf_keys(&self) -> Result<Vec<Vec<u8>>>;
+    fn multi_get(&self, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>>;
 }
 
 pub trait InnerStore: Send + Sync {
@@ -37,6 +40,18 @@ pub trait InnerStore: Send + Sync {
     fn write_batch(&self, batch: WriteBatch) -> Result<()>;
     fn get_len(&self) -> Result<u64>;
     fn keys(&self) -> Result<Vec<Vec<u8>>>;
+    /// Keys of the column family, only a store holding every entry supports it.
+    fn cf_keys(&self, prefix_name: &str) -> Result<Vec<Vec<u8>>> {
+        bail!("Keys of column family {} not supported!", prefix_name)
+    }
+    /// Take a read only snapshot, reads from it don't observe later writes.
+    fn snapshot(&self) -> Result<Arc<dyn InnerStore>>;
+    /// Get the values of keys in one call, the result is in the order of keys.
+    fn multi_get(&self, prefix_name: &str, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>> {
+        keys.into_iter()
+            .map(|key| self.get(prefix_name, key))
+            .collect()
+    }
 }
 
 ///Storage instance type define
@@ -65,6 +80,20 @@ impl StorageInstance {
     pub fn new_cache_and_db_instance(cache: Arc<CacheStorage>, db: Arc<DBStorage>) -> Self {
         Self::CacheAndDb { cache, db }
     }
+
+    /// Take a snapshot of the instance for consistent reads across column families.
+    pub fn snapshot(&self) -> Result<Self> {
+        match self {
+            StorageInstance::CACHE { cache } => Ok(StorageInstance::CACHE {
+                cache: cache.snapshot()?,
+            }),
+            StorageInstance::DB { db } => Ok(StorageInstance::DB { db: db.snapshot()? }),
+            // every write goes to db first, so the db snapshot is enough.
+            StorageInstance::CacheAndDb { cache: _, db } => {
+                Ok(StorageInstance::DB { db: db.snapshot()? })
+            }
+        }
+    }
 }
 impl InnerStore for StorageInstance {
     fn get(&self, prefix_name: &str, key: Vec<u8>) -> Result<Option<Vec<u8>>> {
@@ -104,9 +133,10 @@ impl InnerStore for StorageInstance {
             StorageInstance::CACHE { cache } => cache.contains_key(prefix_name, key),
             StorageInstance::DB { db } => db.contains_key(prefix_name, key),
             StorageInstance::CacheAndDb { cache, db } => {
+                // the cache only keeps recent entries, a miss falls back to the db.
                 match cache.contains_key(prefix_name, key.clone()) {
-                    Err(_) => db.contains_key(prefix_name, key.clone()),
-                    Ok(is_contains) => Ok(is_contains),
+                    Ok(true) => Ok(true),
+                    _ => db.contains_key(prefix_name, key),
                 }
             }
         }
@@ -150,6 +180,50 @@ impl InnerStore for StorageInstance {
             _ => bail!("DB instance not support keys method!"),
         }
     }
+
+    fn cf_keys(&self, prefix_name: &str) -> Result<Vec<Vec<u8>>> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.cf_keys(prefix_name),
+            StorageInstance::DB { db } => db.cf_keys(prefix_name),
+            // every write goes to db, the cache only keeps recent entries.
+            StorageInstance::CacheAndDb { cache: _, db } => db.cf_keys(prefix_name),
+        }
+    }
+
+    fn snapshot(&self) -> Result<Arc<dyn InnerStore>> {
+        Ok(Arc::new(StorageInstance::snapshot(self)?))
+    }
+
+    fn multi_get(&self, prefix_name: &str, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.multi_get(prefix_name, keys),
+            StorageInstance::DB { db } => db.multi_get(prefix_name, keys),
+            StorageInstance::CacheAndDb { cache, db } => {
+                let mut values = cache.multi_get(prefix_name, keys.clone())?;
+                // only read the cache misses from db.
+                let missed: Vec<usize> = values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(i, _)| i)
+                    .collect();
+                if missed.is_empty() {
+                    return Ok(values);
+                }
+                let db_values = db.multi_get(
+                    prefix_name,
+                    missed.iter().map(|i| keys[*i].clone()).collect(),
+                )?;
+                for (i, value) in missed.into_iter().zip(db_values) {
+                    if let Some(value) = value {
+                        cache.put(prefix_name, keys[i].clone(), value.clone())?;
+                        values[i] = Some(value);
+                    }
+                }
+                Ok(values)
+            }
+        }
+    }
 }
 
 /// Define inner storage implement
@@ -195,6 +269,14 @@ impl KVStore for InnerStorage {
     fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.instance.keys()
     }
+
+    fn cf_keys(&self) -> Result<Vec<Vec<u8>>> {
+        self.instance.cf_keys(self.prefix_name)
+    }
+
+    fn multi_get(&self, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>> {
+        self.instance.multi_get(self.prefix_name, keys)
+    }
 }
 
 pub trait KeyCodec: Sized + PartialEq + Debug {
@@ -240,6 +322,21 @@ where
             None => Ok(None),
         }
     }
+    /// Get the values of keys, the result is in the order of keys.
+    pub fn multi_get(&self, keys: Vec<K>) -> Result<Vec<Option<V>>> {
+        let keys = keys
+            .iter()
+            .map(|key| key.encode_key())
+            .collect::<Result<Vec<_>>>()?;
+        self.store
+            .multi_get(keys)?
+            .into_iter()
+            .map(|value| match value {
+                Some(v) => Ok(Some(V::decode_value(v.as_slice())?)),
+                None => Ok(None),
+            })
+            .collect()
+    }
     pub fn put(&self, key: K, value: V) -> Result<()> {
         self.store.put(key.encode_key()?, value.encode_value()?)
     }
@@ -260,6 +357,13 @@ where
     pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
         self.store.keys()
     }
+    pub fn cf_keys(&self) -> Result<Vec<K>> {
+        self.store
+            .cf_keys()?
+            .iter()
+            .map(|key| K::decode_key(key.as_slice()))
+            .collect()
+    }
 }
 
 impl KeyCodec for HashValue {
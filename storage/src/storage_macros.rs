@@ -28,6 +28,14 @@ macro_rules! define_storage {
                 self.store.get(key)
             }
             #[allow(dead_code)]
+            pub fn multi_get(&self, keys: Vec<$key_type>) -> Result<Vec<Option<$value_type>>> {
+                self.store.multi_get(keys)
+            }
+            #[allow(dead_code)]
+            pub fn contains_key(&self, key: $key_type) -> Result<bool> {
+                self.store.contains_key(key)
+            }
+            #[allow(dead_code)]
             pub fn remove(&self, key: $key_type) -> Result<()> {
                 self.store.remove(key)
             }
@@ -43,6 +51,10 @@ macro_rules! define_storage {
             pub fn keys(&self) -> Result<Vec<Vec<u8>>> {
                 self.store.keys()
             }
+            #[allow(dead_code)]
+            pub fn cf_keys(&self) -> Result<Vec<$key_type>> {
+                self.store.cf_keys()
+            }
         }
     };
 }
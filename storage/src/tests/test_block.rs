@@ -3,18 +3,22 @@
 
 extern crate chrono;
 
+use anyhow::bail;
 use chrono::prelude::*;
 use crypto::HashValue;
 
+use crate::block::BlockStorage;
+use crate::block_info::BlockInfoStore;
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
-use crate::storage::StorageInstance;
-use crate::Storage;
+use crate::storage::{KeyCodec, StorageInstance, ValueCodec};
+use crate::{BlockStore, Storage};
 use logger::prelude::*;
 use starcoin_types::account_address::AccountAddress;
-use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockInfo, BlockNumber};
+use starcoin_types::startup_info::{ChainInfo, StartupInfo};
 use starcoin_types::transaction::SignedUserTransaction;
-use starcoin_types::U256;
+use starcoin_types::{U256, U512};
 use std::sync::Arc;
 
 #[test]
@@ -332,3 +336,567 @@ fn test_block_branch_hashes() {
         .unwrap();
     assert_eq!(comm_hash, parent_hash);
 }
+
+#[test]
+fn test_latest_block_on_empty_store() {
+    let cache_storage = Arc::new(CacheStorage::new());
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage,
+        db_storage,
+    ))
+    .unwrap();
+    assert!(storage.block_storage.get_latest_block().unwrap().is_none());
+    assert!(storage
+        .block_storage
+        .get_latest_block_header()
+        .unwrap()
+        .is_none());
+    // and through the BlockStore trait.
+    assert!(storage.get_latest_block().unwrap().is_none());
+    assert!(storage.get_latest_block_header().unwrap().is_none());
+}
+
+#[test]
+fn test_block_known_filter() {
+    let cache_storage = Arc::new(CacheStorage::new());
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let instance = StorageInstance::new_cache_and_db_instance(cache_storage, db_storage);
+    let block_storage = BlockStorage::new(instance.clone());
+    // created before the headers are saved, so its filter knows nothing.
+    let stale_storage = BlockStorage::new(instance.clone());
+
+    let mut parent_hash = HashValue::zero();
+    let mut block_ids = vec![];
+    for number in 0..100 {
+        let header = BlockHeader::new(
+            parent_hash,
+            number,
+            number,
+            AccountAddress::random(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            U256::zero(),
+            vec![],
+        );
+        parent_hash = header.id();
+        block_ids.push(header.id());
+        block_storage.save_header(header).unwrap();
+    }
+    // no false negative
+    for block_id in &block_ids {
+        assert!(block_storage.is_block_known(*block_id).unwrap());
+    }
+    for _ in 0..100 {
+        assert!(!block_storage.is_block_known(HashValue::random()).unwrap());
+    }
+    // the header is in storage, but the filter answers "definitely not" without reading it.
+    assert!(!stale_storage.is_block_known(block_ids[0]).unwrap());
+
+    // the filter is rebuilt from the header store on startup.
+    let reopened_storage = BlockStorage::new(instance);
+    for block_id in &block_ids {
+        assert!(reopened_storage.is_block_known(*block_id).unwrap());
+    }
+}
+
+#[test]
+fn test_block_known_after_reopen() {
+    let tmpdir = libra_temppath::TempPath::new();
+    let mut block_ids = vec![];
+    {
+        let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+        let instance =
+            StorageInstance::new_cache_and_db_instance(Arc::new(CacheStorage::new()), db_storage);
+        let block_storage = BlockStorage::new(instance);
+        let mut parent_hash = HashValue::zero();
+        for number in 0..10 {
+            let block = new_block(parent_hash, number);
+            parent_hash = block.header().id();
+            block_ids.push(block.header().id());
+            block_storage.commit_block(block).unwrap();
+        }
+    }
+
+    // the old blocks are known after a restart, with or without a cache.
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let block_storage = BlockStorage::new(StorageInstance::new_db_instance(db_storage.clone()));
+    for block_id in &block_ids {
+        assert!(block_storage.is_block_known(*block_id).unwrap());
+    }
+    // the cache is empty after a restart, or the headers are evicted from it.
+    let block_storage = BlockStorage::new(StorageInstance::new_cache_and_db_instance(
+        Arc::new(CacheStorage::new_with_capacity(1)),
+        db_storage,
+    ));
+    for block_id in &block_ids {
+        assert!(block_storage.is_block_known(*block_id).unwrap());
+    }
+    assert!(!block_storage.is_block_known(HashValue::random()).unwrap());
+
+    // a cache only storage can not list its headers, it reads the header store.
+    let block_storage = BlockStorage::new(StorageInstance::new_cache_instance(CacheStorage::new()));
+    let block = new_block(HashValue::zero(), 0);
+    block_storage.commit_block(block.clone()).unwrap();
+    assert!(block_storage.is_block_known(block.header().id()).unwrap());
+    assert!(!block_storage.is_block_known(HashValue::random()).unwrap());
+}
+
+#[test]
+fn test_block_number_key_order() {
+    // range scans over the number index rely on byte order matching numeric order.
+    let numbers: Vec<BlockNumber> = vec![
+        0,
+        1,
+        255,
+        256,
+        65535,
+        65536,
+        u32::max_value() as u64,
+        u32::max_value() as u64 + 1,
+        u64::max_value() - 1,
+        u64::max_value(),
+    ];
+    let keys: Vec<Vec<u8>> = numbers
+        .iter()
+        .map(|number| number.encode_key().unwrap())
+        .collect();
+    for (number, key) in numbers.iter().zip(keys.iter()) {
+        assert_eq!(BlockNumber::decode_key(key).unwrap(), *number);
+    }
+    for (numbers, keys) in numbers.windows(2).zip(keys.windows(2)) {
+        assert!(numbers[0] < numbers[1]);
+        assert!(keys[0] < keys[1], "{} {}", numbers[0], numbers[1]);
+    }
+}
+
+#[test]
+fn test_hash_vec_codec() {
+    let hashes: Vec<HashValue> = (0..3).map(|_| HashValue::random()).collect();
+    let encoded = hashes.encode_value().unwrap();
+    assert_eq!(encoded.len(), 3 * HashValue::LENGTH);
+    assert_eq!(Vec::<HashValue>::decode_value(&encoded).unwrap(), hashes);
+    assert!(Vec::<HashValue>::decode_value(&[]).unwrap().is_empty());
+
+    // truncated or trailing bytes are rejected.
+    assert!(Vec::<HashValue>::decode_value(&[0u8; 33]).is_err());
+    assert!(Vec::<HashValue>::decode_value(&[0u8; 31]).is_err());
+    assert!(Vec::<HashValue>::decode_value(&encoded[..encoded.len() - 1]).is_err());
+}
+
+fn new_block(parent_hash: HashValue, number: BlockNumber) -> Block {
+    let header = BlockHeader::new(
+        parent_hash,
+        Local::now().timestamp_nanos() as u64,
+        number,
+        AccountAddress::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        0,
+        U256::zero(),
+        vec![],
+    );
+    Block::new(header, BlockBody::new(vec![]))
+}
+
+#[test]
+fn test_prune_branch() {
+    let cache_storage = Arc::new(CacheStorage::new());
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage,
+        db_storage,
+    ))
+    .unwrap();
+
+    let genesis = new_block(HashValue::zero(), 0);
+    storage.block_storage.commit_block(genesis.clone()).unwrap();
+    // master: genesis -> a1 -> a2, branch: genesis -> b1 -> b2
+    let mut master = vec![];
+    let mut branch = vec![];
+    let mut master_parent = genesis.header().id();
+    let mut branch_parent = genesis.header().id();
+    let branch_id = HashValue::random();
+    for number in 1..3 {
+        let block = new_block(master_parent, number);
+        master_parent = block.header().id();
+        storage.block_storage.commit_block(block.clone()).unwrap();
+        master.push(block);
+
+        let block = new_block(branch_parent, number);
+        branch_parent = block.header().id();
+        storage
+            .block_storage
+            .commit_branch_block(branch_id, block.clone())
+            .unwrap();
+        branch.push(block);
+    }
+    for block in &branch {
+        assert_eq!(
+            storage
+                .block_storage
+                .get_branch_number(branch_id, block.header().number())
+                .unwrap(),
+            Some(block.header().id())
+        );
+    }
+
+    let master_head = master[1].header();
+    storage
+        .save_startup_info(StartupInfo::new(
+            ChainInfo::new(None, master_head.id(), master_head),
+            vec![],
+        ))
+        .unwrap();
+
+    // the master chain can not be pruned.
+    assert!(storage
+        .prune_branch(branch_id, master[1].header().id(), genesis.header().id())
+        .is_err());
+    assert!(storage
+        .prune_branch(branch_id, master[0].header().id(), genesis.header().id())
+        .is_err());
+
+    let pruned = storage
+        .prune_branch(branch_id, branch[1].header().id(), genesis.header().id())
+        .unwrap();
+    assert_eq!(pruned, 2);
+    for block in &branch {
+        let block_id = block.header().id();
+        assert!(storage.block_storage.get(block_id).unwrap().is_none());
+        assert!(storage
+            .block_storage
+            .get_block_header_by_hash(block_id)
+            .unwrap()
+            .is_none());
+        assert!(storage.block_storage.get_body(block_id).unwrap().is_none());
+        assert!(storage.block_storage.get_sons(block_id).is_err());
+        assert!(storage
+            .block_storage
+            .get_branch_number(branch_id, block.header().number())
+            .unwrap()
+            .is_none());
+    }
+    for block in &master {
+        let block_id = block.header().id();
+        assert_eq!(
+            storage.block_storage.get(block_id).unwrap().unwrap(),
+            *block
+        );
+        assert_eq!(
+            storage
+                .block_storage
+                .get_number(block.header().number())
+                .unwrap(),
+            Some(block_id)
+        );
+    }
+    assert_eq!(
+        storage
+            .block_storage
+            .get_sons(genesis.header().id())
+            .unwrap(),
+        vec![master[0].header().id()]
+    );
+    assert_eq!(
+        storage
+            .block_storage
+            .get_sons(master[0].header().id())
+            .unwrap(),
+        vec![master[1].header().id()]
+    );
+}
+
+#[test]
+fn test_get_total_difficulty() {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    let block = new_block(HashValue::zero(), 0);
+    let block_id = block.header().id();
+    storage.block_storage.commit_block(block).unwrap();
+    assert!(storage.get_total_difficulty(block_id).unwrap().is_none());
+
+    let total_difficulty = U512::from(1024);
+    storage
+        .save_block_info(BlockInfo::new(
+            block_id,
+            HashValue::random(),
+            vec![],
+            0,
+            0,
+            total_difficulty,
+        ))
+        .unwrap();
+    assert_eq!(
+        storage.get_total_difficulty(block_id).unwrap(),
+        Some(total_difficulty)
+    );
+    assert_eq!(
+        storage.get_total_difficulty(block_id).unwrap(),
+        storage
+            .get_block_info(block_id)
+            .unwrap()
+            .map(|block_info| block_info.get_total_difficult())
+    );
+    assert!(storage
+        .get_total_difficulty(HashValue::random())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_commit_block_batch_dropped() {
+    let cache_storage = Arc::new(CacheStorage::new());
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage,
+        db_storage,
+    ))
+    .unwrap();
+
+    let genesis = new_block(HashValue::zero(), 0);
+    storage.block_storage.commit_block(genesis.clone()).unwrap();
+    let block = new_block(genesis.header().id(), 1);
+    let block_id = block.header().id();
+
+    // crash before the batch is written.
+    assert!(storage
+        .block_storage
+        .commit_block_with(block.clone(), None, |_sons_store, batch| {
+            drop(batch);
+            bail!("simulated failure before commit")
+        })
+        .is_err());
+    assert!(storage.block_storage.get(block_id).unwrap().is_none());
+    assert!(storage
+        .block_storage
+        .get_block_header_by_hash(block_id)
+        .unwrap()
+        .is_none());
+    assert!(storage.block_storage.get_body(block_id).unwrap().is_none());
+    assert!(storage.block_storage.get_number(1).unwrap().is_none());
+    assert!(storage
+        .block_storage
+        .get_sons(genesis.header().id())
+        .is_err());
+    assert!(!storage.block_storage.is_block_known(block_id).unwrap());
+
+    storage.block_storage.commit_block(block.clone()).unwrap();
+    assert_eq!(storage.block_storage.get(block_id).unwrap(), Some(block));
+    assert_eq!(storage.block_storage.get_number(1).unwrap(), Some(block_id));
+    assert_eq!(
+        storage
+            .block_storage
+            .get_sons(genesis.header().id())
+            .unwrap(),
+        vec![block_id]
+    );
+}
+
+#[test]
+fn test_block_transactions_dedup() {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    let block_id = HashValue::random();
+    let txn1 = HashValue::random();
+    let txn2 = HashValue::random();
+    let txn3 = HashValue::random();
+    storage
+        .save_block_transactions(block_id, vec![txn1, txn2, txn1, txn3, txn2])
+        .unwrap();
+    assert_eq!(
+        storage.get_block_transactions(block_id).unwrap(),
+        vec![txn1, txn2, txn3]
+    );
+    assert!(storage
+        .save_block_transactions(HashValue::random(), vec![])
+        .is_err());
+}
+
+#[test]
+fn test_iter_headers_by_number() {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    assert_eq!(
+        storage
+            .block_storage
+            .iter_headers_by_number()
+            .unwrap()
+            .count(),
+        0
+    );
+
+    let mut parent_hash = HashValue::zero();
+    let mut headers = vec![];
+    for number in 0..5 {
+        let block = new_block(parent_hash, number);
+        parent_hash = block.header().id();
+        headers.push(block.header().clone());
+        storage.block_storage.commit_block(block).unwrap();
+    }
+    // a block after a gap is not reached.
+    storage
+        .block_storage
+        .commit_block(new_block(HashValue::random(), 6))
+        .unwrap();
+
+    let iter_headers = storage
+        .block_storage
+        .iter_headers_by_number()
+        .unwrap()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(iter_headers, headers);
+}
+
+#[test]
+fn test_branch_startup_info() {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    let genesis = new_block(HashValue::zero(), 0);
+    let block1 = new_block(genesis.header().id(), 1);
+    let block2 = new_block(genesis.header().id(), 1);
+    let startup_info = |head: &Block| {
+        StartupInfo::new(
+            ChainInfo::new(None, head.header().id(), head.header()),
+            vec![],
+        )
+    };
+    let branch1 = block1.header().id();
+    let branch2 = block2.header().id();
+    assert!(storage.get_branch_startup_info(branch1).unwrap().is_none());
+
+    storage.save_startup_info(startup_info(&genesis)).unwrap();
+    storage
+        .save_branch_startup_info(branch1, startup_info(&block1))
+        .unwrap();
+    storage
+        .save_branch_startup_info(branch2, startup_info(&block2))
+        .unwrap();
+
+    assert_eq!(
+        storage.get_branch_startup_info(branch1).unwrap(),
+        Some(startup_info(&block1))
+    );
+    assert_eq!(
+        storage.get_branch_startup_info(branch2).unwrap(),
+        Some(startup_info(&block2))
+    );
+    assert_eq!(
+        storage.get_startup_info().unwrap(),
+        Some(startup_info(&genesis))
+    );
+}
+
+#[test]
+fn test_common_ancestor_bounded() {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    let genesis = new_block(HashValue::zero(), 0);
+    storage.block_storage.commit_block(genesis.clone()).unwrap();
+    // master: genesis -> a1 .. a5, branch: genesis -> b1 .. b3
+    let mut master = vec![];
+    let mut parent_hash = genesis.header().id();
+    for number in 1..6 {
+        let block = new_block(parent_hash, number);
+        parent_hash = block.header().id();
+        storage.block_storage.commit_block(block.clone()).unwrap();
+        master.push(block.header().id());
+    }
+    let branch_id = HashValue::random();
+    let mut branch = vec![];
+    let mut parent_hash = genesis.header().id();
+    for number in 1..4 {
+        let block = new_block(parent_hash, number);
+        parent_hash = block.header().id();
+        storage
+            .block_storage
+            .commit_branch_block(branch_id, block.clone())
+            .unwrap();
+        branch.push(block.header().id());
+    }
+
+    // the parent shortcut needs no step.
+    assert_eq!(
+        storage
+            .get_common_ancestor_bounded(master[0], master[1], 0)
+            .unwrap(),
+        Some(master[0])
+    );
+    // a5 walks back 2 blocks to a3, then both walk back 3 blocks to genesis.
+    assert_eq!(
+        storage
+            .get_common_ancestor_bounded(master[4], branch[2], 5)
+            .unwrap(),
+        Some(genesis.header().id())
+    );
+    assert_eq!(
+        storage
+            .get_common_ancestor_bounded(branch[2], master[4], 5)
+            .unwrap(),
+        Some(genesis.header().id())
+    );
+    assert_eq!(
+        storage
+            .get_common_ancestor_bounded(master[4], branch[2], 4)
+            .unwrap(),
+        None
+    );
+    assert!(storage
+        .get_common_ancestor_bounded(master[4], HashValue::random(), 10)
+        .is_err());
+}
+
+/// Set in the child process of `test_block_store_no_stdout`.
+const STDOUT_CHILD_ENV: &str = "STARCOIN_STORAGE_STDOUT_CHILD";
+const STDOUT_BEGIN: &str = "==block store begin==";
+const STDOUT_END: &str = "==block store end==";
+
+/// Run by `test_block_store_no_stdout` in a child process, whose stdout is not captured by the
+/// test harness. Do nothing in a normal test run.
+#[test]
+fn block_store_stdout_child() {
+    if std::env::var(STDOUT_CHILD_ENV).is_err() {
+        return;
+    }
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap();
+    let genesis = new_block(HashValue::zero(), 0);
+    let block1 = new_block(genesis.header().id(), 1);
+    let block2 = new_block(genesis.header().id(), 1);
+    println!("{}", STDOUT_BEGIN);
+    storage.block_storage.save(genesis.clone()).unwrap();
+    storage.block_storage.commit_block(genesis).unwrap();
+    storage.block_storage.commit_block(block1).unwrap();
+    storage.block_storage.commit_block(block2.clone()).unwrap();
+    assert_eq!(storage.block_storage.get_headers().unwrap().len(), 3);
+    storage
+        .block_storage
+        .get_branch_hashes(block2.header().id())
+        .unwrap();
+    println!("{}", STDOUT_END);
+}
+
+#[test]
+fn test_block_store_no_stdout() {
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(&[
+            "--exact",
+            "tests::test_block::block_store_stdout_child",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(STDOUT_CHILD_ENV, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let begin = stdout
+        .find(STDOUT_BEGIN)
+        .unwrap_or_else(|| panic!("child test not run: {}", stdout))
+        + STDOUT_BEGIN.len();
+    let end = stdout.find(STDOUT_END).unwrap();
+    // saving and walking the blocks print nothing to stdout.
+    assert_eq!(stdout[begin..end].trim(), "");
+}
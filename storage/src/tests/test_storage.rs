@@ -5,13 +5,29 @@ extern crate chrono;
 
 use crypto::{hash::CryptoHash, HashValue};
 
+use crate::batch::WriteBatch;
+use crate::block_info::BlockInfoStore;
 use crate::cache_storage::CacheStorage;
-use crate::db_storage::DBStorage;
+use crate::db_storage::{ColumnFamilyPathMap, DBStorage, RocksdbConfig, DEFAULT_CF_NAME};
 use crate::storage::{InnerStore, StorageInstance, ValueCodec};
-use crate::{Storage, TRANSACTION_INFO_PREFIX_NAME};
+use crate::{
+    BlockStore, Storage, TransactionInfoStore, TransactionStore, BLOCK_HEADER_PREFIX_NAME,
+    BLOCK_PREFIX_NAME, STATE_NODE_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME, VEC_PREFIX_NAME,
+};
 use anyhow::Result;
-use starcoin_types::transaction::TransactionInfo;
+use forkable_jellyfish_merkle::{blob::Blob, node_type::Node};
+use rocksdb::{ColumnFamilyOptions, DBOptions, DB};
+use starcoin_accumulator::node_index::NodeIndex;
+use starcoin_accumulator::{
+    Accumulator, AccumulatorNode, AccumulatorReader, AccumulatorWriter, MerkleAccumulator,
+};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use starcoin_types::transaction::{SignedUserTransaction, Transaction, TransactionInfo};
 use starcoin_types::vm_error::StatusCode;
+use starcoin_types::{U256, U512};
+use state_tree::{StateNode, StateNodeStore};
+use std::path::Path;
 use std::sync::Arc;
 
 #[test]
@@ -132,3 +148,431 @@ fn test_two_level_storage_read_through() -> Result<()> {
     assert_eq!(transaction_info3, transaction_info1);
     Ok(())
 }
+
+#[test]
+fn test_prune_transactions_below() -> Result<()> {
+    let cache_storage = Arc::new(CacheStorage::new());
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage,
+        db_storage,
+    ))?;
+
+    let mut parent_hash = HashValue::zero();
+    let mut block_txns = vec![];
+    for number in 0..4 {
+        let user_txns = vec![SignedUserTransaction::mock(), SignedUserTransaction::mock()];
+        let header = BlockHeader::new(
+            parent_hash,
+            number,
+            number,
+            AccountAddress::random(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            U256::zero(),
+            vec![],
+        );
+        let block_id = header.id();
+        storage
+            .block_storage
+            .commit_block(Block::new(header, BlockBody::new(user_txns.clone())))?;
+        let txns: Vec<Transaction> = user_txns
+            .into_iter()
+            .map(Transaction::UserTransaction)
+            .collect();
+        let txn_hashes: Vec<HashValue> = txns.iter().map(|txn| txn.id()).collect();
+        storage.save_block_transactions(block_id, txn_hashes.clone())?;
+        storage.save_transaction_batch(txns)?;
+        storage.save_transaction_infos(
+            txn_hashes
+                .iter()
+                .map(|txn_hash| {
+                    TransactionInfo::new(
+                        *txn_hash,
+                        HashValue::zero(),
+                        HashValue::zero(),
+                        0,
+                        StatusCode::EXECUTED,
+                    )
+                })
+                .collect(),
+        )?;
+        block_txns.push((block_id, txn_hashes));
+        parent_hash = block_id;
+    }
+
+    assert!(storage.prune_transactions_below(10).is_err());
+    assert_eq!(storage.prune_transactions_below(2)?, 4);
+
+    for (number, (block_id, txn_hashes)) in block_txns.iter().enumerate() {
+        let pruned = number < 2;
+        assert!(storage.get_block_header_by_hash(*block_id)?.is_some());
+        assert_eq!(storage.get_block_transactions(*block_id).is_err(), pruned);
+        for txn_hash in txn_hashes {
+            assert_eq!(storage.get_transaction(*txn_hash)?.is_none(), pruned);
+            assert_eq!(storage.get_transaction_info(*txn_hash)?.is_none(), pruned);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_state_node_on_separate_path() -> Result<()> {
+    let root_dir = libra_temppath::TempPath::new();
+    let state_node_dir = libra_temppath::TempPath::new();
+    std::fs::create_dir_all(state_node_dir.path())?;
+    let mut config = RocksdbConfig::default();
+    config.cf_paths.insert(
+        STATE_NODE_PREFIX_NAME.to_string(),
+        state_node_dir.path().to_path_buf(),
+    );
+    let db_storage = Arc::new(DBStorage::open_with_config(
+        root_dir.path(),
+        false,
+        None,
+        config.clone(),
+    )?);
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage.clone()))?;
+
+    let key = HashValue::random();
+    let node = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(vec![1u8, 2, 3]),
+    ));
+    let block_key = HashValue::random();
+    // state node and block are written in one atomic batch of the single db.
+    let mut batch = WriteBatch::new();
+    batch.put(STATE_NODE_PREFIX_NAME, key, node.clone())?;
+    batch.put(BLOCK_PREFIX_NAME, block_key, node.clone())?;
+    db_storage.write_batch(batch)?;
+    db_storage.flush_cf(STATE_NODE_PREFIX_NAME)?;
+    assert_eq!(StateNodeStore::get(&storage, &key)?, Some(node.clone()));
+    assert!(db_storage.contains_key(BLOCK_PREFIX_NAME, block_key.to_vec())?);
+    let has_sst = std::fs::read_dir(state_node_dir.path())?
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.path().extension().map_or(false, |ext| ext == "sst"));
+    assert!(has_sst);
+    drop(storage);
+    drop(db_storage);
+
+    let db_storage = Arc::new(DBStorage::open_with_config(
+        root_dir.path(),
+        false,
+        None,
+        config.clone(),
+    )?);
+    let storage = Storage::new(StorageInstance::new_db_instance(db_storage))?;
+    assert_eq!(StateNodeStore::get(&storage, &key)?, Some(node));
+    drop(storage);
+
+    config.cf_paths.insert(
+        "unknown_cf".to_string(),
+        state_node_dir.path().to_path_buf(),
+    );
+    assert!(DBStorage::open_with_config(root_dir.path(), false, None, config).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_state_node_contains() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
+    let cache_storage = Arc::new(CacheStorage::new());
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        cache_storage.clone(),
+        db_storage.clone(),
+    ))?;
+
+    let key = HashValue::random();
+    let node = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(vec![1u8, 2, 3]),
+    ));
+    assert!(!StateNodeStore::contains(&storage, &key)?);
+    StateNodeStore::put(&storage, key, node)?;
+    assert!(StateNodeStore::contains(&storage, &key)?);
+    // a node only in the db, such as one evicted from the cache.
+    cache_storage.remove(STATE_NODE_PREFIX_NAME, key.to_vec())?;
+    assert!(StateNodeStore::contains(&storage, &key)?);
+
+    // a value which can not be decoded to a node is still found, so the check never decodes.
+    let garbage_key = HashValue::random();
+    db_storage.put(
+        STATE_NODE_PREFIX_NAME,
+        garbage_key.to_vec(),
+        vec![0xffu8; 16],
+    )?;
+    assert!(StateNodeStore::get(&storage, &garbage_key).is_err());
+    assert!(StateNodeStore::contains(&storage, &garbage_key)?);
+    Ok(())
+}
+
+/// Create a raw db at `path` with only the `cf_names` column families.
+fn create_db_with_cfs(path: &Path, cf_names: &[&str]) -> Result<()> {
+    let mut db_opts = DBOptions::new();
+    db_opts.create_if_missing(true);
+    let mut db = DB::open_cf(
+        db_opts,
+        path.to_str().unwrap(),
+        vec![(DEFAULT_CF_NAME, ColumnFamilyOptions::default())],
+    )
+    .map_err(DBStorage::convert_rocksdb_err)?;
+    for cf_name in cf_names {
+        db.create_cf((*cf_name, ColumnFamilyOptions::default()))
+            .map_err(DBStorage::convert_rocksdb_err)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_open_db_with_missing_cfs() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_path = tmpdir.path().join("starcoindb");
+    create_db_with_cfs(&db_path, &[BLOCK_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME])?;
+
+    let db_storage = DBStorage::open(tmpdir.path(), false, None)?;
+    let key = HashValue::random().to_vec();
+    db_storage.put(STATE_NODE_PREFIX_NAME, key.clone(), vec![1u8, 2, 3])?;
+    assert_eq!(
+        db_storage.get(STATE_NODE_PREFIX_NAME, key)?,
+        Some(vec![1u8, 2, 3])
+    );
+    drop(db_storage);
+
+    let cf_names = DB::list_column_families(&DBOptions::new(), db_path.to_str().unwrap())
+        .map_err(DBStorage::convert_rocksdb_err)?;
+    for prefix_name in VEC_PREFIX_NAME.iter() {
+        assert!(cf_names.iter().any(|cf_name| cf_name == prefix_name));
+    }
+    // reopen with every column family.
+    DBStorage::open(tmpdir.path(), false, None)?;
+    Ok(())
+}
+
+#[test]
+fn test_open_db_with_config() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_storage = DBStorage::open(tmpdir.path(), false, None)?;
+    assert_eq!(db_storage.config(), &RocksdbConfig::default());
+    drop(db_storage);
+
+    let config = RocksdbConfig {
+        block_cache_size: Some(16 << 20),
+        write_buffer_size: 8 << 20,
+        max_open_files: 256,
+        cf_paths: ColumnFamilyPathMap::new(),
+    };
+    let db_storage = DBStorage::new_with_config(tmpdir.path(), config.clone());
+    assert_eq!(db_storage.config(), &config);
+    let key = HashValue::random().to_vec();
+    db_storage.put(BLOCK_PREFIX_NAME, key.clone(), vec![1u8, 2, 3])?;
+    assert_eq!(
+        db_storage.get(BLOCK_PREFIX_NAME, key)?,
+        Some(vec![1u8, 2, 3])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_open_db_with_unexpected_cfs() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let db_path = tmpdir.path().join("starcoindb");
+    create_db_with_cfs(&db_path, &[BLOCK_PREFIX_NAME, "unexpected_cf"])?;
+    assert!(DBStorage::open(tmpdir.path(), false, None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_commit_block_with_txn_infos() -> Result<()> {
+    let storage = Storage::new(StorageInstance::new_cache_instance(CacheStorage::new()))?;
+
+    let mut parent_hash = HashValue::zero();
+    let mut block_info = None;
+    for (number, txn_count) in [3usize, 2].iter().enumerate() {
+        let header = BlockHeader::new(
+            parent_hash,
+            number as u64,
+            number as u64,
+            AccountAddress::random(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            U256::one(),
+            vec![],
+        );
+        let txn_infos: Vec<TransactionInfo> = (0..*txn_count)
+            .map(|_| {
+                TransactionInfo::new(
+                    HashValue::random(),
+                    HashValue::zero(),
+                    HashValue::zero(),
+                    0,
+                    StatusCode::EXECUTED,
+                )
+            })
+            .collect();
+        parent_hash = header.id();
+        let info = storage.commit_block_with_txn_infos(
+            Block::new(header, BlockBody::new(vec![])),
+            txn_infos.clone(),
+        )?;
+        assert_eq!(storage.get_block_info(parent_hash)?, Some(info.clone()));
+        block_info = Some((info, txn_infos));
+    }
+
+    let (block_info, txn_infos) = block_info.unwrap();
+    assert_eq!(block_info.num_leaves, 5);
+    assert_eq!(block_info.total_difficulty, U512::from(2u64));
+    let accumulator = MerkleAccumulator::new(
+        block_info.block_id,
+        block_info.accumulator_root,
+        block_info.frozen_subtree_roots.clone(),
+        block_info.num_leaves,
+        block_info.num_nodes,
+        storage.accumulator_storage.clone(),
+    )?;
+    let leaf_index = 4;
+    let proof = accumulator.get_proof(leaf_index)?.unwrap();
+    proof.verify(
+        block_info.accumulator_root,
+        txn_infos[1].crypto_hash(),
+        leaf_index,
+    )?;
+    Ok(())
+}
+
+fn check_snapshot(storage: Storage) -> Result<()> {
+    let header = BlockHeader::new(
+        HashValue::zero(),
+        0,
+        0,
+        AccountAddress::random(),
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        0,
+        U256::zero(),
+        vec![],
+    );
+    let block_id = header.id();
+    let snapshot = storage.snapshot()?;
+    storage
+        .block_storage
+        .commit_block(Block::new(header, BlockBody::new(vec![])))?;
+    assert!(!snapshot.is_block_known(block_id)?);
+    assert!(snapshot.get_block_header_by_hash(block_id)?.is_none());
+    assert!(snapshot.get_body(block_id)?.is_none());
+    assert!(snapshot.get_number(0)?.is_none());
+    assert!(storage.get_block_header_by_hash(block_id)?.is_some());
+    let snapshot = storage.snapshot()?;
+    // the block saved before the snapshot is known to it.
+    assert!(snapshot.is_block_known(block_id)?);
+    assert!(!snapshot.is_block_known(HashValue::random())?);
+    assert!(snapshot.get_block_header_by_hash(block_id)?.is_some());
+    assert_eq!(snapshot.get_number(0)?, Some(block_id));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    check_snapshot(Storage::new(StorageInstance::new_cache_and_db_instance(
+        Arc::new(CacheStorage::new()),
+        Arc::new(DBStorage::new(tmpdir.path())),
+    ))?)?;
+    check_snapshot(Storage::new(StorageInstance::new_cache_instance(
+        CacheStorage::new(),
+    ))?)
+}
+
+#[test]
+fn test_accumulator_multiple_get() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        Arc::new(CacheStorage::new()),
+        Arc::new(DBStorage::new(tmpdir.path())),
+    ))?;
+    let nodes: Vec<AccumulatorNode> = (0..3)
+        .map(|i| AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(i), HashValue::random()))
+        .collect();
+    for node in &nodes {
+        storage.save_node(node.clone())?;
+    }
+    let hashes: Vec<HashValue> = nodes.iter().map(|node| node.hash()).collect();
+    assert_eq!(storage.multiple_get(hashes.clone())?, nodes);
+
+    // missing nodes are skipped.
+    let mut with_missing = hashes;
+    with_missing.insert(1, HashValue::random());
+    assert_eq!(storage.multiple_get(with_missing)?, nodes);
+    Ok(())
+}
+
+#[test]
+fn test_cache_evict_lru() -> Result<()> {
+    let cache = CacheStorage::new_with_capacity(2);
+    cache.put(BLOCK_PREFIX_NAME, vec![1], vec![1])?;
+    cache.put(BLOCK_PREFIX_NAME, vec![2], vec![2])?;
+    // read 1, so 2 becomes the least recently used.
+    assert_eq!(cache.get(BLOCK_PREFIX_NAME, vec![1])?, Some(vec![1]));
+    cache.put(BLOCK_PREFIX_NAME, vec![3], vec![3])?;
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.get(BLOCK_PREFIX_NAME, vec![2])?, None);
+    assert_eq!(cache.get(BLOCK_PREFIX_NAME, vec![1])?, Some(vec![1]));
+    assert_eq!(cache.get(BLOCK_PREFIX_NAME, vec![3])?, Some(vec![3]));
+    Ok(())
+}
+
+#[test]
+fn test_cache_hits_and_misses() -> Result<()> {
+    let cache = Arc::new(CacheStorage::new_with_capacity(16));
+    let tmpdir = libra_temppath::TempPath::new();
+    let db = Arc::new(DBStorage::new(tmpdir.path()));
+    let instance = StorageInstance::new_cache_and_db_instance(cache.clone(), db.clone());
+    assert!(cache.is_empty());
+
+    db.put(BLOCK_PREFIX_NAME, vec![1], vec![1])?;
+    // the first read misses the cache and loads the value from db.
+    assert_eq!(instance.get(BLOCK_PREFIX_NAME, vec![1])?, Some(vec![1]));
+    assert_eq!((cache.hits(), cache.misses()), (0, 1));
+    assert_eq!(instance.get(BLOCK_PREFIX_NAME, vec![1])?, Some(vec![1]));
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    assert_eq!(instance.get(BLOCK_PREFIX_NAME, vec![2])?, None);
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+    assert_eq!(cache.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_get_transactions() -> Result<()> {
+    let tmpdir = libra_temppath::TempPath::new();
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        Arc::new(CacheStorage::new()),
+        Arc::new(DBStorage::new(tmpdir.path())),
+    ))?;
+    let txns: Vec<Transaction> = vec![SignedUserTransaction::mock(), SignedUserTransaction::mock()]
+        .into_iter()
+        .map(Transaction::UserTransaction)
+        .collect();
+    storage.save_transaction_batch(txns.clone())?;
+
+    let missing = HashValue::random();
+    let result =
+        storage.get_transactions(vec![txns[1].id(), missing, txns[0].id(), txns[1].id()])?;
+    assert_eq!(
+        result,
+        vec![
+            Some(txns[1].clone()),
+            None,
+            Some(txns[0].clone()),
+            Some(txns[1].clone())
+        ]
+    );
+    assert!(storage.get_transactions(vec![])?.is_empty());
+    Ok(())
+}
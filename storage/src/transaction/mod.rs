@@ -34,6 +34,10 @@ impl TransactionStore for TransactionStorage {
         self.store.get(txn_hash)
     }
 
+    fn get_transactions(&self, txn_hashes: Vec<HashValue>) -> Result<Vec<Option<Transaction>>> {
+        self.store.multi_get(txn_hashes)
+    }
+
     fn save_transaction(&self, txn_info: Transaction) -> Result<(), Error> {
         self.store.put(txn_info.id(), txn_info)
     }
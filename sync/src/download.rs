@@ -55,6 +55,9 @@ where
     storage: Arc<dyn Store>,
     sync_metadata: SyncMetadata,
     main_network: bool,
+    state_sync_workers: usize,
+    state_sync_max_peer_failures: usize,
+    state_sync_queue_size: usize,
 }
 
 impl<C> DownloadActor<C>
@@ -85,6 +88,9 @@ where
                 storage,
                 sync_metadata,
                 main_network: node_config.base.net().is_main(),
+                state_sync_workers: node_config.sync.state_sync_workers(),
+                state_sync_max_peer_failures: node_config.sync.state_sync_max_peer_failures(),
+                state_sync_queue_size: node_config.sync.state_sync_queue_size(),
             }
         });
         Ok(download_actor)
@@ -148,11 +154,17 @@ where
                 let storage = self.storage.clone();
                 let sync_metadata = self.sync_metadata.clone();
                 let is_main = self.main_network;
+                let state_sync_workers = self.state_sync_workers;
+                let state_sync_max_peer_failures = self.state_sync_max_peer_failures;
+                let state_sync_queue_size = self.state_sync_queue_size;
                 let self_peer_id = self.self_peer_id.as_ref().clone();
                 Arbiter::spawn(async move {
                     Self::sync_state(
                         self_peer_id,
                         is_main,
+                        state_sync_workers,
+                        state_sync_max_peer_failures,
+                        state_sync_queue_size,
                         downloader.clone(),
                         network,
                         storage,
@@ -193,6 +205,9 @@ where
         let storage = self.storage.clone();
         let sync_metadata = self.sync_metadata.clone();
         let is_main = self.main_network;
+        let state_sync_workers = self.state_sync_workers;
+        let state_sync_max_peer_failures = self.state_sync_max_peer_failures;
+        let state_sync_queue_size = self.state_sync_queue_size;
         let self_peer_id = self.self_peer_id.as_ref().clone();
         let ready = self.ready.load(Ordering::Relaxed);
         match msg {
@@ -203,6 +218,9 @@ where
                         Self::sync_state(
                             self_peer_id,
                             is_main,
+                            state_sync_workers,
+                            state_sync_max_peer_failures,
+                            state_sync_queue_size,
                             downloader.clone(),
                             network,
                             storage,
@@ -237,6 +255,9 @@ where
     async fn sync_state(
         self_peer_id: PeerId,
         main_network: bool,
+        state_sync_workers: usize,
+        state_sync_max_peer_failures: usize,
+        state_sync_queue_size: usize,
         downloader: Arc<Downloader<C>>,
         network: NetworkAsyncService,
         storage: Arc<dyn Store>,
@@ -245,6 +266,9 @@ where
         if let Err(e) = Self::sync_state_inner(
             self_peer_id,
             main_network,
+            state_sync_workers,
+            state_sync_max_peer_failures,
+            state_sync_queue_size,
             downloader,
             network,
             storage,
@@ -259,6 +283,9 @@ where
     async fn sync_state_inner(
         self_peer_id: PeerId,
         main_network: bool,
+        state_sync_workers: usize,
+        state_sync_max_peer_failures: usize,
+        state_sync_queue_size: usize,
         downloader: Arc<Downloader<C>>,
         network: NetworkAsyncService,
         storage: Arc<dyn Store>,
@@ -313,6 +340,9 @@ where
                                                     storage,
                                                     network.clone(),
                                                     sync_metadata.clone(),
+                                                    state_sync_workers,
+                                                    state_sync_max_peer_failures,
+                                                    state_sync_queue_size,
                                                 );
                                             sync_metadata
                                                 .update_address(&state_sync_task_address)?
@@ -602,6 +632,14 @@ where
         }
     }
 
+    /// The info of a local block, such as the parent of the blocks to download.
+    pub async fn get_block_info(&self, block_id: &HashValue) -> Option<BlockInfo> {
+        self.chain_reader
+            .clone()
+            .get_block_info_by_hash(block_id)
+            .await
+    }
+
     /// for ancestors
     pub async fn get_hash_by_number_msg_backward(
         network: NetworkAsyncService,
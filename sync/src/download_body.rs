@@ -1,13 +1,18 @@
 use crate::download::Downloader;
 use crate::helper::get_block_by_hash;
 use actix::prelude::*;
-use anyhow::Result;
+use anyhow::{ensure, format_err, Result};
 use crypto::hash::HashValue;
+use futures::Future;
 use logger::prelude::*;
 use network::NetworkAsyncService;
+use starcoin_sync_api::sync_messages::{BatchBlockInfo, BatchBodyMsg, BatchHeaderMsg};
 use std::sync::Arc;
 use traits::Consensus;
-use types::{block::BlockHeader, peer_info::PeerInfo};
+use types::{
+    block::{BlockBody, BlockHeader, BlockInfo},
+    peer_info::{PeerId, PeerInfo},
+};
 
 #[derive(Default, Debug, Message)]
 #[rtype(result = "Result<()>")]
@@ -16,6 +21,122 @@ pub struct SyncBodyEvent {
     pub peers: Vec<PeerInfo>,
 }
 
+/// Bodies and infos are paired with the requested headers by position, the headers returned
+/// along with them must be the requested ones in order and each info must be of its header,
+/// with the accumulator root of its header.
+/// Block headers have no transactions root, so a body is checked by its txn count: a block
+/// appends its txns and the block metadata txn to the txn accumulator, the leaves of its info
+/// must grow by that count from its parent's. The parent info of the first block is the locally
+/// known `parent_info`, the first block is not checked without it.
+pub fn check_bodies(
+    headers: &[BlockHeader],
+    returned_headers: &[BlockHeader],
+    bodies: &[BlockBody],
+    infos: &[BlockInfo],
+    parent_info: Option<&BlockInfo>,
+) -> Result<()> {
+    ensure!(
+        bodies.len() == headers.len(),
+        "bodies count {} mismatch headers count {}",
+        bodies.len(),
+        headers.len()
+    );
+    ensure!(
+        infos.len() == headers.len(),
+        "infos count {} mismatch headers count {}",
+        infos.len(),
+        headers.len()
+    );
+    ensure!(
+        returned_headers.len() == headers.len(),
+        "returned headers count {} mismatch headers count {}",
+        returned_headers.len(),
+        headers.len()
+    );
+    for (i, header) in headers.iter().enumerate() {
+        let id = header.id();
+        ensure!(
+            returned_headers[i].id() == id,
+            "block {:?} at {} is returned as {:?}",
+            id,
+            i,
+            returned_headers[i].id()
+        );
+        ensure!(
+            infos[i].block_id == id,
+            "block {:?} at {} has the info of block {:?}",
+            id,
+            i,
+            infos[i].block_id
+        );
+        ensure!(
+            infos[i].accumulator_root == header.accumulator_root(),
+            "block {:?} at {} has accumulator root {:?}, but its info has {:?}",
+            id,
+            i,
+            header.accumulator_root(),
+            infos[i].accumulator_root
+        );
+        let parent_info = if i == 0 {
+            parent_info
+        } else {
+            Some(&infos[i - 1])
+        };
+        if let Some(parent_info) = parent_info.filter(|info| info.block_id == header.parent_hash())
+        {
+            let txn_count = bodies[i].len() as u64 + 1;
+            ensure!(
+                infos[i].num_leaves == parent_info.num_leaves + txn_count,
+                "block {:?} at {} has {} txns, but its info grows the accumulator from {} to {} leaves",
+                id,
+                i,
+                txn_count,
+                parent_info.num_leaves,
+                infos[i].num_leaves
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Download the bodies and infos of the headers from the peers in turn, a peer failing the
+/// request or returning bodies not matching the headers is skipped and the bodies are requested
+/// from the next peer. `parent_info` is the local info of the parent of the first header.
+pub async fn download_bodies<F, Fut>(
+    headers: &[BlockHeader],
+    parent_info: Option<&BlockInfo>,
+    peers: Vec<PeerInfo>,
+    mut get_block_by_hash: F,
+) -> Result<(Vec<BlockBody>, Vec<BlockInfo>)>
+where
+    F: FnMut(PeerId, Vec<HashValue>) -> Fut,
+    Fut: Future<Output = Result<(BatchHeaderMsg, BatchBodyMsg, BatchBlockInfo)>>,
+{
+    let hashs: Vec<HashValue> = headers.iter().map(|h| h.id()).collect();
+    for peer in peers {
+        let peer_id = peer.get_peer_id();
+        match get_block_by_hash(peer_id.clone(), hashs.clone()).await {
+            Ok((returned_headers, bodies, infos)) => {
+                match check_bodies(
+                    headers,
+                    &returned_headers.headers,
+                    &bodies.bodies,
+                    &infos.infos,
+                    parent_info,
+                ) {
+                    Ok(()) => return Ok((bodies.bodies, infos.infos)),
+                    Err(e) => warn!("invalid bodies from peer {:?}: {:?}", peer_id, e),
+                }
+            }
+            Err(e) => warn!("get bodies from peer {:?} failed: {:?}", peer_id, e),
+        }
+    }
+    Err(format_err!(
+        "download bodies of {} headers failed on all peers.",
+        headers.len()
+    ))
+}
+
 #[derive(Clone)]
 pub struct DownloadBodyActor<C>
 where
@@ -56,29 +177,25 @@ where
 {
     type Result = Result<()>;
     fn handle(&mut self, event: SyncBodyEvent, _ctx: &mut Self::Context) -> Self::Result {
-        let hashs: Vec<HashValue> = event.headers.iter().map(|h| h.id().clone()).collect();
-
         let network = self.network.clone();
         let peers = event.peers.clone();
         let downloader = self.downloader.clone();
 
         let headers = event.headers;
         Arbiter::spawn(async move {
-            for peer in peers {
-                match get_block_by_hash(&network, peer.get_peer_id().clone(), hashs.clone()).await {
-                    Ok((_, bodies, infos)) => {
-                        Downloader::do_blocks(
-                            downloader,
-                            headers.clone(),
-                            bodies.bodies,
-                            infos.infos,
-                        );
-                        break;
-                    }
-                    Err(e) => {
-                        error!("error: {:?}", e);
-                    }
-                };
+            let parent_info = match headers.first() {
+                Some(header) => downloader.get_block_info(&header.parent_hash()).await,
+                None => None,
+            };
+            let bodies =
+                download_bodies(&headers, parent_info.as_ref(), peers, |peer_id, hashs| {
+                    let network = network.clone();
+                    async move { get_block_by_hash(&network, peer_id, hashs).await }
+                })
+                .await;
+            match bodies {
+                Ok((bodies, infos)) => Downloader::do_blocks(downloader, headers, bodies, infos),
+                Err(e) => error!("error: {:?}", e),
             }
         });
 
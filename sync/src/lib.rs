@@ -1,5 +1,5 @@
 mod download;
-mod download_body;
+pub mod download_body;
 mod download_header;
 mod get_txns_handler;
 pub mod helper;
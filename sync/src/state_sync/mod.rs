@@ -1,11 +1,8 @@
 use crate::helper::{get_accumulator_node_by_node_hash, get_state_node_by_node_hash};
 use actix::prelude::*;
 use actix::{Actor, Addr, Context, Handler};
-use anyhow::Result;
+use anyhow::{format_err, Error, Result};
 use crypto::hash::HashValue;
-use forkable_jellyfish_merkle::node_type::Node;
-use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
-use futures::executor::block_on;
 use logger::prelude::*;
 use network::NetworkAsyncService;
 use network_api::NetworkService;
@@ -16,9 +13,23 @@ use starcoin_state_tree::StateNode;
 use starcoin_storage::Store;
 use starcoin_sync_api::{StateSyncReset, SyncMetadata};
 use std::collections::{HashMap, VecDeque};
-use std::convert::TryFrom;
 use std::sync::Arc;
-use types::{account_state::AccountState, peer_info::PeerId};
+use types::peer_info::{PeerId, PeerInfo};
+
+mod peer_failures;
+mod state_node_worker;
+mod storage_roots;
+
+pub use peer_failures::NodePeerFailures;
+pub use state_node_worker::{
+    apply_state_node, state_node_children, StateNodeApplyResult, StateNodeWorkerPool,
+};
+pub use storage_roots::PendingStorageRoots;
+
+/// An internal state node has up to 16 children, they are queued together.
+const MIN_SYNC_QUEUE_SIZE: usize = 16;
+/// Synced nodes between two progress logs.
+const PROGRESS_LOG_INTERVAL: u64 = 1000;
 
 struct Roots {
     state: HashValue,
@@ -34,12 +45,17 @@ impl Roots {
         &self.state
     }
 
-    fn accumulator_root(&self) -> &HashValue {
-        &self.accumulator
+    /// An empty accumulator has no node to sync.
+    fn accumulator_sync_root(&self) -> Option<HashValue> {
+        if self.accumulator != *ACCUMULATOR_PLACEHOLDER_HASH {
+            Some(self.accumulator)
+        } else {
+            None
+        }
     }
 }
 
-async fn _sync_accumulator_node(
+async fn sync_accumulator_node(
     node_key: HashValue,
     peer_id: PeerId,
     network_service: NetworkAsyncService,
@@ -74,7 +90,7 @@ async fn _sync_accumulator_node(
         }
     };
 
-    if let Err(err) = address.try_send(StateSyncTaskEvent::_new_accumulator(
+    if let Err(err) = address.try_send(StateSyncTaskEvent::new_accumulator(
         peer_id,
         node_key,
         accumulator_node,
@@ -118,11 +134,40 @@ async fn sync_state_node(
     };
 }
 
+/// Child nodes of an accumulator node, placeholders are skipped.
+fn accumulator_node_children(accumulator_node: &AccumulatorNode) -> Vec<HashValue> {
+    let mut children = Vec::new();
+    match accumulator_node {
+        AccumulatorNode::Leaf(_leaf) => {}
+        AccumulatorNode::Internal(n) => {
+            if n.left() != *ACCUMULATOR_PLACEHOLDER_HASH {
+                children.push(n.left());
+            }
+            if n.right() != *ACCUMULATOR_PLACEHOLDER_HASH {
+                children.push(n.right());
+            }
+        }
+        _ => {
+            warn!("node {:?} is null.", accumulator_node.hash());
+        }
+    }
+    children
+}
+
 #[derive(Clone)]
 pub struct StateSyncTaskRef {
     address: Addr<StateSyncTaskActor>,
 }
 
+impl StateSyncTaskRef {
+    pub async fn progress(&self) -> Result<StateSyncProgress> {
+        self.address
+            .send(GetStateSyncProgress)
+            .await
+            .map_err(|e| Into::<Error>::into(e))?
+    }
+}
+
 #[async_trait::async_trait]
 impl StateSyncReset for StateSyncTaskRef {
     async fn reset(&self, state_root: HashValue, accumulator_root: HashValue) {
@@ -142,7 +187,7 @@ impl StateSyncReset for StateSyncTaskRef {
 #[derive(Debug, PartialEq)]
 enum TaskType {
     STATE,
-    _ACCUMULATOR,
+    ACCUMULATOR,
 }
 
 #[derive(Debug, Message)]
@@ -155,6 +200,15 @@ struct StateSyncTaskEvent {
     task_type: TaskType,
 }
 
+#[derive(Debug, Message)]
+#[rtype(result = "Result<()>")]
+struct StateNodeApplied {
+    peer_id: PeerId,
+    node_key: HashValue,
+    is_global: bool,
+    result: StateNodeApplyResult,
+}
+
 impl StateSyncTaskEvent {
     pub fn new_state(peer_id: PeerId, node_key: HashValue, state_node: Option<StateNode>) -> Self {
         StateSyncTaskEvent {
@@ -166,7 +220,7 @@ impl StateSyncTaskEvent {
         }
     }
 
-    pub fn _new_accumulator(
+    pub fn new_accumulator(
         peer_id: PeerId,
         node_key: HashValue,
         accumulator_node: Option<AccumulatorNode>,
@@ -176,7 +230,7 @@ impl StateSyncTaskEvent {
             node_key,
             state_node: None,
             accumulator_node,
-            task_type: TaskType::_ACCUMULATOR,
+            task_type: TaskType::ACCUMULATOR,
         }
     }
 
@@ -193,27 +247,102 @@ pub struct StateSyncTaskActor {
     sync_metadata: SyncMetadata,
     state_sync_task: Arc<Mutex<SyncTask<(HashValue, bool)>>>,
     accumulator_sync_task: Arc<Mutex<SyncTask<HashValue>>>,
+    state_node_workers: StateNodeWorkerPool,
+    //state nodes being applied by the workers
+    applying_state_nodes: usize,
+    //nodes waiting for a peer to sync from
+    selecting_peers: usize,
+    state_node_failures: NodePeerFailures,
+    accumulator_node_failures: NodePeerFailures,
+    //saved nodes whose children wait for room in the sync task
+    deferred_state_nodes: VecDeque<(HashValue, bool)>,
+    deferred_accumulator_nodes: VecDeque<HashValue>,
+    pending_storage_roots: PendingStorageRoots,
+    //synced nodes when the progress was logged
+    progress_logged: u64,
 }
 
+/// Nodes of a tree walk, `wait_2_sync` holds at most `capacity` nodes so that a large tree
+/// does not balloon memory before its nodes are saved.
 pub struct SyncTask<T> {
     wait_2_sync: VecDeque<T>,
     syncing_nodes: HashMap<PeerId, T>,
+    capacity: usize,
+    //nodes queued since the walk started, a retried node is counted once
+    seen: u64,
+    synced: u64,
 }
 
 impl<T> SyncTask<T> {
-    fn new() -> Self {
+    pub fn new(capacity: usize) -> Self {
         Self {
             wait_2_sync: VecDeque::new(),
             syncing_nodes: HashMap::new(),
+            capacity: capacity.max(1),
+            seen: 0,
+            synced: 0,
+        }
+    }
+
+    /// Drop all the nodes and counters, and start a new walk from the root.
+    pub fn reset(&mut self, root: Option<T>) {
+        self.clear();
+        self.seen = 0;
+        self.synced = 0;
+        if let Some(root) = root {
+            self.wait_2_sync.push_back(root);
+            self.seen += 1;
         }
     }
 
-    fn is_empty(&mut self) -> bool {
+    /// No node is waiting or being synced.
+    pub fn is_empty(&self) -> bool {
         self.wait_2_sync.is_empty() && self.syncing_nodes.is_empty()
     }
 
-    pub fn push_back(&mut self, value: T) {
-        self.wait_2_sync.push_back(value)
+    /// Number of nodes waiting to sync.
+    pub fn len(&self) -> usize {
+        self.wait_2_sync.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.wait_2_sync.len() >= self.capacity
+    }
+
+    /// Whether `count` more nodes can be queued, the caller should defer expanding the children
+    /// of a node until there is room for all of them.
+    pub fn has_room(&self, count: usize) -> bool {
+        self.wait_2_sync.len() + count <= self.capacity
+    }
+
+    /// Queue a new node, the node is given back if the queue is full.
+    pub fn push_back(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.wait_2_sync.push_back(value);
+        self.seen += 1;
+        Ok(())
+    }
+
+    /// Queue all the values or none of them if there is no room.
+    pub fn push_all(&mut self, values: Vec<T>) -> Result<(), Vec<T>> {
+        if !self.has_room(values.len()) {
+            return Err(values);
+        }
+        self.seen += values.len() as u64;
+        self.wait_2_sync.extend(values);
+        Ok(())
+    }
+
+    /// Queue a node taken from this task again, it is synced first and is never refused, so
+    /// the queue may exceed the capacity by the nodes being synced.
+    pub fn push_front(&mut self, value: T) {
+        self.wait_2_sync.push_front(value)
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
@@ -236,8 +365,53 @@ impl<T> SyncTask<T> {
     pub fn remove(&mut self, peer_id: &PeerId) -> Option<T> {
         self.syncing_nodes.remove(peer_id)
     }
+
+    /// A node taken from this task is saved.
+    pub fn node_synced(&mut self) {
+        self.synced += 1;
+    }
+
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    pub fn synced(&self) -> u64 {
+        self.synced
+    }
+
+    /// Number of nodes requested from peers.
+    pub fn in_flight(&self) -> usize {
+        self.syncing_nodes.len()
+    }
 }
 
+/// A snapshot of the state sync progress, of the state and accumulator nodes together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateSyncProgress {
+    pub total_seen: u64,
+    pub synced: u64,
+    pub in_flight: usize,
+}
+
+impl StateSyncProgress {
+    /// `applying` are the nodes received and not saved yet.
+    pub fn new<S, A>(
+        state_task: &SyncTask<S>,
+        accumulator_task: &SyncTask<A>,
+        applying: usize,
+    ) -> Self {
+        Self {
+            total_seen: state_task.seen() + accumulator_task.seen(),
+            synced: state_task.synced() + accumulator_task.synced(),
+            in_flight: state_task.in_flight() + accumulator_task.in_flight() + applying,
+        }
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "Result<StateSyncProgress>")]
+struct GetStateSyncProgress;
+
 impl StateSyncTaskActor {
     pub fn launch(
         self_peer_id: PeerId,
@@ -245,12 +419,16 @@ impl StateSyncTaskActor {
         storage: Arc<dyn Store>,
         network_service: NetworkAsyncService,
         sync_metadata: SyncMetadata,
+        state_sync_workers: usize,
+        max_peer_failures: usize,
+        queue_size: usize,
     ) -> StateSyncTaskRef {
         let roots = Roots::new(root.0, root.1);
-        let mut state_sync_task = SyncTask::new();
-        state_sync_task.push_back((roots.state_root().clone(), true));
-        let mut accumulator_sync_task = SyncTask::new();
-        accumulator_sync_task.push_back(roots.accumulator_root().clone());
+        let queue_size = queue_size.max(MIN_SYNC_QUEUE_SIZE);
+        let mut state_sync_task = SyncTask::new(queue_size);
+        state_sync_task.reset(Some((roots.state_root().clone(), true)));
+        let mut accumulator_sync_task = SyncTask::new(queue_size);
+        accumulator_sync_task.reset(roots.accumulator_sync_root());
         let address = StateSyncTaskActor::create(move |_ctx| Self {
             self_peer_id,
             roots,
@@ -259,24 +437,99 @@ impl StateSyncTaskActor {
             sync_metadata,
             state_sync_task: Arc::new(Mutex::new(state_sync_task)),
             accumulator_sync_task: Arc::new(Mutex::new(accumulator_sync_task)),
+            state_node_workers: StateNodeWorkerPool::new(state_sync_workers),
+            applying_state_nodes: 0,
+            selecting_peers: 0,
+            state_node_failures: NodePeerFailures::new(max_peer_failures),
+            accumulator_node_failures: NodePeerFailures::new(max_peer_failures),
+            deferred_state_nodes: VecDeque::new(),
+            deferred_accumulator_nodes: VecDeque::new(),
+            pending_storage_roots: PendingStorageRoots::new(),
+            progress_logged: 0,
         });
         StateSyncTaskRef { address }
     }
 
     fn sync_end(&self) -> bool {
-        //self.state_sync_task.lock().is_empty() && self.accumulator_sync_task.lock().is_empty()
-        self.state_sync_task.lock().is_empty()
+        self.state_sync_drained()
+            && self.pending_storage_roots.is_empty()
+            && self.deferred_accumulator_nodes.is_empty()
+            && self.accumulator_sync_task.lock().is_empty()
     }
 
-    fn exe_state_sync_task(&mut self, address: Addr<StateSyncTaskActor>) {
+    /// No state node is waiting, being synced or being applied.
+    fn state_sync_drained(&self) -> bool {
+        self.applying_state_nodes == 0
+            && self.selecting_peers == 0
+            && self.deferred_state_nodes.is_empty()
+            && self.state_sync_task.lock().is_empty()
+    }
+
+    /// Sync the storage roots not applied yet again once the state tree walk is drained, so a
+    /// resource sub-tree lost on the way is not missing when state sync is done.
+    fn requeue_pending_storage_roots(&mut self) {
+        if self.pending_storage_roots.is_empty() || !self.state_sync_drained() {
+            return;
+        }
+        let roots = self.pending_storage_roots.roots();
+        warn!("storage roots {:?} are not synced, sync them again.", roots);
         let mut lock = self.state_sync_task.lock();
-        let value = lock.pop_front();
-        if value.is_some() {
-            let (node_key, is_global) = value.unwrap();
+        for root in roots {
+            lock.push_front((root, false));
+        }
+    }
+
+    /// A peer not failed on the node yet, or any peer if every peer has failed on it.
+    async fn select_peer(
+        network_service: NetworkAsyncService,
+        excluded: Vec<PeerId>,
+    ) -> Option<PeerInfo> {
+        let peer = match network_service.random_peer_excluding(&excluded).await {
+            Ok(None) => network_service.best_peer().await,
+            peer => peer,
+        };
+        peer.unwrap_or_else(|e| {
+            warn!("select peer failed: {:?}", e);
+            None
+        })
+    }
+
+    /// Queue the children of deferred state nodes while there is room, the nodes are saved so
+    /// their children are read back from storage.
+    fn expand_deferred_state_nodes(&mut self) {
+        let mut lock = self.state_sync_task.lock();
+        while let Some((node_key, is_global)) = self.deferred_state_nodes.front().cloned() {
+            let children = match self.storage.get(&node_key) {
+                Ok(Some(state_node)) => state_node_children(&state_node, is_global),
+                Ok(None) => Err(format_err!("state node {:?} is not saved.", node_key)),
+                Err(e) => Err(e),
+            };
+            match children {
+                Ok(children) => {
+                    if lock.push_all(children).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("expand state node {:?} failed: {:?}", node_key, e);
+                    lock.push_front((node_key, is_global));
+                }
+            }
+            self.deferred_state_nodes.pop_front();
+        }
+    }
+
+    fn exe_state_sync_task(&mut self, ctx: &mut Context<Self>) {
+        self.expand_deferred_state_nodes();
+        self.requeue_pending_storage_roots();
+        let value = self.state_sync_task.lock().pop_front();
+        if let Some((node_key, is_global)) = value {
             if let Some(state_node) = self.storage.get(&node_key).unwrap() {
                 debug!("find state_node {:?} in db.", node_key);
-                lock.insert(self.self_peer_id.clone(), (node_key.clone(), is_global));
-                if let Err(err) = address.try_send(StateSyncTaskEvent::new_state(
+                self.state_sync_task
+                    .lock()
+                    .insert(self.self_peer_id.clone(), (node_key, is_global));
+                if let Err(err) = ctx.address().try_send(StateSyncTaskEvent::new_state(
                     self.self_peer_id.clone(),
                     node_key,
                     Some(state_node),
@@ -284,37 +537,54 @@ impl StateSyncTaskActor {
                     warn!("err:{:?}", err);
                 };
             } else {
+                let mut excluded = self.state_node_failures.failed_peers(&node_key);
+                excluded.push(self.self_peer_id.clone());
+                // the node is neither waiting nor syncing until the peer is selected.
+                self.selecting_peers += 1;
+                Self::select_peer(self.network_service.clone(), excluded)
+                    .into_actor(self)
+                    .then(move |best_peer, act, ctx| {
+                        act.selecting_peers -= 1;
+                        act.sync_state_node_from(node_key, is_global, best_peer, ctx.address());
+                        async {}.into_actor(act)
+                    })
+                    .spawn(ctx);
+            }
+        }
+    }
+
+    fn sync_state_node_from(
+        &mut self,
+        node_key: HashValue,
+        is_global: bool,
+        best_peer: Option<PeerInfo>,
+        address: Addr<StateSyncTaskActor>,
+    ) {
+        debug!("sync state_node {:?} from peer {:?}.", node_key, best_peer);
+        let mut lock = self.state_sync_task.lock();
+        match best_peer {
+            Some(best_peer) => {
+                if let Some(syncing) = lock.insert(best_peer.get_peer_id(), (node_key, is_global)) {
+                    lock.push_front(syncing);
+                }
                 let network_service = self.network_service.clone();
-                let best_peer_info = block_on(async move {
-                    let peer_info = network_service.best_peer().await.unwrap();
-                    peer_info
+                Arbiter::spawn(async move {
+                    sync_state_node(node_key, best_peer.get_peer_id(), network_service, address)
+                        .await;
                 });
-                debug!(
-                    "sync state_node {:?} from peer {:?}.",
-                    node_key, best_peer_info
-                );
-                if let Some(best_peer) = best_peer_info {
-                    if self.self_peer_id != best_peer.get_peer_id() {
-                        let network_service = self.network_service.clone();
-                        lock.insert(best_peer.get_peer_id(), (node_key.clone(), is_global));
-                        Arbiter::spawn(async move {
-                            sync_state_node(
-                                node_key,
-                                best_peer.get_peer_id(),
-                                network_service,
-                                address,
-                            )
-                            .await;
-                        });
-                    }
-                } else {
-                    warn!("{:?}", "best peer is none.");
-                }
+            }
+            None => {
+                warn!("{:?}", "best peer is none.");
+                lock.push_front((node_key, is_global));
             }
         }
     }
 
-    fn handle_state_sync(&mut self, task_event: StateSyncTaskEvent) {
+    fn handle_state_sync(
+        &mut self,
+        task_event: StateSyncTaskEvent,
+        address: Addr<StateSyncTaskActor>,
+    ) -> Result<()> {
         let mut lock = self.state_sync_task.lock();
         if let Some((state_node_hash, is_global)) = lock.get(&task_event.peer_id) {
             let is_global = is_global.clone();
@@ -323,43 +593,37 @@ impl StateSyncTaskActor {
             if state_node_hash == &current_node_key {
                 let _ = lock.remove(&task_event.peer_id);
                 if let Some(state_node) = task_event.state_node {
-                    if let Err(e) = self.storage.put(current_node_key, state_node.clone()) {
-                        error!("error : {:?}", e);
-                        lock.push_back((current_node_key, is_global));
-                    } else {
-                        debug!("receive state_node: {:?}", state_node.0.hash());
-                        match state_node.inner() {
-                            Node::Leaf(leaf) => {
-                                if is_global {
-                                    match AccountState::try_from(leaf.blob().as_ref()) {
-                                        Err(e) => {
-                                            error!("error : {:?}", e);
-                                        }
-                                        Ok(account_state) => {
-                                            account_state.storage_roots().iter().for_each(|key| {
-                                                if key.is_some() {
-                                                    let hash = key.unwrap().clone();
-                                                    if hash != *SPARSE_MERKLE_PLACEHOLDER_HASH {
-                                                        lock.push_back((hash, false));
-                                                    }
-                                                }
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                            Node::Internal(n) => {
-                                for child in n.all_child() {
-                                    lock.push_back((child, is_global));
-                                }
-                            }
-                            _ => {
-                                warn!("node {:?} is null.", current_node_key);
+                    debug!("receive state_node: {:?}", state_node.0.hash());
+                    self.applying_state_nodes += 1;
+                    let peer_id = task_event.peer_id;
+                    self.state_node_workers.apply(
+                        self.storage.clone(),
+                        current_node_key,
+                        state_node,
+                        is_global,
+                        move |node_key, is_global, result| {
+                            if let Err(err) = address.try_send(StateNodeApplied {
+                                peer_id,
+                                node_key,
+                                is_global,
+                                result,
+                            }) {
+                                warn!("err:{:?}", err);
                             }
-                        }
-                    }
+                        },
+                    );
                 } else {
-                    lock.push_back((current_node_key, is_global));
+                    if !self
+                        .state_node_failures
+                        .record(current_node_key, task_event.peer_id)
+                    {
+                        return Err(format_err!(
+                            "sync state node {:?} failed on peers {:?}",
+                            current_node_key,
+                            self.state_node_failures.failed_peers(&current_node_key)
+                        ));
+                    }
+                    lock.push_front((current_node_key, is_global));
                 }
             } else {
                 warn!(
@@ -370,17 +634,88 @@ impl StateSyncTaskActor {
         } else {
             warn!("discard state event : {:?}", task_event);
         }
+        Ok(())
     }
 
-    fn _exe_accumulator_sync_task(&mut self, address: Addr<StateSyncTaskActor>) {
+    fn handle_state_node_applied(&mut self, applied: StateNodeApplied) -> Result<()> {
+        self.applying_state_nodes -= 1;
+        let mut lock = self.state_sync_task.lock();
+        match applied.result {
+            StateNodeApplyResult::Applied(children) => {
+                self.state_node_failures.remove(&applied.node_key);
+                lock.node_synced();
+                self.pending_storage_roots
+                    .applied(&applied.node_key, applied.is_global);
+                self.pending_storage_roots
+                    .track(applied.is_global, &children);
+                if lock.push_all(children).is_err() {
+                    debug!("defer children of state node {:?}.", applied.node_key);
+                    self.deferred_state_nodes
+                        .push_back((applied.node_key, applied.is_global));
+                }
+            }
+            StateNodeApplyResult::Invalid(e) => {
+                // the subtree of the node is missing if it is dropped, sync it from other peers.
+                error!(
+                    "invalid state node from peer {:?}: {:?}",
+                    applied.peer_id, e
+                );
+                if !self
+                    .state_node_failures
+                    .record(applied.node_key, applied.peer_id)
+                {
+                    return Err(format_err!(
+                        "sync state node {:?} failed on peers {:?}, last error: {:?}",
+                        applied.node_key,
+                        self.state_node_failures.failed_peers(&applied.node_key),
+                        e
+                    ));
+                }
+                lock.push_front((applied.node_key, applied.is_global));
+            }
+            StateNodeApplyResult::StoreFailed(e) => {
+                error!("error : {:?}", e);
+                lock.push_front((applied.node_key, applied.is_global));
+            }
+        }
+        Ok(())
+    }
+
+    fn expand_deferred_accumulator_nodes(&mut self) {
         let mut lock = self.accumulator_sync_task.lock();
-        let value = lock.pop_front();
-        if value.is_some() {
-            let node_key = value.unwrap();
-            if let Some(accumulator_node) = self.storage.get_node(node_key.clone()).unwrap() {
+        while let Some(node_key) = self.deferred_accumulator_nodes.front().cloned() {
+            match self.storage.get_node(node_key) {
+                Ok(Some(accumulator_node)) => {
+                    if lock
+                        .push_all(accumulator_node_children(&accumulator_node))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    error!("accumulator node {:?} is not saved.", node_key);
+                    lock.push_front(node_key);
+                }
+                Err(e) => {
+                    error!("expand accumulator node {:?} failed: {:?}", node_key, e);
+                    lock.push_front(node_key);
+                }
+            }
+            self.deferred_accumulator_nodes.pop_front();
+        }
+    }
+
+    fn exe_accumulator_sync_task(&mut self, ctx: &mut Context<Self>) {
+        self.expand_deferred_accumulator_nodes();
+        let value = self.accumulator_sync_task.lock().pop_front();
+        if let Some(node_key) = value {
+            if let Some(accumulator_node) = self.storage.get_node(node_key).unwrap() {
                 debug!("find accumulator_node {:?} in db.", node_key);
-                lock.insert(self.self_peer_id.clone(), node_key.clone());
-                if let Err(err) = address.try_send(StateSyncTaskEvent::_new_accumulator(
+                self.accumulator_sync_task
+                    .lock()
+                    .insert(self.self_peer_id.clone(), node_key);
+                if let Err(err) = ctx.address().try_send(StateSyncTaskEvent::new_accumulator(
                     self.self_peer_id.clone(),
                     node_key,
                     Some(accumulator_node),
@@ -388,37 +723,56 @@ impl StateSyncTaskActor {
                     warn!("err:{:?}", err);
                 };
             } else {
+                let mut excluded = self.accumulator_node_failures.failed_peers(&node_key);
+                excluded.push(self.self_peer_id.clone());
+                self.selecting_peers += 1;
+                Self::select_peer(self.network_service.clone(), excluded)
+                    .into_actor(self)
+                    .then(move |best_peer, act, ctx| {
+                        act.selecting_peers -= 1;
+                        act.sync_accumulator_node_from(node_key, best_peer, ctx.address());
+                        async {}.into_actor(act)
+                    })
+                    .spawn(ctx);
+            }
+        }
+    }
+
+    fn sync_accumulator_node_from(
+        &mut self,
+        node_key: HashValue,
+        best_peer: Option<PeerInfo>,
+        address: Addr<StateSyncTaskActor>,
+    ) {
+        debug!(
+            "sync accumulator_node {:?} from peer {:?}.",
+            node_key, best_peer
+        );
+        let mut lock = self.accumulator_sync_task.lock();
+        match best_peer {
+            Some(best_peer) => {
+                if let Some(syncing) = lock.insert(best_peer.get_peer_id(), node_key) {
+                    lock.push_front(syncing);
+                }
                 let network_service = self.network_service.clone();
-                let best_peer_info = block_on(async move {
-                    let peer_info = network_service.best_peer().await.unwrap();
-                    peer_info
+                Arbiter::spawn(async move {
+                    sync_accumulator_node(
+                        node_key,
+                        best_peer.get_peer_id(),
+                        network_service,
+                        address,
+                    )
+                    .await;
                 });
-                debug!(
-                    "sync accumulator_node {:?} from peer {:?}.",
-                    node_key, best_peer_info
-                );
-                if let Some(best_peer) = best_peer_info {
-                    if self.self_peer_id != best_peer.get_peer_id() {
-                        let network_service = self.network_service.clone();
-                        lock.insert(best_peer.get_peer_id(), node_key.clone());
-                        Arbiter::spawn(async move {
-                            _sync_accumulator_node(
-                                node_key,
-                                best_peer.get_peer_id(),
-                                network_service,
-                                address,
-                            )
-                            .await;
-                        });
-                    }
-                } else {
-                    warn!("{:?}", "best peer is none.");
-                }
+            }
+            None => {
+                warn!("{:?}", "best peer is none.");
+                lock.push_front(node_key);
             }
         }
     }
 
-    fn handle_accumulator_sync(&mut self, task_event: StateSyncTaskEvent) {
+    fn handle_accumulator_sync(&mut self, task_event: StateSyncTaskEvent) -> Result<()> {
         let mut lock = self.accumulator_sync_task.lock();
         if let Some(accumulator_node_hash) = lock.get(&task_event.peer_id) {
             //1. push back
@@ -428,26 +782,30 @@ impl StateSyncTaskActor {
                 if let Some(accumulator_node) = task_event.accumulator_node {
                     if let Err(e) = self.storage.save_node(accumulator_node.clone()) {
                         error!("error : {:?}", e);
-                        lock.push_back(current_node_key);
+                        lock.push_front(current_node_key);
                     } else {
                         debug!("receive accumulator_node: {:?}", accumulator_node);
-                        match accumulator_node {
-                            AccumulatorNode::Leaf(_leaf) => {}
-                            AccumulatorNode::Internal(n) => {
-                                if n.left() != *ACCUMULATOR_PLACEHOLDER_HASH {
-                                    lock.push_back(n.left());
-                                }
-                                if n.right() != *ACCUMULATOR_PLACEHOLDER_HASH {
-                                    lock.push_back(n.right());
-                                }
-                            }
-                            _ => {
-                                warn!("node {:?} is null.", current_node_key);
-                            }
+                        self.accumulator_node_failures.remove(&current_node_key);
+                        lock.node_synced();
+                        let children = accumulator_node_children(&accumulator_node);
+                        if lock.push_all(children).is_err() {
+                            debug!("defer children of accumulator node {:?}.", current_node_key);
+                            self.deferred_accumulator_nodes.push_back(current_node_key);
                         }
                     }
                 } else {
-                    lock.push_back(current_node_key);
+                    if !self
+                        .accumulator_node_failures
+                        .record(current_node_key, task_event.peer_id)
+                    {
+                        return Err(format_err!(
+                            "sync accumulator node {:?} failed on peers {:?}",
+                            current_node_key,
+                            self.accumulator_node_failures
+                                .failed_peers(&current_node_key)
+                        ));
+                    }
+                    lock.push_front(current_node_key);
                 }
             } else {
                 warn!(
@@ -458,14 +816,40 @@ impl StateSyncTaskActor {
         } else {
             warn!("discard state event : {:?}", task_event);
         }
+        Ok(())
     }
 
     pub fn reset(&mut self, state_root: &HashValue, accumulator_root: &HashValue) {
         info!("reset state sync task.");
-        let mut lock = self.state_sync_task.lock();
-        lock.clear();
         self.roots = Roots::new(state_root.clone(), accumulator_root.clone());
-        lock.push_back((self.roots.state_root().clone(), true));
+        self.state_sync_task
+            .lock()
+            .reset(Some((self.roots.state_root().clone(), true)));
+        self.accumulator_sync_task
+            .lock()
+            .reset(self.roots.accumulator_sync_root());
+        self.state_node_failures.clear();
+        self.accumulator_node_failures.clear();
+        self.deferred_state_nodes.clear();
+        self.deferred_accumulator_nodes.clear();
+        self.pending_storage_roots.clear();
+        self.progress_logged = 0;
+    }
+
+    fn progress(&self) -> StateSyncProgress {
+        StateSyncProgress::new(
+            &*self.state_sync_task.lock(),
+            &*self.accumulator_sync_task.lock(),
+            self.applying_state_nodes + self.selecting_peers,
+        )
+    }
+
+    fn log_progress(&mut self) {
+        let progress = self.progress();
+        if progress.synced >= self.progress_logged + PROGRESS_LOG_INTERVAL {
+            info!("state sync progress: {:?}", progress);
+            self.progress_logged = progress.synced;
+        }
     }
 }
 
@@ -474,8 +858,8 @@ impl Actor for StateSyncTaskActor {
 
     fn started(&mut self, ctx: &mut Self::Context) {
         info!("StateSyncTaskActor actor started.");
-        self.exe_state_sync_task(ctx.address());
-        //self.exe_accumulator_sync_task(ctx.address());
+        self.exe_state_sync_task(ctx);
+        self.exe_accumulator_sync_task(ctx);
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
@@ -488,11 +872,17 @@ impl Handler<StateSyncTaskEvent> for StateSyncTaskActor {
 
     fn handle(&mut self, task_event: StateSyncTaskEvent, ctx: &mut Self::Context) -> Self::Result {
         let state_or_accumulator = task_event.is_state();
-        if state_or_accumulator {
-            self.handle_state_sync(task_event);
+        let handled = if state_or_accumulator {
+            self.handle_state_sync(task_event, ctx.address())
         } else {
-            self.handle_accumulator_sync(task_event);
+            self.handle_accumulator_sync(task_event)
+        };
+        if let Err(e) = handled {
+            error!("state sync failed: {:?}", e);
+            ctx.stop();
+            return Err(e);
         }
+        self.log_progress();
 
         if self.sync_end() {
             info!("state sync end");
@@ -503,17 +893,48 @@ impl Handler<StateSyncTaskEvent> for StateSyncTaskActor {
 
                 ctx.stop();
             }
+        } else if state_or_accumulator {
+            self.exe_state_sync_task(ctx);
         } else {
-            if state_or_accumulator {
-                self.exe_state_sync_task(ctx.address());
+            self.exe_accumulator_sync_task(ctx);
+        }
+        Ok(())
+    }
+}
+
+impl Handler<StateNodeApplied> for StateSyncTaskActor {
+    type Result = Result<()>;
+
+    fn handle(&mut self, applied: StateNodeApplied, ctx: &mut Self::Context) -> Self::Result {
+        if let Err(e) = self.handle_state_node_applied(applied) {
+            error!("state sync failed: {:?}", e);
+            ctx.stop();
+            return Err(e);
+        }
+        self.log_progress();
+        if self.sync_end() {
+            info!("state sync end");
+            if let Err(e) = self.sync_metadata.state_sync_done() {
+                warn!("err:{:?}", e);
             } else {
-                //self.exe_accumulator_sync_task(ctx.address());
+                info!("sync_done : {:?}", self.sync_metadata.get_pivot());
+                ctx.stop();
             }
+        } else {
+            self.exe_state_sync_task(ctx);
         }
         Ok(())
     }
 }
 
+impl Handler<GetStateSyncProgress> for StateSyncTaskActor {
+    type Result = Result<StateSyncProgress>;
+
+    fn handle(&mut self, _msg: GetStateSyncProgress, _ctx: &mut Self::Context) -> Self::Result {
+        Ok(self.progress())
+    }
+}
+
 #[derive(Default, Debug, Message)]
 #[rtype(result = "Result<()>")]
 struct StateSyncEvent {
@@ -0,0 +1,49 @@
+use crypto::hash::HashValue;
+use std::collections::HashMap;
+use types::peer_info::PeerId;
+
+/// Peers failed to serve a node being synced, so the node is requested from other peers and
+/// given up after too many failures.
+pub struct NodePeerFailures {
+    max_failures: usize,
+    failures: HashMap<HashValue, Vec<PeerId>>,
+}
+
+impl NodePeerFailures {
+    pub fn new(max_failures: usize) -> Self {
+        Self {
+            max_failures: max_failures.max(1),
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Record a failure of the node on the peer, return false when the node has failed
+    /// `max_failures` times and should not be retried.
+    pub fn record(&mut self, node_key: HashValue, peer_id: PeerId) -> bool {
+        let peers = self.failures.entry(node_key).or_insert_with(Vec::new);
+        peers.push(peer_id);
+        peers.len() < self.max_failures
+    }
+
+    /// Peers the node failed on, a peer failed more than once is listed once.
+    pub fn failed_peers(&self, node_key: &HashValue) -> Vec<PeerId> {
+        let mut peers = Vec::new();
+        if let Some(failed) = self.failures.get(node_key) {
+            for peer_id in failed {
+                if !peers.contains(peer_id) {
+                    peers.push(peer_id.clone());
+                }
+            }
+        }
+        peers
+    }
+
+    /// The node is synced.
+    pub fn remove(&mut self, node_key: &HashValue) {
+        self.failures.remove(node_key);
+    }
+
+    pub fn clear(&mut self) {
+        self.failures.clear();
+    }
+}
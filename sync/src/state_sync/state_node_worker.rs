@@ -0,0 +1,163 @@
+use anyhow::{format_err, Error, Result};
+use crypto::hash::HashValue;
+use forkable_jellyfish_merkle::node_type::Node;
+use forkable_jellyfish_merkle::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use logger::prelude::*;
+use starcoin_state_tree::StateNode;
+use starcoin_storage::Store;
+use std::convert::TryFrom;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use types::account_state::AccountState;
+
+/// Pending jobs of every worker before `apply` blocks the caller.
+const JOBS_PER_WORKER: usize = 16;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Debug)]
+pub enum StateNodeApplyResult {
+    /// The node is saved, with the child nodes to sync next.
+    Applied(Vec<(HashValue, bool)>),
+    /// The node can not be decoded, it is not saved and should not be retried.
+    Invalid(Error),
+    /// The node is valid but saving it failed, it should be synced again.
+    StoreFailed(Error),
+}
+
+/// Child nodes of a state node, a global leaf is decoded to an `AccountState` to find the
+/// storage roots of the account.
+pub fn state_node_children(
+    state_node: &StateNode,
+    is_global: bool,
+) -> Result<Vec<(HashValue, bool)>> {
+    let mut children = Vec::new();
+    match state_node.inner() {
+        Node::Leaf(leaf) => {
+            if is_global {
+                let account_state = AccountState::try_from(leaf.blob().as_ref())?;
+                for hash in account_state.storage_roots().iter().flatten() {
+                    if *hash != *SPARSE_MERKLE_PLACEHOLDER_HASH {
+                        children.push((*hash, false));
+                    }
+                }
+            }
+        }
+        Node::Internal(n) => {
+            for child in n.all_child() {
+                children.push((child, is_global));
+            }
+        }
+        _ => {
+            warn!("node {:?} is null.", state_node.0.hash());
+        }
+    }
+    Ok(children)
+}
+
+/// Validate and save a received state node.
+pub fn apply_state_node(
+    storage: &dyn Store,
+    node_key: HashValue,
+    state_node: StateNode,
+    is_global: bool,
+) -> StateNodeApplyResult {
+    let children = match state_node_children(&state_node, is_global) {
+        Ok(children) => children,
+        Err(e) => {
+            return StateNodeApplyResult::Invalid(format_err!(
+                "decode state node {:?} failed: {:?}",
+                node_key,
+                e
+            ))
+        }
+    };
+    // subtrees shared by many accounts are received again, only the presence is checked.
+    if let Ok(true) = storage.contains(&node_key) {
+        return StateNodeApplyResult::Applied(children);
+    }
+    match storage.put(node_key, state_node) {
+        Ok(()) => StateNodeApplyResult::Applied(children),
+        Err(e) => StateNodeApplyResult::StoreFailed(e),
+    }
+}
+
+/// A bounded pool of threads decoding and saving received state nodes, so that the state
+/// sync actor only does the tree walk bookkeeping.
+pub struct StateNodeWorkerPool {
+    sender: Option<SyncSender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl StateNodeWorkerPool {
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        let (sender, receiver) = sync_channel::<Job>(size * JOBS_PER_WORKER);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..size)
+            .map(|i| {
+                let receiver = receiver.clone();
+                thread::Builder::new()
+                    .name(format!("state-node-worker-{}", i))
+                    .spawn(move || Self::work(receiver))
+                    .expect("spawn state node worker failed.")
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    fn work(receiver: Arc<Mutex<Receiver<Job>>>) {
+        loop {
+            let job = receiver
+                .lock()
+                .expect("lock state node jobs failed.")
+                .recv();
+            match job {
+                Ok(job) => job(),
+                // the pool is dropped.
+                Err(_) => break,
+            }
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Apply the state node on a worker, `callback` is called on the worker with the result.
+    pub fn apply<F>(
+        &self,
+        storage: Arc<dyn Store>,
+        node_key: HashValue,
+        state_node: StateNode,
+        is_global: bool,
+        callback: F,
+    ) where
+        F: FnOnce(HashValue, bool, StateNodeApplyResult) + Send + 'static,
+    {
+        let job = Box::new(move || {
+            let result = apply_state_node(storage.as_ref(), node_key, state_node, is_global);
+            callback(node_key, is_global, result);
+        });
+        if let Some(sender) = &self.sender {
+            if let Err(e) = sender.send(job) {
+                error!("send state node job failed: {:?}", e);
+            }
+        }
+    }
+}
+
+impl Drop for StateNodeWorkerPool {
+    fn drop(&mut self) {
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            if let Err(e) = worker.join() {
+                error!("state node worker panic: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crypto::hash::HashValue;
+use std::collections::HashSet;
+
+/// Storage roots of synced accounts whose resource sub-trees are not applied yet, state sync
+/// is not done until every one of them is applied.
+#[derive(Default)]
+pub struct PendingStorageRoots {
+    roots: HashSet<HashValue>,
+}
+
+impl PendingStorageRoots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the storage roots among the children of an applied node, they are the children
+    /// of a global node which are not global.
+    pub fn track(&mut self, is_global: bool, children: &[(HashValue, bool)]) {
+        if is_global {
+            for (child, child_is_global) in children {
+                if !child_is_global {
+                    self.roots.insert(*child);
+                }
+            }
+        }
+    }
+
+    /// The node is applied, it is no longer pending if it is a storage root.
+    pub fn applied(&mut self, node_key: &HashValue, is_global: bool) {
+        if !is_global {
+            self.roots.remove(node_key);
+        }
+    }
+
+    pub fn contains(&self, node_key: &HashValue) -> bool {
+        self.roots.contains(node_key)
+    }
+
+    pub fn roots(&self) -> Vec<HashValue> {
+        self.roots.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.roots.clear();
+    }
+}
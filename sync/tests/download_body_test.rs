@@ -0,0 +1,266 @@
+use anyhow::format_err;
+use crypto::hash::HashValue;
+use futures::executor::block_on;
+use starcoin_sync::download_body::{check_bodies, download_bodies};
+use starcoin_sync_api::sync_messages::{BatchBlockInfo, BatchBodyMsg, BatchHeaderMsg};
+use types::{
+    account_address::AccountAddress,
+    block::{BlockBody, BlockHeader, BlockInfo},
+    peer_info::{PeerId, PeerInfo},
+    transaction::SignedUserTransaction,
+    U256, U512,
+};
+
+/// The leaves of the txn accumulator of the local parent of the first header.
+const PARENT_NUM_LEAVES: u64 = 10;
+
+/// Headers of a chain, each is the parent of the next one.
+fn gen_headers(count: u64) -> Vec<BlockHeader> {
+    let mut parent_hash = HashValue::random();
+    (0..count)
+        .map(|number| {
+            let header = BlockHeader::new(
+                parent_hash,
+                number,
+                number,
+                AccountAddress::random(),
+                HashValue::random(),
+                HashValue::zero(),
+                0,
+                0,
+                U256::zero(),
+                vec![],
+            );
+            parent_hash = header.id();
+            header
+        })
+        .collect()
+}
+
+fn gen_parent_info(headers: &[BlockHeader]) -> BlockInfo {
+    BlockInfo::new(
+        headers[0].parent_hash(),
+        HashValue::random(),
+        vec![],
+        PARENT_NUM_LEAVES,
+        0,
+        U512::zero(),
+    )
+}
+
+/// The block at `i` has `i` txns.
+fn gen_bodies(headers: &[BlockHeader]) -> Vec<BlockBody> {
+    (0..headers.len())
+        .map(|i| BlockBody::new((0..i).map(|_| SignedUserTransaction::mock()).collect()))
+        .collect()
+}
+
+fn gen_response(headers: &[BlockHeader]) -> (BatchHeaderMsg, BatchBodyMsg, BatchBlockInfo) {
+    let bodies = gen_bodies(headers);
+    // every block appends its txns and the block metadata txn.
+    let mut num_leaves = PARENT_NUM_LEAVES;
+    let infos = headers
+        .iter()
+        .zip(bodies.iter())
+        .map(|(header, body)| {
+            num_leaves += body.len() as u64 + 1;
+            BlockInfo::new(
+                header.id(),
+                header.accumulator_root(),
+                vec![],
+                num_leaves,
+                0,
+                U512::zero(),
+            )
+        })
+        .collect();
+    (
+        BatchHeaderMsg {
+            headers: headers.to_vec(),
+        },
+        BatchBodyMsg { bodies },
+        BatchBlockInfo { infos },
+    )
+}
+
+#[test]
+fn test_download_bodies_from_second_peer() {
+    let headers = gen_headers(3);
+    let failing_peer = PeerInfo::new_for_test(PeerId::random());
+    let good_peer = PeerInfo::new_for_test(PeerId::random());
+    let mut requested = vec![];
+    let parent_info = gen_parent_info(&headers);
+    let (bodies, infos) = block_on(download_bodies(
+        &headers,
+        Some(&parent_info),
+        vec![failing_peer.clone(), good_peer.clone()],
+        |peer_id, hashs| {
+            requested.push(peer_id.clone());
+            assert_eq!(hashs.len(), headers.len());
+            let response = if peer_id == failing_peer.get_peer_id() {
+                Err(format_err!("rpc timeout"))
+            } else {
+                Ok(gen_response(&headers))
+            };
+            async move { response }
+        },
+    ))
+    .unwrap();
+    assert_eq!(
+        requested,
+        vec![failing_peer.get_peer_id(), good_peer.get_peer_id()]
+    );
+    assert_eq!(bodies.len(), headers.len());
+    assert_eq!(infos.len(), headers.len());
+}
+
+#[test]
+fn test_download_bodies_all_peers_failed() {
+    let headers = gen_headers(2);
+    let peers = vec![
+        PeerInfo::new_for_test(PeerId::random()),
+        PeerInfo::new_for_test(PeerId::random()),
+    ];
+    let mut requests = 0;
+    let result = block_on(download_bodies(
+        &headers,
+        None,
+        peers,
+        |_peer_id, _hashs| {
+            requests += 1;
+            // one body is missing.
+            let response = Ok(gen_response(&headers[1..]));
+            async move { response }
+        },
+    ));
+    assert!(result.is_err());
+    assert_eq!(requests, 2);
+}
+
+#[test]
+fn test_swapped_bodies_rejected() {
+    let headers = gen_headers(3);
+    let parent_info = gen_parent_info(&headers);
+    let (returned_headers, bodies, infos) = gen_response(&headers);
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &bodies.bodies,
+        &infos.infos,
+        Some(&parent_info)
+    )
+    .is_ok());
+
+    // the first two blocks are returned swapped.
+    let mut swapped_headers = returned_headers.headers.clone();
+    swapped_headers.swap(0, 1);
+    let mut swapped_bodies = bodies.bodies.clone();
+    swapped_bodies.swap(0, 1);
+    let mut swapped_infos = infos.infos.clone();
+    swapped_infos.swap(0, 1);
+    assert!(check_bodies(
+        &headers,
+        &swapped_headers,
+        &swapped_bodies,
+        &swapped_infos,
+        Some(&parent_info)
+    )
+    .is_err());
+    // an info of another block.
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &bodies.bodies,
+        &swapped_infos,
+        Some(&parent_info)
+    )
+    .is_err());
+
+    // only the bodies are swapped, the txn counts mismatch the infos.
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &swapped_bodies,
+        &infos.infos,
+        Some(&parent_info)
+    )
+    .is_err());
+
+    let bad_peer = PeerInfo::new_for_test(PeerId::random());
+    let good_peer = PeerInfo::new_for_test(PeerId::random());
+    let mut requested = vec![];
+    let result = block_on(download_bodies(
+        &headers,
+        Some(&parent_info),
+        vec![bad_peer.clone(), good_peer.clone()],
+        |peer_id, _hashs| {
+            requested.push(peer_id.clone());
+            let response = if peer_id == bad_peer.get_peer_id() {
+                Ok((
+                    BatchHeaderMsg {
+                        headers: swapped_headers.clone(),
+                    },
+                    BatchBodyMsg {
+                        bodies: swapped_bodies.clone(),
+                    },
+                    BatchBlockInfo {
+                        infos: swapped_infos.clone(),
+                    },
+                ))
+            } else {
+                Ok(gen_response(&headers))
+            };
+            async move { response }
+        },
+    ));
+    let (_, infos) = result.unwrap();
+    assert_eq!(
+        requested,
+        vec![bad_peer.get_peer_id(), good_peer.get_peer_id()]
+    );
+    for (header, info) in headers.iter().zip(infos.iter()) {
+        assert_eq!(info.block_id, header.id());
+    }
+}
+
+#[test]
+fn test_info_accumulator_root_mismatch() {
+    let headers = gen_headers(3);
+    let parent_info = gen_parent_info(&headers);
+    let (returned_headers, bodies, mut infos) = gen_response(&headers);
+    infos.infos[1].accumulator_root = HashValue::random();
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &bodies.bodies,
+        &infos.infos,
+        Some(&parent_info)
+    )
+    .is_err());
+}
+
+#[test]
+fn test_first_body_checked_against_local_parent() {
+    let headers = gen_headers(3);
+    let (returned_headers, mut bodies, infos) = gen_response(&headers);
+    // the first block has an extra txn, its info does not grow from the local parent by it.
+    bodies.bodies[0] = BlockBody::new(vec![SignedUserTransaction::mock()]);
+    let parent_info = gen_parent_info(&headers);
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &bodies.bodies,
+        &infos.infos,
+        Some(&parent_info)
+    )
+    .is_err());
+    // without the local parent info, the first block can not be checked.
+    assert!(check_bodies(
+        &headers,
+        &returned_headers.headers,
+        &bodies.bodies,
+        &infos.infos,
+        None
+    )
+    .is_ok());
+}
@@ -0,0 +1,75 @@
+use config::ChainNetwork;
+use crypto::hash::HashValue;
+use starcoin_genesis::Genesis;
+use starcoin_state_tree::StateNodeStore;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_sync::state_sync::{apply_state_node, PendingStorageRoots, StateNodeApplyResult};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+fn new_storage() -> Arc<Storage> {
+    Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap())
+}
+
+#[test]
+fn test_storage_roots_served_late() {
+    let source = new_storage();
+    let startup_info = Genesis::build(ChainNetwork::Dev)
+        .unwrap()
+        .execute(source.clone())
+        .unwrap();
+    let state_root = source
+        .get_block_header_by_hash(startup_info.master.get_head())
+        .unwrap()
+        .unwrap()
+        .state_root();
+
+    let target = new_storage();
+    let mut pending = PendingStorageRoots::new();
+    let apply = |node_key: HashValue, is_global: bool, pending: &mut PendingStorageRoots| {
+        let state_node = StateNodeStore::get(source.as_ref(), &node_key)
+            .unwrap()
+            .unwrap();
+        match apply_state_node(target.as_ref(), node_key, state_node, is_global) {
+            StateNodeApplyResult::Applied(children) => {
+                pending.applied(&node_key, is_global);
+                pending.track(is_global, &children);
+                children
+            }
+            other => panic!("apply state node {:?} failed: {:?}", node_key, other),
+        }
+    };
+
+    // the global tree is served first, the resource sub-trees are held back.
+    let mut global_nodes = VecDeque::new();
+    let mut late_nodes = VecDeque::new();
+    global_nodes.push_back(state_root);
+    while let Some(node_key) = global_nodes.pop_front() {
+        for (child, is_global) in apply(node_key, true, &mut pending) {
+            if is_global {
+                global_nodes.push_back(child);
+            } else {
+                late_nodes.push_back(child);
+            }
+        }
+    }
+    assert!(!late_nodes.is_empty());
+    assert!(!pending.is_empty());
+    for root in &late_nodes {
+        assert!(pending.contains(root));
+        assert!(StateNodeStore::get(target.as_ref(), root)
+            .unwrap()
+            .is_none());
+    }
+
+    while let Some(node_key) = late_nodes.pop_front() {
+        for (child, is_global) in apply(node_key, false, &mut pending) {
+            assert!(!is_global);
+            late_nodes.push_back(child);
+        }
+    }
+    assert!(pending.is_empty());
+    assert_eq!(pending.len(), 0);
+}
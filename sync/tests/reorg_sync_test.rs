@@ -0,0 +1,95 @@
+mod gen_network;
+mod test_node;
+
+use actix_rt::System;
+use logger::prelude::*;
+use std::time::Duration;
+use test_node::{SyncTestNode, TEST_DIFFICULTY};
+use traits::ChainAsyncService;
+use types::U256;
+
+#[test]
+fn test_reorg_to_higher_difficulty_branch() {
+    ::logger::init_for_test();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let handle = rt.handle().clone();
+    let mut system = System::new("test");
+
+    let fut = async move {
+        let node_a = SyncTestNode::launch(handle.clone(), None).await.unwrap();
+        let fork_point = node_a.produce_blocks(1).await.unwrap();
+
+        // master branch: 3 light blocks on top of the fork point.
+        let old_blocks = node_a
+            .produce_branch(fork_point, 3, TEST_DIFFICULTY.into())
+            .await
+            .unwrap();
+        let old_head = old_blocks.last().unwrap().header().id();
+        assert_eq!(node_a.head_id().await.unwrap(), old_head);
+
+        // node b follows the light branch first.
+        let node_b = SyncTestNode::launch(handle, Some(&node_a)).await.unwrap();
+        node_b
+            .wait_for_head(old_head, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // competing branch: 3 heavier blocks on top of the same fork point.
+        let new_blocks = node_a
+            .produce_branch(fork_point, 3, U256::from(TEST_DIFFICULTY * 10))
+            .await
+            .unwrap();
+        let new_head = new_blocks.last().unwrap().header().id();
+        debug!("old head : {:?}, new head : {:?}", old_head, new_head);
+        assert_eq!(node_a.head_id().await.unwrap(), new_head);
+        let new_info = node_a
+            .chain
+            .clone()
+            .get_block_info_by_hash(&new_head)
+            .await
+            .unwrap();
+
+        // node b receives the heavier branch over the network and switches to it.
+        node_b
+            .wait_for_head(new_head, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let info_b = node_b
+            .chain
+            .clone()
+            .get_block_info_by_hash(&new_head)
+            .await
+            .unwrap();
+        assert_eq!(info_b.total_difficulty, new_info.total_difficulty);
+        let old_info_b = node_b
+            .chain
+            .clone()
+            .get_block_info_by_hash(&old_head)
+            .await
+            .unwrap();
+        assert!(info_b.total_difficulty > old_info_b.total_difficulty);
+
+        // number index of node b follows the new canonical branch.
+        for (new_block, old_block) in new_blocks.iter().zip(old_blocks.iter()) {
+            let number = new_block.header().number();
+            assert_eq!(number, old_block.header().number());
+            let canonical = node_b
+                .chain
+                .clone()
+                .master_block_by_number(number)
+                .await
+                .unwrap();
+            assert_eq!(canonical.header().id(), new_block.header().id());
+            // abandoned blocks are still reachable by hash.
+            assert!(node_b
+                .chain
+                .clone()
+                .get_block_by_hash(old_block.header().id())
+                .await
+                .is_ok());
+        }
+    };
+
+    system.block_on(fut);
+    drop(rt);
+}
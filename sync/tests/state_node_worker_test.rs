@@ -0,0 +1,190 @@
+mod gen_network;
+
+use actix_rt::System;
+use bus::BusActor;
+use config::{ChainNetwork, NodeConfig};
+use crypto::hash::HashValue;
+use forkable_jellyfish_merkle::{blob::Blob, node_type::Node};
+use futures_timer::Delay;
+use gen_network::gen_network;
+use starcoin_accumulator::node::ACCUMULATOR_PLACEHOLDER_HASH;
+use starcoin_genesis::Genesis;
+use starcoin_state_tree::{StateNode, StateNodeStore};
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_sync::state_sync::{StateNodeApplyResult, StateNodeWorkerPool, StateSyncTaskActor};
+use starcoin_sync_api::SyncMetadata;
+use std::collections::HashSet;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn new_storage() -> Arc<Storage> {
+    Arc::new(Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap())
+}
+
+#[test]
+fn test_apply_state_nodes_with_workers() {
+    let source = new_storage();
+    let startup_info = Genesis::build(ChainNetwork::Dev)
+        .unwrap()
+        .execute(source.clone())
+        .unwrap();
+    let state_root = source
+        .get_block_header_by_hash(startup_info.master.get_head())
+        .unwrap()
+        .unwrap()
+        .state_root();
+
+    let target = new_storage();
+    let pool = StateNodeWorkerPool::new(4);
+    assert_eq!(pool.size(), 4);
+    let (sender, receiver) = channel();
+    let mut applied = HashSet::new();
+    let mut pending = 0;
+    let apply = |node_key: HashValue, is_global: bool, pending: &mut usize| {
+        let state_node = StateNodeStore::get(source.as_ref(), &node_key)
+            .unwrap()
+            .unwrap();
+        let sender = sender.clone();
+        *pending += 1;
+        pool.apply(
+            target.clone(),
+            node_key,
+            state_node,
+            is_global,
+            move |node_key, _is_global, result| {
+                sender.send((node_key, result)).unwrap();
+            },
+        );
+    };
+    apply(state_root, true, &mut pending);
+    while pending > 0 {
+        let (node_key, result) = receiver.recv().unwrap();
+        pending -= 1;
+        applied.insert(node_key);
+        match result {
+            StateNodeApplyResult::Applied(children) => {
+                for (child, is_global) in children {
+                    apply(child, is_global, &mut pending);
+                }
+            }
+            other => panic!("apply state node {:?} failed: {:?}", node_key, other),
+        }
+    }
+
+    // genesis state has accounts with resources, so there are global and storage nodes.
+    assert!(applied.len() > 1);
+    for node_key in applied {
+        assert_eq!(
+            StateNodeStore::get(target.as_ref(), &node_key).unwrap(),
+            StateNodeStore::get(source.as_ref(), &node_key).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_apply_invalid_state_node() {
+    let target = new_storage();
+    let pool = StateNodeWorkerPool::new(2);
+    let (sender, receiver) = channel();
+
+    // a global leaf must be an account state.
+    let bad_node = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(vec![1u8, 2, 3]),
+    ));
+    let bad_key = bad_node.0.hash();
+    let good_node = StateNode(Node::new_leaf(
+        HashValue::random(),
+        Blob::from(vec![4u8, 5, 6]),
+    ));
+    let good_key = good_node.0.hash();
+    for (node_key, state_node, is_global) in
+        vec![(bad_key, bad_node, true), (good_key, good_node, false)]
+    {
+        let sender = sender.clone();
+        pool.apply(
+            target.clone(),
+            node_key,
+            state_node,
+            is_global,
+            move |node_key, _is_global, result| {
+                sender.send((node_key, result)).unwrap();
+            },
+        );
+    }
+
+    for _ in 0..2 {
+        let (node_key, result) = receiver.recv().unwrap();
+        if node_key == bad_key {
+            match result {
+                StateNodeApplyResult::Invalid(_) => {}
+                other => panic!("bad node should be invalid: {:?}", other),
+            }
+        } else {
+            assert_eq!(node_key, good_key);
+            match result {
+                StateNodeApplyResult::Applied(children) => assert!(children.is_empty()),
+                other => panic!("good node should be applied: {:?}", other),
+            }
+        }
+    }
+    assert!(StateNodeStore::get(target.as_ref(), &bad_key)
+        .unwrap()
+        .is_none());
+    assert!(StateNodeStore::get(target.as_ref(), &good_key)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_state_sync_fails_on_invalid_state_node() {
+    ::logger::init_for_test();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let handle = rt.handle().clone();
+    let mut system = System::new("test");
+
+    let fut = async move {
+        let mut config = NodeConfig::random_for_test();
+        config.sync.fast_sync_mode();
+        let node_config = Arc::new(config);
+        let bus = BusActor::launch();
+        let (network, peer_id) = gen_network(
+            node_config.clone(),
+            bus.clone(),
+            handle,
+            HashValue::random(),
+        );
+        let sync_metadata = SyncMetadata::new(node_config, bus);
+
+        // the root is in storage already, but a global leaf must be an account state.
+        let storage = new_storage();
+        let bad_root = StateNode(Node::new_leaf(
+            HashValue::random(),
+            Blob::from(vec![1u8, 2, 3]),
+        ));
+        let root_key = bad_root.0.hash();
+        StateNodeStore::put(storage.as_ref(), root_key, bad_root).unwrap();
+
+        let max_peer_failures = 2;
+        let task = StateSyncTaskActor::launch(
+            peer_id,
+            (root_key, *ACCUMULATOR_PLACEHOLDER_HASH),
+            storage,
+            network,
+            sync_metadata.clone(),
+            1,
+            max_peer_failures,
+            16,
+        );
+        Delay::new(Duration::from_secs(2)).await;
+
+        // the invalid root is retried, then the state sync fails instead of ending without it.
+        assert!(task.progress().await.is_err());
+        assert!(!sync_metadata.state_done());
+    };
+    system.block_on(fut);
+    drop(rt);
+}
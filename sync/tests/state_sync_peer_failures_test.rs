@@ -0,0 +1,49 @@
+use crypto::hash::HashValue;
+use futures::executor::block_on;
+use network_api::{DummyNetworkService, NetworkService};
+use starcoin_sync::state_sync::NodePeerFailures;
+use types::peer_info::{PeerId, PeerInfo};
+
+#[test]
+fn test_sync_node_from_alternate_peer() {
+    let failing_peer = PeerInfo::new_for_test(PeerId::random());
+    let good_peer = PeerInfo::new_for_test(PeerId::random());
+    let network = DummyNetworkService::new(
+        PeerId::random().into(),
+        vec![failing_peer.clone(), good_peer.clone()],
+    );
+    let mut failures = NodePeerFailures::new(3);
+    let node_key = HashValue::random();
+
+    let mut tried = vec![];
+    loop {
+        let peer = block_on(network.random_peer_excluding(&failures.failed_peers(&node_key)))
+            .unwrap()
+            .unwrap();
+        tried.push(peer.get_peer_id());
+        if peer == failing_peer {
+            assert!(failures.record(node_key, peer.get_peer_id()));
+        } else {
+            failures.remove(&node_key);
+            break;
+        }
+    }
+    // the failing peer is only tried once, then the good peer serves the node.
+    assert_eq!(
+        tried,
+        vec![failing_peer.get_peer_id(), good_peer.get_peer_id()]
+    );
+    assert!(failures.failed_peers(&node_key).is_empty());
+}
+
+#[test]
+fn test_give_up_after_max_peer_failures() {
+    let peer_id = PeerId::random();
+    let mut failures = NodePeerFailures::new(3);
+    let node_key = HashValue::random();
+    assert!(failures.record(node_key, peer_id.clone()));
+    assert!(failures.record(node_key, peer_id.clone()));
+    assert!(!failures.record(node_key, peer_id.clone()));
+    assert_eq!(failures.failed_peers(&node_key), vec![peer_id]);
+    assert!(failures.failed_peers(&HashValue::random()).is_empty());
+}
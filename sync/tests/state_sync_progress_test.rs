@@ -0,0 +1,138 @@
+mod gen_network;
+
+use actix_rt::System;
+use bus::BusActor;
+use config::NodeConfig;
+use crypto::hash::HashValue;
+use futures::future::join_all;
+use futures_timer::Delay;
+use gen_network::gen_network;
+use starcoin_genesis::Genesis;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_sync::state_sync::{StateSyncProgress, StateSyncTaskActor, SyncTask};
+use starcoin_sync_api::SyncMetadata;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use types::peer_info::PeerId;
+
+#[test]
+fn test_state_sync_progress() {
+    let mut state_task = SyncTask::new(16);
+    let mut accumulator_task: SyncTask<HashValue> = SyncTask::new(16);
+    state_task.reset(Some((HashValue::random(), true)));
+    accumulator_task.reset(None);
+    let peer_id = PeerId::random();
+
+    let mut last = StateSyncProgress::new(&state_task, &accumulator_task, 0);
+    assert_eq!(last.total_seen, 1);
+    assert_eq!(last.synced, 0);
+    assert_eq!(last.in_flight, 0);
+
+    let mut steps = 0;
+    while let Some(node) = state_task.pop_front() {
+        assert!(state_task.insert(peer_id.clone(), node).is_none());
+        let progress = StateSyncProgress::new(&state_task, &accumulator_task, 0);
+        assert_eq!(progress.in_flight, 1);
+        assert!(progress.total_seen >= last.total_seen);
+        assert!(progress.synced >= last.synced);
+
+        // every node has two children until the tree has 15 nodes.
+        let (_, is_global) = state_task.remove(&peer_id).unwrap();
+        state_task.node_synced();
+        if progress.total_seen < 15 {
+            state_task
+                .push_all(vec![
+                    (HashValue::random(), is_global),
+                    (HashValue::random(), is_global),
+                ])
+                .unwrap();
+        }
+        let progress = StateSyncProgress::new(&state_task, &accumulator_task, 0);
+        assert_eq!(progress.in_flight, 0);
+        assert!(progress.total_seen >= last.total_seen);
+        assert!(progress.synced > last.synced);
+        last = progress;
+        steps += 1;
+    }
+    assert_eq!(steps, 15);
+    assert_eq!(last.total_seen, 15);
+    assert_eq!(last.synced, 15);
+
+    // a retried node is not seen again.
+    state_task.push_front((HashValue::random(), true));
+    let progress = StateSyncProgress::new(&state_task, &accumulator_task, 0);
+    assert_eq!(progress.total_seen, last.total_seen);
+}
+
+#[test]
+fn test_state_sync_progress_under_flood() {
+    ::logger::init_for_test();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let handle = rt.handle().clone();
+    let mut system = System::new("test");
+
+    let fut = async move {
+        let mut config = NodeConfig::random_for_test();
+        config.sync.fast_sync_mode();
+        let node_config = Arc::new(config);
+        let bus = BusActor::launch();
+        let genesis = Genesis::build(node_config.net()).unwrap();
+        let (network, peer_id) = gen_network(
+            node_config.clone(),
+            bus.clone(),
+            handle,
+            genesis.block().header().id(),
+        );
+        let sync_metadata = SyncMetadata::new(node_config, bus);
+
+        // every node is found in storage, so the tree walk is driven by the actor's own events.
+        let storage = Arc::new(
+            Storage::new(StorageInstance::new_cache_instance(CacheStorage::new())).unwrap(),
+        );
+        let startup_info = genesis.execute(storage.clone()).unwrap();
+        let header = storage
+            .get_block_header_by_hash(startup_info.master.get_head())
+            .unwrap()
+            .unwrap();
+        let task = StateSyncTaskActor::launch(
+            peer_id,
+            (header.state_root(), header.accumulator_root()),
+            storage,
+            network,
+            sync_metadata.clone(),
+            2,
+            3,
+            16,
+        );
+
+        // flood the mailbox while the walk is running, every request is answered and the
+        // walk keeps going until it ends.
+        let begin = Instant::now();
+        let mut last = StateSyncProgress::default();
+        while !sync_metadata.state_done() {
+            assert!(
+                begin.elapsed() < Duration::from_secs(30),
+                "state sync stalled at {:?}",
+                last
+            );
+            let requests = (0..100).map(|_| task.progress());
+            for progress in join_all(requests).await {
+                match progress {
+                    Ok(progress) => {
+                        assert!(progress.synced >= last.synced);
+                        assert!(progress.total_seen >= last.total_seen);
+                        last = progress;
+                    }
+                    // the actor stops once the state sync is done.
+                    Err(_) => break,
+                }
+            }
+            Delay::new(Duration::from_millis(10)).await;
+        }
+        assert!(last.synced > 0);
+    };
+    system.block_on(fut);
+    drop(rt);
+}
@@ -12,10 +12,12 @@ use libp2p::multiaddr::Multiaddr;
 use logger::prelude::*;
 use miner::{MinerActor, MinerClientActor};
 use network_api::NetworkService;
+use starcoin_accumulator::AccumulatorReader;
 use starcoin_genesis::Genesis;
+use starcoin_state_tree::StateNodeStore;
 use starcoin_storage::cache_storage::CacheStorage;
 use starcoin_storage::storage::StorageInstance;
-use starcoin_storage::Storage;
+use starcoin_storage::{BlockStore, Storage};
 use starcoin_sync::SyncActor;
 use starcoin_sync_api::SyncMetadata;
 use starcoin_wallet_api::WalletAccount;
@@ -217,6 +219,21 @@ fn test_state_sync() {
             !sync_metadata_actor_2.state_syncing(),
             "state_syncing is true."
         );
+        // both the state tree and the accumulator of the pivot block are synced.
+        let pivot = sync_metadata_actor_2.get_pivot().unwrap().unwrap();
+        let pivot_header = storage_1
+            .get_block_header_by_number(pivot)
+            .unwrap()
+            .unwrap();
+        assert!(
+            StateNodeStore::get(storage_2.as_ref(), &pivot_header.state_root())
+                .unwrap()
+                .is_some()
+        );
+        assert!(storage_2
+            .get_node(pivot_header.accumulator_root())
+            .unwrap()
+            .is_some());
     };
 
     system.block_on(fut);
@@ -0,0 +1,63 @@
+use starcoin_sync::state_sync::SyncTask;
+use std::collections::{HashSet, VecDeque};
+
+const FAN_OUT: u64 = 4;
+const TREE_SIZE: u64 = 1365;
+
+/// Children of node `n` in a complete tree of `TREE_SIZE` nodes.
+fn children(n: u64) -> Vec<u64> {
+    (1..=FAN_OUT)
+        .map(|i| n * FAN_OUT + i)
+        .filter(|child| *child < TREE_SIZE)
+        .collect()
+}
+
+#[test]
+fn test_sync_task_capacity() {
+    let mut task = SyncTask::new(2);
+    assert_eq!(task.capacity(), 2);
+    assert!(task.push_back(1u64).is_ok());
+    assert!(task.has_room(1));
+    assert!(task.push_all(vec![2, 3]).is_err());
+    assert_eq!(task.len(), 1);
+    assert!(task.push_back(2).is_ok());
+    assert!(task.is_full());
+    assert_eq!(task.push_back(3), Err(3));
+    // a retried node is never refused.
+    task.push_front(3);
+    assert_eq!(task.len(), 3);
+    assert_eq!(task.pop_front(), Some(3));
+}
+
+#[test]
+fn test_sync_task_bounded_walk() {
+    let capacity = 8;
+    let mut task = SyncTask::new(capacity);
+    let mut deferred = VecDeque::new();
+    let mut synced = HashSet::new();
+    task.push_front(0u64);
+
+    loop {
+        // expand deferred nodes while there is room, as the state sync actor does.
+        while let Some(node) = deferred.front().cloned() {
+            if task.push_all(children(node)).is_err() {
+                break;
+            }
+            deferred.pop_front();
+        }
+        assert!(task.len() <= capacity);
+        let node = match task.pop_front() {
+            Some(node) => node,
+            None => break,
+        };
+        assert!(synced.insert(node));
+        if task.push_all(children(node)).is_err() {
+            assert!(!task.has_room(children(node).len()));
+            deferred.push_back(node);
+        }
+        assert!(task.len() <= capacity);
+    }
+
+    assert!(deferred.is_empty());
+    assert_eq!(synced.len() as u64, TREE_SIZE);
+}
@@ -0,0 +1,206 @@
+#![allow(dead_code)]
+
+use crate::gen_network::gen_network;
+use actix::Addr;
+use anyhow::{format_err, Result};
+use bus::{Broadcast, BusActor};
+use chain::{ChainActor, ChainActorRef};
+use config::{get_available_port, NodeConfig};
+use consensus::dummy::{DummyConsensus, DummyHeader};
+use crypto::hash::HashValue;
+use futures_timer::Delay;
+use libp2p::multiaddr::Multiaddr;
+use logger::prelude::*;
+use network::network::NetworkAsyncService;
+use starcoin_genesis::Genesis;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::Storage;
+use starcoin_sync::SyncActor;
+use starcoin_sync_api::SyncMetadata;
+use starcoin_wallet_api::WalletAccount;
+use std::{sync::Arc, time::Duration};
+use tokio::runtime::Handle;
+use traits::ChainAsyncService;
+use txpool::TxPoolRef;
+use types::{block::Block, peer_info::PeerId, system_events::SystemEvents, U256};
+
+/// Fixed difficulty used for blocks produced by the harness, so a run does not depend on the
+/// random difficulty of `DummyConsensus`.
+pub const TEST_DIFFICULTY: u64 = 100;
+
+/// An in-process node with memory storage and dummy consensus, used by the sync integration tests.
+pub struct SyncTestNode {
+    pub config: Arc<NodeConfig>,
+    pub bus: Addr<BusActor>,
+    pub storage: Arc<Storage>,
+    pub txpool: TxPoolRef,
+    pub network: NetworkAsyncService,
+    pub peer_id: PeerId,
+    pub chain: ChainActorRef<DummyConsensus>,
+    pub sync: Addr<SyncActor<DummyConsensus>>,
+    miner_account: WalletAccount,
+}
+
+impl SyncTestNode {
+    /// Boot a node, optionally dialing `seed` on startup, and begin sync.
+    pub async fn launch(handle: Handle, seed: Option<&SyncTestNode>) -> Result<Self> {
+        let bus = BusActor::launch();
+        let storage = Arc::new(Storage::new(StorageInstance::new_cache_instance(
+            CacheStorage::new(),
+        ))?);
+
+        let mut config = NodeConfig::random_for_test();
+        config.network.listen = format!("/ip4/127.0.0.1/tcp/{}", get_available_port()).parse()?;
+        if let Some(seed) = seed {
+            let seed_addr: Multiaddr = format!(
+                "{}/p2p/{}",
+                &seed.config.network.listen,
+                seed.peer_id.to_base58()
+            )
+            .parse()?;
+            config.network.seeds = vec![seed_addr];
+        }
+        let config = Arc::new(config);
+
+        let genesis = Genesis::build(config.net())?;
+        let genesis_hash = genesis.block().header().id();
+        let startup_info = genesis.execute(storage.clone())?;
+        let txpool = TxPoolRef::start(
+            config.tx_pool.clone(),
+            storage.clone(),
+            startup_info.master.get_head(),
+            bus.clone(),
+        );
+
+        let (network, peer_id) = gen_network(config.clone(), bus.clone(), handle, genesis_hash);
+        debug!("launch sync test node: {:?}", peer_id);
+
+        let sync_metadata = SyncMetadata::new(config.clone(), bus.clone());
+        let chain = ChainActor::<DummyConsensus>::launch(
+            config.clone(),
+            startup_info,
+            storage.clone(),
+            Some(network.clone()),
+            bus.clone(),
+            txpool.clone(),
+            sync_metadata.clone(),
+        )?;
+        let sync = SyncActor::<DummyConsensus>::launch(
+            config.clone(),
+            bus.clone(),
+            Arc::new(network.identify().clone().into()),
+            chain.clone(),
+            txpool.clone(),
+            network.clone(),
+            storage.clone(),
+            sync_metadata,
+        )?;
+        Delay::new(Duration::from_secs(1)).await;
+        bus.clone()
+            .send(Broadcast {
+                msg: SystemEvents::SyncBegin(),
+            })
+            .await?;
+
+        Ok(Self {
+            config,
+            bus,
+            storage,
+            txpool,
+            network,
+            peer_id,
+            chain,
+            sync,
+            miner_account: WalletAccount::random(),
+        })
+    }
+
+    /// Build a block on top of `parent_hash` (or the master head) with the given difficulty.
+    pub async fn create_block(
+        &self,
+        parent_hash: Option<HashValue>,
+        difficulty: U256,
+    ) -> Result<Block> {
+        let template = self
+            .chain
+            .clone()
+            .create_block_template(
+                *self.miner_account.address(),
+                Some(self.miner_account.get_auth_key().prefix().to_vec()),
+                parent_hash,
+                Vec::new(),
+            )
+            .await?;
+        Ok(template.into_block(DummyHeader {}, difficulty))
+    }
+
+    /// Produce `count` blocks on the master head and return the id of the last one.
+    pub async fn produce_blocks(&self, count: u64) -> Result<HashValue> {
+        let mut head = self.head_id().await?;
+        for _ in 0..count {
+            let block = self.create_block(None, TEST_DIFFICULTY.into()).await?;
+            head = block.header().id();
+            self.connect_block(block).await?;
+        }
+        Ok(head)
+    }
+
+    /// Produce a branch of `count` blocks on top of `parent_hash` with the given difficulty.
+    pub async fn produce_branch(
+        &self,
+        parent_hash: HashValue,
+        count: u64,
+        difficulty: U256,
+    ) -> Result<Vec<Block>> {
+        let mut parent_hash = parent_hash;
+        let mut blocks = Vec::new();
+        for _ in 0..count {
+            let block = self.create_block(Some(parent_hash), difficulty).await?;
+            parent_hash = block.header().id();
+            self.connect_block(block.clone()).await?;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Connect the block to the chain, fail if the chain does not accept it.
+    async fn connect_block(&self, block: Block) -> Result<()> {
+        let block_id = block.header().id();
+        self.chain
+            .clone()
+            .try_connect(block)
+            .await?
+            .map_err(|e| format_err!("connect block {:?} failed: {}", block_id, e))
+    }
+
+    pub async fn head_id(&self) -> Result<HashValue> {
+        self.chain
+            .clone()
+            .master_head_header()
+            .await
+            .map(|header| header.id())
+            .ok_or_else(|| format_err!("master head header is none."))
+    }
+
+    /// Poll the master head until it equals `expect`, or fail after `timeout`.
+    pub async fn wait_for_head(&self, expect: HashValue, timeout: Duration) -> Result<()> {
+        let interval = Duration::from_millis(500);
+        let mut waited = Duration::from_secs(0);
+        loop {
+            let head = self.head_id().await?;
+            if head == expect {
+                return Ok(());
+            }
+            if waited >= timeout {
+                return Err(format_err!(
+                    "wait for head {:?} timeout, current head is {:?}",
+                    expect,
+                    head
+                ));
+            }
+            Delay::new(interval).await;
+            waited += interval;
+        }
+    }
+}
@@ -0,0 +1,36 @@
+mod gen_network;
+mod test_node;
+
+use actix_rt::System;
+use logger::prelude::*;
+use std::time::Duration;
+use test_node::SyncTestNode;
+use traits::ChainAsyncService;
+
+#[test]
+fn test_two_node_sync() {
+    ::logger::init_for_test();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let handle = rt.handle().clone();
+    let mut system = System::new("test");
+
+    let fut = async move {
+        let count = 5;
+        let node_a = SyncTestNode::launch(handle.clone(), None).await.unwrap();
+        let head_a = node_a.produce_blocks(count).await.unwrap();
+        debug!("node a head : {:?}", head_a);
+
+        let node_b = SyncTestNode::launch(handle, Some(&node_a)).await.unwrap();
+        node_b
+            .wait_for_head(head_a, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let header_b = node_b.chain.clone().master_head_header().await.unwrap();
+        assert_eq!(header_b.number(), count);
+        assert_eq!(header_b.id(), head_a);
+    };
+
+    system.block_on(fut);
+    drop(rt);
+}
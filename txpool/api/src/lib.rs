@@ -1,17 +1,81 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use actix::prelude::*;
 use anyhow::Result;
 use futures_channel::mpsc;
-use starcoin_crypto::hash::HashValue;
-use starcoin_types::{transaction, transaction::SignedUserTransaction};
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::{ed25519::Ed25519PublicKey, hash::HashValue};
+use starcoin_types::{
+    account_address::AccountAddress,
+    transaction,
+    transaction::{
+        AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction, TxStatus,
+    },
+};
 use std::sync::Arc;
 
+/// Status change of a txn in the pool, broadcast on the bus by txpool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Message)]
+#[rtype(result = "()")]
+pub enum TxnStatusEvent {
+    /// The txn is added to the pool, and waits to be mined.
+    TxnAdded {
+        txn_hash: HashValue,
+        sender: AccountAddress,
+    },
+    /// The txn is removed from the pool without being mined, `status` is why it is removed.
+    TxnDropped {
+        txn_hash: HashValue,
+        sender: AccountAddress,
+        status: TxStatus,
+    },
+    /// The txn is included in a block of the master chain.
+    TxnMined {
+        txn_hash: HashValue,
+        sender: AccountAddress,
+    },
+}
+
+impl TxnStatusEvent {
+    pub fn txn_hash(&self) -> HashValue {
+        match self {
+            TxnStatusEvent::TxnAdded { txn_hash, .. }
+            | TxnStatusEvent::TxnDropped { txn_hash, .. }
+            | TxnStatusEvent::TxnMined { txn_hash, .. } => *txn_hash,
+        }
+    }
+
+    pub fn sender(&self) -> AccountAddress {
+        match self {
+            TxnStatusEvent::TxnAdded { sender, .. }
+            | TxnStatusEvent::TxnDropped { sender, .. }
+            | TxnStatusEvent::TxnMined { sender, .. } => *sender,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait TxPoolAsyncService: Clone + std::marker::Unpin + Send + Sync {
     /// TODO: should be deprecated, use add_txns instead.
     async fn add(self, txn: SignedUserTransaction) -> Result<bool>;
 
+    /// Submit the `txn` into txn pool, and return why it is accepted or discarded.
+    async fn submit_txn(self, txn: SignedUserTransaction) -> Result<AdmissionResult>;
+
+    /// Submit the `txns` into txn pool in order, and return the result of every txn.
+    /// Once a txn is rejected for its sequence number, the later txns of the same sender are
+    /// discarded.
+    async fn submit_txns(self, txns: Vec<SignedUserTransaction>) -> Result<Vec<AdmissionResult>>;
+
+    /// Check whether the unsigned `raw_txn` would be admitted into txn pool, and simulate it on
+    /// the head state to estimate its gas. `public_key` is the public key of the sender.
+    async fn preflight(
+        self,
+        raw_txn: RawUserTransaction,
+        public_key: Ed25519PublicKey,
+    ) -> Result<PreflightResult>;
+
     /// Add all the `txns` into txn pool
     async fn add_txns(
         self,
@@ -28,6 +92,10 @@ pub trait TxPoolAsyncService: Clone + std::marker::Unpin + Send + Sync {
         is_invalid: bool,
     ) -> Result<Option<SignedUserTransaction>>;
 
+    /// The sequence number after the largest one of the `address` txns in the pool, None if the
+    /// pool has no txn of the `address`.
+    async fn next_sequence_number(self, address: AccountAddress) -> Result<Option<u64>>;
+
     /// Get all pending txns which is ok to be packaged to mining.
     async fn get_pending_txns(self, max_len: Option<u64>) -> Result<Vec<SignedUserTransaction>>;
 
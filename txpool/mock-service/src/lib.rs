@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use crypto::hash::HashValue;
+use crypto::{ed25519::Ed25519PublicKey, hash::HashValue};
 use futures_channel::mpsc;
 use starcoin_txpool_api::TxPoolAsyncService;
 use std::iter::Iterator;
 use std::sync::{Arc, Mutex};
+use types::account_address::AccountAddress;
 use types::transaction;
-use types::transaction::SignedUserTransaction;
+use types::transaction::{
+    AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction,
+};
 
 #[derive(Clone)]
 pub struct MockTxPoolService {
@@ -34,6 +37,18 @@ impl TxPoolAsyncService for MockTxPoolService {
         //TODO check txn is exist.
         Ok(true)
     }
+    async fn submit_txn(self, txn: SignedUserTransaction) -> Result<AdmissionResult> {
+        self.pool.lock().unwrap().push(txn);
+        Ok(AdmissionResult::Accepted)
+    }
+    async fn submit_txns(
+        self,
+        mut txns: Vec<SignedUserTransaction>,
+    ) -> Result<Vec<AdmissionResult>> {
+        let len = txns.len();
+        self.pool.lock().unwrap().append(&mut txns);
+        Ok(vec![AdmissionResult::Accepted; len])
+    }
     async fn add_txns(
         self,
         mut txns: Vec<SignedUserTransaction>,
@@ -44,6 +59,13 @@ impl TxPoolAsyncService for MockTxPoolService {
         results.resize_with(len, || Ok(()));
         Ok(results)
     }
+    async fn preflight(
+        self,
+        _raw_txn: RawUserTransaction,
+        _public_key: Ed25519PublicKey,
+    ) -> Result<PreflightResult> {
+        unimplemented!()
+    }
     async fn remove_txn(
         self,
         _txn_hash: HashValue,
@@ -52,6 +74,18 @@ impl TxPoolAsyncService for MockTxPoolService {
         unimplemented!()
     }
 
+    async fn next_sequence_number(self, address: AccountAddress) -> Result<Option<u64>> {
+        Ok(self
+            .pool
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|txn| txn.sender() == address)
+            .map(|txn| txn.sequence_number())
+            .max()
+            .map(|seq_number| seq_number + 1))
+    }
+
     async fn get_pending_txns(self, max_len: Option<u64>) -> Result<Vec<SignedUserTransaction>> {
         match max_len {
             Some(max) => Ok(self
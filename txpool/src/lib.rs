@@ -15,10 +15,12 @@ extern crate transaction_pool as tx_pool;
 
 pub use crate::pool::TxStatus;
 use crate::tx_pool_service_impl::{
-    ChainNewBlock, GetPendingTxns, ImportTxns, RemoveTxn, SubscribeTxns, TxPoolActor,
+    ChainNewBlock, GetPendingTxns, ImportTxns, NextSequenceNumber, Preflight, RemoveTxn, SubmitTxn,
+    SubmitTxns, SubscribeTxns, TxPoolActor,
 };
 use actix::prelude::*;
 use anyhow::Result;
+use common_crypto::ed25519::Ed25519PublicKey;
 use common_crypto::hash::HashValue;
 use futures_channel::mpsc;
 use starcoin_bus::BusActor;
@@ -29,7 +31,13 @@ use storage::Storage;
 use storage::{BlockStore, Store};
 #[cfg(test)]
 use types::block::BlockHeader;
-use types::{block::Block, transaction, transaction::SignedUserTransaction};
+use types::{
+    account_address::AccountAddress,
+    block::Block,
+    time::{RealTimeService, TimeService},
+    transaction,
+    transaction::{AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction},
+};
 mod counters;
 mod pool;
 mod pool_client;
@@ -52,6 +60,23 @@ impl TxPoolRef {
         storage: Arc<dyn Store>,
         best_block_hash: HashValue,
         bus: actix::Addr<BusActor>,
+    ) -> TxPoolRef {
+        Self::start_with_time_service(
+            pool_config,
+            storage,
+            best_block_hash,
+            bus,
+            Arc::new(RealTimeService::new()),
+        )
+    }
+
+    /// Start the pool, the expiration of txns is checked against `time_service`.
+    pub fn start_with_time_service(
+        pool_config: TxPoolConfig,
+        storage: Arc<dyn Store>,
+        best_block_hash: HashValue,
+        bus: actix::Addr<BusActor>,
+        time_service: Arc<dyn TimeService>,
     ) -> TxPoolRef {
         let best_block = match storage.get_block_by_hash(best_block_hash) {
             Err(e) => panic!("fail to read storage, {}", e),
@@ -62,7 +87,7 @@ impl TxPoolRef {
             Ok(Some(block)) => block,
         };
         let best_block_header = best_block.into_inner().0;
-        let pool = TxPoolActor::new(pool_config, storage, best_block_header, bus);
+        let pool = TxPoolActor::new(pool_config, storage, best_block_header, bus, time_service);
         let pool_addr = pool.start();
         TxPoolRef { addr: pool_addr }
     }
@@ -73,7 +98,13 @@ impl TxPoolRef {
         best_block_header: BlockHeader,
         bus: actix::Addr<BusActor>,
     ) -> TxPoolRef {
-        let pool = TxPoolActor::new(TxPoolConfig::default(), storage, best_block_header, bus);
+        let pool = TxPoolActor::new(
+            TxPoolConfig::default(),
+            storage,
+            best_block_header,
+            bus,
+            Arc::new(RealTimeService::new()),
+        );
         let pool_addr = pool.start();
         TxPoolRef { addr: pool_addr }
     }
@@ -86,6 +117,33 @@ impl TxPoolAsyncService for TxPoolRef {
         Ok(result.pop().unwrap().is_ok())
     }
 
+    async fn submit_txn(self, txn: SignedUserTransaction) -> Result<AdmissionResult> {
+        match self.addr.send(SubmitTxn { txn }).await {
+            Err(e) => Err(e.into()),
+            Ok(r) => Ok(r),
+        }
+    }
+
+    async fn submit_txns(self, txns: Vec<SignedUserTransaction>) -> Result<Vec<AdmissionResult>> {
+        match self.addr.send(SubmitTxns { txns }).await {
+            Err(e) => Err(e.into()),
+            Ok(r) => Ok(r),
+        }
+    }
+
+    async fn preflight(
+        self,
+        raw_txn: RawUserTransaction,
+        public_key: Ed25519PublicKey,
+    ) -> Result<PreflightResult> {
+        self.addr
+            .send(Preflight {
+                raw_txn,
+                public_key,
+            })
+            .await?
+    }
+
     async fn add_txns(
         self,
         txns: Vec<SignedUserTransaction>,
@@ -116,6 +174,13 @@ impl TxPoolAsyncService for TxPoolRef {
         }
     }
 
+    async fn next_sequence_number(self, address: AccountAddress) -> Result<Option<u64>> {
+        match self.addr.send(NextSequenceNumber { address }).await {
+            Err(e) => Err(e.into()),
+            Ok(r) => Ok(r),
+        }
+    }
+
     async fn get_pending_txns(self, max_len: Option<u64>) -> Result<Vec<SignedUserTransaction>> {
         match self
             .addr
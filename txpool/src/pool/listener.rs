@@ -8,6 +8,7 @@ use common_crypto::hash::HashValue as H256;
 use futures_channel::mpsc;
 use transaction_pool as tx_pool;
 use tx_pool::VerifiedTransaction;
+use types::account_address::AccountAddress as Address;
 /// Transaction pool logger.
 #[derive(Default, Debug)]
 pub struct Logger;
@@ -66,7 +67,8 @@ impl tx_pool::Listener<Transaction> for Logger {
 pub struct TransactionsPoolNotifier {
     full_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<(H256, TxStatus)>>>>,
     pending_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<H256>>>>,
-    tx_statuses: Vec<(H256, TxStatus)>,
+    status_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<(H256, Address, TxStatus)>>>>,
+    tx_statuses: Vec<(H256, Address, TxStatus)>,
 }
 
 impl TransactionsPoolNotifier {
@@ -80,6 +82,14 @@ impl TransactionsPoolNotifier {
         self.pending_listeners.push(f);
     }
 
+    /// Add new status listener to receive notifications with the sender of txns.
+    pub fn add_status_listener(
+        &mut self,
+        f: mpsc::UnboundedSender<Arc<Vec<(H256, Address, TxStatus)>>>,
+    ) {
+        self.status_listeners.push(f);
+    }
+
     /// Notify listeners about all currently transactions.
     pub fn notify(&mut self) {
         if self.tx_statuses.is_empty() {
@@ -90,15 +100,30 @@ impl TransactionsPoolNotifier {
             self.tx_statuses
                 .clone()
                 .into_iter()
-                .map(|(hash, _)| hash)
+                .map(|(hash, _, _)| hash)
                 .collect(),
         );
         self.pending_listeners
             .retain(|listener| listener.unbounded_send(to_pending_send.clone()).is_ok());
 
-        let to_full_send = Arc::new(std::mem::replace(&mut self.tx_statuses, Vec::new()));
+        let to_full_send: Arc<Vec<(H256, TxStatus)>> = Arc::new(
+            self.tx_statuses
+                .clone()
+                .into_iter()
+                .map(|(hash, _, status)| (hash, status))
+                .collect(),
+        );
         self.full_listeners
             .retain(|listener| listener.unbounded_send(to_full_send.clone()).is_ok());
+
+        let to_status_send = Arc::new(std::mem::replace(&mut self.tx_statuses, Vec::new()));
+        self.status_listeners
+            .retain(|listener| listener.unbounded_send(to_status_send.clone()).is_ok());
+    }
+
+    fn push_status(&mut self, tx: &Arc<Transaction>, status: TxStatus) {
+        self.tx_statuses
+            .push((tx.hash.clone(), *tx.sender(), status));
     }
 }
 
@@ -107,13 +132,18 @@ impl fmt::Debug for TransactionsPoolNotifier {
         fmt.debug_struct("TransactionsPoolNotifier")
             .field("full_listeners", &self.full_listeners.len())
             .field("pending_listeners", &self.pending_listeners.len())
+            .field("status_listeners", &self.status_listeners.len())
             .finish()
     }
 }
 
 impl tx_pool::Listener<Transaction> for TransactionsPoolNotifier {
-    fn added(&mut self, tx: &Arc<Transaction>, _old: Option<&Arc<Transaction>>) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Added));
+    fn added(&mut self, tx: &Arc<Transaction>, old: Option<&Arc<Transaction>>) {
+        // the replaced txn is not reported by `dropped`.
+        if let Some(old) = old {
+            self.push_status(old, TxStatus::Dropped);
+        }
+        self.push_status(tx, TxStatus::Added);
     }
 
     fn rejected<H: fmt::Debug + fmt::LowerHex>(
@@ -121,23 +151,23 @@ impl tx_pool::Listener<Transaction> for TransactionsPoolNotifier {
         tx: &Arc<Transaction>,
         _reason: &tx_pool::Error<H>,
     ) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Rejected));
+        self.push_status(tx, TxStatus::Rejected);
     }
 
     fn dropped(&mut self, tx: &Arc<Transaction>, _new: Option<&Transaction>) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Dropped));
+        self.push_status(tx, TxStatus::Dropped);
     }
 
     fn invalid(&mut self, tx: &Arc<Transaction>) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Invalid));
+        self.push_status(tx, TxStatus::Invalid);
     }
 
     fn canceled(&mut self, tx: &Arc<Transaction>) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Canceled));
+        self.push_status(tx, TxStatus::Canceled);
     }
 
     fn culled(&mut self, tx: &Arc<Transaction>) {
-        self.tx_statuses.push((tx.hash.clone(), TxStatus::Culled));
+        self.push_status(tx, TxStatus::Culled);
     }
 }
 
@@ -159,10 +189,12 @@ mod tests {
         // given
         let (full_sender, mut full_receiver) = mpsc::unbounded();
         let (pending_sender, mut pending_receiver) = mpsc::unbounded();
+        let (status_sender, mut status_receiver) = mpsc::unbounded();
 
         let mut tx_listener = TransactionsPoolNotifier::default();
         tx_listener.add_full_listener(full_sender);
         tx_listener.add_pending_listener(pending_sender);
+        tx_listener.add_status_listener(status_sender);
 
         // when
         let tx = new_tx();
@@ -177,6 +209,34 @@ mod tests {
             Some(Arc::new(vec![(tx.hash().clone(), TxStatus::Added)]))
         );
         assert_eq!(pending_res, Some(Arc::new(vec![tx.hash().clone()])));
+        let status_res = status_receiver.try_next().unwrap();
+        assert_eq!(
+            status_res,
+            Some(Arc::new(vec![(
+                tx.hash().clone(),
+                *tx.sender(),
+                TxStatus::Added
+            )]))
+        );
+    }
+
+    #[test]
+    fn should_notify_replaced_as_dropped() {
+        let (status_sender, mut status_receiver) = mpsc::unbounded();
+        let mut tx_listener = TransactionsPoolNotifier::default();
+        tx_listener.add_status_listener(status_sender);
+
+        let old = new_tx();
+        let tx = new_tx();
+        tx_listener.added(&tx, Some(&old));
+        tx_listener.notify();
+        assert_eq!(
+            status_receiver.try_next().unwrap(),
+            Some(Arc::new(vec![
+                (old.hash().clone(), *old.sender(), TxStatus::Dropped),
+                (tx.hash().clone(), *tx.sender(), TxStatus::Added),
+            ]))
+        );
     }
 
     fn new_tx() -> Arc<Transaction> {
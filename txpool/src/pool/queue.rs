@@ -21,7 +21,7 @@ use std::{
     },
 };
 use tx_pool::{self, Verifier};
-use types::{account_address::AccountAddress as Address, transaction};
+use types::{account_address::AccountAddress as Address, time::TimeService, transaction};
 
 type Listener = (
     LocalTransactionsList,
@@ -197,14 +197,17 @@ pub struct TransactionQueue {
     options: RwLock<verifier::Options>,
     cached_pending: RwLock<CachedPending>,
     recently_rejected: RecentlyRejected,
+    time_service: Arc<dyn TimeService>,
 }
 
 impl TransactionQueue {
-    /// Create new queue with given pool limits and initial verification options.
+    /// Create new queue with given pool limits and initial verification options,
+    /// the expiration of txns is checked against `time_service`.
     pub fn new(
         limits: tx_pool::Options,
         verification_options: verifier::Options,
         strategy: PrioritizationStrategy,
+        time_service: Arc<dyn TimeService>,
     ) -> Self {
         let max_count = limits.max_count;
         TransactionQueue {
@@ -220,6 +223,7 @@ impl TransactionQueue {
                 MIN_REJECTED_CACHE_SIZE,
                 max_count / 4,
             )),
+            time_service,
         }
     }
 
@@ -274,6 +278,7 @@ impl TransactionQueue {
             options,
             self.insertion_id.clone(),
             transaction_to_replace,
+            self.time_service.now_secs(),
         );
 
         let mut replace =
@@ -380,7 +385,13 @@ impl TransactionQueue {
         //     return pending;
         // }
 
-        let ready = Self::ready(client, block_number, current_timestamp, nonce_cap);
+        let ready = Self::ready(
+            client,
+            block_number,
+            current_timestamp,
+            nonce_cap,
+            self.time_service.now_secs(),
+        );
 
         match ordering {
             // In case we don't have a cached set, but we don't care about order
@@ -413,6 +424,7 @@ impl TransactionQueue {
         block_number: u64,
         current_timestamp: u64,
         nonce_cap: Option<SeqNumber>,
+        now_secs: u64,
     ) -> (ready::Condition, ready::State<C>)
     where
         C: client::AccountSeqNumberClient,
@@ -420,7 +432,7 @@ impl TransactionQueue {
         let pending_readiness = ready::Condition::new(block_number, current_timestamp);
         // don't mark any transactions as stale at this point.
         let stale_id = None;
-        let state_readiness = ready::State::new(client, stale_id, nonce_cap);
+        let state_readiness = ready::State::new(client, stale_id, nonce_cap, now_secs);
 
         (pending_readiness, state_readiness)
     }
@@ -444,6 +456,8 @@ impl TransactionQueue {
 
         self.recently_rejected.clear();
 
+        // expired transactions are stale, so they are culled as well.
+        let now_secs = self.time_service.now_secs();
         let mut removed = 0;
         let senders: Vec<_> = {
             let pool = &self.pool.read();
@@ -452,9 +466,11 @@ impl TransactionQueue {
         };
         for chunk in senders.chunks(CULL_SENDERS_CHUNK) {
             trace_time!("pool::cull::chunk");
-            let state_readiness = ready::State::new(client.clone(), stale_id, nonce_cap);
+            let state_readiness = ready::State::new(client.clone(), stale_id, nonce_cap, now_secs);
             removed += self.pool.write().cull(Some(chunk), state_readiness);
         }
+        // Notify about culled transactions.
+        (self.pool.write().listener_mut().1).0.notify();
         debug!(target: "txqueue", "Removed {} stalled transactions. {}", removed, self.status());
     }
 
@@ -478,6 +494,27 @@ impl TransactionQueue {
     //         .map(|tx| tx.signed().sequence_number().saturating_add(1))
     // }
 
+    /// Returns true if the pool holds a transaction of `sender` with the `seq_number`.
+    pub fn has_sequence_number(&self, sender: &Address, seq_number: SeqNumber) -> bool {
+        let ready = |_tx: &pool::VerifiedTransaction| tx_pool::Readiness::Ready;
+        self.pool
+            .read()
+            .pending_from_sender(ready, sender)
+            .any(|tx| tx.signed().sequence_number() == seq_number)
+    }
+
+    /// The sequence number after the largest one of the `sender` txns in the pool, None if the
+    /// pool has no txn of the `sender`.
+    pub fn next_sequence_number(&self, sender: &Address) -> Option<SeqNumber> {
+        let ready = |_tx: &pool::VerifiedTransaction| tx_pool::Readiness::Ready;
+        self.pool
+            .read()
+            .pending_from_sender(ready, sender)
+            .map(|tx| tx.signed().sequence_number())
+            .max()
+            .map(|seq_number| seq_number + 1)
+    }
+
     /// Retrieve a transaction from the pool.
     ///
     /// Given transaction hash looks up that transaction in the pool
@@ -506,6 +543,9 @@ impl TransactionQueue {
             removed
         };
 
+        // Notify about removed transactions.
+        (self.pool.write().listener_mut().1).0.notify();
+
         if results.iter().any(Option::is_some) {
             self.cached_pending.write().clear();
         }
@@ -582,6 +622,16 @@ impl TransactionQueue {
         (self.pool.write().listener_mut().1).0.add_full_listener(f);
     }
 
+    /// Add a listener to be notified about the status and sender of all transactions the pool
+    pub fn add_status_listener(
+        &self,
+        f: mpsc::UnboundedSender<Arc<Vec<(HashValue, Address, TxStatus)>>>,
+    ) {
+        (self.pool.write().listener_mut().1)
+            .0
+            .add_status_listener(f);
+    }
+
     /// Check if pending set is cached.
     #[cfg(test)]
     pub fn is_pending_cached(&self) -> bool {
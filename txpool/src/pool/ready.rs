@@ -21,6 +21,8 @@
 //! - The transaction is ready (current; state nonce == transaction nonce)
 //! - The transaction is not ready yet (future; state nonce < transaction nonce)
 //!
+//! An expired transaction can never be included, so it is stale regardless of its nonce.
+//!
 //! NOTE The transactions are always checked for readines in order they are stored within the queue.
 //! First `Readiness::Future` response also causes all subsequent transactions from the same sender
 //! to be marked as `Future`.
@@ -39,22 +41,32 @@ pub struct State<C> {
     state: C,
     max_nonce: Option<SeqNumber>,
     stale_id: Option<usize>,
+    now_secs: u64,
 }
 
 impl<C> State<C> {
-    /// Create new State checker, given client interface.
-    pub fn new(state: C, stale_id: Option<usize>, max_nonce: Option<SeqNumber>) -> Self {
+    /// Create new State checker, given client interface and the current time in seconds.
+    pub fn new(
+        state: C,
+        stale_id: Option<usize>,
+        max_nonce: Option<SeqNumber>,
+        now_secs: u64,
+    ) -> Self {
         State {
             nonces: Default::default(),
             state,
             max_nonce,
             stale_id,
+            now_secs,
         }
     }
 }
 
 impl<C: AccountSeqNumberClient> tx_pool::Ready<VerifiedTransaction> for State<C> {
     fn is_ready(&mut self, tx: &VerifiedTransaction) -> tx_pool::Readiness {
+        if tx.signed().is_expired(self.now_secs) {
+            return tx_pool::Readiness::Stale;
+        }
         // Check max nonce
         match self.max_nonce {
             Some(nonce) if tx.transaction.sequence_number() > nonce => {
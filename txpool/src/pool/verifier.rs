@@ -15,12 +15,15 @@ use crate::pool::{
 use std::sync::{atomic::AtomicUsize, Arc};
 use tx_pool;
 use types::transaction;
+use types::transaction::SignedUserTransaction;
 
 /// Verification options.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Options {
     /// Minimal allowed gas price.
     pub minimal_gas_price: GasPrice,
+    /// Minimal gas price of new txns by the local relay policy, retracted txns are not checked.
+    pub local_min_gas_price: GasPrice,
     /// Current block gas limit.
     pub block_gas_limit: Gas,
     /// Maximal gas limit for a single transaction.
@@ -34,6 +37,7 @@ impl Default for Options {
     fn default() -> Self {
         Options {
             minimal_gas_price: 0,
+            local_min_gas_price: 0,
             block_gas_limit: Gas::max_value(),
             tx_gas_limit: Gas::max_value(),
             no_early_reject: false,
@@ -41,6 +45,54 @@ impl Default for Options {
     }
 }
 
+/// Checks the gas price and gas limit of the txn against the verification options.
+pub fn check_options(
+    options: &Options,
+    txn: &SignedUserTransaction,
+) -> Result<(), transaction::TransactionError> {
+    if txn.gas_unit_price() < options.minimal_gas_price {
+        return Err(transaction::TransactionError::InsufficientGasPrice {
+            minimal: options.minimal_gas_price,
+            got: txn.gas_unit_price(),
+        });
+    }
+    if txn.max_gas_amount() > options.tx_gas_limit {
+        return Err(transaction::TransactionError::GasLimitExceeded {
+            limit: options.tx_gas_limit,
+            got: txn.max_gas_amount(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks the txn is not expired at `now_secs`, seconds since the unix epoch.
+pub fn check_expiration(
+    now_secs: u64,
+    txn: &SignedUserTransaction,
+) -> Result<(), transaction::TransactionError> {
+    if txn.is_expired(now_secs) {
+        return Err(transaction::TransactionError::Expired {
+            expiration: txn.expiration_time().as_secs(),
+            now: now_secs,
+        });
+    }
+    Ok(())
+}
+
+/// Checks the gas price of a new txn against the local relay policy.
+pub fn check_local_min_gas_price(
+    options: &Options,
+    txn: &SignedUserTransaction,
+) -> Result<(), transaction::TransactionError> {
+    if txn.gas_unit_price() < options.local_min_gas_price {
+        return Err(transaction::TransactionError::InsufficientGasPrice {
+            minimal: options.local_min_gas_price,
+            got: txn.gas_unit_price(),
+        });
+    }
+    Ok(())
+}
+
 /// Transaction verifier.
 ///
 /// Verification can be run in parallel for all incoming transactions.
@@ -50,21 +102,25 @@ pub struct Verifier<C, S, V> {
     options: Options,
     id: Arc<AtomicUsize>,
     transaction_to_replace: Option<(S, Arc<V>)>,
+    now_secs: u64,
 }
 
 impl<C, S, V> Verifier<C, S, V> {
-    /// Creates new transaction verfier with specified options.
+    /// Creates new transaction verfier with specified options,
+    /// txns expired at `now_secs` are rejected.
     pub fn new(
         client: C,
         options: Options,
         id: Arc<AtomicUsize>,
         transaction_to_replace: Option<(S, Arc<V>)>,
+        now_secs: u64,
     ) -> Self {
         Verifier {
             client,
             options,
             id,
             transaction_to_replace,
+            now_secs,
         }
     }
 }
@@ -84,6 +140,18 @@ impl<C: Client> tx_pool::Verifier<PoolTransaction>
         let is_retracted = tx.is_retracted();
         let verified_txn = match tx {
             PoolTransaction::Unverified(unverified) | PoolTransaction::Retracted(unverified) => {
+                // retracted txns were valid in a block, the local policy does not apply.
+                let checked = if is_retracted {
+                    check_options(&self.options, &unverified)
+                } else {
+                    check_options(&self.options, &unverified)
+                        .and_then(|_| check_local_min_gas_price(&self.options, &unverified))
+                }
+                .and_then(|_| check_expiration(self.now_secs, &unverified));
+                if let Err(err) = checked {
+                    debug!(target: "txqueue", "[{:?}] Rejected tx {:?}", hash, err);
+                    return Err(err);
+                }
                 match self.client.verify_transaction(unverified) {
                     Ok(txn) => transaction::PendingTransaction::from(txn.into_inner()),
                     Err(err) => {
@@ -94,6 +162,10 @@ impl<C: Client> tx_pool::Verifier<PoolTransaction>
             }
             PoolTransaction::Local(txn) => {
                 let user_txn = txn.transaction.clone();
+                if let Err(err) = check_expiration(self.now_secs, &user_txn) {
+                    warn!(target: "txqueue", "[{:?}] Rejected local tx {:?}", hash, err);
+                    return Err(err);
+                }
                 match self
                     .client
                     .verify_transaction(UnverifiedUserTransaction::from(user_txn))
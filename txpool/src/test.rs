@@ -1,22 +1,28 @@
 use crate::pool::AccountSeqNumberClient;
-use crate::TxPoolRef;
+use crate::{TxPoolRef, TxStatus};
+use actix::Addr;
 use anyhow::Result;
 use common_crypto::hash::CryptoHash;
 use common_crypto::keygen::KeyGen;
+use futures::{future, StreamExt};
 use parking_lot::RwLock;
-use starcoin_bus::BusActor;
+use starcoin_bus::{Bus, BusActor};
 use starcoin_config::{NodeConfig, TxPoolConfig};
 use starcoin_executor::executor::Executor;
 use starcoin_executor::TransactionExecutor;
 use starcoin_genesis::Genesis;
-use starcoin_txpool_api::TxPoolAsyncService;
+use starcoin_txpool_api::{TxPoolAsyncService, TxnStatusEvent};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use storage::cache_storage::CacheStorage;
 use storage::db_storage::DBStorage;
 use storage::storage::StorageInstance;
 use storage::Storage;
 use types::account_address::AccountAddress;
+use types::time::{MockTimeService, RealTimeService, TimeService};
+use types::transaction::{AdmissionResult, SignedUserTransaction};
+use types::vm_error::StatusCode;
 
 #[derive(Clone, Debug)]
 struct MockNonceClient {
@@ -62,6 +68,160 @@ async fn test_tx_pool() -> Result<()> {
     Ok(())
 }
 
+#[actix_rt::test]
+async fn test_submit_txn_accepted() -> Result<()> {
+    let pool = gen_pool_for_test();
+    let result = pool.clone().submit_txn(gen_mint_txn(1)?).await?;
+    assert_eq!(result, AdmissionResult::Accepted);
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_submit_txn_rejected() -> Result<()> {
+    let pool = gen_pool_for_test();
+    // association account sequence number is 1 after genesis.
+    let result = pool.clone().submit_txn(gen_mint_txn(0)?).await?;
+    match result {
+        AdmissionResult::Rejected(status) => {
+            assert_eq!(status.major_status, StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+        }
+        _ => panic!("expect rejected, but got {:?}", result),
+    }
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_submit_txns() -> Result<()> {
+    let pool = gen_pool_for_test();
+    // association account sequence number is 1 after genesis.
+    let txns = vec![1, 0, 2]
+        .into_iter()
+        .map(gen_mint_txn)
+        .collect::<Result<Vec<_>>>()?;
+    let txn_hash = txns[0].crypto_hash();
+    let results = pool.clone().submit_txns(txns).await?;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0], AdmissionResult::Accepted);
+    match &results[1] {
+        AdmissionResult::Rejected(status) => {
+            assert_eq!(status.major_status, StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+        }
+        result => panic!("expect rejected, but got {:?}", result),
+    }
+    // the txn after the rejected one is discarded though it is valid.
+    match &results[2] {
+        AdmissionResult::Discarded(_) => {}
+        result => panic!("expect discarded, but got {:?}", result),
+    }
+    let pending_txns = pool.clone().get_pending_txns(None).await?;
+    assert_eq!(pending_txns.len(), 1);
+    assert_eq!(pending_txns[0].crypto_hash(), txn_hash);
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_preflight() -> Result<()> {
+    let pool = gen_pool_for_test();
+    let txn = gen_mint_txn(1)?;
+    let result = pool
+        .clone()
+        .preflight(txn.raw_txn().clone(), txn.public_key())
+        .await?;
+    assert!(result.would_admit, "{:?}", result);
+    assert!(result.reason.is_none());
+    assert!(result.estimated_gas > 0);
+    assert_eq!(result.simulated_status.major_status, StatusCode::EXECUTED);
+    // the txn is not submitted.
+    assert!(pool.get_pending_txns(None).await?.is_empty());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_preflight_underpriced() -> Result<()> {
+    let (pool, _bus) = gen_pool_and_bus_with_config_for_test(TxPoolConfig {
+        minimal_gas_price: 2,
+        ..TxPoolConfig::default()
+    });
+    // the gas unit price of mint txn is 1.
+    let txn = gen_mint_txn(1)?;
+    let result = pool
+        .clone()
+        .preflight(txn.raw_txn().clone(), txn.public_key())
+        .await?;
+    assert!(!result.would_admit);
+    assert!(result
+        .reason
+        .expect("underpriced txn should have a reason")
+        .contains("Insufficient gas price"));
+    // it is rejected by the pool too.
+    match pool.submit_txn(txn).await? {
+        AdmissionResult::Discarded(_) => {}
+        result => panic!("expect discarded, but got {:?}", result),
+    }
+    Ok(())
+}
+
+fn gen_mint_txn(seq_num: u64) -> Result<SignedUserTransaction> {
+    let (_private_key, public_key) = KeyGen::from_os_rng().generate_keypair();
+    let account_address = AccountAddress::from_public_key(&public_key);
+    let auth_prefix = AccountAddress::authentication_key(&public_key)
+        .prefix()
+        .to_vec();
+    // the gas unit price of mint txn is 1.
+    let txn = Executor::build_mint_txn(account_address, auth_prefix, seq_num, 10000);
+    Ok(txn.as_signed_user_txn()?.clone())
+}
+
+#[actix_rt::test]
+async fn test_local_min_gas_price() -> Result<()> {
+    // a txn at the local floor is admitted.
+    let (pool, _bus) = gen_pool_and_bus_with_config_for_test(TxPoolConfig {
+        local_min_gas_price: 1,
+        ..TxPoolConfig::default()
+    });
+    match pool.clone().submit_txn(gen_mint_txn(1)?).await? {
+        AdmissionResult::Accepted => {}
+        result => panic!("expect accepted, but got {:?}", result),
+    }
+    // the txn is still verified by the VM.
+    match pool.submit_txn(gen_mint_txn(0)?).await? {
+        AdmissionResult::Rejected(status) => {
+            assert_eq!(status.major_status, StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+        }
+        result => panic!("expect rejected, but got {:?}", result),
+    }
+
+    // a txn below the local floor is not.
+    let (pool, _bus) = gen_pool_and_bus_with_config_for_test(TxPoolConfig {
+        local_min_gas_price: 2,
+        ..TxPoolConfig::default()
+    });
+    match pool.clone().submit_txn(gen_mint_txn(1)?).await? {
+        AdmissionResult::Discarded(reason) => assert!(reason.contains("Insufficient gas price")),
+        result => panic!("expect discarded, but got {:?}", result),
+    }
+    assert!(pool.get_pending_txns(None).await?.is_empty());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_local_min_gas_price_not_apply_to_retracted() -> Result<()> {
+    let (pool, _bus) = gen_pool_and_bus_with_config_for_test(TxPoolConfig {
+        local_min_gas_price: 2,
+        ..TxPoolConfig::default()
+    });
+    // the txn was valid in a retracted block, it goes back to the pool.
+    let txn = gen_mint_txn(1)?;
+    pool.clone().rollback(vec![], vec![txn.clone()]).await?;
+    let pending = pool.get_pending_txns(None).await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        CryptoHash::crypto_hash(&pending[0]),
+        CryptoHash::crypto_hash(&txn)
+    );
+    Ok(())
+}
+
 #[actix_rt::test]
 async fn test_subscribe_txns() {
     let pool = gen_pool_for_test();
@@ -105,7 +265,127 @@ async fn test_rollback() -> Result<()> {
     Ok(())
 }
 
+#[actix_rt::test]
+async fn test_txn_status_events() -> Result<()> {
+    let (pool, bus) = gen_pool_and_bus_for_test();
+    let events = bus.channel::<TxnStatusEvent>().await?;
+    let txn = gen_mint_txn(1)?;
+    let txn_hash = txn.crypto_hash();
+    let sender = txn.sender();
+
+    let mut result = pool.clone().add_txns(vec![txn.clone()]).await?;
+    assert!(result.pop().unwrap().is_ok());
+    // mined in a new block.
+    pool.clone().rollback(vec![txn], vec![]).await?;
+
+    let sender_events: Vec<_> = events
+        .filter(|event| future::ready(event.sender() == sender))
+        .take(2)
+        .collect()
+        .await;
+    assert_eq!(
+        sender_events,
+        vec![
+            TxnStatusEvent::TxnAdded { txn_hash, sender },
+            TxnStatusEvent::TxnMined { txn_hash, sender },
+        ]
+    );
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_txn_dropped_event_on_replace() -> Result<()> {
+    let (pool, bus) = gen_pool_and_bus_for_test();
+    let events = bus.channel::<TxnStatusEvent>().await?;
+    // both mint txns are sent by the association account with the same sequence number,
+    // the later one replaces the earlier one in the pool.
+    let old_txn = gen_mint_txn(1)?;
+    let new_txn = gen_mint_txn(1)?;
+    let sender = old_txn.sender();
+    assert_eq!(
+        pool.clone().submit_txn(old_txn.clone()).await?,
+        AdmissionResult::Accepted
+    );
+    assert_eq!(
+        pool.clone().submit_txn(new_txn.clone()).await?,
+        AdmissionResult::Replaced
+    );
+
+    let sender_events: Vec<_> = events
+        .filter(|event| future::ready(event.sender() == sender))
+        .take(3)
+        .collect()
+        .await;
+    assert!(sender_events.contains(&TxnStatusEvent::TxnDropped {
+        txn_hash: old_txn.crypto_hash(),
+        sender,
+        status: TxStatus::Dropped,
+    }));
+    assert!(sender_events.contains(&TxnStatusEvent::TxnAdded {
+        txn_hash: new_txn.crypto_hash(),
+        sender,
+    }));
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_next_sequence_number() -> Result<()> {
+    let pool = gen_pool_for_test();
+    let txn = gen_mint_txn(1)?;
+    let sender = txn.sender();
+    assert_eq!(pool.clone().next_sequence_number(sender).await?, None);
+    assert_eq!(
+        pool.clone().submit_txn(txn).await?,
+        AdmissionResult::Accepted
+    );
+    assert_eq!(pool.clone().next_sequence_number(sender).await?, Some(2));
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_txn_expired() -> Result<()> {
+    let txn = gen_mint_txn(1)?;
+    let expiration = txn.expiration_time().as_secs();
+    let time_service = Arc::new(MockTimeService::new_with_secs(expiration - 10));
+    let (pool, _bus) =
+        gen_pool_and_bus_with_time_service_for_test(TxPoolConfig::default(), time_service.clone());
+    assert_eq!(
+        pool.clone().submit_txn(txn.clone()).await?,
+        AdmissionResult::Accepted
+    );
+    let pending_txns = pool.clone().get_pending_txns(Some(10)).await?;
+    assert_eq!(pending_txns, vec![txn]);
+
+    // the pooled txn expires, and is not pending any more.
+    time_service.increase(Duration::from_secs(20));
+    assert!(pool.clone().get_pending_txns(Some(10)).await?.is_empty());
+
+    // a new txn already expired on the pool clock is discarded.
+    let txn = gen_mint_txn(2)?;
+    assert!(txn.is_expired(time_service.now_secs()));
+    match pool.clone().submit_txn(txn).await? {
+        AdmissionResult::Discarded(reason) => assert!(reason.contains("expired")),
+        other => panic!("expect discarded, got {:?}", other),
+    }
+    Ok(())
+}
+
 fn gen_pool_for_test() -> TxPoolRef {
+    gen_pool_and_bus_for_test().0
+}
+
+fn gen_pool_and_bus_for_test() -> (TxPoolRef, Addr<BusActor>) {
+    gen_pool_and_bus_with_config_for_test(TxPoolConfig::default())
+}
+
+fn gen_pool_and_bus_with_config_for_test(pool_config: TxPoolConfig) -> (TxPoolRef, Addr<BusActor>) {
+    gen_pool_and_bus_with_time_service_for_test(pool_config, Arc::new(RealTimeService::new()))
+}
+
+fn gen_pool_and_bus_with_time_service_for_test(
+    pool_config: TxPoolConfig,
+    time_service: Arc<dyn TimeService>,
+) -> (TxPoolRef, Addr<BusActor>) {
     let cache_storage = Arc::new(CacheStorage::new());
     let tmpdir = tempfile::tempdir().unwrap();
     let db_storage = Arc::new(DBStorage::new(tmpdir.path()));
@@ -121,12 +401,13 @@ fn gen_pool_for_test() -> TxPoolRef {
     let genesis = Genesis::build(node_config.net()).unwrap();
     let startup_info = genesis.execute(storage.clone()).unwrap();
     let bus = BusActor::launch();
-    let pool = TxPoolRef::start(
-        TxPoolConfig::default(),
+    let pool = TxPoolRef::start_with_time_service(
+        pool_config,
         storage.clone(),
         startup_info.master.get_head(),
-        bus,
+        bus.clone(),
+        time_service,
     );
 
-    pool
+    (pool, bus)
 }
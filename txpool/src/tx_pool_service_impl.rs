@@ -13,16 +13,30 @@ use crate::{
 };
 use actix::prelude::*;
 use anyhow::Result;
+use common_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_LENGTH};
 use common_crypto::hash::{CryptoHash, HashValue};
 use futures_channel::mpsc;
-use starcoin_bus::{Bus, BusActor};
+use starcoin_bus::{Broadcast, Bus, BusActor};
 use starcoin_config::TxPoolConfig;
+use starcoin_executor::{executor::Executor, TransactionExecutor};
+use starcoin_statedb::ChainStateDB;
+use starcoin_txpool_api::TxnStatusEvent;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::sync::Arc;
 use storage::Store;
 use tx_relay::{PeerTransactions, PropagateNewTransactions};
 use types::{
-    block::BlockHeader, system_events::SystemEvents, transaction,
-    transaction::SignedUserTransaction,
+    account_address::AccountAddress,
+    block::BlockHeader,
+    system_events::SystemEvents,
+    time::TimeService,
+    transaction,
+    transaction::{
+        AdmissionResult, PreflightResult, RawUserTransaction, SignedUserTransaction,
+        TransactionStatus,
+    },
+    vm_error::StatusCode,
 };
 
 type TxnQueue = pool::TransactionQueue;
@@ -33,6 +47,7 @@ pub(crate) struct TxPoolActor {
     storage: Arc<dyn Store>,
     sequence_number_cache: NonceCache,
     bus: actix::Addr<BusActor>,
+    time_service: Arc<dyn TimeService>,
 }
 impl std::fmt::Debug for TxPoolActor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -52,9 +67,11 @@ impl TxPoolActor {
         storage: Arc<dyn Store>,
         chain_header: BlockHeader,
         bus: actix::Addr<BusActor>,
+        time_service: Arc<dyn TimeService>,
     ) -> Self {
         let verifier_options = pool::VerifierOptions {
             minimal_gas_price: pool_config.minimal_gas_price,
+            local_min_gas_price: pool_config.local_min_gas_price,
             block_gas_limit: Gas::max_value(),
             tx_gas_limit: pool_config.tx_gas_limit,
             no_early_reject: false,
@@ -67,6 +84,7 @@ impl TxPoolActor {
             },
             verifier_options,
             PrioritizationStrategy::GasPriceOnly,
+            time_service.clone(),
         );
         let queue = Arc::new(queue);
         Self {
@@ -75,6 +93,7 @@ impl TxPoolActor {
             chain_header,
             bus,
             sequence_number_cache: NonceCache::new(128),
+            time_service,
         }
     }
     fn get_pending(&self, max_len: u64) -> Vec<Arc<VerifiedTransaction>> {
@@ -92,6 +111,58 @@ impl TxPoolActor {
         );
         self.queue.pending(client, pending_settings)
     }
+
+    /// Import a single txn, and return why it is accepted or discarded.
+    fn submit_txn(&mut self, txn: SignedUserTransaction) -> AdmissionResult {
+        let imported = vec![txn_hash_and_sender(&txn)];
+        let replaced = self
+            .queue
+            .has_sequence_number(&txn.sender(), txn.sequence_number());
+        let client = PoolClient::new(
+            self.chain_header.clone(),
+            self.storage.clone(),
+            self.sequence_number_cache.clone(),
+        );
+        let mut import_result = self.queue.import(
+            client,
+            vec![PoolTransaction::Unverified(
+                UnverifiedUserTransaction::from(txn),
+            )],
+        );
+        self.broadcast_txn_status_events(added_events(imported, &import_result));
+        // the last result is the result of verify and import.
+        match import_result
+            .pop()
+            .expect("import should return at least one result")
+        {
+            Ok(_) if replaced => AdmissionResult::Replaced,
+            Ok(_) => AdmissionResult::Accepted,
+            Err(e) => AdmissionResult::from(e),
+        }
+    }
+
+    /// Broadcast the status events of txns in order.
+    fn broadcast_txn_status_events(&self, events: Vec<TxnStatusEvent>) {
+        for event in events {
+            self.bus.do_send(Broadcast { msg: event });
+        }
+    }
+}
+
+/// `TxnAdded` events of the imported txns, `txns` is the (hash, sender) of every txn in import order.
+fn added_events(
+    txns: Vec<(HashValue, AccountAddress)>,
+    import_result: &[Result<(), transaction::TransactionError>],
+) -> Vec<TxnStatusEvent> {
+    txns.into_iter()
+        .zip(import_result)
+        .filter(|(_, result)| result.is_ok())
+        .map(|((txn_hash, sender), _)| TxnStatusEvent::TxnAdded { txn_hash, sender })
+        .collect()
+}
+
+fn txn_hash_and_sender(txn: &SignedUserTransaction) -> (HashValue, AccountAddress) {
+    (txn.crypto_hash(), txn.sender())
 }
 
 impl actix::Actor for TxPoolActor {
@@ -130,7 +201,7 @@ impl actix::Actor for TxPoolActor {
 
         let receiver = {
             let (tx, rx) = mpsc::unbounded();
-            self.queue.add_full_listener(tx);
+            self.queue.add_status_listener(tx);
             rx
         };
         ctx.add_stream(receiver);
@@ -138,13 +209,29 @@ impl actix::Actor for TxPoolActor {
         info!("txn pool started");
     }
 }
-type TxnStatusEvent = Arc<Vec<(HashValue, TxStatus)>>;
-/// Listen to txn status, and propagate to remote peers if necessary.
-impl StreamHandler<TxnStatusEvent> for TxPoolActor {
-    fn handle(&mut self, item: TxnStatusEvent, ctx: &mut Context<Self>) {
+type TxnStatusFullEvent = Arc<Vec<(HashValue, AccountAddress, TxStatus)>>;
+/// Listen to txn status, broadcast the dropped txns,
+/// and propagate to remote peers if necessary.
+impl StreamHandler<TxnStatusFullEvent> for TxPoolActor {
+    fn handle(&mut self, item: TxnStatusFullEvent, ctx: &mut Context<Self>) {
+        // the canceled txns are either mined or canceled by `RemoveTxn`,
+        // both are broadcast by their handlers.
+        let dropped_events = item
+            .iter()
+            .filter(|(_, _, s)| {
+                matches!(s, TxStatus::Dropped | TxStatus::Invalid | TxStatus::Culled)
+            })
+            .map(|(txn_hash, sender, status)| TxnStatusEvent::TxnDropped {
+                txn_hash: *txn_hash,
+                sender: *sender,
+                status: *status,
+            })
+            .collect();
+        self.broadcast_txn_status_events(dropped_events);
+
         // TODO: need peer info to do more accurate sending.
         let mut txns = vec![];
-        for (h, s) in item.iter() {
+        for (h, _, s) in item.iter() {
             match *s {
                 TxStatus::Added => {
                     TXPOOL_TXNS_GAUGE.inc();
@@ -232,6 +319,7 @@ impl actix::Handler<ImportTxns> for TxPoolActor {
     fn handle(&mut self, msg: ImportTxns, _ctx: &mut Self::Context) -> Self::Result {
         let ImportTxns { txns } = msg;
 
+        let imported: Vec<_> = txns.iter().map(txn_hash_and_sender).collect();
         let txns = txns
             .into_iter()
             .map(|t| PoolTransaction::Unverified(UnverifiedUserTransaction::from(t)));
@@ -241,10 +329,123 @@ impl actix::Handler<ImportTxns> for TxPoolActor {
             self.sequence_number_cache.clone(),
         );
         let import_result = { self.queue.import(client, txns) };
+        self.broadcast_txn_status_events(added_events(imported, &import_result));
         actix::MessageResult(import_result)
     }
 }
 
+pub(crate) struct SubmitTxn {
+    pub(crate) txn: transaction::SignedUserTransaction,
+}
+
+impl actix::Message for SubmitTxn {
+    type Result = AdmissionResult;
+}
+impl actix::Handler<SubmitTxn> for TxPoolActor {
+    type Result = actix::MessageResult<SubmitTxn>;
+
+    fn handle(&mut self, msg: SubmitTxn, _ctx: &mut Self::Context) -> Self::Result {
+        actix::MessageResult(self.submit_txn(msg.txn))
+    }
+}
+
+/// Submit the txns in order, once a txn is rejected for its sequence number, the later txns of
+/// the same sender can not be valid, so they are discarded without verifying.
+pub(crate) struct SubmitTxns {
+    pub(crate) txns: Vec<transaction::SignedUserTransaction>,
+}
+
+impl actix::Message for SubmitTxns {
+    type Result = Vec<AdmissionResult>;
+}
+impl actix::Handler<SubmitTxns> for TxPoolActor {
+    type Result = actix::MessageResult<SubmitTxns>;
+
+    fn handle(&mut self, msg: SubmitTxns, _ctx: &mut Self::Context) -> Self::Result {
+        let mut rejected_senders = HashSet::new();
+        let results = msg
+            .txns
+            .into_iter()
+            .map(|txn| {
+                let sender = txn.sender();
+                if rejected_senders.contains(&sender) {
+                    return AdmissionResult::Discarded(format!(
+                        "an earlier txn of sender {} in the batch is rejected",
+                        sender
+                    ));
+                }
+                let result = self.submit_txn(txn);
+                if is_sequence_number_rejected(&result) {
+                    rejected_senders.insert(sender);
+                }
+                result
+            })
+            .collect();
+        actix::MessageResult(results)
+    }
+}
+
+fn is_sequence_number_rejected(result: &AdmissionResult) -> bool {
+    match result {
+        AdmissionResult::Rejected(status) => match status.major_status {
+            StatusCode::SEQUENCE_NUMBER_TOO_OLD | StatusCode::SEQUENCE_NUMBER_TOO_NEW => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check whether the txn would be admitted, and simulate it on the head state.
+pub(crate) struct Preflight {
+    pub(crate) raw_txn: RawUserTransaction,
+    /// Public key of the sender, the authentication key of the sender is checked against it.
+    pub(crate) public_key: Ed25519PublicKey,
+}
+
+impl actix::Message for Preflight {
+    type Result = Result<PreflightResult>;
+}
+impl actix::Handler<Preflight> for TxPoolActor {
+    type Result = Result<PreflightResult>;
+
+    fn handle(&mut self, msg: Preflight, _ctx: &mut Self::Context) -> Self::Result {
+        let Preflight {
+            raw_txn,
+            public_key,
+        } = msg;
+        // the simulation does not check the signature, so the txn is not required to be signed.
+        let signature = Ed25519Signature::try_from(&[0u8; ED25519_SIGNATURE_LENGTH][..])?;
+        let txn = SignedUserTransaction::new(raw_txn, public_key, signature);
+        let statedb = ChainStateDB::new(
+            self.storage.clone().into_super_arc(),
+            Some(self.chain_header.state_root()),
+        );
+        let output = Executor::simulate_transaction(&statedb, txn.clone())?;
+        let options = self.queue.status().options;
+        let reason = match pool::verifier::check_options(&options, &txn)
+            .and_then(|_| pool::verifier::check_local_min_gas_price(&options, &txn))
+            .and_then(|_| pool::verifier::check_expiration(self.time_service.now_secs(), &txn))
+        {
+            Err(e) => Some(e.to_string()),
+            Ok(()) => match output.status() {
+                // txns with a future sequence number are admitted to the pool.
+                TransactionStatus::Discard(status)
+                    if status.major_status != StatusCode::SEQUENCE_NUMBER_TOO_NEW =>
+                {
+                    Some(format!("{:?}", status))
+                }
+                _ => None,
+            },
+        };
+        Ok(PreflightResult {
+            would_admit: reason.is_none(),
+            reason,
+            estimated_gas: output.gas_used(),
+            simulated_status: output.status().vm_status().clone(),
+        })
+    }
+}
+
 pub(crate) struct RemoveTxn {
     pub(crate) txn_hash: HashValue,
     pub(crate) is_invalid: bool,
@@ -264,6 +465,14 @@ impl actix::Handler<RemoveTxn> for TxPoolActor {
         let removed = removed
             .pop()
             .expect("remove should return one result per hash");
+        // the invalid txns are broadcast by the status listener.
+        if let (Some(txn), false) = (&removed, is_invalid) {
+            self.broadcast_txn_status_events(vec![TxnStatusEvent::TxnDropped {
+                txn_hash,
+                sender: txn.signed().sender(),
+                status: TxStatus::Canceled,
+            }]);
+        }
         actix::MessageResult(removed)
     }
 }
@@ -286,6 +495,22 @@ impl actix::Handler<GetPendingTxns> for TxPoolActor {
     }
 }
 
+pub(crate) struct NextSequenceNumber {
+    pub(crate) address: AccountAddress,
+}
+
+impl actix::Message for NextSequenceNumber {
+    type Result = Option<u64>;
+}
+
+impl actix::Handler<NextSequenceNumber> for TxPoolActor {
+    type Result = actix::MessageResult<NextSequenceNumber>;
+
+    fn handle(&mut self, msg: NextSequenceNumber, _ctx: &mut Self::Context) -> Self::Result {
+        actix::MessageResult(self.queue.next_sequence_number(&msg.address))
+    }
+}
+
 pub(crate) struct SubscribeTxns;
 impl actix::Message for SubscribeTxns {
     type Result = mpsc::UnboundedReceiver<Arc<Vec<(HashValue, TxStatus)>>>;
@@ -330,7 +555,19 @@ impl actix::Handler<ChainNewBlock> for TxPoolActor {
         );
 
         let hashes: Vec<_> = enacted.iter().map(|t| t.crypto_hash()).collect();
-        let _ = self.queue.remove(hashes.iter(), false);
+        let removed = self.queue.remove(hashes.iter(), false);
+        // only the txns pending in the pool change to mined.
+        let mined_events = hashes
+            .into_iter()
+            .zip(removed)
+            .filter_map(|(txn_hash, removed)| {
+                removed.map(|txn| TxnStatusEvent::TxnMined {
+                    txn_hash,
+                    sender: txn.signed().sender(),
+                })
+            })
+            .collect();
+        self.broadcast_txn_status_events(mined_events);
 
         let client = PoolClient::new(
             self.chain_header.clone(),
@@ -338,10 +575,12 @@ impl actix::Handler<ChainNewBlock> for TxPoolActor {
             self.sequence_number_cache.clone(),
         );
 
+        let imported: Vec<_> = retracted.iter().map(txn_hash_and_sender).collect();
         let txns = retracted
             .into_iter()
             .map(|t| PoolTransaction::Retracted(UnverifiedUserTransaction::from(t)));
-        let _ = self.queue.import(client.clone(), txns);
+        let import_result = self.queue.import(client.clone(), txns);
+        self.broadcast_txn_status_events(added_events(imported, &import_result));
         // ignore import result
         // for r in import_result {
         //     r?;
@@ -4,8 +4,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transaction::authenticator::AuthenticationKey;
+use crate::transaction::authenticator::{AuthenticationKey, SchemedAuthenticationKey};
 use anyhow::{ensure, Error, Result};
+use bech32::{FromBase32, ToBase32};
 use bytes::Bytes;
 use rand::{rngs::OsRng, Rng};
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
@@ -54,6 +55,14 @@ impl AccountAddress {
             .into()
     }
 
+    /// Address of a `threshold`-of-n multi ed25519 account.
+    pub fn from_multi_ed25519_public_keys(
+        public_keys: &[Ed25519PublicKey],
+        threshold: u8,
+    ) -> Result<Self> {
+        Ok(SchemedAuthenticationKey::multi_ed25519(public_keys, threshold)?.derived_address())
+    }
+
     pub fn from_hex_literal(literal: &str) -> Result<Self> {
         ensure!(literal.starts_with("0x"), "literal must start with 0x.");
 
@@ -68,6 +77,12 @@ impl AccountAddress {
         };
 
         let len = result.len();
+        ensure!(
+            len <= ADDRESS_LENGTH,
+            "address literal too long: got {} bytes, max {}",
+            len,
+            ADDRESS_LENGTH
+        );
         let padded_result = if len < ADDRESS_LENGTH {
             let mut padded = Vec::with_capacity(ADDRESS_LENGTH);
             padded.resize(ADDRESS_LENGTH - len, 0u8);
@@ -80,6 +95,20 @@ impl AccountAddress {
         AccountAddress::try_from(padded_result)
     }
 
+    /// Encode the address as bech32 with the human readable part `hrp`, the hrp must be valid,
+    /// 1 to 83 ascii chars in the range 33 to 126 and not mixed case.
+    pub fn to_bech32(&self, hrp: &str) -> String {
+        bech32::encode(hrp, self.0.to_base32())
+            .unwrap_or_else(|e| panic!("encode address with hrp {} failed: {}", hrp, e))
+    }
+
+    /// Decode a bech32 encoded address, any human readable part is accepted.
+    pub fn from_bech32(s: &str) -> Result<Self> {
+        let (_hrp, data) = bech32::decode(s)?;
+        let bytes = Vec::<u8>::from_base32(&data)?;
+        AccountAddress::try_from(bytes)
+    }
+
     pub fn into_inner(self) -> [u8; ADDRESS_LENGTH] {
         self.0
     }
@@ -263,4 +292,68 @@ mod tests {
         let address2: AccountAddress = address1.into();
         assert_eq!(address0, address2);
     }
+
+    #[test]
+    fn test_from_hex_literal() {
+        let address = AccountAddress::from_hex_literal("0x1").unwrap();
+        let mut expected = [0u8; ADDRESS_LENGTH];
+        expected[ADDRESS_LENGTH - 1] = 1;
+        assert_eq!(address, AccountAddress::new(expected));
+
+        let address = AccountAddress::from_hex_literal("0xa550c18").unwrap();
+        expected[ADDRESS_LENGTH - 4..].copy_from_slice(&[0x0a, 0x55, 0x0c, 0x18]);
+        assert_eq!(address, AccountAddress::new(expected));
+
+        let full = format!("0x{}", "ab".repeat(ADDRESS_LENGTH));
+        assert_eq!(
+            AccountAddress::from_hex_literal(&full).unwrap(),
+            AccountAddress::new([0xab; ADDRESS_LENGTH])
+        );
+    }
+
+    #[test]
+    fn test_bech32() {
+        let address = AccountAddress::random();
+        let encoded = address.to_bech32("stc");
+        assert!(encoded.starts_with("stc1"));
+        assert_eq!(AccountAddress::from_bech32(&encoded).unwrap(), address);
+        // the hex form still works.
+        assert_eq!(
+            AccountAddress::from_str(&hex::encode(address.as_ref())).unwrap(),
+            address
+        );
+
+        let address = AccountAddress::DEFAULT;
+        assert_eq!(
+            AccountAddress::from_bech32(&address.to_bech32("tstc")).unwrap(),
+            address
+        );
+        // the data of another length is not an address.
+        let short = bech32::encode("stc", vec![1u8; 8].to_base32()).unwrap();
+        assert!(AccountAddress::from_bech32(&short).is_err());
+    }
+
+    #[test]
+    fn test_bech32_checksum() {
+        let encoded = AccountAddress::random().to_bech32("stc");
+        let mut chars: Vec<char> = encoded.chars().collect();
+        let index = encoded.len() - 10;
+        chars[index] = if chars[index] == 'q' { 'p' } else { 'q' };
+        let corrupted: String = chars.into_iter().collect();
+        assert_ne!(corrupted, encoded);
+        assert!(AccountAddress::from_bech32(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_from_hex_literal_too_long() {
+        let literal = format!("0x{}", "ab".repeat(ADDRESS_LENGTH + 1));
+        let err = AccountAddress::from_hex_literal(&literal).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "address literal too long: got 17 bytes, max 16"
+        );
+        // an odd length literal is padded before the check.
+        let literal = format!("0x1{}", "ab".repeat(ADDRESS_LENGTH));
+        assert!(AccountAddress::from_hex_literal(&literal).is_err());
+    }
 }
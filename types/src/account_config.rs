@@ -243,6 +243,16 @@ impl AccountResource {
     pub fn authentication_key(&self) -> &[u8] {
         self.0.authentication_key()
     }
+
+    /// Return the count of the sent payment events for the given AccountResource
+    pub fn sent_events_count(&self) -> u64 {
+        self.0.sent_events().count()
+    }
+
+    /// Return the count of the received payment events for the given AccountResource
+    pub fn received_events_count(&self) -> u64 {
+        self.0.received_events().count()
+    }
 }
 
 impl TryInto<Vec<u8>> for AccountResource {
@@ -332,4 +342,34 @@ mod tests {
             address.to_vec().len()
         );
     }
+
+    #[test]
+    fn test_decode_event_counts() {
+        let address = libra_types::account_address::AccountAddress::random();
+        let account_res = libra_types::account_config::AccountResource::new(
+            1,
+            address.to_vec(),
+            false,
+            false,
+            libra_types::event::EventHandle::new(
+                libra_types::event::EventKey::new_from_address(&address, 0),
+                3,
+            ),
+            libra_types::event::EventHandle::new(
+                libra_types::event::EventKey::new_from_address(&address, 1),
+                5,
+            ),
+            0,
+        );
+        let bytes: Vec<u8> = AccountResource::from(account_res).try_into().unwrap();
+        let account_res = AccountResource::make_from(bytes.as_slice()).unwrap();
+        assert_eq!(account_res.sequence_number(), 1);
+        assert_eq!(account_res.sent_events_count(), 3);
+        assert_eq!(account_res.received_events_count(), 5);
+
+        // a new account has not sent or received any payment.
+        let account_res = AccountResource::new(0, address.to_vec());
+        assert_eq!(account_res.sent_events_count(), 0);
+        assert_eq!(account_res.received_events_count(), 0);
+    }
 }
@@ -7,11 +7,17 @@ use crate::transaction::SignedUserTransaction;
 use starcoin_crypto::{hash::CryptoHash, HashValue};
 
 use crate::{U256, U512};
+use anyhow::{ensure, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use starcoin_accumulator::{
+    node::ACCUMULATOR_PLACEHOLDER_HASH, Accumulator, MerkleAccumulator, MockAccumulatorStore,
+};
 use starcoin_crypto::hash::create_literal_hash;
 use std::cmp::Ordering;
 use std::cmp::PartialOrd;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 /// Type for block number.
 pub type BlockNumber = u64;
@@ -148,6 +154,21 @@ impl BlockHeader {
         self.consensus_header.as_slice()
     }
 
+    /// Decode the consensus extend header field to the typed header of the consensus.
+    pub fn decode_consensus_header<H>(&self) -> Result<H>
+    where
+        H: TryFrom<Vec<u8>>,
+        H::Error: Into<anyhow::Error>,
+    {
+        H::try_from(self.consensus_header.clone()).map_err(|e| {
+            let e: anyhow::Error = e.into();
+            e.context(format!(
+                "decode consensus header of block {:?} failed",
+                self.id()
+            ))
+        })
+    }
+
     pub fn into_metadata(self) -> BlockMetadata {
         BlockMetadata::new(
             self.parent_hash(),
@@ -160,7 +181,42 @@ impl BlockHeader {
         self.difficult
     }
 
+    /// Verify the header extends `parent`: its number is the next number, its parent hash is the
+    /// id of `parent`, and its total difficulty is the total difficulty of `parent` plus its
+    /// difficulty. The header does not carry the total difficulty, so both come from the block
+    /// infos.
+    pub fn verify_cumulative_difficulty(
+        &self,
+        total_difficulty: U512,
+        parent: &BlockHeader,
+        parent_total_difficulty: U512,
+    ) -> Result<()> {
+        ensure!(
+            self.number == parent.number + 1,
+            "Block number {} is not the next number of parent block number {}",
+            self.number,
+            parent.number
+        );
+        ensure!(
+            self.parent_hash == parent.id(),
+            "Block parent hash {} mismatch parent block id {}",
+            self.parent_hash,
+            parent.id()
+        );
+        let expect_total_difficulty = parent_total_difficulty + self.difficult.into();
+        ensure!(
+            total_difficulty == expect_total_difficulty,
+            "Total difficulty of block {}(number {}) is {}, expect {}",
+            self.id(),
+            self.number,
+            total_difficulty,
+            expect_total_difficulty
+        );
+        Ok(())
+    }
+
     pub fn genesis_block_header(
+        timestamp: u64,
         accumulator_root: HashValue,
         state_root: HashValue,
         difficult: U256,
@@ -169,8 +225,7 @@ impl BlockHeader {
         Self {
             //TODO should use a placeholder hash?
             parent_hash: HashValue::zero(),
-            //TODO hard code a genesis block time.
-            timestamp: 0,
+            timestamp,
             number: 0,
             author: AccountAddress::default(),
             auth_key_prefix: None,
@@ -183,6 +238,16 @@ impl BlockHeader {
             consensus_header,
         }
     }
+
+    /// The genesis block header with timestamp 0.
+    pub fn genesis_block_header_for_test(
+        accumulator_root: HashValue,
+        state_root: HashValue,
+        difficult: U256,
+        consensus_header: Vec<u8>,
+    ) -> Self {
+        Self::genesis_block_header(0, accumulator_root, state_root, difficult, consensus_header)
+    }
 }
 
 impl Ord for BlockHeader {
@@ -208,6 +273,41 @@ impl BlockBody {
     pub fn new(transactions: Vec<SignedUserTransaction>) -> Self {
         Self { transactions }
     }
+
+    pub fn transactions(&self) -> &[SignedUserTransaction] {
+        self.transactions.as_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// The root of an in memory merkle accumulator whose leaves are the transaction hashes, the
+    /// placeholder hash for an empty body.
+    pub fn transaction_accumulator_root(&self) -> HashValue {
+        let leaves: Vec<HashValue> = self
+            .transactions
+            .iter()
+            .map(|txn| txn.crypto_hash())
+            .collect();
+        let accumulator = MerkleAccumulator::new(
+            HashValue::zero(),
+            *ACCUMULATOR_PLACEHOLDER_HASH,
+            vec![],
+            0,
+            0,
+            Arc::new(MockAccumulatorStore::new()),
+        )
+        .expect("create an empty accumulator should succeed");
+        let (root, _) = accumulator
+            .append_only_cache(&leaves)
+            .expect("append to an in memory accumulator should succeed");
+        root
+    }
 }
 
 impl Into<BlockBody> for Vec<SignedUserTransaction> {
@@ -246,19 +346,21 @@ impl Block {
         &self.header
     }
     pub fn transactions(&self) -> &[SignedUserTransaction] {
-        self.body.transactions.as_slice()
+        self.body.transactions()
     }
     pub fn into_inner(self) -> (BlockHeader, BlockBody) {
         (self.header, self.body)
     }
 
     pub fn genesis_block(
+        timestamp: u64,
         accumulator_root: HashValue,
         state_root: HashValue,
         difficult: U256,
         consensus_header: Vec<u8>,
     ) -> Self {
         let header = BlockHeader::genesis_block_header(
+            timestamp,
             accumulator_root,
             state_root,
             difficult,
@@ -472,3 +574,303 @@ impl BlockDetail {
         self.block.header()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    //! Block id fixtures.
+    //!
+    //! A block id is the sha3 of the scs bytes of the header, so adding, removing, reordering or
+    //! retyping a header field changes every block id and breaks the compatibility with other
+    //! nodes. The fixtures rebuild the expected bytes field by field from typed literals, any
+    //! serialization affecting change of `BlockHeader` or `Block` fails them.
+    //!
+    //! To change the layout intentionally, update `expected_header_bytes` to the new field
+    //! declaration order and types. Such a change needs a new genesis, it can not be applied to a
+    //! running network.
+    use super::*;
+    use crate::transaction::RawUserTransaction;
+    use rand::{rngs::StdRng, SeedableRng};
+    use starcoin_crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+        test_utils::KeyPair,
+    };
+
+    struct HeaderFixture {
+        parent_hash: HashValue,
+        timestamp: u64,
+        number: u64,
+        author: AccountAddress,
+        auth_key_prefix: Option<Vec<u8>>,
+        accumulator_root: HashValue,
+        state_root: HashValue,
+        gas_used: u64,
+        gas_limit: u64,
+        difficult: U256,
+        consensus_header: Vec<u8>,
+    }
+
+    fn expected_header_bytes(fixture: &HeaderFixture) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(scs::to_bytes(&fixture.parent_hash).unwrap());
+        bytes.extend(&fixture.timestamp.to_le_bytes());
+        bytes.extend(&fixture.number.to_le_bytes());
+        bytes.extend(scs::to_bytes(&fixture.author).unwrap());
+        match &fixture.auth_key_prefix {
+            Some(prefix) => {
+                bytes.push(1);
+                bytes.extend(scs::to_bytes(prefix).unwrap());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend(scs::to_bytes(&fixture.accumulator_root).unwrap());
+        bytes.extend(scs::to_bytes(&fixture.state_root).unwrap());
+        bytes.extend(&fixture.gas_used.to_le_bytes());
+        bytes.extend(&fixture.gas_limit.to_le_bytes());
+        bytes.extend(scs::to_bytes(&fixture.difficult).unwrap());
+        bytes.extend(scs::to_bytes(&fixture.consensus_header).unwrap());
+        bytes
+    }
+
+    fn genesis_fixture() -> HeaderFixture {
+        HeaderFixture {
+            parent_hash: HashValue::zero(),
+            timestamp: 0,
+            number: 0,
+            author: AccountAddress::DEFAULT,
+            auth_key_prefix: None,
+            accumulator_root: HashValue::new([1u8; HashValue::LENGTH]),
+            state_root: HashValue::new([2u8; HashValue::LENGTH]),
+            gas_used: 0,
+            gas_limit: 0,
+            difficult: U256::from(1024u64),
+            consensus_header: vec![],
+        }
+    }
+
+    fn filled_fixture() -> HeaderFixture {
+        HeaderFixture {
+            parent_hash: HashValue::new([3u8; HashValue::LENGTH]),
+            timestamp: 1_590_000_000_000,
+            number: 42,
+            author: AccountAddress::new([4u8; 16]),
+            auth_key_prefix: Some(vec![5u8; 16]),
+            accumulator_root: HashValue::new([6u8; HashValue::LENGTH]),
+            state_root: HashValue::new([7u8; HashValue::LENGTH]),
+            gas_used: 10_000,
+            gas_limit: 1_000_000,
+            difficult: U256::from(0x1234_5678u64),
+            consensus_header: vec![8u8; 12],
+        }
+    }
+
+    fn fixture_header(fixture: &HeaderFixture) -> BlockHeader {
+        BlockHeader::new_with_auth(
+            fixture.parent_hash,
+            fixture.timestamp,
+            fixture.number,
+            fixture.author,
+            fixture.auth_key_prefix.clone(),
+            fixture.accumulator_root,
+            fixture.state_root,
+            fixture.gas_used,
+            fixture.gas_limit,
+            fixture.difficult,
+            fixture.consensus_header.clone(),
+        )
+    }
+
+    fn fixture_txns() -> Vec<SignedUserTransaction> {
+        let mut rng = StdRng::from_seed([9u8; 32]);
+        let key_pair = KeyPair::<Ed25519PrivateKey, Ed25519PublicKey>::generate(&mut rng);
+        let txn = RawUserTransaction::mock_by_sender(AccountAddress::new([10u8; 16]))
+            .sign(&key_pair.private_key, key_pair.public_key)
+            .unwrap()
+            .into_inner();
+        vec![txn]
+    }
+
+    #[test]
+    fn test_genesis_block_header_id() {
+        let fixture = genesis_fixture();
+        let header = BlockHeader::genesis_block_header_for_test(
+            fixture.accumulator_root,
+            fixture.state_root,
+            fixture.difficult,
+            fixture.consensus_header.clone(),
+        );
+        let expected_bytes = expected_header_bytes(&fixture);
+        assert_eq!(scs::to_bytes(&header).unwrap(), expected_bytes);
+        assert_eq!(header.id(), HashValue::from_sha3_256(&expected_bytes));
+        assert_eq!(header.id(), fixture_header(&fixture).id());
+
+        // a genesis block is the header with an empty body.
+        let block = Block::genesis_block(
+            fixture.timestamp,
+            fixture.accumulator_root,
+            fixture.state_root,
+            fixture.difficult,
+            fixture.consensus_header,
+        );
+        let mut expected_block_bytes = expected_bytes;
+        expected_block_bytes.extend(scs::to_bytes(&Vec::<SignedUserTransaction>::new()).unwrap());
+        assert_eq!(
+            block.crypto_hash(),
+            HashValue::from_sha3_256(&expected_block_bytes)
+        );
+    }
+
+    #[test]
+    fn test_genesis_block_timestamp() {
+        let fixture = genesis_fixture();
+        let genesis_block = |timestamp| {
+            Block::genesis_block(
+                timestamp,
+                fixture.accumulator_root,
+                fixture.state_root,
+                fixture.difficult,
+                fixture.consensus_header.clone(),
+            )
+        };
+        let block = genesis_block(1_590_000_000_000);
+        assert_eq!(block.header().timestamp(), 1_590_000_000_000);
+        assert_ne!(block.header().id(), genesis_block(0).header().id());
+        assert_ne!(
+            block.header().id(),
+            genesis_block(1_590_000_000_001).header().id()
+        );
+        assert_eq!(
+            block.header().id(),
+            genesis_block(1_590_000_000_000).header().id()
+        );
+    }
+
+    #[test]
+    fn test_filled_block_id() {
+        let fixture = filled_fixture();
+        let header = fixture_header(&fixture);
+        let expected_bytes = expected_header_bytes(&fixture);
+        assert_eq!(scs::to_bytes(&header).unwrap(), expected_bytes);
+        assert_eq!(header.id(), HashValue::from_sha3_256(&expected_bytes));
+
+        let txns = fixture_txns();
+        let block = Block::new(header.clone(), txns.clone());
+        let mut expected_block_bytes = expected_bytes;
+        expected_block_bytes.extend(scs::to_bytes(&txns).unwrap());
+        assert_eq!(
+            block.crypto_hash(),
+            HashValue::from_sha3_256(&expected_block_bytes)
+        );
+        // the fixture is reproducible.
+        assert_eq!(
+            Block::new(fixture_header(&filled_fixture()), fixture_txns()).crypto_hash(),
+            block.crypto_hash()
+        );
+    }
+
+    fn mock_txns(count: u8) -> Vec<SignedUserTransaction> {
+        let mut rng = StdRng::from_seed([12u8; 32]);
+        let key_pair = KeyPair::<Ed25519PrivateKey, Ed25519PublicKey>::generate(&mut rng);
+        (0..count)
+            .map(|i| {
+                RawUserTransaction::mock_by_sender(AccountAddress::new([i; 16]))
+                    .sign(&key_pair.private_key, key_pair.public_key.clone())
+                    .unwrap()
+                    .into_inner()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_body() {
+        let body = BlockBody::default();
+        assert!(body.is_empty());
+        assert_eq!(body.len(), 0);
+        assert!(body.transactions().is_empty());
+        assert_eq!(
+            body.transaction_accumulator_root(),
+            *ACCUMULATOR_PLACEHOLDER_HASH
+        );
+    }
+
+    #[test]
+    fn test_body_transaction_accumulator_root() {
+        let txns = mock_txns(3);
+        let body = BlockBody::new(txns.clone());
+        assert!(!body.is_empty());
+        assert_eq!(body.len(), 3);
+        assert_eq!(body.transactions(), txns.as_slice());
+        let root = body.transaction_accumulator_root();
+        assert_ne!(root, *ACCUMULATOR_PLACEHOLDER_HASH);
+        assert_eq!(
+            root,
+            BlockBody::new(txns.clone()).transaction_accumulator_root()
+        );
+
+        // the root of a single transaction is the transaction hash.
+        let single = BlockBody::new(txns[..1].to_vec());
+        assert_eq!(single.transaction_accumulator_root(), txns[0].crypto_hash());
+
+        // a missing or reordered transaction changes the root.
+        let missing = BlockBody::new(txns[..2].to_vec());
+        assert_ne!(missing.transaction_accumulator_root(), root);
+        let mut swapped = txns;
+        swapped.swap(0, 1);
+        assert_ne!(BlockBody::new(swapped).transaction_accumulator_root(), root);
+    }
+
+    fn child_header(parent: &BlockHeader, difficult: U256) -> BlockHeader {
+        BlockHeader::new(
+            parent.id(),
+            parent.timestamp() + 1,
+            parent.number() + 1,
+            AccountAddress::new([11u8; 16]),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+            difficult,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_verify_cumulative_difficulty() {
+        let parent = fixture_header(&filled_fixture());
+        let parent_total_difficulty = U512::from(1_000_000u64);
+        let difficult = U256::from(100u64);
+        let total_difficulty = parent_total_difficulty + difficult.into();
+        let header = child_header(&parent, difficult);
+        assert!(header
+            .verify_cumulative_difficulty(total_difficulty, &parent, parent_total_difficulty)
+            .is_ok());
+
+        // the total difficulty is not the parent total difficulty plus the difficulty.
+        assert!(header
+            .verify_cumulative_difficulty(
+                total_difficulty + U512::one(),
+                &parent,
+                parent_total_difficulty
+            )
+            .is_err());
+        assert!(header
+            .verify_cumulative_difficulty(parent_total_difficulty, &parent, parent_total_difficulty)
+            .is_err());
+
+        // the number is not the next number of the parent.
+        let mut skipped = header.clone();
+        skipped.number = parent.number() + 2;
+        assert!(skipped
+            .verify_cumulative_difficulty(total_difficulty, &parent, parent_total_difficulty)
+            .is_err());
+        assert!(parent
+            .verify_cumulative_difficulty(total_difficulty, &parent, parent_total_difficulty)
+            .is_err());
+
+        // the parent hash is not the id of the parent.
+        let mut orphan = header;
+        orphan.parent_hash = HashValue::random();
+        assert!(orphan
+            .verify_cumulative_difficulty(total_difficulty, &parent, parent_total_difficulty)
+            .is_err());
+    }
+}
@@ -8,7 +8,8 @@ use crate::account_address::AccountAddress;
 use crate::block::BlockHeader;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use starcoin_crypto::{hash::CryptoHash, HashValue};
+use starcoin_crypto::{ed25519::Ed25519Signature, hash::CryptoHash, HashValue};
+use std::collections::BTreeMap;
 
 /// Struct that will be persisted on chain to store the information of the current block.
 ///
@@ -27,7 +28,10 @@ pub struct BlockMetadata {
     timestamp: u64,
     author: AccountAddress,
     auth_key_prefix: Option<Vec<u8>>,
-    //TODO add more field.
+    /// Consensus round, 0 if absent.
+    round: Option<u64>,
+    /// Votes on the previous block, empty if absent.
+    previous_vote: Option<BTreeMap<AccountAddress, Ed25519Signature>>,
 }
 
 impl BlockMetadata {
@@ -42,9 +46,32 @@ impl BlockMetadata {
             timestamp,
             author,
             auth_key_prefix,
+            round: None,
+            previous_vote: None,
         }
     }
 
+    pub fn with_round(mut self, round: u64) -> Self {
+        self.round = Some(round);
+        self
+    }
+
+    pub fn with_previous_vote(
+        mut self,
+        previous_vote: BTreeMap<AccountAddress, Ed25519Signature>,
+    ) -> Self {
+        self.previous_vote = Some(previous_vote);
+        self
+    }
+
+    pub fn round(&self) -> Option<u64> {
+        self.round
+    }
+
+    pub fn previous_vote(&self) -> Option<&BTreeMap<AccountAddress, Ed25519Signature>> {
+        self.previous_vote.as_ref()
+    }
+
     pub fn into_inner(self) -> Result<(Vec<u8>, u64, AccountAddress, Option<Vec<u8>>)> {
         let id = self.id.to_vec();
         Ok((id, self.timestamp, self.author, self.auth_key_prefix))
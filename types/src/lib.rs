@@ -18,6 +18,7 @@ pub mod proof;
 pub mod startup_info;
 pub mod state_set;
 pub mod system_events;
+pub mod time;
 pub mod transaction;
 pub mod vm_error;
 pub mod write_set;
@@ -0,0 +1,100 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Clock used by time dependent logic, so tests can inject a mock clock instead of the wall clock.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub trait TimeService: Send + Sync + std::fmt::Debug {
+    /// Seconds since the unix epoch.
+    fn now_secs(&self) -> u64 {
+        self.now_millis() / 1000
+    }
+
+    /// Milliseconds since the unix epoch.
+    fn now_millis(&self) -> u64;
+
+    /// Wait for `duration` on this clock.
+    fn sleep(&self, duration: Duration);
+}
+
+/// The wall clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealTimeService;
+
+impl RealTimeService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TimeService for RealTimeService {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before the unix epoch.")
+            .as_millis() as u64
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A clock that only moves when it is told to.
+#[derive(Debug, Default)]
+pub struct MockTimeService {
+    millis: AtomicU64,
+}
+
+impl MockTimeService {
+    pub fn new_with_millis(millis: u64) -> Self {
+        Self {
+            millis: AtomicU64::new(millis),
+        }
+    }
+
+    pub fn new_with_secs(secs: u64) -> Self {
+        Self::new_with_millis(secs * 1000)
+    }
+
+    pub fn set_millis(&self, millis: u64) {
+        self.millis.store(millis, Ordering::SeqCst);
+    }
+
+    pub fn increase(&self, duration: Duration) {
+        self.millis
+            .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl TimeService for MockTimeService {
+    fn now_millis(&self) -> u64 {
+        self.millis.load(Ordering::SeqCst)
+    }
+
+    /// Move the clock forward instead of waiting.
+    fn sleep(&self, duration: Duration) {
+        self.increase(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_time_service() {
+        let time_service = MockTimeService::new_with_secs(100);
+        assert_eq!(time_service.now_secs(), 100);
+        assert_eq!(time_service.now_millis(), 100_000);
+        time_service.increase(Duration::from_millis(1500));
+        assert_eq!(time_service.now_millis(), 101_500);
+        assert_eq!(time_service.now_secs(), 101);
+        time_service.set_millis(0);
+        assert_eq!(time_service.now_secs(), 0);
+        time_service.sleep(Duration::from_secs(2));
+        assert_eq!(time_service.now_secs(), 2);
+    }
+}
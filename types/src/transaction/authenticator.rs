@@ -1,4 +1,179 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::account_address::AccountAddress;
+use anyhow::{ensure, format_err, Error, Result};
 pub use libra_types::transaction::authenticator::*;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::{ed25519::Ed25519PublicKey, HashValue};
+use std::convert::TryFrom;
+
+/// Max number of public keys of a multi ed25519 authentication key.
+pub const MAX_MULTI_ED25519_KEYS: usize = 32;
+
+/// Scheme of an authentication key, it is the identifier byte of `SchemedAuthenticationKey`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum AuthenticationKeyScheme {
+    Ed25519 = 0,
+    MultiEd25519 = 1,
+}
+
+impl TryFrom<u8> for AuthenticationKeyScheme {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(AuthenticationKeyScheme::Ed25519),
+            1 => Ok(AuthenticationKeyScheme::MultiEd25519),
+            _ => Err(format_err!("Unknown authentication key scheme: {}", value)),
+        }
+    }
+}
+
+/// An `AuthenticationKey` with the scheme it is derived by.
+/// The scheme byte is part of the hashed preimage, so keys of different schemes derive different
+/// addresses, and a single ed25519 key derives the same key as `AuthenticationKey::ed25519`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SchemedAuthenticationKey {
+    scheme: AuthenticationKeyScheme,
+    key: AuthenticationKey,
+}
+
+impl SchemedAuthenticationKey {
+    pub fn ed25519(public_key: &Ed25519PublicKey) -> Self {
+        Self {
+            scheme: AuthenticationKeyScheme::Ed25519,
+            key: AuthenticationKey::ed25519(public_key),
+        }
+    }
+
+    /// Authentication key of a `threshold`-of-n multi signature account.
+    pub fn multi_ed25519(public_keys: &[Ed25519PublicKey], threshold: u8) -> Result<Self> {
+        ensure!(
+            !public_keys.is_empty() && public_keys.len() <= MAX_MULTI_ED25519_KEYS,
+            "Invalid multi ed25519 public key number: {}",
+            public_keys.len()
+        );
+        ensure!(
+            threshold > 0 && threshold as usize <= public_keys.len(),
+            "Invalid multi ed25519 threshold {} of {} keys",
+            threshold,
+            public_keys.len()
+        );
+        let mut preimage = Vec::with_capacity(public_keys.len() * 32 + 2);
+        for public_key in public_keys {
+            preimage.extend_from_slice(&public_key.to_bytes());
+        }
+        preimage.push(threshold);
+        preimage.push(AuthenticationKeyScheme::MultiEd25519 as u8);
+        let mut key = [0u8; AuthenticationKey::LENGTH];
+        key.copy_from_slice(HashValue::from_sha3_256(&preimage).as_ref());
+        Ok(Self {
+            scheme: AuthenticationKeyScheme::MultiEd25519,
+            key: AuthenticationKey::new(key),
+        })
+    }
+
+    pub fn scheme(&self) -> AuthenticationKeyScheme {
+        self.scheme
+    }
+
+    pub fn key(&self) -> &AuthenticationKey {
+        &self.key
+    }
+
+    pub fn prefix(&self) -> [u8; AuthenticationKey::LENGTH - AccountAddress::LENGTH] {
+        self.key.prefix()
+    }
+
+    pub fn derived_address(&self) -> AccountAddress {
+        self.key.derived_address().into()
+    }
+
+    /// The scheme byte followed by the key bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(AuthenticationKey::LENGTH + 1);
+        bytes.push(self.scheme as u8);
+        bytes.extend_from_slice(self.key.as_ref());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for SchemedAuthenticationKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == AuthenticationKey::LENGTH + 1,
+            "Invalid schemed authentication key length: {}",
+            bytes.len()
+        );
+        Ok(Self {
+            scheme: AuthenticationKeyScheme::try_from(bytes[0])?,
+            key: AuthenticationKey::try_from(&bytes[1..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use starcoin_crypto::{ed25519::Ed25519PrivateKey, test_utils::KeyPair};
+
+    #[test]
+    fn test_multi_ed25519_authentication_key() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let public_keys: Vec<Ed25519PublicKey> = (0..3)
+            .map(|_| KeyPair::<Ed25519PrivateKey, Ed25519PublicKey>::generate(&mut rng).public_key)
+            .collect();
+
+        let key = SchemedAuthenticationKey::multi_ed25519(&public_keys, 2).unwrap();
+        assert_eq!(key.scheme(), AuthenticationKeyScheme::MultiEd25519);
+        let address = key.derived_address();
+        assert_eq!(
+            SchemedAuthenticationKey::multi_ed25519(&public_keys, 2)
+                .unwrap()
+                .derived_address(),
+            address
+        );
+        assert_ne!(
+            SchemedAuthenticationKey::multi_ed25519(&public_keys, 3)
+                .unwrap()
+                .derived_address(),
+            address
+        );
+        for public_key in &public_keys {
+            let single_key = SchemedAuthenticationKey::ed25519(public_key);
+            assert_eq!(single_key.key(), &AuthenticationKey::ed25519(public_key));
+            assert_eq!(
+                single_key.derived_address(),
+                AccountAddress::from_public_key(public_key)
+            );
+            assert_ne!(single_key.derived_address(), address);
+        }
+
+        assert!(SchemedAuthenticationKey::multi_ed25519(&public_keys, 0).is_err());
+        assert!(SchemedAuthenticationKey::multi_ed25519(&public_keys, 4).is_err());
+    }
+
+    #[test]
+    fn test_authentication_key_scheme_bytes() {
+        let mut rng = StdRng::from_seed([1u8; 32]);
+        let public_keys: Vec<Ed25519PublicKey> = (0..3)
+            .map(|_| KeyPair::<Ed25519PrivateKey, Ed25519PublicKey>::generate(&mut rng).public_key)
+            .collect();
+        let multi_key = SchemedAuthenticationKey::multi_ed25519(&public_keys, 2).unwrap();
+        let single_key = SchemedAuthenticationKey::ed25519(&public_keys[0]);
+        for key in vec![multi_key, single_key] {
+            let bytes = key.to_bytes();
+            assert_eq!(bytes[0], key.scheme() as u8);
+            assert_eq!(
+                SchemedAuthenticationKey::try_from(bytes.as_slice()).unwrap(),
+                key
+            );
+        }
+        assert!(AuthenticationKeyScheme::try_from(2u8).is_err());
+    }
+}
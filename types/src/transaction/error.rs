@@ -49,6 +49,13 @@ pub enum Error {
         /// Declared transaction gas
         got: Gas,
     },
+    /// Transaction is expired at the time of the pool.
+    Expired {
+        /// Transaction expiration time, seconds since the unix epoch.
+        expiration: u64,
+        /// Current time of the pool, seconds since the unix epoch.
+        now: u64,
+    },
     /// Transaction's gas limit (aka gas) is invalid.
     //    InvalidGasLimit(OutOfBounds<Gas>),
     /// Transaction sender is banned.
@@ -94,6 +101,10 @@ impl fmt::Display for Error {
             GasLimitExceeded { limit, got } => {
                 format!("Gas limit exceeded. Limit={}, Given={}", limit, got)
             }
+            Expired { expiration, now } => format!(
+                "Transaction expired. Expiration={}, Now={}",
+                expiration, now
+            ),
             //            InvalidGasLimit(ref err) => format!("Invalid gas limit. {}", err),
             SenderBanned => "Sender is temporarily banned.".into(),
             RecipientBanned => "Recipient is temporarily banned.".into(),
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_config::starcoin_type_tag,
     block_metadata::BlockMetadata,
@@ -9,6 +10,7 @@ use crate::{
     language_storage::TypeTag,
     state_set::ChainStateSet,
     vm_error::{StatusCode, StatusType, VMStatus},
+    write_set::{WriteOp, WriteSet},
 };
 use anyhow::{format_err, Error, Result};
 use starcoin_crypto::{ed25519::*, hash::CryptoHash, traits::*, HashValue};
@@ -402,6 +404,11 @@ impl SignedUserTransaction {
         self.raw_txn.expiration_time
     }
 
+    /// Whether the txn is expired at `now_secs`, seconds since the unix epoch.
+    pub fn is_expired(&self, now_secs: u64) -> bool {
+        self.expiration_time().as_secs() <= now_secs
+    }
+
     pub fn raw_txn_bytes_len(&self) -> usize {
         scs::to_bytes(&self.raw_txn)
             .expect("Unable to serialize RawUserTransaction")
@@ -416,6 +423,13 @@ impl SignedUserTransaction {
         Ok(SignatureCheckedTransaction(self))
     }
 
+    /// Returns whether the signature of the transaction is valid, without consuming it.
+    pub fn is_signature_valid(&self) -> bool {
+        self.public_key
+            .verify_signature(&self.raw_txn.crypto_hash(), &self.signature)
+            .is_ok()
+    }
+
     pub fn format_for_client(&self, get_transaction_name: impl Fn(&[u8]) -> String) -> String {
         format!(
             "SignedUserTransaction {{ \n \
@@ -503,6 +517,9 @@ impl From<VMStatus> for TransactionStatus {
 /// The output of executing a transaction.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TransactionOutput {
+    /// The access paths written by this transaction.
+    write_set: WriteSet,
+
     /// The list of events emitted during this transaction.
     events: Vec<ContractEvent>,
 
@@ -514,18 +531,39 @@ pub struct TransactionOutput {
 }
 
 impl TransactionOutput {
-    pub fn new(events: Vec<ContractEvent>, gas_used: u64, status: TransactionStatus) -> Self {
+    pub fn new(
+        write_set: WriteSet,
+        events: Vec<ContractEvent>,
+        gas_used: u64,
+        status: TransactionStatus,
+    ) -> Self {
         TransactionOutput {
+            write_set,
             events,
             gas_used,
             status,
         }
     }
 
+    pub fn write_set(&self) -> &WriteSet {
+        &self.write_set
+    }
+
+    /// Every access path written by this transaction with its new value or deletion, so the
+    /// effects can be indexed without decoding the raw write set.
+    pub fn changed_resources(&self) -> Vec<(AccessPath, WriteOp)> {
+        self.write_set.iter().cloned().collect()
+    }
+
     pub fn events(&self) -> &[ContractEvent] {
         &self.events
     }
 
+    /// The events emitted by this transaction, empty until `ContractEvent` is supported.
+    pub fn emitted_events(&self) -> &[ContractEvent] {
+        &self.events
+    }
+
     pub fn gas_used(&self) -> u64 {
         self.gas_used
     }
@@ -695,6 +733,57 @@ impl std::fmt::Display for TxStatus {
     }
 }
 
+/// Admission result of a transaction submitted to the pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdmissionResult {
+    /// Transaction is added to the pool.
+    Accepted,
+    /// Transaction is added and replaces a pooled transaction with the same sequence number.
+    Replaced,
+    /// Transaction is rejected by the VM verification.
+    Rejected(VMStatus),
+    /// Transaction is not added because the pool limit has been reached.
+    PoolFull,
+    /// Transaction is discarded for other reason, such as already imported or bad signature.
+    Discarded(String),
+}
+
+impl AdmissionResult {
+    pub fn is_accepted(&self) -> bool {
+        match self {
+            AdmissionResult::Accepted | AdmissionResult::Replaced => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<TransactionError> for AdmissionResult {
+    fn from(err: TransactionError) -> Self {
+        match err {
+            TransactionError::CallErr(CallError::Execution(status)) => {
+                AdmissionResult::Rejected(status)
+            }
+            TransactionError::LimitReached => AdmissionResult::PoolFull,
+            err => AdmissionResult::Discarded(err.to_string()),
+        }
+    }
+}
+
+/// Result of checking a transaction against the pool and simulating it on the head state,
+/// the transaction is not submitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightResult {
+    /// Whether the transaction would be admitted by the pool.
+    pub would_admit: bool,
+    /// Why the transaction would not be admitted.
+    pub reason: Option<String>,
+    /// Gas used by the simulated execution.
+    pub estimated_gas: u64,
+    /// Status of the simulated execution.
+    pub simulated_status: VMStatus,
+}
+
 //======================= libra type converter ============================
 
 impl Into<libra_types::transaction::TransactionPayload> for TransactionPayload {
@@ -749,9 +838,27 @@ impl From<libra_types::transaction::TransactionStatus> for TransactionStatus {
 impl From<libra_types::transaction::TransactionOutput> for TransactionOutput {
     fn from(output: libra_types::transaction::TransactionOutput) -> Self {
         TransactionOutput::new(
+            WriteSet::from(output.write_set()),
             vec![], // ToDo: support ContractEvent
             output.gas_used(),
             TransactionStatus::from(output.status().clone()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_signature_valid() {
+        let txn = SignedUserTransaction::mock();
+        assert!(txn.is_signature_valid());
+
+        let mut raw_txn = txn.raw_txn().clone();
+        raw_txn.sequence_number += 1;
+        let tampered = SignedUserTransaction::new(raw_txn, txn.public_key(), txn.signature());
+        assert!(!tampered.is_signature_valid());
+        assert!(tampered.check_signature().is_err());
+    }
+}
@@ -36,8 +36,8 @@ impl Script {
         &self.args
     }
 
-    pub fn into_inner(self) -> (Vec<u8>, Vec<TransactionArgument>) {
-        (self.code, self.args)
+    pub fn into_inner(self) -> (Vec<u8>, Vec<TypeTag>, Vec<TransactionArgument>) {
+        (self.code, self.ty_args, self.args)
     }
 }
 
@@ -60,3 +60,22 @@ impl Into<libra_types::transaction::Script> for Script {
         libra_types::transaction::Script::new(self.code().to_vec(), ty_args, args)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_config::{lbr_type_tag, starcoin_type_tag};
+
+    #[test]
+    fn test_into_inner() {
+        let code = vec![1u8, 2, 3];
+        let ty_args = vec![starcoin_type_tag(), lbr_type_tag()];
+        let args = vec![TransactionArgument::U64(1), TransactionArgument::Bool(true)];
+        let script = Script::new(code.clone(), ty_args.clone(), args.clone());
+        let (code1, ty_args1, args1) = script.clone().into_inner();
+        assert_eq!(code1, code);
+        assert_eq!(ty_args1, ty_args);
+        assert_eq!(args1, args);
+        assert_eq!(Script::new(code1, ty_args1, args1), script);
+    }
+}
@@ -13,12 +13,14 @@ pub enum TransactionArgument {
     Address(AccountAddress),
     U8Vector(Vec<u8>),
     Bool(bool),
+    U128(u128),
 }
 
 impl fmt::Debug for TransactionArgument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             TransactionArgument::U64(value) => write!(f, "{{U64: {}}}", value),
+            TransactionArgument::U128(value) => write!(f, "{{U128: {}}}", value),
             TransactionArgument::Bool(boolean) => write!(f, "{{BOOL: {}}}", boolean),
             TransactionArgument::Address(address) => write!(f, "{{ADDRESS: {:?}}}", address),
             TransactionArgument::U8Vector(vector) => {
@@ -88,6 +90,15 @@ pub fn parse_as_u64(s: &str) -> Result<TransactionArgument> {
     Ok(TransactionArgument::U64(s.parse::<u64>()?))
 }
 
+/// Parses the given string as u128, the literal must have the `u128` suffix, such as `42u128`.
+pub fn parse_as_u128(s: &str) -> Result<TransactionArgument> {
+    if s.ends_with("u128") {
+        Ok(TransactionArgument::U128(s[..s.len() - 4].parse::<u128>()?))
+    } else {
+        Err(ErrorKind::ParseError(format!("\"{}\" is not a u128", s)).into())
+    }
+}
+
 /// Parses the given string as a bool.
 pub fn parse_as_bool(s: &str) -> Result<TransactionArgument> {
     Ok(TransactionArgument::Bool(s.parse::<bool>()?))
@@ -105,6 +116,7 @@ macro_rules! return_if_ok {
 pub fn parse_as_transaction_argument(s: &str) -> Result<TransactionArgument> {
     return_if_ok!(parse_as_address(s));
     return_if_ok!(parse_as_u64(s));
+    return_if_ok!(parse_as_u128(s));
     return_if_ok!(parse_as_bool(s));
     return_if_ok!(parse_as_u8_vector(s));
     Err(ErrorKind::ParseError(format!("cannot parse \"{}\" as transaction argument", s)).into())
@@ -118,6 +130,9 @@ impl Into<libra_types::transaction::TransactionArgument> for TransactionArgument
             TransactionArgument::U64(value) => {
                 libra_types::transaction::TransactionArgument::U64(value)
             }
+            TransactionArgument::U128(value) => {
+                libra_types::transaction::TransactionArgument::U128(value)
+            }
             TransactionArgument::Bool(boolean) => {
                 libra_types::transaction::TransactionArgument::Bool(boolean)
             }
@@ -145,6 +160,35 @@ mod test_transaction_argument {
         }
     }
 
+    #[test]
+    fn parse_u128() {
+        for s in &[
+            "0u128",
+            "42u128",
+            "340282366920938463463374607431768211455u128",
+        ] {
+            parse_as_u128(s).unwrap();
+        }
+        for s in &[
+            "42",
+            "u128",
+            "-3u128",
+            "340282366920938463463374607431768211456u128",
+        ] {
+            parse_as_u128(s).unwrap_err();
+        }
+        assert_eq!(
+            parse_as_transaction_argument("18446744073709551616u128").unwrap(),
+            TransactionArgument::U128(u128::from(u64::max_value()) + 1)
+        );
+        let arg: libra_types::transaction::TransactionArgument =
+            TransactionArgument::U128(u128::max_value()).into();
+        assert_eq!(
+            arg,
+            libra_types::transaction::TransactionArgument::U128(u128::max_value())
+        );
+    }
+
     #[test]
     fn parse_bool() {
         parse_as_bool("true").unwrap();
@@ -188,7 +232,7 @@ mod test_transaction_argument {
 
     #[test]
     fn parse_args() {
-        for s in &["123", "0xf", "b\"aaa\""] {
+        for s in &["123", "123u128", "0xf", "b\"aaa\""] {
             parse_as_transaction_argument(s).unwrap();
         }
 
@@ -4,9 +4,14 @@
 //! For each transaction the VM executes, the VM will output a `WriteSet` that contains each access
 //! path it updates. For each access path, the VM can either give its new value or delete it.
 
-use crate::access_path::AccessPath;
+use crate::{
+    access_path::{AccessPath, DataType},
+    account_address::AccountAddress,
+    account_config::{ACCOUNT_RESOURCE_PATH, BALANCE_RESOURCE_PATH},
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum WriteOp {
@@ -66,6 +71,72 @@ impl WriteSet {
     pub fn into_mut(self) -> WriteSetMut {
         self.0
     }
+
+    /// Describe every op of the write set for debugging.
+    /// A write set does not know whether a written access path existed before, so `exists` is
+    /// asked for it to tell a create from a modify.
+    pub fn describe<F>(&self, exists: F) -> Vec<WriteOpDescription>
+    where
+        F: Fn(&AccessPath) -> bool,
+    {
+        self.iter()
+            .map(|(access_path, write_op)| {
+                let (kind, value_len) = match write_op {
+                    WriteOp::Deletion => (WriteOpKind::Delete, 0),
+                    WriteOp::Value(value) if exists(access_path) => {
+                        (WriteOpKind::Modify, value.len())
+                    }
+                    WriteOp::Value(value) => (WriteOpKind::Create, value.len()),
+                };
+                WriteOpDescription {
+                    address: access_path.address(),
+                    tag: access_path_tag(access_path),
+                    kind,
+                    value_len,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Only the well known resources can be named, other access paths are only known by the hash.
+fn access_path_tag(access_path: &AccessPath) -> String {
+    let data_hash = access_path.data_hash();
+    match access_path.data_type() {
+        DataType::RESOURCE if data_hash == *ACCOUNT_RESOURCE_PATH => "LibraAccount::T".to_string(),
+        DataType::RESOURCE if data_hash == *BALANCE_RESOURCE_PATH => {
+            "LibraAccount::Balance".to_string()
+        }
+        DataType::RESOURCE => format!("resource {}", data_hash.to_hex()),
+        DataType::CODE => format!("code {}", data_hash.to_hex()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum WriteOpKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+/// A human readable form of a `WriteOp` and the access path it writes.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WriteOpDescription {
+    pub address: AccountAddress,
+    pub tag: String,
+    pub kind: WriteOpKind,
+    /// Length of the new value, 0 for a deletion.
+    pub value_len: usize,
+}
+
+impl fmt::Display for WriteOpDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {:x}/{}", self.kind, self.address, self.tag)?;
+        if self.kind != WriteOpKind::Delete {
+            write!(f, " ({} bytes)", self.value_len)?;
+        }
+        Ok(())
+    }
 }
 
 /// A mutable version of `WriteSet`.
@@ -128,3 +199,59 @@ impl ::std::iter::IntoIterator for WriteSet {
         self.0.write_set.into_iter()
     }
 }
+
+impl From<&libra_types::write_set::WriteSet> for WriteSet {
+    fn from(libra_write_set: &libra_types::write_set::WriteSet) -> Self {
+        WriteSet(
+            libra_write_set
+                .iter()
+                .map(|(access_path, write_op)| {
+                    let write_op = match write_op {
+                        libra_types::write_set::WriteOp::Deletion => WriteOp::Deletion,
+                        libra_types::write_set::WriteOp::Value(value) => {
+                            WriteOp::Value(value.clone())
+                        }
+                    };
+                    (AccessPath::from(access_path.clone()), write_op)
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::HashValue;
+
+    #[test]
+    fn test_describe_write_set() {
+        let address = AccountAddress::random();
+        let account_path = AccessPath::new_for_account(address);
+        let balance_path = AccessPath::new_for_balance(address);
+        let code_path = AccessPath::new(address, DataType::CODE, HashValue::random());
+        let write_set = WriteSetMut::new(vec![
+            (account_path.clone(), WriteOp::Value(vec![1, 2, 3])),
+            (balance_path, WriteOp::Value(vec![4])),
+            (code_path.clone(), WriteOp::Deletion),
+        ])
+        .freeze()
+        .unwrap();
+
+        let descriptions = write_set.describe(|access_path| *access_path == account_path);
+        assert_eq!(descriptions.len(), 3);
+        assert_eq!(descriptions[0].address, address);
+        assert_eq!(descriptions[0].tag, "LibraAccount::T");
+        assert_eq!(descriptions[0].kind, WriteOpKind::Modify);
+        assert_eq!(descriptions[0].value_len, 3);
+        assert_eq!(descriptions[1].tag, "LibraAccount::Balance");
+        assert_eq!(descriptions[1].kind, WriteOpKind::Create);
+        assert_eq!(descriptions[1].value_len, 1);
+        assert_eq!(
+            descriptions[2].tag,
+            format!("code {}", code_path.data_hash().to_hex())
+        );
+        assert_eq!(descriptions[2].kind, WriteOpKind::Delete);
+        assert_eq!(descriptions[2].value_len, 0);
+    }
+}
@@ -15,15 +15,21 @@ use types::{
     account_address::AccountAddress,
     account_config,
     language_storage::TypeTag,
+    time::{RealTimeService, TimeService},
     transaction::{
         authenticator::AuthenticationKey, RawUserTransaction, Script, SignedUserTransaction,
         TransactionArgument, TransactionPayload,
     },
 };
 
-// TTL is 86400s. Initial time was set to 0.
+/// TTL of the txns in seconds.
 pub const DEFAULT_EXPIRATION_TIME: u64 = 40_000;
 
+/// Expiration time of a txn created now, `DEFAULT_EXPIRATION_TIME` seconds from the wall clock.
+pub fn default_expiration_time() -> Duration {
+    Duration::from_secs(RealTimeService::new().now_secs() + DEFAULT_EXPIRATION_TIME)
+}
+
 /// Details about a Libra account.
 ///
 /// Tests will typically create a set of `Account` instances to run transactions on. This type
@@ -110,7 +116,7 @@ impl Account {
                 max_gas_amount,
                 gas_unit_price,
                 gas_specifier,
-                Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+                default_expiration_time(),
             ),
             TransactionPayload::Script(script) => RawUserTransaction::new_script(
                 *self.address(),
@@ -119,7 +125,7 @@ impl Account {
                 max_gas_amount,
                 gas_unit_price,
                 gas_specifier,
-                Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+                default_expiration_time(),
             ),
         };
 
@@ -204,8 +210,7 @@ impl Account {
             max_gas_amount,
             gas_unit_price,
             gas_specifier,
-            // TTL is 86400s. Initial time was set to 0.
-            Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+            default_expiration_time(),
         )
         .sign(&self.privkey, self.pubkey.clone())
         .unwrap()
@@ -235,8 +240,7 @@ pub fn create_signed_txn_with_association_account(
         max_gas_amount,
         gas_unit_price,
         gas_specifier,
-        // TTL is 86400s. Initial time was set to 0.
-        Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+        default_expiration_time(),
     )
     .sign(&GENESIS_KEYPAIR.0, GENESIS_KEYPAIR.1.clone())
     .unwrap()
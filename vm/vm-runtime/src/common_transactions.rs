@@ -3,9 +3,10 @@
 
 //! Support for encoding transactions for common situations.
 
+use crate::account::{
+    create_signed_txn_with_association_account, default_expiration_time, Account,
+};
 use crate::transaction_scripts::{CREATE_ACCOUNT_TXN, MINT_TXN, PEER_TO_PEER_TXN};
-use crate::{account::create_signed_txn_with_association_account, account::Account};
-use std::time::Duration;
 use types::account_address::AccountAddress;
 use types::account_config::starcoin_type_tag;
 use types::transaction::{
@@ -14,6 +15,7 @@ use types::transaction::{
 
 //TODO
 pub const TXN_RESERVED: u64 = 50_000_000;
+/// TTL of the txns in seconds.
 pub const DEFAULT_EXPIRATION_TIME: u64 = 40_000;
 
 /// Returns a transaction to create a new account with the given arguments.
@@ -156,6 +158,6 @@ pub fn raw_peer_to_peer_txn(
         TXN_RESERVED,
         1,
         starcoin_type_tag(),
-        Duration::from_secs(DEFAULT_EXPIRATION_TIME),
+        default_expiration_time(),
     )
 }
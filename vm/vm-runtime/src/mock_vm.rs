@@ -18,6 +18,7 @@ use types::{
         TransactionOutput, TransactionPayload, TransactionStatus,
     },
     vm_error::{StatusCode, VMStatus},
+    write_set::WriteSet,
 };
 
 enum MockTransaction {
@@ -68,9 +69,21 @@ impl MockVM {
             Err(_) => return Some(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
         };
 
+        self.verify_sender(
+            chain_state,
+            signature_verified_txn.sender(),
+            signature_verified_txn.sequence_number(),
+        )
+    }
+
+    fn verify_sender(
+        &self,
+        chain_state: &dyn ChainState,
+        sender: AccountAddress,
+        sequence_number: u64,
+    ) -> Option<VMStatus> {
         // get account resource from db
         let state_store = StateStore::new(chain_state);
-        let sender = signature_verified_txn.sender();
         let access_path = AccessPath::new_for_account(sender);
         let account_resource: AccountResource = match state_store.get_from_statedb(&access_path) {
             Err(e) => {
@@ -87,12 +100,31 @@ impl MockVM {
             },
         };
         // check seq number
-        if signature_verified_txn.sequence_number() < account_resource.sequence_number() {
+        if sequence_number < account_resource.sequence_number() {
             return Some(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_OLD));
         }
         None
     }
 
+    /// Verify the user txn without checking the signature, and return the output of executing it
+    /// without updating `chain_state`.
+    /// The mock txns always keep an empty write set, so the txn is not executed.
+    pub fn simulate_transaction(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> TransactionOutput {
+        let status = match self.verify_sender(chain_state, txn.sender(), txn.sequence_number()) {
+            Some(vm_status) => TransactionStatus::Discard(vm_status),
+            None => {
+                // panic on the payload the mock vm can not execute, as `execute_transaction` does.
+                decode_transaction(&txn);
+                KEEP_STATUS.clone()
+            }
+        };
+        TransactionOutput::new(WriteSet::default(), vec![], 0, status)
+    }
+
     pub fn execute_transaction(
         &mut self,
         chain_state: &dyn ChainState,
@@ -118,7 +150,8 @@ impl MockVM {
                     state_store
                         .set(access_path, new_account_resource.try_into()?)
                         .unwrap();
-                    output = TransactionOutput::new(vec![], 0, KEEP_STATUS.clone());
+                    output =
+                        TransactionOutput::new(WriteSet::default(), vec![], 0, KEEP_STATUS.clone());
                 }
                 MockTransaction::Payment {
                     sender,
@@ -172,6 +205,7 @@ impl MockVM {
                     //                        new_account_resource_receiver.try_into()?,
                     //                    )?;
                     output = TransactionOutput::new(
+                        WriteSet::default(),
                         vec![],
                         0,
                         TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
@@ -200,14 +234,15 @@ impl MockVM {
                 //                    account_resource.authentication_key().to_vec(),
                 //                );
                 //                state_store.set(access_path, new_account_resource.try_into()?)?;
-                output = TransactionOutput::new(vec![], 0, KEEP_STATUS.clone());
+                output =
+                    TransactionOutput::new(WriteSet::default(), vec![], 0, KEEP_STATUS.clone());
             }
             Transaction::StateSet(state_set) => {
                 let result_status = match chain_state.apply(state_set) {
                     Ok(_) => KEEP_STATUS.clone(),
                     Err(_) => DISCARD_STATUS.clone(),
                 };
-                output = TransactionOutput::new(vec![], 0, result_status)
+                output = TransactionOutput::new(WriteSet::default(), vec![], 0, result_status)
             }
         }
         Ok(output)
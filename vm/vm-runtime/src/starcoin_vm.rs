@@ -5,6 +5,7 @@ use crate::{chain_state::StateStore, system_module_names::*};
 use crypto::ed25519::Ed25519Signature;
 use libra_state_view::StateView;
 use libra_types::{
+    access_path::AccessPath as LibraAccessPath,
     account_address::AccountAddress as LibraAccountAddress,
     transaction::{
         TransactionOutput as LibraTransactionOutput, TransactionStatus as LibraTransactionStatus,
@@ -31,10 +32,11 @@ use types::{
     block_metadata::BlockMetadata,
     language_storage::{ModuleId, TypeTag},
     transaction::{
-        SignatureCheckedTransaction, SignedUserTransaction, Transaction, TransactionArgument,
-        TransactionOutput, TransactionPayload, TransactionStatus, MAX_TRANSACTION_SIZE_IN_BYTES,
+        SignedUserTransaction, Transaction, TransactionArgument, TransactionOutput,
+        TransactionPayload, TransactionStatus, MAX_TRANSACTION_SIZE_IN_BYTES,
     },
     vm_error::{StatusCode, VMStatus},
+    write_set::{WriteOpDescription, WriteSet},
 };
 use vm::errors::convert_prologue_runtime_error;
 use vm::{
@@ -59,27 +61,86 @@ pub static DISCARD_STATUS: Lazy<TransactionStatus> = Lazy::new(|| {
 pub static MAXIMUM_NUMBER_OF_GAS_UNITS: Lazy<GasUnits<GasCarrier>> =
     Lazy::new(|| GasUnits::new(100_000_000));
 
+/// Limits of the VM which may differ between networks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VMConfig {
+    /// Max gas units a transaction can submit.
+    pub max_gas_units: u64,
+}
+
+impl Default for VMConfig {
+    fn default() -> Self {
+        Self {
+            max_gas_units: MAXIMUM_NUMBER_OF_GAS_UNITS.get(),
+        }
+    }
+}
+
 #[derive(Clone)]
 /// Wrapper of MoveVM
 pub struct StarcoinVM {
     move_vm: Arc<MoveVM>,
     gas_schedule: Option<CostTable>,
+    //where the gas schedule is loaded from, a write to it invalidates the loaded schedule
+    gas_schedule_path: Option<LibraAccessPath>,
+    gas_schedule_loads: u64,
+    config: VMConfig,
 }
 
 impl StarcoinVM {
     pub fn new() -> Self {
+        Self::new_with_config(VMConfig::default())
+    }
+
+    pub fn new_with_config(config: VMConfig) -> Self {
         let inner = MoveVM::new();
         Self {
             move_vm: Arc::new(inner),
             gas_schedule: None,
+            gas_schedule_path: None,
+            gas_schedule_loads: 0,
+            config,
         }
     }
 
+    pub fn config(&self) -> &VMConfig {
+        &self.config
+    }
+
+    /// Times the gas schedule is loaded from the state.
+    pub fn gas_schedule_loads(&self) -> u64 {
+        self.gas_schedule_loads
+    }
+
+    /// Load the gas schedule unless it is loaded already, it is reused by the following
+    /// transactions until a transaction writes it.
     fn load_gas_schedule(&mut self, data_cache: &dyn RemoteCache) {
+        if self.gas_schedule.is_some() {
+            return;
+        }
         info!("load gas schedule");
+        self.gas_schedule_loads += 1;
         self.gas_schedule = self.fetch_gas_schedule(data_cache).ok();
     }
 
+    fn invalidate_gas_schedule(&mut self) {
+        self.gas_schedule = None;
+    }
+
+    /// The write set is applied to the state, drop the gas schedule if it is written.
+    fn on_write_set_applied(&mut self, write_set: &LibraWriteSet) {
+        let written = match &self.gas_schedule_path {
+            Some(gas_schedule_path) => write_set
+                .iter()
+                .any(|(access_path, _)| access_path == gas_schedule_path),
+            None => false,
+        };
+        if written {
+            debug!("gas schedule is written, reload it by the next transaction.");
+            self.invalidate_gas_schedule();
+        }
+    }
+
     fn fetch_gas_schedule(&mut self, data_cache: &dyn RemoteCache) -> VMResult<CostTable> {
         let address = account_config::association_address();
         let mut ctx = SystemExecutionContext::new(data_cache, GasUnits::new(0));
@@ -92,6 +153,7 @@ impl StarcoinVM {
             })?;
 
         let access_path = create_access_path(address.into(), gas_struct_ty.into_struct_tag()?);
+        self.gas_schedule_path = Some(access_path.clone());
 
         let data_blob = data_cache
             .get(&access_path)
@@ -142,15 +204,15 @@ impl StarcoinVM {
 
         // The submitted max gas units that the transaction can consume is greater than the
         // maximum number of gas units bound
-        if txn.max_gas_amount() > MAXIMUM_NUMBER_OF_GAS_UNITS.get() {
+        if txn.max_gas_amount() > self.config.max_gas_units {
             let error_str = format!(
                 "max gas units: {}, gas units submitted: {}",
-                MAXIMUM_NUMBER_OF_GAS_UNITS.get(),
+                self.config.max_gas_units,
                 txn.max_gas_amount()
             );
             warn!(
                 "[VM] Gas unit error; max {}, submitted {}",
-                MAXIMUM_NUMBER_OF_GAS_UNITS.get(),
+                self.config.max_gas_units,
                 txn.max_gas_amount()
             );
             return Err(
@@ -251,7 +313,7 @@ impl StarcoinVM {
 
     fn verify_transaction_impl(
         &mut self,
-        transaction: &SignatureCheckedTransaction,
+        transaction: &SignedUserTransaction,
         _state_view: &dyn StateView,
         remote_cache: &dyn RemoteCache,
         txn_data: &TransactionMetadata,
@@ -290,18 +352,19 @@ impl StarcoinVM {
         }
     }
 
-    pub fn verify_transaction(
+    /// Verify the txn, a txn with a sequence number ahead of the sender's is `Future`.
+    pub fn verify_transaction_result(
         &mut self,
         chain_state: &dyn ChainState,
         txn: SignedUserTransaction,
-    ) -> Option<VMStatus> {
+    ) -> VerifyResult {
         let state_store = StateStore::new(chain_state);
         let data_cache = BlockDataCache::new(&state_store);
         let libra_txn = txn.clone().into();
         let txn_data = TransactionMetadata::new(&libra_txn);
         let signature_verified_txn = match txn.check_signature() {
             Ok(t) => t,
-            Err(_) => return Some(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
+            Err(_) => return VerifyResult::Invalid(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
         };
         match self.verify_transaction_impl(
             &signature_verified_txn,
@@ -309,16 +372,26 @@ impl StarcoinVM {
             &data_cache,
             &txn_data,
         ) {
-            Ok(_) => None,
+            Ok(_) => VerifyResult::Ok,
             Err(err) => {
                 if err.major_status == StatusCode::SEQUENCE_NUMBER_TOO_NEW {
-                    None
+                    VerifyResult::Future
                 } else {
-                    Some(err)
+                    VerifyResult::Invalid(err)
                 }
             }
         }
     }
+
+    /// Verify the txn, a `Future` txn is treated as valid.
+    pub fn verify_transaction(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> Option<VMStatus> {
+        self.verify_transaction_result(chain_state, txn).into()
+    }
+
     fn execute_verified_payload(
         &mut self,
         remote_cache: &mut BlockDataCache<'_>,
@@ -442,22 +515,7 @@ impl StarcoinVM {
             TransactionExecutionContext::new(txn_data.max_gas_amount(), remote_cache);
         let gas_schedule = CostTable::zero();
 
-        if let Ok((id, timestamp, author, auth)) = block_metadata.into_inner() {
-            let previous_vote: BTreeMap<LibraAccountAddress, Ed25519Signature> = BTreeMap::new();
-            let vote_maps = scs::to_bytes(&previous_vote).unwrap();
-            let round = 0u64;
-            let args = vec![
-                Value::u64(round),
-                Value::u64(timestamp),
-                Value::vector_u8(id),
-                Value::vector_u8(vote_maps),
-                Value::address(author.into()),
-                match auth {
-                    Some(prefix) => Value::vector_u8(prefix),
-                    None => Value::vector_u8(Vec::new()),
-                },
-            ];
-
+        if let Ok(args) = block_prologue_args(block_metadata) {
             self.move_vm.execute_function(
                 &LIBRA_BLOCK_MODULE,
                 &BLOCK_PROLOGUE,
@@ -481,6 +539,105 @@ impl StarcoinVM {
         })
     }
 
+    /// Check the signature, then verify and execute the user txn. Err if the signature is invalid.
+    fn execute_user_transaction(
+        &mut self,
+        state_store: &StateStore<'_>,
+        data_cache: &mut BlockDataCache<'_>,
+        txn: SignedUserTransaction,
+    ) -> Result<LibraTransactionOutput, VMStatus> {
+        let txn = txn
+            .check_signature()
+            .map_err(|_| VMStatus::new(StatusCode::INVALID_SIGNATURE))?;
+        Ok(self.execute_unchecked_user_transaction(state_store, data_cache, &txn))
+    }
+
+    /// Execute the user txn without checking its signature.
+    fn execute_unchecked_user_transaction(
+        &mut self,
+        state_store: &StateStore<'_>,
+        data_cache: &mut BlockDataCache<'_>,
+        txn: &SignedUserTransaction,
+    ) -> LibraTransactionOutput {
+        let libra_txn = txn.clone().into();
+        let txn_data = TransactionMetadata::new(&libra_txn);
+        let verified_payload =
+            self.verify_transaction_impl(txn, state_store, &*data_cache, &txn_data);
+        match verified_payload {
+            Ok(payload) => self.execute_verified_payload(data_cache, &txn_data, payload),
+            Err(e) => discard_libra_error_output(e.into()),
+        }
+    }
+
+    /// Execute the user txn against `chain_state` without applying its write set, to see
+    /// whether it would succeed and how much gas it would use.
+    /// The signature is not checked, so a txn can be simulated before it is signed.
+    pub fn simulate_transaction(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> TransactionOutput {
+        let state_store = StateStore::new(chain_state);
+        let mut data_cache = BlockDataCache::new(&state_store);
+        self.load_gas_schedule(&data_cache);
+        TransactionOutput::from(self.execute_unchecked_user_transaction(
+            &state_store,
+            &mut data_cache,
+            &txn,
+        ))
+    }
+
+    /// Gas units the txn would use, its payload is executed with the max gas units bound against
+    /// a throwaway data cache, so nothing is committed. The signature is not checked.
+    pub fn estimate_gas(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> Result<u64, VMStatus> {
+        let state_store = StateStore::new(chain_state);
+        let data_cache = BlockDataCache::new(&state_store);
+        self.load_gas_schedule(&data_cache);
+        let libra_txn = txn.clone().into();
+        let txn_data = TransactionMetadata::new(&libra_txn);
+        let payload = self.verify_transaction_impl(&txn, &state_store, &data_cache, &txn_data)?;
+        let max_gas_units = GasUnits::new(self.config.max_gas_units);
+        let mut ctx = TransactionExecutionContext::new(max_gas_units, &data_cache);
+        let result = match payload {
+            VerifiedTranscationPayload::Module(m) => {
+                self.move_vm.publish_module(m, &mut ctx, &txn_data)
+            }
+            VerifiedTranscationPayload::Script(s, ty_args, args) => {
+                let gas_schedule = self.get_gas_schedule()?;
+                self.move_vm.execute_script(
+                    s,
+                    gas_schedule,
+                    &mut ctx,
+                    &txn_data,
+                    ty_args,
+                    convert_txn_args(args),
+                )
+            }
+        };
+        if let Err(e) = result {
+            return Err(e.into());
+        }
+        Ok(max_gas_units.sub(ctx.remaining_gas()).get())
+    }
+
+    /// Like `simulate_transaction`, but also describe the state changes of the txn, for debugging.
+    pub fn trace_transaction(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txn: SignedUserTransaction,
+    ) -> (TransactionOutput, Vec<WriteOpDescription>) {
+        let state_store = StateStore::new(chain_state);
+        let mut data_cache = BlockDataCache::new(&state_store);
+        self.load_gas_schedule(&data_cache);
+        let result = self.execute_unchecked_user_transaction(&state_store, &mut data_cache, &txn);
+        let descriptions = describe_write_set(&state_store, result.write_set());
+        (TransactionOutput::from(result), descriptions)
+    }
+
     pub fn execute_transaction(
         &mut self,
         chain_state: &dyn ChainState,
@@ -491,63 +648,110 @@ impl StarcoinVM {
         self.load_gas_schedule(&data_cache);
         match txn {
             Transaction::UserTransaction(txn) => {
-                let libra_txn = txn.clone().into();
-                let txn_data = TransactionMetadata::new(&libra_txn);
-
-                // check signature
-                let signature_checked_txn = match txn.check_signature() {
-                    Ok(t) => Ok(t),
-                    Err(_) => Err(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
-                };
-
-                let output = match signature_checked_txn {
-                    Ok(txn) => {
-                        let verified_payload = self.verify_transaction_impl(
-                            &txn,
-                            &state_store,
-                            &data_cache,
-                            &txn_data,
-                        );
-                        let result = match verified_payload {
-                            Ok(payload) => {
-                                self.execute_verified_payload(&mut data_cache, &txn_data, payload)
-                            }
-                            Err(e) => discard_libra_error_output(e.into()),
-                        };
-
+                match self.execute_user_transaction(&state_store, &mut data_cache, txn) {
+                    Ok(result) => {
                         if let LibraTransactionStatus::Keep(_) = result.status() {
-                            state_store.add_write_set(result.write_set())
+                            trace_write_set(&state_store, result.write_set());
+                            state_store.add_write_set(result.write_set());
+                            self.on_write_set_applied(result.write_set());
                         };
                         TransactionOutput::from(result)
                     }
                     Err(e) => discard_error_output(e),
-                };
-                output
+                }
             }
             Transaction::BlockMetadata(block_metadata) => {
                 let result = self
                     .process_block_metadata(&mut data_cache, block_metadata)
                     .unwrap_or_else(discard_libra_error_output);
                 if let LibraTransactionStatus::Keep(_) = result.status() {
-                    state_store.add_write_set(result.write_set())
+                    trace_write_set(&state_store, result.write_set());
+                    state_store.add_write_set(result.write_set());
+                    self.on_write_set_applied(result.write_set());
                 };
                 TransactionOutput::from(result)
             }
             Transaction::StateSet(state_set) => {
+                // a state set is keyed by hashes, it may replace the gas schedule.
+                self.invalidate_gas_schedule();
                 let result_status = match chain_state.apply(state_set) {
                     Ok(_) => KEEP_STATUS.clone(),
                     Err(_) => DISCARD_STATUS.clone(),
                 };
-                TransactionOutput::new(vec![], 0, result_status)
+                TransactionOutput::new(WriteSet::default(), vec![], 0, result_status)
             }
         }
     }
+
+    /// Execute the txns in order through one data cache, the write set of a kept txn is pushed
+    /// to the cache so the following txns see it, and is applied to `chain_state` as
+    /// `execute_transaction` does.
+    pub fn execute_block(
+        &mut self,
+        chain_state: &dyn ChainState,
+        txns: Vec<Transaction>,
+    ) -> Vec<TransactionOutput> {
+        let state_store = StateStore::new(chain_state);
+        let mut data_cache = BlockDataCache::new(&state_store);
+        let mut outputs = Vec::with_capacity(txns.len());
+        for txn in txns {
+            self.load_gas_schedule(&data_cache);
+            let output = match txn {
+                Transaction::UserTransaction(txn) => {
+                    match self.execute_user_transaction(&state_store, &mut data_cache, txn) {
+                        Ok(result) => {
+                            if let LibraTransactionStatus::Keep(_) = result.status() {
+                                data_cache.push_write_set(result.write_set());
+                                self.commit_write_set(chain_state, result.write_set());
+                            };
+                            TransactionOutput::from(result)
+                        }
+                        Err(e) => discard_error_output(e),
+                    }
+                }
+                Transaction::BlockMetadata(block_metadata) => {
+                    // the write set is pushed to the data cache by `process_block_metadata`.
+                    let result = self
+                        .process_block_metadata(&mut data_cache, block_metadata)
+                        .unwrap_or_else(discard_libra_error_output);
+                    if let LibraTransactionStatus::Keep(_) = result.status() {
+                        self.commit_write_set(chain_state, result.write_set());
+                    };
+                    TransactionOutput::from(result)
+                }
+                Transaction::StateSet(state_set) => {
+                    self.invalidate_gas_schedule();
+                    let result_status = match chain_state.apply(state_set) {
+                        Ok(_) => KEEP_STATUS.clone(),
+                        Err(_) => DISCARD_STATUS.clone(),
+                    };
+                    // values pushed to the cache may be replaced by the state set.
+                    data_cache = BlockDataCache::new(&state_store);
+                    TransactionOutput::new(WriteSet::default(), vec![], 0, result_status)
+                }
+            };
+            outputs.push(output);
+        }
+        outputs
+    }
+
+    fn commit_write_set(&mut self, chain_state: &dyn ChainState, write_set: &LibraWriteSet) {
+        let mut state_store = StateStore::new(chain_state);
+        trace_write_set(&state_store, write_set);
+        state_store.add_write_set(write_set);
+        self.on_write_set_applied(write_set);
+    }
 }
 
 pub(crate) fn discard_error_output(err: VMStatus) -> TransactionOutput {
     info!("discard error output: {:?}", err);
     // Since this transaction will be discarded, no writeset will be included.
-    TransactionOutput::new(vec![], 0, TransactionStatus::Discard(err))
+    TransactionOutput::new(
+        WriteSet::default(),
+        vec![],
+        0,
+        TransactionStatus::Discard(err),
+    )
 }
 
 pub(crate) fn discard_libra_error_output(err: LibraVMStatus) -> LibraTransactionOutput {
@@ -561,11 +765,39 @@ pub(crate) fn discard_libra_error_output(err: LibraVMStatus) -> LibraTransaction
     )
 }
 
+/// Arguments of `BLOCK_PROLOGUE`, an absent round is 0 and absent votes are empty.
+fn block_prologue_args(block_metadata: BlockMetadata) -> anyhow::Result<Vec<Value>> {
+    let round = block_metadata.round().unwrap_or(0);
+    let previous_vote: BTreeMap<LibraAccountAddress, Ed25519Signature> = block_metadata
+        .previous_vote()
+        .map(|votes| {
+            votes
+                .iter()
+                .map(|(address, signature)| ((*address).into(), signature.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let vote_maps = scs::to_bytes(&previous_vote)?;
+    let (id, timestamp, author, auth) = block_metadata.into_inner()?;
+    Ok(vec![
+        Value::u64(round),
+        Value::u64(timestamp),
+        Value::vector_u8(id),
+        Value::vector_u8(vote_maps),
+        Value::address(author.into()),
+        match auth {
+            Some(prefix) => Value::vector_u8(prefix),
+            None => Value::vector_u8(Vec::new()),
+        },
+    ])
+}
+
 /// Convert the transaction arguments into move values.
 fn convert_txn_args(args: Vec<TransactionArgument>) -> Vec<Value> {
     args.into_iter()
         .map(|arg| match arg {
             TransactionArgument::U64(i) => Value::u64(i),
+            TransactionArgument::U128(i) => Value::u128(i),
             TransactionArgument::Address(a) => Value::address(a.into()),
             TransactionArgument::Bool(b) => Value::bool(b),
             TransactionArgument::U8Vector(v) => Value::vector_u8(v),
@@ -573,6 +805,27 @@ fn convert_txn_args(args: Vec<TransactionArgument>) -> Vec<Value> {
         .collect()
 }
 
+/// Describe the write set against the state before it is applied.
+fn describe_write_set(
+    state_store: &StateStore<'_>,
+    write_set: &LibraWriteSet,
+) -> Vec<WriteOpDescription> {
+    WriteSet::from(write_set).describe(|access_path| {
+        state_store
+            .get_from_statedb(access_path)
+            .map(|value| value.is_some())
+            .unwrap_or(false)
+    })
+}
+
+fn trace_write_set(state_store: &StateStore<'_>, write_set: &LibraWriteSet) {
+    if log_enabled!(Level::Trace) {
+        for description in describe_write_set(state_store, write_set) {
+            trace!("write set: {}", description);
+        }
+    }
+}
+
 fn get_transaction_output(
     ctx: &mut (impl LibraChainState + ExecutionContext),
     txn_data: &TransactionMetadata,
@@ -606,7 +859,71 @@ pub fn failed_transaction_output(
     }
 }
 
+/// Result of verifying a user txn.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifyResult {
+    Ok,
+    /// The sequence number is ahead of the sender's, the txn may be valid later.
+    Future,
+    Invalid(VMStatus),
+}
+
+impl VerifyResult {
+    pub fn is_ok(&self) -> bool {
+        self == &VerifyResult::Ok
+    }
+}
+
+impl Into<Option<VMStatus>> for VerifyResult {
+    fn into(self) -> Option<VMStatus> {
+        match self {
+            VerifyResult::Ok | VerifyResult::Future => None,
+            VerifyResult::Invalid(status) => Some(status),
+        }
+    }
+}
+
 pub enum VerifiedTranscationPayload {
     Script(Vec<u8>, Vec<Type>, Vec<TransactionArgument>),
     Module(Vec<u8>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto::HashValue;
+    use types::{account_address::AccountAddress, transaction::Script};
+
+    #[test]
+    fn test_block_prologue_args() {
+        let author = AccountAddress::random();
+        let metadata = BlockMetadata::new(HashValue::random(), 1, author, None);
+        let args = block_prologue_args(metadata.clone()).unwrap();
+        assert!(args[0].equals(&Value::u64(0)).unwrap());
+        let empty_votes: BTreeMap<LibraAccountAddress, Ed25519Signature> = BTreeMap::new();
+        assert!(args[3]
+            .equals(&Value::vector_u8(scs::to_bytes(&empty_votes).unwrap()))
+            .unwrap());
+
+        let args = block_prologue_args(metadata.with_round(7)).unwrap();
+        assert!(args[0].equals(&Value::u64(7)).unwrap());
+        assert!(args[1].equals(&Value::u64(1)).unwrap());
+        assert!(args[4].equals(&Value::address(author.into())).unwrap());
+    }
+
+    #[test]
+    fn test_convert_u128_arg() {
+        let script = Script::new(
+            vec![],
+            vec![],
+            vec![
+                TransactionArgument::U64(1),
+                TransactionArgument::U128(u128::max_value()),
+            ],
+        );
+        let args = convert_txn_args(script.args().to_vec());
+        assert_eq!(args.len(), 2);
+        assert!(args[0].equals(&Value::u64(1)).unwrap());
+        assert!(args[1].equals(&Value::u128(u128::max_value())).unwrap());
+    }
+}
@@ -16,6 +16,8 @@ pub enum AccountServiceError {
     InvalidPassword(AccountAddress),
     #[error("invalid private key")]
     InvalidPrivateKey,
+    #[error("invalid mnemonic, {0}")]
+    InvalidMnemonic(String),
 
     // service error
     #[error("account error, {0:?}")]
@@ -35,6 +37,7 @@ impl From<WalletError> for AccountServiceError {
             }
             WalletError::InvalidPassword(a) => AccountServiceError::InvalidPassword(a),
             WalletError::InvalidPrivateKey => AccountServiceError::InvalidPrivateKey,
+            WalletError::InvalidMnemonic(e) => AccountServiceError::InvalidMnemonic(e),
 
             WalletError::TransactionSignError(e) => AccountServiceError::AccountError(e),
             // WalletError::DecryptPrivateKeyError(e) => AccountServiceError::AccountError(e),
@@ -63,6 +66,8 @@ pub enum WalletError {
     InvalidPassword(AccountAddress),
     #[error("invalid private key")]
     InvalidPrivateKey,
+    #[error("invalid mnemonic, {0}")]
+    InvalidMnemonic(String),
 
     // logic error
     #[error("transaction sign error, {0:?}")]
@@ -0,0 +1,110 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derive the account private key from a BIP39 mnemonic phrase, by SLIP-0010 ed25519 derivation.
+
+use crate::error::WalletError;
+use crate::WalletResult;
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use starcoin_crypto::ed25519::Ed25519PrivateKey;
+use std::convert::TryFrom;
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ED25519_CURVE_SEED: &[u8] = b"ed25519 seed";
+
+/// The derivation path m/44'/101010'/0'/0'/0', 101010 is the SLIP-0044 coin type of Starcoin.
+/// Every index is hardened, ed25519 only supports hardened derivation.
+pub const DERIVATION_PATH: [u32; 5] = [44, 101010, 0, 0, 0];
+
+/// Derive the private key at `DERIVATION_PATH` from an english mnemonic phrase and its
+/// passphrase, the word count and the checksum of the phrase are checked.
+pub fn derive_private_key(mnemonic: &str, passphrase: &str) -> WalletResult<Ed25519PrivateKey> {
+    let mnemonic = Mnemonic::from_phrase(mnemonic, Language::English)
+        .map_err(|e| WalletError::InvalidMnemonic(e.to_string()))?;
+    let seed = Seed::new(&mnemonic, passphrase);
+    let key = derive_key(seed.as_bytes(), &DERIVATION_PATH);
+    Ed25519PrivateKey::try_from(&key[..]).map_err(|_| WalletError::InvalidPrivateKey)
+}
+
+/// Derive the ed25519 private key at the hardened `path` from `seed`.
+pub fn derive_key(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let (mut key, mut chain_code) = hmac_sha512(ED25519_CURVE_SEED, &[seed]);
+    for index in path {
+        let index = (index | HARDENED_OFFSET).to_be_bytes();
+        let (child_key, child_chain_code) = hmac_sha512(&chain_code, &[&[0u8], &key, &index]);
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+    key
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_varkey(key).expect("hmac accepts a key of any length");
+    for bytes in data {
+        mac.input(bytes);
+    }
+    let result = mac.result().code();
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&result[..32]);
+    right.copy_from_slice(&result[32..]);
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                               abandon abandon abandon about";
+
+    #[test]
+    fn test_derive_key() {
+        // SLIP-0010 ed25519 test vector 1.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(derive_key(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(derive_key(&seed, &[0])),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            hex::encode(derive_key(&seed, &[0, 1, 2, 2, 1_000_000_000])),
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+        );
+    }
+
+    #[test]
+    fn test_derive_private_key() {
+        let key = derive_private_key(TEST_PHRASE, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(key.to_bytes()),
+            "70af66877a7698bab2a6d0881da8d5953cb3327a9c42a0cc367c106dd010bbfc"
+        );
+        let key = derive_private_key(TEST_PHRASE, "").unwrap();
+        assert_eq!(
+            hex::encode(key.to_bytes()),
+            "d13cfeb0388bc4d814fbc17e854cf5c1e0f134bd3aa9a19b6815d177db7bd8b4"
+        );
+    }
+
+    #[test]
+    fn test_invalid_mnemonic() {
+        // the checksum word is wrong.
+        let phrase = TEST_PHRASE.replace("about", "abandon");
+        match derive_private_key(phrase.as_str(), "") {
+            Err(WalletError::InvalidMnemonic(_)) => {}
+            other => panic!("expect invalid mnemonic, got {:?}", other.map(|_| ())),
+        }
+        // a word is missing.
+        let phrase = TEST_PHRASE.replacen("abandon ", "", 1);
+        assert!(derive_private_key(phrase.as_str(), "").is_err());
+        // not a word of the list.
+        let phrase = TEST_PHRASE.replacen("abandon", "starcoin", 1);
+        assert!(derive_private_key(phrase.as_str(), "").is_err());
+    }
+}
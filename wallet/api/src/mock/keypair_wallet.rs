@@ -14,7 +14,7 @@ use starcoin_types::{
     transaction::{RawUserTransaction, SignedUserTransaction},
 };
 use std::convert::TryFrom;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 type KeyPair = starcoin_crypto::test_utils::KeyPair<Ed25519PrivateKey, Ed25519PublicKey>;
 
@@ -133,6 +133,11 @@ where
         Ok(())
     }
 
+    fn get_unlocked_accounts(&self) -> WalletResult<Vec<(AccountAddress, Instant)>> {
+        //accounts are never locked, so no unlock expires.
+        Ok(vec![])
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction> {
         let address = raw_txn.sender();
         if !self.contains(&address)? {
@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::mock::{KeyPairWallet, MemWalletStore};
-use crate::{ServiceResult, Wallet, WalletAccount, WalletAsyncService};
+use crate::{mnemonic, ServiceResult, Wallet, WalletAccount, WalletAsyncService};
 use anyhow::Result;
+use starcoin_crypto::ed25519::Ed25519PublicKey;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Clone)]
 pub struct MockWalletService {
@@ -40,6 +41,10 @@ impl WalletAsyncService for MockWalletService {
         Ok(self.wallet.get_account(&address)?)
     }
 
+    async fn set_default_account(self, address: AccountAddress) -> ServiceResult<()> {
+        Ok(self.wallet.set_default(&address)?)
+    }
+
     async fn sign_txn(self, raw_txn: RawUserTransaction) -> ServiceResult<SignedUserTransaction> {
         Ok(self.wallet.sign_txn(raw_txn)?)
     }
@@ -55,6 +60,14 @@ impl WalletAsyncService for MockWalletService {
             .unlock_account(address, password.as_str(), duration)?)
     }
 
+    async fn lock_account(self, address: AccountAddress) -> ServiceResult<()> {
+        Ok(self.wallet.lock_account(address)?)
+    }
+
+    async fn get_unlocked_accounts(self) -> ServiceResult<Vec<(AccountAddress, Instant)>> {
+        Ok(self.wallet.get_unlocked_accounts()?)
+    }
+
     async fn import_account(
         self,
         address: AccountAddress,
@@ -74,4 +87,19 @@ impl WalletAsyncService for MockWalletService {
     ) -> ServiceResult<Vec<u8>> {
         Ok(self.wallet.export_account(&address, password.as_str())?)
     }
+
+    async fn import_from_mnemonic(
+        self,
+        mnemonic: String,
+        passphrase: String,
+        account_password: String,
+    ) -> ServiceResult<WalletAccount> {
+        let private_key = mnemonic::derive_private_key(mnemonic.as_str(), passphrase.as_str())?;
+        let address = AccountAddress::from_public_key(&Ed25519PublicKey::from(&private_key));
+        Ok(self.wallet.import_account(
+            address,
+            private_key.to_bytes().to_vec(),
+            account_password.as_str(),
+        )?)
+    }
 }
@@ -5,6 +5,7 @@ use crate::error::AccountServiceError;
 use crate::{Wallet, WalletAccount};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
+use std::time::Instant;
 
 pub type ServiceResult<T> = std::result::Result<T, AccountServiceError>;
 
@@ -20,6 +21,9 @@ pub trait WalletAsyncService: Clone + std::marker::Unpin + Send + Sync {
 
     async fn get_account(self, address: AccountAddress) -> ServiceResult<Option<WalletAccount>>;
 
+    /// Set the account of `address` as the default account, the account must exist.
+    async fn set_default_account(self, address: AccountAddress) -> ServiceResult<()>;
+
     async fn sign_txn(self, raw_txn: RawUserTransaction) -> ServiceResult<SignedUserTransaction>;
     async fn unlock_account(
         self,
@@ -27,6 +31,10 @@ pub trait WalletAsyncService: Clone + std::marker::Unpin + Send + Sync {
         password: String,
         duration: std::time::Duration,
     ) -> ServiceResult<()>;
+    /// Lock the account before the unlock duration elapses.
+    async fn lock_account(self, address: AccountAddress) -> ServiceResult<()>;
+    /// Return the unlocked accounts with the instant their unlock expires.
+    async fn get_unlocked_accounts(self) -> ServiceResult<Vec<(AccountAddress, Instant)>>;
     async fn import_account(
         self,
         address: AccountAddress,
@@ -40,4 +48,12 @@ pub trait WalletAsyncService: Clone + std::marker::Unpin + Send + Sync {
         address: AccountAddress,
         password: String,
     ) -> ServiceResult<Vec<u8>>;
+
+    /// Import the account whose private key is derived from a BIP39 mnemonic phrase.
+    async fn import_from_mnemonic(
+        self,
+        mnemonic: String,
+        passphrase: String,
+        account_password: String,
+    ) -> ServiceResult<WalletAccount>;
 }
@@ -5,7 +5,7 @@ use crate::error::WalletError;
 use crate::WalletAccount;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub type WalletResult<T> = std::result::Result<T, WalletError>;
 
@@ -35,6 +35,9 @@ pub trait Wallet {
 
     fn lock_account(&self, address: AccountAddress) -> WalletResult<()>;
 
+    /// Return the unlocked accounts with the instant their unlock expires.
+    fn get_unlocked_accounts(&self) -> WalletResult<Vec<(AccountAddress, Instant)>>;
+
     /// Sign transaction by txn sender's Account.
     /// If the wallet is protected by password, should unlock the sender's account first.
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction>;
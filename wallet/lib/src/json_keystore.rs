@@ -0,0 +1,244 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Web3 secret storage (version 3) keystore, the private key is encrypted by aes-128-ctr with a
+//! key derived from the password by scrypt, and authenticated by a keccak256 mac.
+
+use aes_ctr::stream_cipher::generic_array::GenericArray;
+use aes_ctr::stream_cipher::{NewStreamCipher, SyncStreamCipher};
+use aes_ctr::Aes128Ctr;
+use anyhow::format_err;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use starcoin_crypto::ed25519::Ed25519PrivateKey;
+use starcoin_types::account_address::AccountAddress;
+use std::convert::TryFrom;
+use wallet_api::error::WalletError;
+
+pub type Result<T> = std::result::Result<T, WalletError>;
+
+const KEYSTORE_VERSION: u32 = 3;
+const CIPHER: &str = "aes-128-ctr";
+const KDF: &str = "scrypt";
+/// scrypt N is 2^14, as the light params of other wallets, an export must not stall the node.
+const SCRYPT_LOG_N: u8 = 14;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// The largest scrypt N accepted on import.
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const DKLEN: usize = 32;
+const SALT_LEN: usize = 32;
+const IV_LEN: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyStore {
+    pub address: String,
+    pub crypto: CryptoParams,
+    pub id: String,
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CryptoParams {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: String,
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+    pub mac: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub dklen: u32,
+    pub n: u32,
+    pub p: u32,
+    pub r: u32,
+    pub salt: String,
+}
+
+/// Encrypt the private key of `address` by `password` to a keystore json.
+pub fn encrypt_key(
+    address: AccountAddress,
+    private_key: &Ed25519PrivateKey,
+    password: &str,
+) -> Result<String> {
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let mut iv = [0u8; IV_LEN];
+    rng.fill_bytes(&mut iv);
+    let mut id = [0u8; 16];
+    rng.fill_bytes(&mut id);
+
+    let derived_key = derive_key(password, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+    let mut ciphertext = private_key.to_bytes().to_vec();
+    apply_cipher(&derived_key, &iv, &mut ciphertext);
+    let keystore = KeyStore {
+        address: hex::encode(address),
+        crypto: CryptoParams {
+            cipher: CIPHER.to_string(),
+            cipherparams: CipherParams {
+                iv: hex::encode(iv),
+            },
+            ciphertext: hex::encode(&ciphertext),
+            kdf: KDF.to_string(),
+            kdfparams: KdfParams {
+                dklen: DKLEN as u32,
+                n: 1 << SCRYPT_LOG_N,
+                p: SCRYPT_P,
+                r: SCRYPT_R,
+                salt: hex::encode(salt),
+            },
+            mac: hex::encode(mac(&derived_key, &ciphertext)),
+        },
+        id: format_uuid(id),
+        version: KEYSTORE_VERSION,
+    };
+    serde_json::to_string(&keystore).map_err(|e| WalletError::StoreError(e.into()))
+}
+
+/// Decrypt the keystore json by `password`, a wrong password fails the mac check.
+pub fn decrypt_key(json: &str, password: &str) -> Result<(AccountAddress, Ed25519PrivateKey)> {
+    let keystore: KeyStore =
+        serde_json::from_str(json).map_err(|e| WalletError::StoreError(e.into()))?;
+    let address = AccountAddress::try_from(hex::decode(&keystore.address).map_err(invalid)?)?;
+    let crypto = &keystore.crypto;
+    let params = &crypto.kdfparams;
+    ensure_keystore(keystore.version == KEYSTORE_VERSION, "unsupported version")?;
+    ensure_keystore(crypto.cipher == CIPHER, "unsupported cipher")?;
+    ensure_keystore(crypto.kdf == KDF, "unsupported kdf")?;
+    ensure_keystore(params.dklen as usize == DKLEN, "unsupported dklen")?;
+    ensure_keystore(
+        params.n.is_power_of_two() && params.n.trailing_zeros() <= MAX_SCRYPT_LOG_N as u32,
+        "invalid scrypt n",
+    )?;
+
+    let salt = hex::decode(&params.salt).map_err(invalid)?;
+    let iv = hex::decode(&crypto.cipherparams.iv).map_err(invalid)?;
+    ensure_keystore(iv.len() == IV_LEN, "invalid iv")?;
+    let mut ciphertext = hex::decode(&crypto.ciphertext).map_err(invalid)?;
+    let expect_mac = hex::decode(&crypto.mac).map_err(invalid)?;
+
+    let derived_key = derive_key(
+        password,
+        &salt,
+        params.n.trailing_zeros() as u8,
+        params.r,
+        params.p,
+    )?;
+    if mac(&derived_key, &ciphertext).to_vec() != expect_mac {
+        return Err(WalletError::InvalidPassword(address));
+    }
+    apply_cipher(&derived_key, &iv, &mut ciphertext);
+    let private_key = Ed25519PrivateKey::try_from(ciphertext.as_slice())
+        .map_err(|_| WalletError::InvalidPrivateKey)?;
+    Ok((address, private_key))
+}
+
+fn derive_key(password: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<[u8; DKLEN]> {
+    let params = scrypt::ScryptParams::new(log_n, r, p)
+        .map_err(|e| WalletError::StoreError(format_err!("invalid scrypt params: {}", e)))?;
+    let mut derived_key = [0u8; DKLEN];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut derived_key)
+        .map_err(|e| WalletError::StoreError(format_err!("scrypt failed: {}", e)))?;
+    Ok(derived_key)
+}
+
+fn apply_cipher(derived_key: &[u8; DKLEN], iv: &[u8], data: &mut [u8]) {
+    let mut cipher = Aes128Ctr::new(
+        GenericArray::from_slice(&derived_key[..16]),
+        GenericArray::from_slice(iv),
+    );
+    cipher.apply_keystream(data);
+}
+
+fn mac(derived_key: &[u8; DKLEN], ciphertext: &[u8]) -> [u8; 32] {
+    let mut data = derived_key[16..].to_vec();
+    data.extend_from_slice(ciphertext);
+    tiny_keccak::keccak256(&data)
+}
+
+/// Format random bytes as a version 4 uuid.
+fn format_uuid(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn ensure_keystore(condition: bool, message: &str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(WalletError::StoreError(format_err!(
+            "invalid keystore, {}",
+            message
+        )))
+    }
+}
+
+fn invalid(e: hex::FromHexError) -> WalletError {
+    WalletError::StoreError(format_err!("invalid keystore, {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use starcoin_crypto::Uniform;
+
+    fn gen_private_key() -> Ed25519PrivateKey {
+        let mut rng = StdRng::from_seed(rand::thread_rng().gen());
+        Ed25519PrivateKey::generate(&mut rng)
+    }
+
+    #[test]
+    fn test_keystore_round_trip() -> Result<()> {
+        let private_key = gen_private_key();
+        let address = AccountAddress::random();
+        let json = encrypt_key(address, &private_key, "pass")?;
+        let keystore: KeyStore = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(keystore.version, KEYSTORE_VERSION);
+        assert_eq!(keystore.crypto.kdfparams.n, 1 << SCRYPT_LOG_N);
+        // the plain private key is not in the keystore.
+        assert!(!json.contains(hex::encode(private_key.to_bytes()).as_str()));
+
+        let (address1, private_key1) = decrypt_key(json.as_str(), "pass")?;
+        assert_eq!(address1, address);
+        assert_eq!(private_key1.to_bytes(), private_key.to_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_keystore_wrong_password() -> Result<()> {
+        let private_key = gen_private_key();
+        let address = AccountAddress::random();
+        let json = encrypt_key(address, &private_key, "pass")?;
+        match decrypt_key(json.as_str(), "wrong pass") {
+            Err(WalletError::InvalidPassword(a)) => assert_eq!(a, address),
+            other => panic!("expect invalid password error, got {:?}", other.map(|_| ())),
+        }
+
+        // a tampered ciphertext fails the mac check too.
+        let mut keystore: KeyStore = serde_json::from_str(json.as_str()).unwrap();
+        let mut ciphertext = hex::decode(&keystore.crypto.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        keystore.crypto.ciphertext = hex::encode(ciphertext);
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert!(decrypt_key(json.as_str(), "pass").is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::json_keystore;
 use anyhow::format_err;
 use rand::prelude::*;
 use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
@@ -56,7 +57,16 @@ impl KeyCache {
         }
     }
 
-    #[allow(dead_code)]
+    /// The accounts not expired yet with their unlock expiry.
+    pub fn unlocked_accounts(&self) -> Vec<(AccountAddress, Instant)> {
+        let cur_instant = Instant::now();
+        self.cache
+            .iter()
+            .filter(|(_account, (ttl, _))| &cur_instant < ttl)
+            .map(|(account, (ttl, _))| (*account, *ttl))
+            .collect()
+    }
+
     pub fn clean_expired(&mut self) {
         let cur_instant = Instant::now();
         self.cache.retain(|_account, (ttl, _)| &cur_instant < ttl);
@@ -128,6 +138,10 @@ where
         Ok(())
     }
 
+    fn get_unlocked_accounts(&self) -> Result<Vec<(AccountAddress, Instant)>> {
+        Ok(self.key_cache.read().unwrap().unlocked_accounts())
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> Result<SignedUserTransaction> {
         let address = raw_txn.sender();
         if !self.contains(&address)? {
@@ -216,6 +230,26 @@ where
         Ok(wallet)
     }
 
+    /// Export the account key as an encrypted json keystore, the keystore is encrypted by the
+    /// account password.
+    pub fn export_keystore(&self, address: &AccountAddress, password: &str) -> Result<String> {
+        let keypair = self.unlock_prikey(address, password)?;
+        json_keystore::encrypt_key(*address, &keypair.private_key, password)
+    }
+
+    /// Import the account of an encrypted json keystore, the account password is the password
+    /// of the keystore.
+    pub fn import_keystore(&self, json: &str, password: &str) -> Result<WalletAccount> {
+        let (address, private_key) = json_keystore::decrypt_key(json, password)?;
+        self.import_account(address, private_key.to_bytes().to_vec(), password)
+    }
+
+    /// Remove the keys whose unlock expired from memory, even if they are never accessed again.
+    /// The dropped private keys are zeroized.
+    pub fn clean_expired_keys(&self) {
+        self.key_cache.write().unwrap().clean_expired();
+    }
+
     fn save_account(
         &self,
         account: WalletAccount,
@@ -281,6 +315,7 @@ mod tests {
     use anyhow::Result;
     use starcoin_types::account_address::AccountAddress;
     use std::time::Duration;
+    use wallet_api::error::WalletError;
 
     #[test]
     fn test_wallet() -> Result<()> {
@@ -305,6 +340,52 @@ mod tests {
 
         Ok(())
     }
+    #[test]
+    fn test_clean_expired_keys() -> Result<()> {
+        let tmp_path = tempfile::tempdir()?;
+        let wallet_store = FileWalletStore::new(tmp_path.path());
+        let wallet = KeyStoreWallet::new(wallet_store)?;
+        let account1 = wallet.create_account("pass1")?;
+        let account2 = wallet.create_account("pass2")?;
+        wallet.unlock_account(account1.address, "pass1", Duration::from_millis(100))?;
+        wallet.unlock_account(account2.address, "pass2", Duration::from_secs(60))?;
+        assert_eq!(wallet.key_cache.read().unwrap().cache.len(), 2);
+
+        std::thread::sleep(Duration::from_millis(200));
+        // the expired key is still in memory until the sweep.
+        assert_eq!(wallet.key_cache.read().unwrap().cache.len(), 2);
+        wallet.clean_expired_keys();
+        let key_cache = wallet.key_cache.read().unwrap();
+        assert_eq!(key_cache.cache.len(), 1);
+        assert!(key_cache.cache.contains_key(&account2.address));
+        Ok(())
+    }
+
+    #[test]
+    fn test_keystore_export_import() -> Result<()> {
+        let tmp_path = tempfile::tempdir()?;
+        let wallet = KeyStoreWallet::new(FileWalletStore::new(tmp_path.path()))?;
+        let account = wallet.create_account("pass")?;
+        let private_key = wallet.export_account(&account.address, "pass")?;
+        assert!(wallet.export_keystore(&account.address, "wrong").is_err());
+        let keystore = wallet.export_keystore(&account.address, "pass")?;
+
+        let tmp_path2 = tempfile::tempdir()?;
+        let wallet2 = KeyStoreWallet::new(FileWalletStore::new(tmp_path2.path()))?;
+        match wallet2.import_keystore(keystore.as_str(), "wrong") {
+            Err(WalletError::InvalidPassword(address)) => assert_eq!(address, account.address),
+            other => panic!("expect invalid password error, got {:?}", other),
+        }
+        assert!(!wallet2.contains(&account.address)?);
+        let account2 = wallet2.import_keystore(keystore.as_str(), "pass")?;
+        assert_eq!(account2.address, account.address);
+        assert_eq!(
+            wallet2.export_account(&account.address, "pass")?,
+            private_key
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_wallet_import_account_and_sign() -> Result<()> {
         let tmp_path = tempfile::tempdir()?;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod file_wallet_store;
+pub mod json_keystore;
 pub mod keystore_wallet;
 
 #[cfg(test)]
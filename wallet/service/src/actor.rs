@@ -3,7 +3,7 @@
 
 use crate::message::{WalletRequest, WalletResponse};
 use crate::service::WalletServiceImpl;
-use actix::{Actor, Addr, Context, Handler};
+use actix::{Actor, Addr, AsyncContext, Context, Handler};
 use anyhow::Result;
 use starcoin_config::NodeConfig;
 use starcoin_types::account_address::AccountAddress;
@@ -14,6 +14,10 @@ use starcoin_wallet_lib::{file_wallet_store::FileWalletStore, keystore_wallet::K
 use starcoin_wallet_api::error::AccountServiceError;
 use starcoin_wallet_api::{ServiceResult, Wallet, WalletAccount, WalletAsyncService, WalletResult};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Interval of the sweep which removes the expired unlocked keys from memory.
+const CLEAN_EXPIRED_KEYS_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct WalletActor {
     service: WalletServiceImpl<KeyStoreWallet<FileWalletStore>>,
@@ -33,6 +37,12 @@ impl WalletActor {
 
 impl Actor for WalletActor {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(CLEAN_EXPIRED_KEYS_INTERVAL, |act, _ctx| {
+            act.service.clean_expired_keys();
+        });
+    }
 }
 
 impl Handler<WalletRequest> for WalletActor {
@@ -60,6 +70,17 @@ impl Handler<WalletRequest> for WalletActor {
                     .unlock_account(address, password.as_str(), duration)?;
                 WalletResponse::UnlockAccountResponse
             }
+            WalletRequest::LockAccount(address) => {
+                self.service.lock_account(address)?;
+                WalletResponse::LockAccountResponse
+            }
+            WalletRequest::GetUnlockedAccounts() => {
+                WalletResponse::UnlockedAccounts(self.service.get_unlocked_accounts()?)
+            }
+            WalletRequest::SetDefaultAccount(address) => {
+                self.service.set_default(&address)?;
+                WalletResponse::SetDefaultAccountResponse
+            }
             WalletRequest::ExportAccount { address, password } => {
                 let data = self.service.export_account(&address, password.as_str())?;
                 WalletResponse::ExportAccountResponse(data)
@@ -74,6 +95,18 @@ impl Handler<WalletRequest> for WalletActor {
                         .import_account(address, private_key, password.as_str())?;
                 WalletResponse::ImportAccountResponse(account)
             }
+            WalletRequest::ImportFromMnemonic {
+                mnemonic,
+                passphrase,
+                account_password,
+            } => {
+                let account = self.service.import_from_mnemonic(
+                    mnemonic.as_str(),
+                    passphrase.as_str(),
+                    account_password.as_str(),
+                )?;
+                WalletResponse::ImportAccountResponse(account)
+            }
         };
         return Ok(response);
     }
@@ -179,6 +212,45 @@ impl WalletAsyncService for WalletActorRef {
         }
     }
 
+    async fn lock_account(self, address: AccountAddress) -> ServiceResult<()> {
+        let response = self
+            .0
+            .send(WalletRequest::LockAccount(address))
+            .await
+            .map_err(|e| AccountServiceError::OtherError(Box::new(e)))??;
+        if let WalletResponse::LockAccountResponse = response {
+            Ok(())
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
+    async fn get_unlocked_accounts(self) -> ServiceResult<Vec<(AccountAddress, Instant)>> {
+        let response = self
+            .0
+            .send(WalletRequest::GetUnlockedAccounts())
+            .await
+            .map_err(|e| AccountServiceError::OtherError(Box::new(e)))??;
+        if let WalletResponse::UnlockedAccounts(accounts) = response {
+            Ok(accounts)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
+    async fn set_default_account(self, address: AccountAddress) -> ServiceResult<()> {
+        let response = self
+            .0
+            .send(WalletRequest::SetDefaultAccount(address))
+            .await
+            .map_err(|e| AccountServiceError::OtherError(Box::new(e)))??;
+        if let WalletResponse::SetDefaultAccountResponse = response {
+            Ok(())
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
+
     async fn import_account(
         self,
         address: AccountAddress,
@@ -217,15 +289,38 @@ impl WalletAsyncService for WalletActorRef {
             panic!("Unexpect response type.")
         }
     }
+
+    async fn import_from_mnemonic(
+        self,
+        mnemonic: String,
+        passphrase: String,
+        account_password: String,
+    ) -> ServiceResult<WalletAccount> {
+        let response = self
+            .0
+            .send(WalletRequest::ImportFromMnemonic {
+                mnemonic,
+                passphrase,
+                account_password,
+            })
+            .await
+            .map_err(|e| AccountServiceError::OtherError(Box::new(e)))??;
+        if let WalletResponse::ImportAccountResponse(account) = response {
+            Ok(account)
+        } else {
+            panic!("Unexpect response type.")
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use starcoin_config::{BaseConfig, ChainNetwork, ConfigModule};
+    use tempfile::TempDir;
 
-    #[stest::test]
-    async fn test_actor_launch() -> Result<()> {
+    /// Launch a wallet actor whose vault is in a new temp dir, the temp dir is removed on drop.
+    fn launch_actor_for_test() -> Result<(WalletActorRef, Arc<NodeConfig>, TempDir)> {
         let temp_dir = tempfile::tempdir()?;
         let base_config = BaseConfig::new(ChainNetwork::Dev, Some(temp_dir.path().to_path_buf()));
         std::fs::create_dir_all(base_config.data_dir())?;
@@ -233,9 +328,192 @@ mod tests {
         let mut node_config = NodeConfig::random_for_test();
         node_config.vault.random(&base_config);
         let config = Arc::new(node_config);
-        let actor = WalletActor::launch(config)?;
+        let actor = WalletActor::launch(config.clone())?;
+        Ok((actor, config, temp_dir))
+    }
+
+    #[stest::test]
+    async fn test_actor_launch() -> Result<()> {
+        let (actor, _config, _temp_dir) = launch_actor_for_test()?;
         let account = actor.get_default_account().await?;
         assert!(account.is_none());
         Ok(())
     }
+
+    #[stest::test]
+    async fn test_lock_account() -> Result<()> {
+        let (actor, _config, _temp_dir) = launch_actor_for_test()?;
+        let account = actor.clone().create_account("pass".to_string()).await?;
+        let raw_txn = RawUserTransaction::mock_by_sender(account.address);
+        actor
+            .clone()
+            .unlock_account(
+                account.address,
+                "pass".to_string(),
+                std::time::Duration::from_secs(60),
+            )
+            .await?;
+        let txn = actor.clone().sign_txn(raw_txn.clone()).await?;
+        assert!(txn.check_signature().is_ok());
+
+        actor.clone().lock_account(account.address).await?;
+        match actor.clone().sign_txn(raw_txn.clone()).await {
+            Err(AccountServiceError::AccountLocked(address)) => {
+                assert_eq!(address, account.address)
+            }
+            other => panic!("expect account locked error, got {:?}", other),
+        }
+
+        // sign again after unlock.
+        actor
+            .clone()
+            .unlock_account(
+                account.address,
+                "pass".to_string(),
+                std::time::Duration::from_secs(60),
+            )
+            .await?;
+        assert!(actor.sign_txn(raw_txn).await.is_ok());
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_sign_txn_with_locked_account() -> Result<()> {
+        let (actor, _config, _temp_dir) = launch_actor_for_test()?;
+        // the account is never unlocked.
+        let account = actor.clone().create_account("pass".to_string()).await?;
+        let raw_txn = RawUserTransaction::mock_by_sender(account.address);
+        match actor.clone().sign_txn(raw_txn).await {
+            Err(AccountServiceError::AccountLocked(address)) => {
+                assert_eq!(address, account.address)
+            }
+            other => panic!("expect account locked error, got {:?}", other),
+        }
+
+        // an unknown sender is not reported as locked.
+        let raw_txn = RawUserTransaction::mock_by_sender(AccountAddress::random());
+        match actor.sign_txn(raw_txn).await {
+            Err(AccountServiceError::AccountNotExist(_)) => {}
+            other => panic!("expect account not exist error, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_get_unlocked_accounts() -> Result<()> {
+        let (actor, _config, _temp_dir) = launch_actor_for_test()?;
+        let account1 = actor.clone().create_account("pass1".to_string()).await?;
+        let account2 = actor.clone().create_account("pass2".to_string()).await?;
+        let account3 = actor.clone().create_account("pass3".to_string()).await?;
+        assert!(actor.clone().get_unlocked_accounts().await?.is_empty());
+
+        let before = Instant::now();
+        actor
+            .clone()
+            .unlock_account(
+                account1.address,
+                "pass1".to_string(),
+                std::time::Duration::from_secs(60),
+            )
+            .await?;
+        actor
+            .clone()
+            .unlock_account(
+                account2.address,
+                "pass2".to_string(),
+                std::time::Duration::from_secs(120),
+            )
+            .await?;
+        let after = Instant::now();
+
+        let unlocked = actor.clone().get_unlocked_accounts().await?;
+        assert_eq!(unlocked.len(), 2);
+        let expiry_of = |address| {
+            unlocked
+                .iter()
+                .find(|(account, _)| *account == address)
+                .map(|(_, expiry)| *expiry)
+                .unwrap()
+        };
+        let expiry1 = expiry_of(account1.address);
+        let expiry2 = expiry_of(account2.address);
+        assert!(expiry1 >= before + std::time::Duration::from_secs(60));
+        assert!(expiry1 <= after + std::time::Duration::from_secs(60));
+        assert!(expiry2 >= before + std::time::Duration::from_secs(120));
+        assert!(expiry2 <= after + std::time::Duration::from_secs(120));
+        assert!(expiry1 < expiry2);
+        assert!(unlocked
+            .iter()
+            .all(|(account, _)| *account != account3.address));
+
+        actor.clone().lock_account(account1.address).await?;
+        let unlocked = actor.get_unlocked_accounts().await?;
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].0, account2.address);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_set_default_account() -> Result<()> {
+        let (actor, config, _temp_dir) = launch_actor_for_test()?;
+        let account1 = actor.clone().create_account("pass1".to_string()).await?;
+        let account2 = actor.clone().create_account("pass2".to_string()).await?;
+        assert!(account1.is_default);
+        assert!(!account2.is_default);
+
+        match actor
+            .clone()
+            .set_default_account(AccountAddress::random())
+            .await
+        {
+            Err(AccountServiceError::AccountNotExist(_)) => {}
+            other => panic!("expect account not exist error, got {:?}", other),
+        }
+        actor.clone().set_default_account(account2.address).await?;
+        let default_account = actor.get_default_account().await?.unwrap();
+        assert_eq!(default_account.address, account2.address);
+
+        // the default account is persisted by the wallet store.
+        let actor = WalletActor::launch(config)?;
+        let default_account = actor.clone().get_default_account().await?.unwrap();
+        assert_eq!(default_account.address, account2.address);
+        let accounts = actor.get_accounts().await?;
+        assert_eq!(accounts.iter().filter(|a| a.is_default).count(), 1);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_import_from_mnemonic() -> Result<()> {
+        let (actor, _config, _temp_dir) = launch_actor_for_test()?;
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                      abandon abandon about";
+        let account = actor
+            .clone()
+            .import_from_mnemonic(phrase.to_string(), "TREZOR".to_string(), "pass".to_string())
+            .await?;
+        let private_key = actor
+            .clone()
+            .export_account(account.address, "pass".to_string())
+            .await?;
+        assert_eq!(
+            private_key,
+            hex::decode("70af66877a7698bab2a6d0881da8d5953cb3327a9c42a0cc367c106dd010bbfc")?
+        );
+        assert_eq!(
+            account.address,
+            AccountAddress::from_public_key(&account.public_key)
+        );
+        assert!(actor.clone().get_account(account.address).await?.is_some());
+
+        // the last word is replaced, the checksum does not match.
+        let corrupted = phrase.replace("about", "abandon");
+        let result = actor
+            .import_from_mnemonic(corrupted, "TREZOR".to_string(), "pass".to_string())
+            .await;
+        match result {
+            Err(AccountServiceError::InvalidMnemonic(_)) => {}
+            other => panic!("expect invalid mnemonic error, got {:?}", other),
+        }
+        Ok(())
+    }
 }
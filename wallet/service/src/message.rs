@@ -6,6 +6,7 @@ use actix::Message;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
 use starcoin_wallet_api::{WalletAccount, WalletResult};
+use std::time::Instant;
 
 #[derive(Debug, Clone)]
 pub enum WalletRequest {
@@ -15,6 +16,9 @@ pub enum WalletRequest {
     GetAccount(AccountAddress),
     SignTxn(RawUserTransaction),
     UnlockAccount(AccountAddress, String, Duration),
+    LockAccount(AccountAddress),
+    GetUnlockedAccounts(),
+    SetDefaultAccount(AccountAddress),
     ImportAccount {
         address: AccountAddress,
         private_key: Vec<u8>,
@@ -24,6 +28,11 @@ pub enum WalletRequest {
         address: AccountAddress,
         password: String,
     },
+    ImportFromMnemonic {
+        mnemonic: String,
+        passphrase: String,
+        account_password: String,
+    },
 }
 
 impl Message for WalletRequest {
@@ -38,6 +47,9 @@ pub enum WalletResponse {
     SignedTxn(SignedUserTransaction),
     Account(Option<WalletAccount>),
     UnlockAccountResponse,
+    LockAccountResponse,
+    UnlockedAccounts(Vec<(AccountAddress, Instant)>),
+    SetDefaultAccountResponse,
     ImportAccountResponse(WalletAccount),
     ExportAccountResponse(Vec<u8>),
     None,
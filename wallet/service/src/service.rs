@@ -1,10 +1,14 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use starcoin_crypto::ed25519::Ed25519PublicKey;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::transaction::{RawUserTransaction, SignedUserTransaction};
-use starcoin_wallet_api::{Wallet, WalletAccount, WalletResult, WalletService};
-use std::time::Duration;
+use starcoin_wallet_api::{
+    mnemonic, Wallet, WalletAccount, WalletResult, WalletService, WalletStore,
+};
+use starcoin_wallet_lib::keystore_wallet::KeyStoreWallet;
+use std::time::{Duration, Instant};
 
 pub struct WalletServiceImpl<W>
 where
@@ -21,6 +25,30 @@ where
     pub fn new(wallet: W) -> Self {
         Self { wallet }
     }
+
+    /// Import the account whose private key is derived from the mnemonic phrase and passphrase,
+    /// the key is saved encrypted by `account_password`.
+    pub fn import_from_mnemonic(
+        &self,
+        mnemonic: &str,
+        passphrase: &str,
+        account_password: &str,
+    ) -> WalletResult<WalletAccount> {
+        let private_key = mnemonic::derive_private_key(mnemonic, passphrase)?;
+        let address = AccountAddress::from_public_key(&Ed25519PublicKey::from(&private_key));
+        self.wallet
+            .import_account(address, private_key.to_bytes().to_vec(), account_password)
+    }
+}
+
+impl<S> WalletServiceImpl<KeyStoreWallet<S>>
+where
+    S: WalletStore,
+{
+    /// Remove the unlocked keys which expired from memory.
+    pub fn clean_expired_keys(&self) {
+        self.wallet.clean_expired_keys()
+    }
 }
 
 impl<W> WalletService for WalletServiceImpl<W> where W: Wallet {}
@@ -67,6 +95,10 @@ where
         self.wallet.lock_account(address)
     }
 
+    fn get_unlocked_accounts(&self) -> WalletResult<Vec<(AccountAddress, Instant)>> {
+        self.wallet.get_unlocked_accounts()
+    }
+
     fn sign_txn(&self, raw_txn: RawUserTransaction) -> WalletResult<SignedUserTransaction> {
         self.wallet.sign_txn(raw_txn)
     }